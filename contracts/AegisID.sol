@@ -13,6 +13,9 @@ contract AegisID is ERC721, ERC721URIStorage, AccessControl {
     bytes32 public constant MINTER_ROLE = keccak256("MINTER_ROLE");
     uint256 private _nextTokenId;
 
+    /// @notice Emitted when an identity is withdrawn, e.g. because its verification lapsed.
+    event Revoked(uint256 indexed tokenId, address indexed holder, string reason);
+
     constructor() ERC721("AegisID", "AEGIS") {
         _grantRole(DEFAULT_ADMIN_ROLE, msg.sender);
         _grantRole(MINTER_ROLE, msg.sender);
@@ -28,6 +31,16 @@ contract AegisID is ERC721, ERC721URIStorage, AccessControl {
         _setTokenURI(tokenId, uri);
     }
 
+    /// @notice Withdraws an identity by burning it.
+    /// @dev Only callable by accounts with MINTER_ROLE; the issuer revokes what it issued.
+    /// @param tokenId The identity to revoke.
+    /// @param reason Why the identity was withdrawn (e.g. "KYC review overdue").
+    function revoke(uint256 tokenId, string memory reason) public onlyRole(MINTER_ROLE) {
+        address holder = ownerOf(tokenId);
+        _burn(tokenId);
+        emit Revoked(tokenId, holder, reason);
+    }
+
     // Hook: Validates that the token is being minted or burned, acting as a "Soulbound" guard.
     // We strictly forbid transfers between two non-zero addresses.
     function _update(address to, uint256 tokenId, address auth)
@@ -2,7 +2,9 @@
 pragma solidity ^0.8.20;
 
 import "@openzeppelin/contracts/access/Ownable.sol";
+import "@openzeppelin/contracts/token/ERC20/extensions/IERC20Metadata.sol";
 import "./interfaces/IAegisRules.sol";
+import "./interfaces/IPriceOracle.sol";
 
 contract AegisRules is Ownable, IAegisRules {
     struct AgentRule {
@@ -13,8 +15,28 @@ contract AegisRules is Ownable, IAegisRules {
 
     mapping(address => AgentRule) public agentRules;
 
+    // Per-token budgets in the token's base units (address(0) = native coin)
+    mapping(address => mapping(address => AgentRule)) public tokenRules;
+    mapping(address => mapping(address => bool)) public hasTokenLimit;
+
+    // Budgets in the oracle's reference currency (18 decimals), across all assets
+    mapping(address => AgentRule) public valueRules;
+    mapping(address => bool) public hasValueLimit;
+
+    IPriceOracle public priceOracle;
+
+    // Agents barred from spending regardless of their limits (e.g. while frozen)
+    mapping(address => bool) public blocked;
+
     event LimitSet(address indexed agent, uint256 limit);
     event TransactionChecked(address indexed agent, uint256 amount, uint256 remaining);
+    event TokenLimitSet(address indexed agent, address indexed token, uint256 limit);
+    event TokenLimitRemoved(address indexed agent, address indexed token);
+    event ValueLimitSet(address indexed agent, uint256 limit);
+    event ValueLimitRemoved(address indexed agent);
+    event PriceOracleSet(address indexed oracle);
+    event TransferChecked(address indexed agent, address indexed token, uint256 amount, uint256 value);
+    event AgentBlocked(address indexed agent, bool blocked);
 
     constructor() Ownable(msg.sender) {}
 
@@ -25,9 +47,87 @@ contract AegisRules is Ownable, IAegisRules {
         emit LimitSet(agent, limit);
     }
 
-    function checkTransaction(address agent, uint256 amount) external override returns (bool) {
+    function setTokenLimit(address agent, address token, uint256 limit) external onlyOwner {
+        tokenRules[agent][token].dailyLimit = limit;
+        hasTokenLimit[agent][token] = true;
+        emit TokenLimitSet(agent, token, limit);
+    }
+
+    function removeTokenLimit(address agent, address token) external onlyOwner {
+        delete tokenRules[agent][token];
+        hasTokenLimit[agent][token] = false;
+        emit TokenLimitRemoved(agent, token);
+    }
+
+    function setValueLimit(address agent, uint256 limit) external onlyOwner {
+        valueRules[agent].dailyLimit = limit;
+        hasValueLimit[agent] = true;
+        emit ValueLimitSet(agent, limit);
+    }
+
+    function removeValueLimit(address agent) external onlyOwner {
+        delete valueRules[agent];
+        hasValueLimit[agent] = false;
+        emit ValueLimitRemoved(agent);
+    }
+
+    function setBlocked(address agent, bool isBlocked) external onlyOwner {
+        blocked[agent] = isBlocked;
+        emit AgentBlocked(agent, isBlocked);
+    }
+
+    function setPriceOracle(address oracle) external onlyOwner {
+        priceOracle = IPriceOracle(oracle);
+        emit PriceOracleSet(oracle);
+    }
+
+    function checkTransaction(address agent, uint256 amount) public override returns (bool) {
+        require(!blocked[agent], "AegisRules: Agent blocked");
         AgentRule storage rule = agentRules[agent];
-        
+        _spend(rule, amount, "AegisRules: Daily limit exceeded");
+
+        emit TransactionChecked(agent, amount, rule.dailyLimit - rule.spentToday);
+        return true;
+    }
+
+    /**
+     * @dev Refuses blocked agents, then applies the agent's per-token limit and value limit when configured.
+     * Transfers governed by neither fall back to the raw daily limit of `checkTransaction`.
+     */
+    function checkTransfer(address agent, address token, uint256 amount) external override returns (bool) {
+        require(!blocked[agent], "AegisRules: Agent blocked");
+        bool governed = false;
+        uint256 value = 0;
+
+        if (hasTokenLimit[agent][token]) {
+            _spend(tokenRules[agent][token], amount, "AegisRules: Token limit exceeded");
+            governed = true;
+        }
+
+        if (hasValueLimit[agent]) {
+            value = valueOf(token, amount);
+            _spend(valueRules[agent], value, "AegisRules: Value limit exceeded");
+            governed = true;
+        }
+
+        if (!governed) {
+            return checkTransaction(agent, amount);
+        }
+
+        emit TransferChecked(agent, token, amount, value);
+        return true;
+    }
+
+    /**
+     * @dev Value of `amount` base units of `token` in the reference currency (18 decimals).
+     */
+    function valueOf(address token, uint256 amount) public view returns (uint256) {
+        require(address(priceOracle) != address(0), "AegisRules: No price oracle");
+        uint8 decimals = token == address(0) ? 18 : IERC20Metadata(token).decimals();
+        return amount * priceOracle.getPrice(token) / (10 ** decimals);
+    }
+
+    function _spend(AgentRule storage rule, uint256 amount, string memory error) internal {
         // 1. Check if day has passed (24h rolling or midnight reset)
         // Simple 24h checks from last reset
         if (block.timestamp >= rule.lastResetTime + 1 days) {
@@ -36,12 +136,9 @@ contract AegisRules is Ownable, IAegisRules {
         }
 
         // 2. Check limits
-        require(rule.spentToday + amount <= rule.dailyLimit, "AegisRules: Daily limit exceeded");
+        require(rule.spentToday + amount <= rule.dailyLimit, error);
 
         // 3. Update State
         rule.spentToday += amount;
-        
-        emit TransactionChecked(agent, amount, rule.dailyLimit - rule.spentToday);
-        return true;
     }
 }
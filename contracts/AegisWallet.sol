@@ -4,6 +4,7 @@ pragma solidity ^0.8.20;
 import "@openzeppelin/contracts/token/ERC20/IERC20.sol";
 import "@openzeppelin/contracts/access/Ownable.sol";
 import "@openzeppelin/contracts/utils/ReentrancyGuard.sol";
+import "@openzeppelin/contracts/utils/Pausable.sol";
 import "./interfaces/IAegisID.sol";
 import "./interfaces/IAegisRules.sol";
 
@@ -12,7 +13,7 @@ import "./interfaces/IAegisRules.sol";
  * @dev A smart account that holds funds and restricts spending to verified Identity holders.
  * Part 1 of AEGIS Infrastructure.
  */
-contract AegisWallet is Ownable, ReentrancyGuard {
+contract AegisWallet is Ownable, ReentrancyGuard, Pausable {
     IAegisID public immutable aegisID;
     IAegisRules public rulesContract;
 
@@ -40,13 +41,13 @@ contract AegisWallet is Ownable, ReentrancyGuard {
      * 
      * Requirement: Caller must hold at least 1 AegisID token (Proof of Compliance).
      */
-    function execute(address target, uint256 value, bytes calldata data) external payable nonReentrant {
+    function execute(address target, uint256 value, bytes calldata data) external payable nonReentrant whenNotPaused {
         // 1. Identity Check
         require(aegisID.balanceOf(msg.sender) > 0, "AegisWallet: Caller not verified Agent");
 
-        // 2. Budget Check
+        // 2. Budget Check (native value, address(0))
         if (address(rulesContract) != address(0)) {
-            require(rulesContract.checkTransaction(msg.sender, value), "AegisWallet: Rule denied transaction");
+            require(rulesContract.checkTransfer(msg.sender, address(0), value), "AegisWallet: Rule denied transaction");
         }
         
         // 3. Execution
@@ -60,16 +61,15 @@ contract AegisWallet is Ownable, ReentrancyGuard {
      * @dev Execute ERC20 Transfer with Rule Checks
      * Prevents Governance Loophole where users could drain tokens without ETH value checks.
      */
-    function executeERC20(address token, address to, uint256 amount) external nonReentrant {
+    function executeERC20(address token, address to, uint256 amount) external nonReentrant whenNotPaused {
         // 1. Identity Check
         require(aegisID.balanceOf(msg.sender) > 0, "AegisWallet: Caller not verified Agent");
 
-        // 2. Budget Check (Count token amount as spend)
-        // Note: For production, we need an Oracle to convert Token->USD for unified limits.
-        // For MVP, we assume 1:1 parity or separate limits per token?
-        // Let's assume we treat the amount raw for now (Simple MVP Rule).
+        // 2. Budget Check
+        // Per-token and oracle-priced value limits apply when configured in AegisRules;
+        // otherwise the raw amount counts against the legacy daily limit.
         if (address(rulesContract) != address(0)) {
-            require(rulesContract.checkTransaction(msg.sender, amount), "AegisWallet: Rule denied transaction");
+            require(rulesContract.checkTransfer(msg.sender, token, amount), "AegisWallet: Rule denied transaction");
         }
 
         // 3. Execution
@@ -88,4 +88,16 @@ contract AegisWallet is Ownable, ReentrancyGuard {
         rulesContract = IAegisRules(_rules);
         emit RulesUpdated(_rules);
     }
+
+    /**
+     * @dev Emergency stop: blocks `execute` and `executeERC20` until unpaused.
+     * Owner withdrawals stay available.
+     */
+    function pause() external onlyOwner {
+        _pause();
+    }
+
+    function unpause() external onlyOwner {
+        _unpause();
+    }
 }
@@ -12,4 +12,11 @@ interface IAegisRules {
      * Reverts or returns false if denied.
      */
     function checkTransaction(address agent, uint256 amount) external returns (bool);
+
+    /**
+     * @dev checks a transfer of `amount` base units of `token` (address(0) for the native coin)
+     * against the agent's per-token and value limits.
+     * Reverts or returns false if denied.
+     */
+    function checkTransfer(address agent, address token, uint256 amount) external returns (bool);
 }
@@ -0,0 +1,13 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+/**
+ * @dev Price source for value-denominated spending limits.
+ */
+interface IPriceOracle {
+    /**
+     * @dev Price of one whole unit of `asset` (address(0) for the native coin)
+     * in the reference currency, scaled to 18 decimals. Reverts if unknown.
+     */
+    function getPrice(address asset) external view returns (uint256);
+}
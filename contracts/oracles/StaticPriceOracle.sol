@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import "@openzeppelin/contracts/access/Ownable.sol";
+import "../interfaces/IPriceOracle.sol";
+
+/**
+ * @title StaticPriceOracle
+ * @dev Owner-maintained prices. Used for tests, local nodes and pilots without a market feed.
+ */
+contract StaticPriceOracle is Ownable, IPriceOracle {
+    mapping(address => uint256) public prices;
+
+    event PriceSet(address indexed asset, uint256 price);
+
+    constructor() Ownable(msg.sender) {}
+
+    function setPrice(address asset, uint256 price) external onlyOwner {
+        prices[asset] = price;
+        emit PriceSet(asset, price);
+    }
+
+    function getPrice(address asset) external view override returns (uint256) {
+        uint256 price = prices[asset];
+        require(price > 0, "StaticPriceOracle: Unknown asset");
+        return price;
+    }
+}
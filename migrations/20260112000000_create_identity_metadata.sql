@@ -0,0 +1,11 @@
+-- Content-addressed ERC-721 metadata for AegisID tokens.
+-- `document` holds the exact bytes that were hashed so the hash can be re-verified when served.
+CREATE TABLE IF NOT EXISTS identity_metadata (
+    content_hash VARCHAR(66) PRIMARY KEY,
+    entity_id INTEGER NOT NULL REFERENCES legal_entities(id),
+    document TEXT NOT NULL,
+    token_id VARCHAR(78),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_identity_metadata_token_id ON identity_metadata(token_id);
@@ -0,0 +1,14 @@
+-- Registry of AegisWallet contracts deployed by the backend.
+CREATE TABLE IF NOT EXISTS wallets (
+    id SERIAL PRIMARY KEY,
+    address VARCHAR(42) NOT NULL UNIQUE,
+    entity_id INTEGER NOT NULL REFERENCES legal_entities(id),
+    owner_address VARCHAR(42) NOT NULL,
+    rules_contract VARCHAR(42) NOT NULL,
+    chain_id BIGINT NOT NULL,
+    deploy_tx_hash VARCHAR(66) NOT NULL,
+    label VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallets_entity_id ON wallets(entity_id);
@@ -0,0 +1,11 @@
+-- ERC-20 token registry. `symbol` and `decimals` are read from the token contract on registration.
+CREATE TABLE IF NOT EXISTS tokens (
+    id SERIAL PRIMARY KEY,
+    address VARCHAR(42) NOT NULL,
+    symbol VARCHAR(32) NOT NULL,
+    decimals SMALLINT NOT NULL CHECK (decimals >= 0 AND decimals <= 77),
+    chain_id BIGINT NOT NULL,
+    allowed BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (address, chain_id)
+);
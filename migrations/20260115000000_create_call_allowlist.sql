@@ -0,0 +1,11 @@
+-- Contracts (and optionally specific function selectors) agents may call through AegisWallet.execute.
+-- A NULL selector allows any function on the target, including plain value transfers.
+CREATE TABLE IF NOT EXISTS call_allowlist (
+    id SERIAL PRIMARY KEY,
+    target_address VARCHAR(42) NOT NULL,
+    selector VARCHAR(10),
+    label VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_call_allowlist_entry ON call_allowlist(target_address, COALESCE(selector, ''));
@@ -0,0 +1,13 @@
+-- Stored results of requests made with an Idempotency-Key header, scoped per user and endpoint.
+-- `response_status` is NULL while the original request is still being processed.
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    username VARCHAR(255) NOT NULL,
+    scope VARCHAR(64) NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL,
+    request_hash VARCHAR(66) NOT NULL,
+    response_status SMALLINT,
+    response_body TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    PRIMARY KEY (username, scope, idempotency_key)
+);
@@ -0,0 +1,32 @@
+-- Agent payments as a state machine:
+-- created -> screened -> approved -> submitted -> confirmed | failed, with cancellation before submission.
+CREATE TABLE IF NOT EXISTS payment_intents (
+    id SERIAL PRIMARY KEY,
+    wallet_id INTEGER NOT NULL REFERENCES wallets(id),
+    target_address VARCHAR(42) NOT NULL,
+    amount VARCHAR(100) NOT NULL,
+    token_address VARCHAR(42),
+    status VARCHAR(20) NOT NULL DEFAULT 'created'
+        CHECK (status IN ('created', 'screened', 'approved', 'submitted', 'confirmed', 'failed', 'cancelled')),
+    tx_hash VARCHAR(66),
+    failure_reason TEXT,
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_intents_wallet_id ON payment_intents(wallet_id);
+CREATE INDEX IF NOT EXISTS idx_payment_intents_status ON payment_intents(status);
+
+-- Audit trail: every status change with who made it and why.
+CREATE TABLE IF NOT EXISTS payment_intent_events (
+    id SERIAL PRIMARY KEY,
+    intent_id INTEGER NOT NULL REFERENCES payment_intents(id),
+    from_status VARCHAR(20),
+    to_status VARCHAR(20) NOT NULL,
+    actor VARCHAR(255) NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_intent_events_intent_id ON payment_intent_events(intent_id);
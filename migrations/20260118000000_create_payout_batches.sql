@@ -0,0 +1,28 @@
+-- Batch payouts: a list of payments validated together and executed item by item.
+CREATE TABLE IF NOT EXISTS payout_batches (
+    id SERIAL PRIMARY KEY,
+    wallet_id INTEGER NOT NULL REFERENCES wallets(id),
+    status VARCHAR(20) NOT NULL
+        CHECK (status IN ('rejected', 'processing', 'completed', 'partially_failed', 'failed')),
+    item_count INTEGER NOT NULL,
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+-- Each item becomes its own payment intent once the batch passes validation.
+CREATE TABLE IF NOT EXISTS payout_items (
+    id SERIAL PRIMARY KEY,
+    batch_id INTEGER NOT NULL REFERENCES payout_batches(id),
+    line_no INTEGER NOT NULL,
+    target_address VARCHAR(42) NOT NULL,
+    amount VARCHAR(100) NOT NULL,
+    token_address VARCHAR(42),
+    status VARCHAR(20) NOT NULL
+        CHECK (status IN ('rejected', 'pending', 'confirmed', 'failed')),
+    intent_id INTEGER REFERENCES payment_intents(id),
+    error TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_payout_items_batch_id ON payout_items(batch_id);
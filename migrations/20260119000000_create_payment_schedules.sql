@@ -0,0 +1,36 @@
+-- Recurring agent payments executed by the background scheduler.
+-- Exactly one of `cron_expression` / `interval_seconds` defines the cadence.
+CREATE TABLE IF NOT EXISTS payment_schedules (
+    id SERIAL PRIMARY KEY,
+    wallet_id INTEGER NOT NULL REFERENCES wallets(id),
+    target_address VARCHAR(42) NOT NULL,
+    amount VARCHAR(100) NOT NULL,
+    token_address VARCHAR(42),
+    cron_expression VARCHAR(255),
+    interval_seconds BIGINT CHECK (interval_seconds IS NULL OR interval_seconds >= 60),
+    start_at TIMESTAMPTZ NOT NULL,
+    end_at TIMESTAMPTZ,
+    max_occurrences INTEGER CHECK (max_occurrences IS NULL OR max_occurrences > 0),
+    occurrences INTEGER NOT NULL DEFAULT 0,
+    next_run_at TIMESTAMPTZ,
+    status VARCHAR(20) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'paused', 'completed')),
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((cron_expression IS NULL) <> (interval_seconds IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_schedules_due ON payment_schedules(status, next_run_at);
+
+-- One row per due occurrence: executed (with its payment intent), failed, or skipped.
+CREATE TABLE IF NOT EXISTS schedule_runs (
+    id SERIAL PRIMARY KEY,
+    schedule_id INTEGER NOT NULL REFERENCES payment_schedules(id),
+    scheduled_for TIMESTAMPTZ NOT NULL,
+    status VARCHAR(20) NOT NULL CHECK (status IN ('confirmed', 'failed', 'skipped')),
+    intent_id INTEGER REFERENCES payment_intents(id),
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_schedule_runs_schedule_id ON schedule_runs(schedule_id);
@@ -0,0 +1,48 @@
+-- Maker-checker: payments at or above a threshold wait for approvals from users other than the initiator.
+-- NULL `wallet_id` / `agent` match any wallet / initiator; `token_address` NULL means native ETH.
+CREATE TABLE IF NOT EXISTS approval_thresholds (
+    id SERIAL PRIMARY KEY,
+    wallet_id INTEGER REFERENCES wallets(id),
+    agent VARCHAR(255),
+    token_address VARCHAR(42),
+    threshold_amount VARCHAR(100) NOT NULL,
+    required_approvals INTEGER NOT NULL CHECK (required_approvals > 0),
+    expiry_seconds BIGINT NOT NULL DEFAULT 86400 CHECK (expiry_seconds >= 60),
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE payment_intents
+    ADD COLUMN IF NOT EXISTS required_approvals INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS approval_expires_at TIMESTAMPTZ;
+
+ALTER TABLE payment_intents DROP CONSTRAINT IF EXISTS payment_intents_status_check;
+ALTER TABLE payment_intents ADD CONSTRAINT payment_intents_status_check
+    CHECK (status IN ('created', 'screened', 'pending_approval', 'approved', 'submitted', 'confirmed',
+                      'failed', 'cancelled', 'rejected', 'expired'));
+
+-- One decision per approver and intent.
+CREATE TABLE IF NOT EXISTS payment_approvals (
+    id SERIAL PRIMARY KEY,
+    intent_id INTEGER NOT NULL REFERENCES payment_intents(id),
+    approver VARCHAR(255) NOT NULL,
+    decision VARCHAR(10) NOT NULL CHECK (decision IN ('approve', 'reject')),
+    comment TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (intent_id, approver)
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_intents_approval_expiry ON payment_intents(status, approval_expires_at);
+
+-- Batch items and scheduled runs can now end up waiting for approval.
+ALTER TABLE payout_batches DROP CONSTRAINT IF EXISTS payout_batches_status_check;
+ALTER TABLE payout_batches ADD CONSTRAINT payout_batches_status_check
+    CHECK (status IN ('rejected', 'processing', 'completed', 'partially_failed', 'failed', 'awaiting_approval'));
+
+ALTER TABLE payout_items DROP CONSTRAINT IF EXISTS payout_items_status_check;
+ALTER TABLE payout_items ADD CONSTRAINT payout_items_status_check
+    CHECK (status IN ('rejected', 'pending', 'confirmed', 'failed', 'pending_approval'));
+
+ALTER TABLE schedule_runs DROP CONSTRAINT IF EXISTS schedule_runs_status_check;
+ALTER TABLE schedule_runs ADD CONSTRAINT schedule_runs_status_check
+    CHECK (status IN ('confirmed', 'failed', 'skipped', 'pending_approval'));
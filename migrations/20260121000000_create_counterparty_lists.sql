@@ -0,0 +1,31 @@
+-- Counterparty screening for agent payments.
+-- `block` wallets may pay anyone not blocklisted; `allow_only` wallets may only pay allowlisted addresses.
+ALTER TABLE wallets ADD COLUMN IF NOT EXISTS counterparty_mode VARCHAR(20) NOT NULL DEFAULT 'block'
+    CHECK (counterparty_mode IN ('block', 'allow_only'));
+
+-- NULL `wallet_id` makes an entry global (applies to every wallet).
+CREATE TABLE IF NOT EXISTS counterparty_entries (
+    id SERIAL PRIMARY KEY,
+    wallet_id INTEGER REFERENCES wallets(id),
+    address VARCHAR(42) NOT NULL,
+    list_type VARCHAR(10) NOT NULL CHECK (list_type IN ('allow', 'block')),
+    label VARCHAR(255),
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_counterparty_entries_unique
+    ON counterparty_entries(COALESCE(wallet_id, 0), address, list_type);
+CREATE INDEX IF NOT EXISTS idx_counterparty_entries_address ON counterparty_entries(address);
+
+-- Who changed which compliance setting, and how.
+CREATE TABLE IF NOT EXISTS compliance_audit_log (
+    id SERIAL PRIMARY KEY,
+    actor VARCHAR(255) NOT NULL,
+    action VARCHAR(100) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_compliance_audit_log_subject ON compliance_audit_log(subject);
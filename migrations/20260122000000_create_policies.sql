@@ -0,0 +1,37 @@
+-- Off-chain payment policies. Rules are declarative JSON and every change creates a new version;
+-- NULL `wallet_id` applies the policy to all wallets.
+CREATE TABLE IF NOT EXISTS policies (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    wallet_id INTEGER REFERENCES wallets(id),
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    current_version INTEGER NOT NULL DEFAULT 1,
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS policy_versions (
+    id SERIAL PRIMARY KEY,
+    policy_id INTEGER NOT NULL REFERENCES policies(id),
+    version INTEGER NOT NULL,
+    rules JSONB NOT NULL,
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (policy_id, version)
+);
+
+-- Decision log: the outcome of every rule evaluated for a payment intent.
+CREATE TABLE IF NOT EXISTS policy_decisions (
+    id SERIAL PRIMARY KEY,
+    intent_id INTEGER NOT NULL REFERENCES payment_intents(id),
+    policy_id INTEGER NOT NULL REFERENCES policies(id),
+    policy_version INTEGER NOT NULL,
+    rule_index INTEGER NOT NULL,
+    rule_type VARCHAR(50) NOT NULL,
+    outcome VARCHAR(10) NOT NULL CHECK (outcome IN ('allow', 'deny')),
+    reason TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_policy_decisions_intent_id ON policy_decisions(intent_id);
@@ -0,0 +1,24 @@
+-- Registry of agents: the key an agent acts with, the API user it authenticates as,
+-- the legal entity that owns it and the wallets it may drive.
+CREATE TABLE IF NOT EXISTS agents (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    address VARCHAR(42) NOT NULL UNIQUE,
+    username VARCHAR(255) NOT NULL UNIQUE REFERENCES users(username),
+    entity_id INTEGER NOT NULL REFERENCES legal_entities(id),
+    status VARCHAR(20) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'suspended')),
+    status_reason TEXT,
+    per_tx_limit_eth VARCHAR(100),
+    daily_limit_eth VARCHAR(100),
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS agent_wallets (
+    agent_id INTEGER NOT NULL REFERENCES agents(id),
+    wallet_id INTEGER NOT NULL REFERENCES wallets(id),
+    granted_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (agent_id, wallet_id)
+);
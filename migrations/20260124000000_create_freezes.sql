@@ -0,0 +1,32 @@
+-- Emergency kill switch. An active freeze (not lifted) blocks payments in its scope;
+-- `target_id` is an agent, wallet or entity id and NULL for the global scope.
+CREATE TABLE IF NOT EXISTS freezes (
+    id SERIAL PRIMARY KEY,
+    scope VARCHAR(10) NOT NULL CHECK (scope IN ('agent', 'wallet', 'entity', 'global')),
+    target_id INTEGER,
+    reason TEXT NOT NULL,
+    frozen_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    lifted_by VARCHAR(255),
+    lifted_at TIMESTAMPTZ,
+    lift_reason TEXT,
+    CHECK ((scope = 'global') = (target_id IS NULL))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_freezes_active
+    ON freezes(scope, COALESCE(target_id, 0)) WHERE lifted_at IS NULL;
+
+-- On-chain side effects of freezing and unfreezing, with the limit to restore.
+CREATE TABLE IF NOT EXISTS freeze_actions (
+    id SERIAL PRIMARY KEY,
+    freeze_id INTEGER NOT NULL REFERENCES freezes(id),
+    action VARCHAR(20) NOT NULL CHECK (action IN ('zero_limit', 'pause', 'restore_limit', 'unpause')),
+    wallet_id INTEGER NOT NULL REFERENCES wallets(id),
+    agent_address VARCHAR(42),
+    previous_limit VARCHAR(78),
+    tx_hash VARCHAR(66),
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_freeze_actions_freeze_id ON freeze_actions(freeze_id);
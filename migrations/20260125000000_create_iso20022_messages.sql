@@ -0,0 +1,28 @@
+-- Confirmed transfers from the backend signer into wallets (POST /api/finance/fund).
+CREATE TABLE IF NOT EXISTS wallet_fundings (
+    id SERIAL PRIMARY KEY,
+    wallet_id INTEGER NOT NULL REFERENCES wallets(id),
+    amount VARCHAR(100) NOT NULL,
+    tx_hash VARCHAR(66) NOT NULL UNIQUE,
+    funded_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_fundings_wallet_id ON wallet_fundings(wallet_id);
+
+-- Generated ISO 20022 messages, kept with the agent payment or funding they describe.
+CREATE TABLE IF NOT EXISTS iso20022_messages (
+    id SERIAL PRIMARY KEY,
+    message_type VARCHAR(20) NOT NULL,
+    msg_id VARCHAR(35) NOT NULL UNIQUE,
+    intent_id INTEGER REFERENCES payment_intents(id),
+    funding_id INTEGER REFERENCES wallet_fundings(id),
+    document TEXT NOT NULL,
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((intent_id IS NULL) <> (funding_id IS NULL))
+);
+
+-- One message of each type per transaction.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_iso20022_messages_intent ON iso20022_messages(message_type, intent_id) WHERE intent_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_iso20022_messages_funding ON iso20022_messages(message_type, funding_id) WHERE funding_id IS NOT NULL;
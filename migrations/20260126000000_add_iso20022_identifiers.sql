@@ -0,0 +1,9 @@
+-- Identifiers carried in ISO 20022 messages. `bic` is the BIC of the bank servicing the entity's account.
+ALTER TABLE legal_entities
+    ADD COLUMN IF NOT EXISTS legal_name VARCHAR(140),
+    ADD COLUMN IF NOT EXISTS lei VARCHAR(20),
+    ADD COLUMN IF NOT EXISTS bic VARCHAR(11),
+    ADD COLUMN IF NOT EXISTS iban VARCHAR(34);
+
+-- ISO 4217 currency reported for a token (e.g. USD for a USD stablecoin); defaults to the symbol when it is a valid code.
+ALTER TABLE tokens ADD COLUMN IF NOT EXISTS iso_currency VARCHAR(3);
@@ -0,0 +1,44 @@
+-- Received ISO 20022 messages (pain.001 initiations, pacs.008 transfers), kept as sent.
+CREATE TABLE IF NOT EXISTS iso20022_inbound_messages (
+    id SERIAL PRIMARY KEY,
+    message_type VARCHAR(20) NOT NULL,
+    msg_id VARCHAR(35) NOT NULL,
+    document TEXT NOT NULL,
+    received_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (message_type, msg_id)
+);
+
+-- Funds a bank reports as credited to one of our wallets; `amount` is in whole units of `currency`.
+CREATE TABLE IF NOT EXISTS incoming_funds (
+    id SERIAL PRIMARY KEY,
+    wallet_id INTEGER NOT NULL REFERENCES wallets(id),
+    amount VARCHAR(100) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    token_address VARCHAR(42),
+    debtor_name TEXT,
+    debtor_entity_id INTEGER REFERENCES legal_entities(id),
+    end_to_end_id VARCHAR(35) NOT NULL,
+    uetr VARCHAR(36),
+    settlement_date DATE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_incoming_funds_wallet_id ON incoming_funds(wallet_id);
+
+-- Each credit transfer of an inbound message and what it became.
+CREATE TABLE IF NOT EXISTS iso20022_inbound_items (
+    id SERIAL PRIMARY KEY,
+    message_id INTEGER NOT NULL REFERENCES iso20022_inbound_messages(id),
+    payment_info_id VARCHAR(35),
+    end_to_end_id VARCHAR(35) NOT NULL,
+    amount VARCHAR(100) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    status VARCHAR(20) NOT NULL CHECK (status IN ('intent_created', 'funds_recorded', 'rejected')),
+    intent_id INTEGER REFERENCES payment_intents(id),
+    incoming_funds_id INTEGER REFERENCES incoming_funds(id),
+    rejection_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_iso20022_inbound_items_message_id ON iso20022_inbound_items(message_id);
@@ -0,0 +1,17 @@
+-- pacs.002 status reports: about an agent payment or about a whole inbound message.
+ALTER TABLE iso20022_messages ADD COLUMN IF NOT EXISTS inbound_message_id INTEGER REFERENCES iso20022_inbound_messages(id);
+-- MsgId of the message a status report answers.
+ALTER TABLE iso20022_messages ADD COLUMN IF NOT EXISTS original_msg_id VARCHAR(35);
+-- Transaction statuses a report carries ("ACSC", "PDNG,RJCT", ...).
+ALTER TABLE iso20022_messages ADD COLUMN IF NOT EXISTS status_codes VARCHAR(255);
+
+ALTER TABLE iso20022_messages DROP CONSTRAINT IF EXISTS iso20022_messages_check;
+ALTER TABLE iso20022_messages ADD CONSTRAINT iso20022_messages_subject_check
+    CHECK (num_nonnulls(intent_id, funding_id, inbound_message_id) = 1);
+
+-- A new report is stored whenever the statuses change; earlier ones stay on file.
+DROP INDEX IF EXISTS idx_iso20022_messages_intent;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_iso20022_messages_intent
+    ON iso20022_messages(message_type, intent_id, COALESCE(status_codes, '')) WHERE intent_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_iso20022_messages_inbound
+    ON iso20022_messages(message_type, inbound_message_id, status_codes) WHERE inbound_message_id IS NOT NULL;
@@ -0,0 +1,45 @@
+-- Balance changes of registered wallets, indexed from AegisWallet and ERC-20 logs (finance/indexer.rs).
+-- `token_address` is NULL for the native asset; `amount` is in base units.
+CREATE TABLE IF NOT EXISTS wallet_movements (
+    id SERIAL PRIMARY KEY,
+    wallet_id INTEGER NOT NULL REFERENCES wallets(id),
+    chain_id BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    block_time TIMESTAMPTZ NOT NULL,
+    tx_hash VARCHAR(66) NOT NULL,
+    log_index INTEGER NOT NULL,
+    event VARCHAR(16) NOT NULL CHECK (event IN ('Received', 'Executed', 'Transfer')),
+    token_address VARCHAR(42),
+    direction VARCHAR(6) NOT NULL CHECK (direction IN ('credit', 'debit')),
+    amount VARCHAR(78) NOT NULL,
+    counterparty VARCHAR(42) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- A token transfer between two of our wallets is one log and two movements
+    UNIQUE (chain_id, tx_hash, log_index, wallet_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_movements_wallet_time ON wallet_movements(wallet_id, block_time);
+
+-- Last block indexed per chain, and when the indexer last caught up with the chain head.
+CREATE TABLE IF NOT EXISTS chain_cursors (
+    chain_id BIGINT PRIMARY KEY,
+    last_block BIGINT NOT NULL,
+    synced_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- camt.053 statements (one per wallet and day) and camt.054 notifications (one per movement).
+ALTER TABLE iso20022_messages ADD COLUMN IF NOT EXISTS wallet_id INTEGER REFERENCES wallets(id);
+ALTER TABLE iso20022_messages ADD COLUMN IF NOT EXISTS statement_date DATE;
+ALTER TABLE iso20022_messages ADD COLUMN IF NOT EXISTS movement_id INTEGER REFERENCES wallet_movements(id);
+
+ALTER TABLE iso20022_messages DROP CONSTRAINT IF EXISTS iso20022_messages_subject_check;
+ALTER TABLE iso20022_messages ADD CONSTRAINT iso20022_messages_subject_check
+    CHECK (num_nonnulls(intent_id, funding_id, inbound_message_id, wallet_id, movement_id) = 1);
+ALTER TABLE iso20022_messages ADD CONSTRAINT iso20022_messages_statement_date_check
+    CHECK ((wallet_id IS NULL) = (statement_date IS NULL));
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_iso20022_messages_statement
+    ON iso20022_messages(message_type, wallet_id, statement_date) WHERE wallet_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_iso20022_messages_movement
+    ON iso20022_messages(message_type, movement_id) WHERE movement_id IS NOT NULL;
@@ -0,0 +1,64 @@
+-- KYC verification cases (compliance/kyc.rs). An entity's `kyc_level` is only ever set by approving a case.
+CREATE TABLE IF NOT EXISTS kyc_cases (
+    id SERIAL PRIMARY KEY,
+    entity_id INTEGER NOT NULL REFERENCES legal_entities(id),
+    requested_level SMALLINT NOT NULL CHECK (requested_level > 0),
+    status VARCHAR(16) NOT NULL DEFAULT 'submitted'
+        CHECK (status IN ('submitted', 'in_review', 'approved', 'rejected', 'expired')),
+    submitted_by VARCHAR(255) NOT NULL,
+    assigned_to VARCHAR(255),
+    decided_by VARCHAR(255),
+    decided_at TIMESTAMPTZ,
+    decision_reason TEXT,
+    -- When an approved verification lapses and must be renewed
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One open case per entity at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_kyc_cases_open ON kyc_cases(entity_id) WHERE status IN ('submitted', 'in_review');
+CREATE INDEX IF NOT EXISTS idx_kyc_cases_assignee ON kyc_cases(assigned_to, status);
+
+CREATE TABLE IF NOT EXISTS kyc_case_events (
+    id SERIAL PRIMARY KEY,
+    case_id INTEGER NOT NULL REFERENCES kyc_cases(id),
+    from_status VARCHAR(16) NOT NULL,
+    to_status VARCHAR(16) NOT NULL,
+    actor VARCHAR(255) NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Uploaded evidence. The file itself is AES-256-GCM encrypted on local disk under `storage_key`;
+-- `content_hash` (keccak256) is over the plaintext so a decrypted download can be checked against the upload.
+CREATE TABLE IF NOT EXISTS kyc_documents (
+    id SERIAL PRIMARY KEY,
+    case_id INTEGER NOT NULL REFERENCES kyc_cases(id),
+    document_type VARCHAR(32) NOT NULL
+        CHECK (document_type IN ('certificate_of_incorporation', 'register_extract', 'proof_of_address', 'ubo_declaration', 'identity_document', 'other')),
+    file_name VARCHAR(255) NOT NULL,
+    content_type VARCHAR(255) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    content_hash VARCHAR(66) NOT NULL,
+    storage_key VARCHAR(64) NOT NULL UNIQUE,
+    uploaded_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_kyc_documents_case ON kyc_documents(case_id);
+
+CREATE TABLE IF NOT EXISTS kyc_case_notes (
+    id SERIAL PRIMARY KEY,
+    case_id INTEGER NOT NULL REFERENCES kyc_cases(id),
+    author VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_kyc_case_notes_case ON kyc_case_notes(case_id);
+
+-- The approved case behind the entity's current level; NULL for entities registered before cases existed
+ALTER TABLE legal_entities
+    ADD COLUMN IF NOT EXISTS kyc_case_id INTEGER REFERENCES kyc_cases(id),
+    ADD COLUMN IF NOT EXISTS kyc_verified_at TIMESTAMPTZ;
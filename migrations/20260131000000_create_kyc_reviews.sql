@@ -0,0 +1,59 @@
+-- How long a KYC verification holds before it must be renewed (compliance/reverification.rs).
+-- NULL `jurisdiction` or `kyc_level` matches any; the most specific period applies.
+CREATE TABLE IF NOT EXISTS kyc_review_periods (
+    id SERIAL PRIMARY KEY,
+    jurisdiction VARCHAR(100),
+    kyc_level SMALLINT CHECK (kyc_level > 0),
+    review_days INTEGER NOT NULL CHECK (review_days > 0),
+    -- Entities are flagged and compliance notified this many days before the deadline
+    warning_days INTEGER NOT NULL CHECK (warning_days >= 0),
+    on_lapse VARCHAR(10) NOT NULL CHECK (on_lapse IN ('downgrade', 'suspend')),
+    downgrade_to SMALLINT CHECK (downgrade_to >= 0),
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((on_lapse = 'downgrade') = (downgrade_to IS NOT NULL)),
+    CHECK (kyc_level IS NULL OR downgrade_to IS NULL OR downgrade_to < kyc_level),
+    UNIQUE NULLS NOT DISTINCT (jurisdiction, kyc_level)
+);
+
+-- Where an entity stands in the review cycle. `expiring` entities are past their warning date;
+-- `downgraded` and `suspended` ones missed the deadline and need a new KYC case.
+ALTER TABLE legal_entities
+    ADD COLUMN IF NOT EXISTS kyc_status VARCHAR(16) NOT NULL DEFAULT 'unverified'
+        CHECK (kyc_status IN ('unverified', 'verified', 'expiring', 'downgraded', 'suspended'));
+
+-- Entities verified before KYC cases existed count from their registration
+UPDATE legal_entities
+SET kyc_status = 'verified', kyc_verified_at = COALESCE(kyc_verified_at, created_at)
+WHERE kyc_level > 0 AND kyc_status = 'unverified';
+
+-- What the review job did to an entity, including the on-chain side effects of a lapse.
+CREATE TABLE IF NOT EXISTS kyc_review_actions (
+    id SERIAL PRIMARY KEY,
+    entity_id INTEGER NOT NULL REFERENCES legal_entities(id),
+    action VARCHAR(20) NOT NULL CHECK (action IN ('flagged', 'downgraded', 'suspended', 'identity_revoked', 'frozen')),
+    due_at TIMESTAMPTZ NOT NULL,
+    kyc_level_before SMALLINT,
+    kyc_level_after SMALLINT,
+    token_id VARCHAR(255),
+    freeze_id INTEGER REFERENCES freezes(id),
+    tx_hash VARCHAR(66),
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_kyc_review_actions_entity ON kyc_review_actions(entity_id);
+
+-- Items raised for the compliance team by background jobs, until someone acknowledges them.
+CREATE TABLE IF NOT EXISTS compliance_notifications (
+    id SERIAL PRIMARY KEY,
+    kind VARCHAR(50) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    message TEXT NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    acknowledged_by VARCHAR(255),
+    acknowledged_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_compliance_notifications_open ON compliance_notifications(created_at) WHERE acknowledged_at IS NULL;
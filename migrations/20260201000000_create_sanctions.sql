@@ -0,0 +1,83 @@
+-- Imported versions of sanctions lists (compliance/sanctions). Only the latest import of a source is active;
+-- earlier ones are kept so past hits still point at the entry they matched.
+CREATE TABLE IF NOT EXISTS sanctions_lists (
+    id SERIAL PRIMARY KEY,
+    source VARCHAR(20) NOT NULL CHECK (source IN ('ofac_sdn', 'eu_consolidated')),
+    format VARCHAR(3) NOT NULL CHECK (format IN ('xml', 'csv')),
+    version VARCHAR(100) NOT NULL,
+    content_hash VARCHAR(66) NOT NULL,
+    entry_count INTEGER NOT NULL,
+    address_count INTEGER NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    imported_by VARCHAR(255) NOT NULL,
+    imported_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Set once the population has been re-screened against this version
+    screened_at TIMESTAMPTZ,
+    UNIQUE (source, content_hash)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_sanctions_lists_active ON sanctions_lists(source) WHERE active;
+
+CREATE TABLE IF NOT EXISTS sanctions_entries (
+    id SERIAL PRIMARY KEY,
+    list_id INTEGER NOT NULL REFERENCES sanctions_lists(id),
+    -- OFAC ent_num / uid, EU logicalId
+    source_uid VARCHAR(50) NOT NULL,
+    entry_type VARCHAR(20) NOT NULL CHECK (entry_type IN ('individual', 'entity', 'vessel', 'aircraft')),
+    name TEXT NOT NULL,
+    programs TEXT[] NOT NULL DEFAULT '{}',
+    UNIQUE (list_id, source_uid)
+);
+
+-- Primary names and aliases. `keys` are the blocking keys fuzzy matching looks candidates up by.
+CREATE TABLE IF NOT EXISTS sanctions_names (
+    id SERIAL PRIMARY KEY,
+    entry_id INTEGER NOT NULL REFERENCES sanctions_entries(id),
+    name TEXT NOT NULL,
+    normalized TEXT NOT NULL,
+    keys TEXT[] NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sanctions_names_entry ON sanctions_names(entry_id);
+CREATE INDEX IF NOT EXISTS idx_sanctions_names_keys ON sanctions_names USING GIN (keys);
+
+-- Digital currency addresses listed for an entry; hex and bech32 addresses are stored lowercase.
+CREATE TABLE IF NOT EXISTS sanctions_addresses (
+    id SERIAL PRIMARY KEY,
+    entry_id INTEGER NOT NULL REFERENCES sanctions_entries(id),
+    currency VARCHAR(10) NOT NULL,
+    address VARCHAR(128) NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sanctions_addresses_address ON sanctions_addresses(address);
+
+-- A subject (entity name or address) matching a list entry. One row per subject and listed party, across list
+-- versions, so a dismissed false positive stays dismissed when the next version still contains the entry.
+CREATE TABLE IF NOT EXISTS sanctions_hits (
+    id SERIAL PRIMARY KEY,
+    subject_type VARCHAR(10) NOT NULL CHECK (subject_type IN ('entity', 'address')),
+    subject VARCHAR(128) NOT NULL,
+    -- The entity screened, or the owner of a screened wallet address
+    entity_id INTEGER REFERENCES legal_entities(id),
+    source VARCHAR(20) NOT NULL,
+    source_uid VARCHAR(50) NOT NULL,
+    list_id INTEGER NOT NULL REFERENCES sanctions_lists(id),
+    entry_id INTEGER NOT NULL REFERENCES sanctions_entries(id),
+    listed_name TEXT NOT NULL,
+    match_type VARCHAR(10) NOT NULL CHECK (match_type IN ('address', 'name')),
+    matched_value TEXT NOT NULL,
+    score DOUBLE PRECISION NOT NULL,
+    context VARCHAR(20) NOT NULL CHECK (context IN ('registration', 'payment', 'rescreen')),
+    -- `withdrawn`: the entry left the list before anyone reviewed the hit
+    status VARCHAR(10) NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'confirmed', 'dismissed', 'withdrawn')),
+    reviewed_by VARCHAR(255),
+    reviewed_at TIMESTAMPTZ,
+    review_note TEXT,
+    freeze_id INTEGER REFERENCES freezes(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (subject_type, subject, source, source_uid)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sanctions_hits_entity ON sanctions_hits(entity_id) WHERE status IN ('open', 'confirmed');
+CREATE INDEX IF NOT EXISTS idx_sanctions_hits_open ON sanctions_hits(created_at) WHERE status = 'open';
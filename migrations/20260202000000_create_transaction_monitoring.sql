@@ -0,0 +1,72 @@
+-- Detection scenarios of the transaction monitoring engine (compliance/monitoring). `rule` is a tagged JSON
+-- object whose `type` is the scenario kind; NULL `wallet_id` monitors all wallets.
+CREATE TABLE IF NOT EXISTS monitoring_scenarios (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    kind VARCHAR(30) NOT NULL CHECK (kind IN ('structuring', 'rapid_in_out', 'fan_out', 'dormant_reactivation')),
+    rule JSONB NOT NULL,
+    wallet_id INTEGER REFERENCES wallets(id),
+    severity VARCHAR(10) NOT NULL CHECK (severity IN ('low', 'medium', 'high')),
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Alerts as a triage workflow: open -> investigating -> escalated | dismissed | resolved.
+CREATE TABLE IF NOT EXISTS monitoring_alerts (
+    id SERIAL PRIMARY KEY,
+    scenario_id INTEGER NOT NULL REFERENCES monitoring_scenarios(id),
+    kind VARCHAR(30) NOT NULL,
+    wallet_id INTEGER NOT NULL REFERENCES wallets(id),
+    entity_id INTEGER NOT NULL REFERENCES legal_entities(id),
+    severity VARCHAR(10) NOT NULL,
+    status VARCHAR(15) NOT NULL DEFAULT 'open'
+        CHECK (status IN ('open', 'investigating', 'escalated', 'dismissed', 'resolved')),
+    summary TEXT NOT NULL,
+    -- The scenario as evaluated, its metrics and the flows that triggered it
+    evidence JSONB NOT NULL,
+    assigned_to VARCHAR(255),
+    closed_by VARCHAR(255),
+    closed_at TIMESTAMPTZ,
+    close_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_monitoring_alerts_status ON monitoring_alerts(status, created_at);
+CREATE INDEX IF NOT EXISTS idx_monitoring_alerts_wallet ON monitoring_alerts(wallet_id);
+
+-- The payments and movements behind each alert. A flow triggers a scenario at most once,
+-- so repeated runs over the same period never raise the same alert twice.
+CREATE TABLE IF NOT EXISTS monitoring_alert_flows (
+    alert_id INTEGER NOT NULL REFERENCES monitoring_alerts(id),
+    scenario_id INTEGER NOT NULL REFERENCES monitoring_scenarios(id),
+    flow_type VARCHAR(10) NOT NULL CHECK (flow_type IN ('intent', 'movement')),
+    flow_id INTEGER NOT NULL,
+    UNIQUE (scenario_id, flow_type, flow_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_monitoring_alert_flows_alert ON monitoring_alert_flows(alert_id);
+
+CREATE TABLE IF NOT EXISTS monitoring_alert_events (
+    id SERIAL PRIMARY KEY,
+    alert_id INTEGER NOT NULL REFERENCES monitoring_alerts(id),
+    from_status VARCHAR(15),
+    to_status VARCHAR(15) NOT NULL,
+    actor VARCHAR(255) NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_monitoring_alert_events_alert ON monitoring_alert_events(alert_id);
+
+CREATE TABLE IF NOT EXISTS monitoring_alert_notes (
+    id SERIAL PRIMARY KEY,
+    alert_id INTEGER NOT NULL REFERENCES monitoring_alerts(id),
+    author VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_monitoring_alert_notes_alert ON monitoring_alert_notes(alert_id);
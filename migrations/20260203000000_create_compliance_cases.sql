@@ -0,0 +1,81 @@
+-- Compliance investigations (compliance/cases): open -> investigating -> escalated -> reported, closable at
+-- any point with a reason. `due_at` is the SLA deadline for reaching a decision or filing.
+CREATE TABLE IF NOT EXISTS compliance_cases (
+    id SERIAL PRIMARY KEY,
+    title VARCHAR(255) NOT NULL,
+    priority VARCHAR(10) NOT NULL CHECK (priority IN ('low', 'medium', 'high', 'critical')),
+    status VARCHAR(15) NOT NULL DEFAULT 'open'
+        CHECK (status IN ('open', 'investigating', 'escalated', 'reported', 'closed')),
+    -- Free-text account of the activity, carried into the SAR/STR draft
+    narrative TEXT,
+    opened_by VARCHAR(255) NOT NULL,
+    assigned_to VARCHAR(255),
+    due_at TIMESTAMPTZ NOT NULL,
+    sla_warned_at TIMESTAMPTZ,
+    sla_breached_at TIMESTAMPTZ,
+    -- Reference the regulator or FIU gave the filed report
+    filing_reference VARCHAR(255),
+    filed_by VARCHAR(255),
+    filed_at TIMESTAMPTZ,
+    closed_by VARCHAR(255),
+    closed_at TIMESTAMPTZ,
+    close_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_compliance_cases_status ON compliance_cases(status, due_at);
+CREATE INDEX IF NOT EXISTS idx_compliance_cases_assignee ON compliance_cases(assigned_to, status);
+
+-- What a case is about: entities, wallets, payments, movements, and the alerts, screening hits and
+-- KYC cases that raised the concern. `link_id` points into the table named by `link_type`.
+CREATE TABLE IF NOT EXISTS compliance_case_links (
+    id SERIAL PRIMARY KEY,
+    case_id INTEGER NOT NULL REFERENCES compliance_cases(id),
+    link_type VARCHAR(20) NOT NULL
+        CHECK (link_type IN ('entity', 'wallet', 'intent', 'movement', 'monitoring_alert', 'sanctions_hit', 'kyc_case')),
+    link_id INTEGER NOT NULL,
+    note TEXT,
+    added_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (case_id, link_type, link_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_compliance_case_links_target ON compliance_case_links(link_type, link_id);
+
+CREATE TABLE IF NOT EXISTS compliance_case_events (
+    id SERIAL PRIMARY KEY,
+    case_id INTEGER NOT NULL REFERENCES compliance_cases(id),
+    from_status VARCHAR(15),
+    to_status VARCHAR(15) NOT NULL,
+    actor VARCHAR(255) NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_compliance_case_events_case ON compliance_case_events(case_id);
+
+CREATE TABLE IF NOT EXISTS compliance_case_comments (
+    id SERIAL PRIMARY KEY,
+    case_id INTEGER NOT NULL REFERENCES compliance_cases(id),
+    author VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_compliance_case_comments_case ON compliance_case_comments(case_id);
+
+-- Supporting files, encrypted in the same document store as KYC evidence (see kyc_documents).
+CREATE TABLE IF NOT EXISTS compliance_case_attachments (
+    id SERIAL PRIMARY KEY,
+    case_id INTEGER NOT NULL REFERENCES compliance_cases(id),
+    file_name VARCHAR(255) NOT NULL,
+    content_type VARCHAR(255) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    content_hash VARCHAR(66) NOT NULL,
+    storage_key VARCHAR(64) NOT NULL UNIQUE,
+    uploaded_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_compliance_case_attachments_case ON compliance_case_attachments(case_id);
@@ -0,0 +1,7 @@
+-- Payout items follow their payment intent after the batch run: a payment sent without a receipt yet is
+-- `submitted`, and items held for approval settle once the intent does.
+ALTER TABLE payout_items DROP CONSTRAINT IF EXISTS payout_items_status_check;
+ALTER TABLE payout_items ADD CONSTRAINT payout_items_status_check
+    CHECK (status IN ('rejected', 'pending', 'confirmed', 'failed', 'pending_approval', 'submitted'));
+
+CREATE INDEX IF NOT EXISTS idx_payout_items_intent_id ON payout_items(intent_id);
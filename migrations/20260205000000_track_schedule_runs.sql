@@ -0,0 +1,7 @@
+-- Schedule runs follow their payment intent after the run, like payout items: `submitted` while the
+-- receipt is outstanding, and runs held for approval settle once the intent does.
+ALTER TABLE schedule_runs DROP CONSTRAINT IF EXISTS schedule_runs_status_check;
+ALTER TABLE schedule_runs ADD CONSTRAINT schedule_runs_status_check
+    CHECK (status IN ('confirmed', 'failed', 'skipped', 'pending_approval', 'submitted'));
+
+CREATE INDEX IF NOT EXISTS idx_schedule_runs_intent_id ON schedule_runs(intent_id);
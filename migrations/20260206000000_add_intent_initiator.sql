@@ -0,0 +1,13 @@
+-- The person a payment is made on behalf of. Equal to `created_by` except for intents raised by a
+-- payment schedule, where `created_by` names the schedule and `initiated_by` the user who set it up.
+-- Self-approval, approval thresholds and agent checks go by the initiator.
+ALTER TABLE payment_intents ADD COLUMN IF NOT EXISTS initiated_by TEXT;
+
+UPDATE payment_intents i
+SET initiated_by = COALESCE(
+    (SELECT s.created_by FROM payment_schedules s WHERE i.created_by = 'schedule:' || s.id),
+    i.created_by
+)
+WHERE initiated_by IS NULL;
+
+ALTER TABLE payment_intents ALTER COLUMN initiated_by SET NOT NULL;
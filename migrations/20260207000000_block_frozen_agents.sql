@@ -0,0 +1,5 @@
+-- Freezes block agents on `AegisRules` (which overrides token and value limits) instead of zeroing
+-- the legacy daily limit. `zero_limit` / `restore_limit` remain for freezes recorded before.
+ALTER TABLE freeze_actions DROP CONSTRAINT IF EXISTS freeze_actions_action_check;
+ALTER TABLE freeze_actions ADD CONSTRAINT freeze_actions_action_check
+    CHECK (action IN ('zero_limit', 'pause', 'restore_limit', 'unpause', 'block', 'unblock'));
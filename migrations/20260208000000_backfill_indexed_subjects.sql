@@ -0,0 +1,5 @@
+-- The indexer keeps one cursor per chain, so wallets and tokens registered after it passed their history
+-- are backfilled separately (finance/indexer.rs). Existing rows start un-backfilled so movements missed
+-- before this migration are picked up once; movements already recorded are skipped.
+ALTER TABLE wallets ADD COLUMN IF NOT EXISTS backfilled BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE tokens ADD COLUMN IF NOT EXISTS backfilled BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,8 @@
+-- AegisIDs burned by the KYC review job (compliance/reverification.rs), so a lapse revokes every
+-- identity minted for the entity and failed revocations can be retried.
+ALTER TABLE identity_metadata ADD COLUMN IF NOT EXISTS revoked_at TIMESTAMPTZ;
+
+UPDATE identity_metadata m
+SET revoked_at = a.created_at
+FROM kyc_review_actions a
+WHERE a.action = 'identity_revoked' AND a.tx_hash IS NOT NULL AND a.token_id = m.token_id AND m.revoked_at IS NULL;
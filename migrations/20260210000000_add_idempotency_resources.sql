@@ -0,0 +1,3 @@
+-- What a request made with an Idempotency-Key created (`kind:id`), recorded as soon as it exists so a retry
+-- after the in-progress lease reports it instead of creating another (idempotency.rs).
+ALTER TABLE idempotency_keys ADD COLUMN IF NOT EXISTS resource VARCHAR(255);
@@ -0,0 +1,4 @@
+-- A wallet is recorded as soon as its contract is deployed and stays `incomplete` until its rules contract
+-- is linked, so a failed link leaves a known wallet to retry (PUT /wallets/:id/rules) rather than a lost one.
+ALTER TABLE wallets ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'active'
+    CHECK (status IN ('incomplete', 'active'));
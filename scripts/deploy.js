@@ -9,6 +9,25 @@ async function main() {
 
     const address = await aegisId.getAddress();
     console.log(`>> Success! AegisID deployed at: ${address}`);
+
+    // AegisWallets are deployed by the backend (POST /api/finance/wallets) and linked to these rules.
+    const AegisRules = await hre.ethers.getContractFactory("AegisRules");
+    const rules = await AegisRules.deploy();
+    await rules.waitForDeployment();
+    console.log(`>> Success! AegisRules deployed at: ${await rules.getAddress()}`);
+
+    // Prices for value-denominated limits (AegisRules.setPriceOracle); swap for a market feed in production.
+    const StaticPriceOracle = await hre.ethers.getContractFactory("StaticPriceOracle");
+    const oracle = await StaticPriceOracle.deploy();
+    await oracle.waitForDeployment();
+    await rules.setPriceOracle(await oracle.getAddress());
+    console.log(`>> Success! StaticPriceOracle deployed at: ${await oracle.getAddress()}`);
+
+    // Local nodes lack the canonical Multicall3; set MULTICALL_ADDRESS for the backend's batched reads.
+    const Multicall3 = await hre.ethers.getContractFactory("Multicall3");
+    const multicall = await Multicall3.deploy();
+    await multicall.waitForDeployment();
+    console.log(`>> Success! Multicall3 deployed at: ${await multicall.getAddress()}`);
     // Design Note: We might need to auto-verify on Etherscan in prod.
 }
 
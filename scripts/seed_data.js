@@ -19,8 +19,7 @@ async function seedData() {
 
         const entityRes = await axios.post('http://localhost:8080/api/compliance/register', {
             hash_id: "US_DEL_559922",
-            jurisdiction: "Delaware, US",
-            kyc_level: 3
+            jurisdiction: "Delaware, US"
         }, config);
 
         console.log("Entity Registered!", entityRes.data);
@@ -0,0 +1,212 @@
+use axum::http::StatusCode;
+use ethers::types::U256;
+use sqlx::PgPool;
+use crate::auth::{models::Claims, require_role};
+use crate::state::AppState;
+use crate::finance::registry::find_allowed_token;
+use crate::finance::amounts::{parse_amount, NATIVE_DECIMALS};
+use crate::governance::models::ApprovalThreshold;
+use super::intents::{self, IntentStatus, SYSTEM_ACTOR};
+use super::models::{PaymentApproval, PaymentIntent};
+
+/// Roles whose users may approve or reject payments (admins included).
+pub const APPROVER_ROLES: &[&str] = &["approver", "admin"];
+
+/// How often stale approval requests are expired.
+const EXPIRY_SWEEP_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Approve,
+    Reject,
+}
+
+impl Decision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Decision::Approve => "approve",
+            Decision::Reject => "reject",
+        }
+    }
+}
+
+/// The strictest threshold (most approvals) that `amount` reaches for this intent, if any.
+pub async fn matching_threshold(
+    state: &AppState,
+    intent: &PaymentIntent,
+    amount: U256,
+) -> Result<Option<ApprovalThreshold>, (StatusCode, String)> {
+    let thresholds = sqlx::query_as::<_, ApprovalThreshold>(
+        r#"
+        SELECT * FROM approval_thresholds
+        WHERE (wallet_id IS NULL OR wallet_id = $1)
+          AND (agent IS NULL OR agent = $2)
+          AND token_address IS NOT DISTINCT FROM $3
+        "#
+    )
+    .bind(intent.wallet_id)
+    .bind(&intent.initiated_by)
+    .bind(&intent.token_address)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if thresholds.is_empty() {
+        return Ok(None);
+    }
+
+    let decimals = match &intent.token_address {
+        Some(token_addr) => find_allowed_token(&state.pool, token_addr, state.chain.chain_id()).await?.decimals as u8,
+        None => NATIVE_DECIMALS,
+    };
+
+    let mut matched: Option<ApprovalThreshold> = None;
+    for threshold in thresholds {
+        let limit = parse_amount(&threshold.threshold_amount, decimals)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Approval threshold {}: {}", threshold.id, e)))?;
+        if amount >= limit && matched.as_ref().is_none_or(|m| threshold.required_approvals > m.required_approvals) {
+            matched = Some(threshold);
+        }
+    }
+
+    Ok(matched)
+}
+
+/// Parks a screened intent in `pending_approval` until enough approvers sign off or the request expires.
+pub async fn request_approval(
+    pool: &PgPool,
+    intent: PaymentIntent,
+    threshold: &ApprovalThreshold,
+) -> Result<PaymentIntent, (StatusCode, String)> {
+    sqlx::query(
+        r#"
+        UPDATE payment_intents
+        SET required_approvals = $1, approval_expires_at = NOW() + make_interval(secs => $2)
+        WHERE id = $3 AND status = 'screened'
+        "#
+    )
+    .bind(threshold.required_approvals)
+    .bind(threshold.expiry_seconds as f64)
+    .bind(intent.id)
+    .execute(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let note = format!("Requires {} approval(s) under threshold {}", threshold.required_approvals, threshold.id);
+    intents::transition(pool, intent.id, IntentStatus::Screened, IntentStatus::PendingApproval, SYSTEM_ACTOR, Some(&note), None).await
+}
+
+pub async fn intent_approvals(pool: &PgPool, intent_id: i32) -> Result<Vec<PaymentApproval>, (StatusCode, String)> {
+    sqlx::query_as::<_, PaymentApproval>("SELECT * FROM payment_approvals WHERE intent_id = $1 ORDER BY id")
+        .bind(intent_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Approvers (and admins) other than the payment's initiator may decide on it.
+fn check_approver(claims: &Claims, intent: &PaymentIntent) -> Result<(), (StatusCode, String)> {
+    require_role(claims, APPROVER_ROLES)?;
+    if intent.initiated_by == claims.sub {
+        return Err((StatusCode::FORBIDDEN, "The initiator cannot approve their own payment".to_string()));
+    }
+    Ok(())
+}
+
+/// Releases an intent held for review (`screened`) for submission. The same people who may approve
+/// payments may release them.
+pub async fn release(state: &AppState, intent: PaymentIntent, claims: &Claims) -> Result<PaymentIntent, (StatusCode, String)> {
+    check_approver(claims, &intent)?;
+    intents::approve_and_submit(state, intent, &claims.sub).await
+}
+
+/// Records an approver's decision. A rejection ends the intent; the approval that completes
+/// the quorum moves it to `approved` and submits it.
+pub async fn decide(
+    state: &AppState,
+    intent: PaymentIntent,
+    claims: &Claims,
+    decision: Decision,
+    comment: Option<&str>,
+) -> Result<PaymentIntent, (StatusCode, String)> {
+    check_approver(claims, &intent)?;
+    let approver = claims.sub.as_str();
+    if IntentStatus::parse(&intent.status) != Some(IntentStatus::PendingApproval) {
+        return Err((StatusCode::CONFLICT, format!("Payment intent {} is {}", intent.id, intent.status)));
+    }
+    if intent.approval_expires_at.is_some_and(|at| at <= chrono::Utc::now()) {
+        let intent = expire(&state.pool, intent.id).await?;
+        return Err((StatusCode::CONFLICT, format!("Approval request for payment intent {} has {}", intent.id, intent.status)));
+    }
+
+    let inserted = sqlx::query(
+        "INSERT INTO payment_approvals (intent_id, approver, decision, comment) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING"
+    )
+    .bind(intent.id)
+    .bind(approver)
+    .bind(decision.as_str())
+    .bind(comment)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if inserted.rows_affected() == 0 {
+        return Err((StatusCode::CONFLICT, format!("{} already decided on payment intent {}", approver, intent.id)));
+    }
+
+    if decision == Decision::Reject {
+        let note = comment.unwrap_or("Rejected by approver");
+        return intents::transition(&state.pool, intent.id, IntentStatus::PendingApproval, IntentStatus::Rejected, approver, Some(note), None).await;
+    }
+
+    let approvals: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM payment_approvals WHERE intent_id = $1 AND decision = 'approve'")
+        .bind(intent.id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if approvals < intent.required_approvals as i64 {
+        return Ok(intent);
+    }
+
+    let note = format!("{} of {} approvals", approvals, intent.required_approvals);
+    match intents::transition(&state.pool, intent.id, IntentStatus::PendingApproval, IntentStatus::Approved, approver, Some(&note), None).await {
+        Ok(intent) => intents::submit(state, intent).await,
+        // A concurrent approval completed the quorum first; its caller submits the payment
+        Err((StatusCode::CONFLICT, _)) => intents::find_intent(&state.pool, intent.id).await,
+        Err(e) => Err(e),
+    }
+}
+
+async fn expire(pool: &PgPool, intent_id: i32) -> Result<PaymentIntent, (StatusCode, String)> {
+    match intents::transition(pool, intent_id, IntentStatus::PendingApproval, IntentStatus::Expired, SYSTEM_ACTOR, Some("Approval window elapsed"), None).await {
+        Err((StatusCode::CONFLICT, _)) => intents::find_intent(pool, intent_id).await,
+        result => result,
+    }
+}
+
+/// Background worker: expires approval requests nobody acted on in time.
+pub async fn run_expiry(pool: PgPool) {
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(EXPIRY_SWEEP_SECS));
+    loop {
+        ticker.tick().await;
+        if let Err((_, e)) = expire_stale(&pool).await {
+            println!("Approval expiry error: {}", e);
+        }
+    }
+}
+
+async fn expire_stale(pool: &PgPool) -> Result<(), (StatusCode, String)> {
+    let stale: Vec<i32> = sqlx::query_scalar(
+        "SELECT id FROM payment_intents WHERE status = 'pending_approval' AND approval_expires_at <= NOW()"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for intent_id in stale {
+        expire(pool, intent_id).await?;
+    }
+
+    Ok(())
+}
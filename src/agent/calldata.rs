@@ -0,0 +1,94 @@
+use ethers::abi::{AbiParser, Function, Token};
+use ethers::abi::token::{LenientTokenizer, Tokenizer};
+use ethers::types::{Bytes, I256};
+use serde_json::Value;
+
+/// Parses a human-readable signature such as `transfer(address to, uint256 amount) returns (bool)`.
+pub fn parse_signature(signature: &str) -> Result<Function, String> {
+    let signature = signature.trim();
+    let signature = if signature.starts_with("function ") {
+        signature.to_string()
+    } else {
+        format!("function {}", signature)
+    };
+
+    AbiParser::default()
+        .parse_function(&signature)
+        .map_err(|e| format!("Invalid function signature: {}", e))
+}
+
+/// ABI-encodes `args` (given as strings, arrays as `[a,b]`) for `function`.
+pub fn encode_call(function: &Function, args: &[String]) -> Result<Bytes, String> {
+    if args.len() != function.inputs.len() {
+        return Err(format!(
+            "{} expects {} arguments, got {}",
+            function.name,
+            function.inputs.len(),
+            args.len()
+        ));
+    }
+
+    let tokens = function.inputs.iter()
+        .zip(args)
+        .map(|(param, arg)| {
+            LenientTokenizer::tokenize(&param.kind, arg)
+                .map_err(|e| format!("Invalid value for '{}' ({}): {}", param.name, param.kind, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    function.encode_input(&tokens)
+        .map(Bytes::from)
+        .map_err(|e| e.to_string())
+}
+
+pub fn parse_calldata(calldata: &str) -> Result<Bytes, String> {
+    calldata.parse::<Bytes>().map_err(|_| "Invalid calldata hex".to_string())
+}
+
+/// Returns the 4-byte selector as `0x`-prefixed hex, or `None` for empty/short calldata (plain value transfer).
+pub fn selector_of(data: &Bytes) -> Option<String> {
+    (data.len() >= 4).then(|| format!("0x{}", ethers::utils::hex::encode(&data[..4])))
+}
+
+/// Token functions that move funds or grant an allowance over them (ERC-20, ERC-721, ERC-1155, EIP-2612).
+const FUND_MOVING_FUNCTIONS: &[&str] = &[
+    "transfer(address,uint256)",
+    "transferFrom(address,address,uint256)",
+    "approve(address,uint256)",
+    "increaseAllowance(address,uint256)",
+    "permit(address,address,uint256,uint256,uint8,bytes32,bytes32)",
+    "safeTransferFrom(address,address,uint256)",
+    "safeTransferFrom(address,address,uint256,bytes)",
+    "setApprovalForAll(address,bool)",
+    "safeTransferFrom(address,address,uint256,uint256,bytes)",
+    "safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)",
+];
+
+/// Returns the signature of the token function `data` calls when it moves or approves funds.
+/// Such calls must go through payment intents, where counterparty lists, policies and approvals apply.
+pub fn fund_moving_function(data: &Bytes) -> Option<&'static str> {
+    let selector = data.get(..4)?;
+    FUND_MOVING_FUNCTIONS.iter()
+        .copied()
+        .find(|signature| ethers::utils::id(signature) == selector)
+}
+
+/// Decodes return data into JSON using the function's declared outputs.
+pub fn decode_output(function: &Function, output: &Bytes) -> Result<Value, String> {
+    let tokens = function.decode_output(output).map_err(|e| e.to_string())?;
+    Ok(Value::Array(tokens.into_iter().map(token_to_json).collect()))
+}
+
+fn token_to_json(token: Token) -> Value {
+    match token {
+        Token::Address(a) => Value::from(format!("{:?}", a)),
+        Token::Uint(u) => Value::from(u.to_string()),
+        Token::Int(i) => Value::from(I256::from_raw(i).to_string()),
+        Token::Bool(b) => Value::from(b),
+        Token::String(s) => Value::from(s),
+        Token::Bytes(b) | Token::FixedBytes(b) => Value::from(format!("0x{}", ethers::utils::hex::encode(b))),
+        Token::Array(items) | Token::FixedArray(items) | Token::Tuple(items) => {
+            Value::Array(items.into_iter().map(token_to_json).collect())
+        }
+    }
+}
@@ -1,37 +1,397 @@
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     Json,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
+use crate::auth::{require_role, Claims};
+use crate::idempotency::{idempotent, resource_id, ClaimedKey};
 use crate::state::AppState;
-use super::models::{TransactionRequest, TransactionResponse};
+use crate::finance::registry::find_wallet;
+use crate::finance::amounts::{parse_amount, NATIVE_DECIMALS};
+use crate::chain::Simulation;
+use crate::compliance::sanctions::screening;
+use crate::governance::{agents, freezes, policy};
+use super::calldata;
+use super::approvals::{self, Decision, APPROVER_ROLES};
+use super::intents::{self, IntentStatus};
+use super::payouts;
+use super::scheduler;
+use super::models::{
+    TransactionRequest, TransactionResponse, ContractCallRequest, ContractCallResponse,
+    CreateIntentRequest, PaymentIntent, PaymentIntentDetail, IntentFilter, CancelIntentRequest, DecisionRequest,
+    CreatePayoutRequest, CsvPayoutQuery, PayoutBatch, PayoutBatchDetail,
+    CreateScheduleRequest, PaymentSchedule, PaymentScheduleDetail,
+};
 
+/// Honours `Idempotency-Key` so a retried payment is never broadcast twice.
+/// Shorthand for creating a payment intent that is screened, approved and submitted immediately.
 pub async fn execute_transaction(
     State(state): State<AppState>,
+    Claims(claims): Claims,
+    headers: HeaderMap,
     Json(payload): Json<TransactionRequest>,
+) -> Response {
+    idempotent(
+        &state.pool, &headers, &claims.sub, "agent.pay", &payload,
+        |key| send_payment(&state, &payload, &claims.sub, key),
+        |resource| async { payment_response(interrupted_intent(&state, resource).await?.0) },
+    ).await
+}
+
+async fn send_payment(
+    state: &AppState,
+    payload: &TransactionRequest,
+    actor: &str,
+    key: ClaimedKey,
 ) -> Result<Json<TransactionResponse>, (StatusCode, String)> {
-    
-    let tx_hash = if let Some(token_addr) = &payload.token_address {
-        // ERC20 Flow
-        state.chain.execute_erc20(
-            &payload.wallet_address,
-            token_addr,
-            &payload.target_address,
-            &payload.amount
-        ).await
-    } else {
-        // Native ETH Flow
-        state.chain.execute_native(
-            &payload.wallet_address,
-            &payload.target_address,
-            &payload.amount
-        ).await
+    find_wallet(&state.pool, payload.wallet_id).await?;
+
+    let request = CreateIntentRequest::from(payload.clone());
+    let intent = intents::create_intent(&state.pool, &request, actor, actor).await?;
+    key.record(&format!("payment_intent:{}", intent.id)).await?;
+    let intent = intents::process(state, intent, false).await?;
+    payment_response(intent)
+}
+
+fn payment_response(intent: PaymentIntent) -> Result<Json<TransactionResponse>, (StatusCode, String)> {
+    match (IntentStatus::parse(&intent.status), intent.tx_hash) {
+        (Some(IntentStatus::Confirmed), Some(tx_hash)) => Ok(Json(TransactionResponse {
+            intent_id: intent.id,
+            tx_hash: Some(tx_hash),
+            status: "Transaction Sent".to_string(),
+        })),
+        // Sent, but the receipt has not been seen yet; reconciliation settles the intent
+        (Some(IntentStatus::Submitted), Some(tx_hash)) => Ok(Json(TransactionResponse {
+            intent_id: intent.id,
+            tx_hash: Some(tx_hash),
+            status: "Transaction Sent (awaiting receipt)".to_string(),
+        })),
+        (Some(IntentStatus::PendingApproval), _) => Ok(Json(TransactionResponse {
+            intent_id: intent.id,
+            tx_hash: None,
+            status: format!("Pending Approval ({} required)", intent.required_approvals),
+        })),
+        // Only seen when reporting an interrupted request: the payment is still on its way
+        (Some(IntentStatus::Created | IntentStatus::Screened | IntentStatus::Approved | IntentStatus::Submitted), _) => Err((
+            StatusCode::CONFLICT,
+            format!("Payment intent {} is still {}; poll it instead of retrying", intent.id, intent.status),
+        )),
+        _ => Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Payment intent {} {}: {}", intent.id, intent.status, intent.failure_reason.unwrap_or_default()),
+        )),
+    }
+}
+
+pub async fn create_intent(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    headers: HeaderMap,
+    Json(payload): Json<CreateIntentRequest>,
+) -> Response {
+    idempotent(
+        &state.pool, &headers, &claims.sub, "agent.intents", &payload,
+        |key| open_intent(&state, &payload, &claims.sub, key),
+        |resource| interrupted_intent(&state, resource),
+    ).await
+}
+
+async fn open_intent(
+    state: &AppState,
+    payload: &CreateIntentRequest,
+    actor: &str,
+    key: ClaimedKey,
+) -> Result<Json<PaymentIntent>, (StatusCode, String)> {
+    find_wallet(&state.pool, payload.wallet_id).await?;
+
+    let intent = intents::create_intent(&state.pool, payload, actor, actor).await?;
+    key.record(&format!("payment_intent:{}", intent.id)).await?;
+    let intent = intents::process(state, intent, payload.hold_for_review).await?;
+    Ok(Json(intent))
+}
+
+/// Reports the intent an interrupted request created; reconciliation and approvals carry it on from there.
+async fn interrupted_intent(state: &AppState, resource: String) -> Result<Json<PaymentIntent>, (StatusCode, String)> {
+    Ok(Json(intents::find_intent(&state.pool, resource_id(&resource, "payment_intent")?).await?))
+}
+
+pub async fn list_intents(
+    State(state): State<AppState>,
+    Query(filter): Query<IntentFilter>,
+) -> Result<Json<Vec<PaymentIntent>>, (StatusCode, String)> {
+    let intents = sqlx::query_as::<_, PaymentIntent>(
+        r#"
+        SELECT * FROM payment_intents
+        WHERE ($1::VARCHAR IS NULL OR status = $1) AND ($2::INTEGER IS NULL OR wallet_id = $2)
+        ORDER BY created_at DESC
+        "#
+    )
+    .bind(filter.status)
+    .bind(filter.wallet_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(intents))
+}
+
+pub async fn get_intent(
+    State(state): State<AppState>,
+    Path(intent_id): Path<i32>,
+) -> Result<Json<PaymentIntentDetail>, (StatusCode, String)> {
+    let intent = intents::find_intent(&state.pool, intent_id).await?;
+    let events = intents::intent_events(&state.pool, intent_id).await?;
+    let approvals = approvals::intent_approvals(&state.pool, intent_id).await?;
+    let policy_decisions = policy::intent_decisions(&state.pool, intent_id).await?;
+
+    Ok(Json(PaymentIntentDetail { intent, events, approvals, policy_decisions }))
+}
+
+/// Releases an intent held for review, or records an approval on one awaiting maker-checker sign-off.
+/// The intent is submitted on-chain once it is fully approved.
+pub async fn approve_intent(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(intent_id): Path<i32>,
+    payload: Option<Json<DecisionRequest>>,
+) -> Result<Json<PaymentIntent>, (StatusCode, String)> {
+    let intent = intents::find_intent(&state.pool, intent_id).await?;
+    let Json(payload) = payload.unwrap_or_default();
+
+    let intent = match IntentStatus::parse(&intent.status) {
+        Some(IntentStatus::PendingApproval) => {
+            approvals::decide(&state, intent, &claims, Decision::Approve, payload.comment.as_deref()).await?
+        }
+        Some(IntentStatus::Screened) => approvals::release(&state, intent, &claims).await?,
+        _ => return Err((StatusCode::CONFLICT, format!("Payment intent {} is {}; only held or pending intents can be approved", intent.id, intent.status))),
+    };
+
+    Ok(Json(intent))
+}
+
+pub async fn reject_intent(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(intent_id): Path<i32>,
+    payload: Option<Json<DecisionRequest>>,
+) -> Result<Json<PaymentIntent>, (StatusCode, String)> {
+    let intent = intents::find_intent(&state.pool, intent_id).await?;
+    let Json(payload) = payload.unwrap_or_default();
+
+    let intent = approvals::decide(&state, intent, &claims, Decision::Reject, payload.comment.as_deref()).await?;
+
+    Ok(Json(intent))
+}
+
+/// The initiator, approvers and admins may cancel an intent until it is submitted.
+pub async fn cancel_intent(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(intent_id): Path<i32>,
+    Json(payload): Json<CancelIntentRequest>,
+) -> Result<Json<PaymentIntent>, (StatusCode, String)> {
+    let intent = intents::find_intent(&state.pool, intent_id).await?;
+    if intent.initiated_by != claims.sub {
+        require_role(&claims, APPROVER_ROLES)
+            .map_err(|_| (StatusCode::FORBIDDEN, "Only the initiator, an approver or an admin can cancel a payment".to_string()))?;
+    }
+    let from = IntentStatus::parse(&intent.status)
+        .ok_or((StatusCode::INTERNAL_SERVER_ERROR, format!("Unknown status {}", intent.status)))?;
+    if !from.can_transition_to(IntentStatus::Cancelled) {
+        return Err((StatusCode::CONFLICT, format!("Payment intent {} is {} and can no longer be cancelled", intent.id, intent.status)));
+    }
+
+    let reason = payload.reason.unwrap_or_else(|| "Cancelled by user".to_string());
+    let intent = intents::transition(&state.pool, intent_id, from, IntentStatus::Cancelled, &claims.sub, Some(&reason), None).await?;
+
+    Ok(Json(intent))
+}
+
+pub async fn create_payout(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    headers: HeaderMap,
+    Json(payload): Json<CreatePayoutRequest>,
+) -> Response {
+    idempotent(
+        &state.pool, &headers, &claims.sub, "agent.payouts", &payload,
+        |key| open_batch(&state, &payload, &claims.sub, key),
+        |resource| interrupted_batch(&state, resource),
+    ).await
+}
+
+/// Same as `create_payout`, with items uploaded as CSV (`target_address,amount,token_address`).
+pub async fn create_payout_csv(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    headers: HeaderMap,
+    Query(query): Query<CsvPayoutQuery>,
+    body: String,
+) -> Response {
+    let items = match payouts::parse_csv(&body) {
+        Ok(items) => items,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let payload = CreatePayoutRequest { wallet_id: query.wallet_id, items };
+
+    idempotent(
+        &state.pool, &headers, &claims.sub, "agent.payouts", &payload,
+        |key| open_batch(&state, &payload, &claims.sub, key),
+        |resource| interrupted_batch(&state, resource),
+    ).await
+}
+
+async fn open_batch(
+    state: &AppState,
+    payload: &CreatePayoutRequest,
+    actor: &str,
+    key: ClaimedKey,
+) -> Result<Json<PayoutBatchDetail>, (StatusCode, String)> {
+    Ok(Json(payouts::create_batch(state, payload.wallet_id, payload.items.clone(), actor, &key).await?))
+}
+
+/// Reports the batch an interrupted request recorded; `payouts::resume` executes it after a restart.
+async fn interrupted_batch(state: &AppState, resource: String) -> Result<Json<PayoutBatchDetail>, (StatusCode, String)> {
+    Ok(Json(payouts::find_batch(&state.pool, resource_id(&resource, "payout_batch")?).await?))
+}
+
+pub async fn list_payouts(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<PayoutBatch>>, (StatusCode, String)> {
+    let batches = sqlx::query_as::<_, PayoutBatch>("SELECT * FROM payout_batches ORDER BY created_at DESC")
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(batches))
+}
+
+pub async fn get_payout(
+    State(state): State<AppState>,
+    Path(batch_id): Path<i32>,
+) -> Result<Json<PayoutBatchDetail>, (StatusCode, String)> {
+    Ok(Json(payouts::find_batch(&state.pool, batch_id).await?))
+}
+
+pub async fn create_schedule(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<CreateScheduleRequest>,
+) -> Result<(StatusCode, Json<PaymentScheduleDetail>), (StatusCode, String)> {
+    let schedule = scheduler::create_schedule(&state, &payload, &claims.sub).await?;
+    Ok((StatusCode::CREATED, Json(schedule)))
+}
+
+pub async fn list_schedules(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<PaymentSchedule>>, (StatusCode, String)> {
+    let schedules = sqlx::query_as::<_, PaymentSchedule>("SELECT * FROM payment_schedules ORDER BY created_at DESC")
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(schedules))
+}
+
+pub async fn get_schedule(
+    State(state): State<AppState>,
+    Path(schedule_id): Path<i32>,
+) -> Result<Json<PaymentScheduleDetail>, (StatusCode, String)> {
+    Ok(Json(scheduler::find_schedule(&state.pool, schedule_id).await?))
+}
+
+pub async fn pause_schedule(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(schedule_id): Path<i32>,
+) -> Result<Json<PaymentScheduleDetail>, (StatusCode, String)> {
+    Ok(Json(scheduler::pause_schedule(&state.pool, schedule_id, &claims).await?))
+}
+
+pub async fn resume_schedule(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(schedule_id): Path<i32>,
+) -> Result<Json<PaymentScheduleDetail>, (StatusCode, String)> {
+    Ok(Json(scheduler::resume_schedule(&state.pool, schedule_id, &claims).await?))
+}
+
+pub async fn call_contract(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<ContractCallRequest>,
+) -> Result<Json<ContractCallResponse>, (StatusCode, String)> {
+    let wallet = find_wallet(&state.pool, payload.wallet_id).await?;
+    freezes::check(&state.pool, &wallet, Some(&claims.sub)).await.map_err(|e| (StatusCode::FORBIDDEN, e))?;
+    // Calls move no funds, but an agent still needs to be active and granted the wallet
+    agents::authorize(&state, &claims.sub, wallet.id, &[]).await.map_err(|e| (StatusCode::FORBIDDEN, e))?;
+
+    let target: ethers::types::Address = payload.target_address.parse()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid target address".to_string()))?;
+    let target = format!("{:?}", target);
+    // Allowlisted targets still go through sanctions screening: a listing after the allowlist entry must stop calls
+    screening::check_payment(&state.pool, &wallet, &target).await.map_err(|e| (StatusCode::FORBIDDEN, e))?;
+
+    // Value leaves the wallet only as a payment intent, where counterparty lists, policies and approvals apply
+    let value = parse_amount(payload.value.as_deref().unwrap_or("0"), NATIVE_DECIMALS)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    if !value.is_zero() {
+        return Err((StatusCode::FORBIDDEN, "Calls cannot carry value; send it as a payment intent".to_string()));
+    }
+
+    let (function, data) = match (&payload.function_signature, &payload.calldata) {
+        (Some(signature), None) => {
+            let function = calldata::parse_signature(signature).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+            let data = calldata::encode_call(&function, &payload.args).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+            (Some(function), data)
+        }
+        (None, Some(raw)) => (None, calldata::parse_calldata(raw).map_err(|e| (StatusCode::BAD_REQUEST, e))?),
+        _ => return Err((StatusCode::BAD_REQUEST, "Provide exactly one of function_signature or calldata".to_string())),
+    };
+
+    if let Some(function) = calldata::fund_moving_function(&data) {
+        return Err((StatusCode::FORBIDDEN, format!("{} moves funds; send it as a payment intent", function)));
+    }
+
+    // Allowlist: an entry with a NULL selector allows any call to the target
+    let selector = calldata::selector_of(&data);
+    let allowed: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM call_allowlist WHERE target_address = $1 AND (selector IS NULL OR selector = $2))"
+    )
+    .bind(&target)
+    .bind(&selector)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if !allowed {
+        return Err((
+            StatusCode::FORBIDDEN,
+            format!("Call to {} (selector {}) is not allowlisted", target, selector.as_deref().unwrap_or("none")),
+        ));
+    }
+
+    let output = match state.chain.simulate_execute(&wallet.address, &target, value, data.clone()).await {
+        Ok(Simulation::Success(output)) => output,
+        Ok(Simulation::Reverted(reason)) => return Err((StatusCode::UNPROCESSABLE_ENTITY, format!("Call reverted: {}", reason))),
+        Err(e) => return Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    };
+
+    let result = match &function {
+        Some(function) if !function.outputs.is_empty() => {
+            calldata::decode_output(function, &output).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        }
+        _ => serde_json::Value::from(output.to_string()),
     };
 
-    let tx_hash = tx_hash.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let tx_hash = state.chain.execute_call(&wallet.address, &target, value, data)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    Ok(Json(TransactionResponse {
+    Ok(Json(ContractCallResponse {
         tx_hash,
         status: "Transaction Sent".to_string(),
+        result,
     }))
 }
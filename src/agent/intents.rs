@@ -0,0 +1,466 @@
+use axum::http::StatusCode;
+use sqlx::PgPool;
+use ethers::types::{Bytes, TxHash, U256};
+use crate::state::AppState;
+use crate::chain::TxStatus;
+use crate::finance::models::Wallet;
+use crate::finance::registry::{find_wallet, find_allowed_token};
+use crate::finance::amounts::{parse_amount, NATIVE_DECIMALS};
+use crate::compliance::{counterparties, notifications};
+use crate::compliance::sanctions::screening;
+use crate::governance::{agents, freezes, policy};
+use crate::governance::limits::Spend;
+use super::{approvals, payouts, scheduler};
+use super::models::{CreateIntentRequest, PaymentIntent, PaymentIntentEvent};
+
+/// Actor recorded for transitions the service makes on its own.
+pub const SYSTEM_ACTOR: &str = "system";
+
+/// How often, and after how long without a receipt, submitted payments are looked up on-chain again.
+const RECONCILE_INTERVAL_SECS: u64 = 60;
+
+/// How long an intent may stay `submitted` without a tx hash; broadcasting returns within seconds.
+const UNRECORDED_SUBMISSION_SECS: u64 = 900;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntentStatus {
+    Created,
+    Screened,
+    PendingApproval,
+    Approved,
+    Submitted,
+    Confirmed,
+    Failed,
+    Cancelled,
+    Rejected,
+    Expired,
+}
+
+impl IntentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IntentStatus::Created => "created",
+            IntentStatus::Screened => "screened",
+            IntentStatus::PendingApproval => "pending_approval",
+            IntentStatus::Approved => "approved",
+            IntentStatus::Submitted => "submitted",
+            IntentStatus::Confirmed => "confirmed",
+            IntentStatus::Failed => "failed",
+            IntentStatus::Cancelled => "cancelled",
+            IntentStatus::Rejected => "rejected",
+            IntentStatus::Expired => "expired",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "created" => Some(IntentStatus::Created),
+            "screened" => Some(IntentStatus::Screened),
+            "pending_approval" => Some(IntentStatus::PendingApproval),
+            "approved" => Some(IntentStatus::Approved),
+            "submitted" => Some(IntentStatus::Submitted),
+            "confirmed" => Some(IntentStatus::Confirmed),
+            "failed" => Some(IntentStatus::Failed),
+            "cancelled" => Some(IntentStatus::Cancelled),
+            "rejected" => Some(IntentStatus::Rejected),
+            "expired" => Some(IntentStatus::Expired),
+            _ => None,
+        }
+    }
+
+    /// Allowed edges of the state machine. Cancellation is only possible before submission;
+    /// payments above an approval threshold pass through `pending_approval`.
+    pub fn can_transition_to(&self, next: IntentStatus) -> bool {
+        use IntentStatus::*;
+        matches!(
+            (self, next),
+            (Created, Screened) | (Created, Failed) | (Created, Cancelled)
+                | (Screened, Approved) | (Screened, PendingApproval) | (Screened, Failed) | (Screened, Cancelled)
+                | (PendingApproval, Approved) | (PendingApproval, Rejected) | (PendingApproval, Expired)
+                | (PendingApproval, Failed) | (PendingApproval, Cancelled)
+                | (Approved, Submitted) | (Approved, Failed) | (Approved, Cancelled)
+                | (Submitted, Confirmed) | (Submitted, Failed)
+        )
+    }
+}
+
+pub async fn find_intent(pool: &PgPool, intent_id: i32) -> Result<PaymentIntent, (StatusCode, String)> {
+    sqlx::query_as::<_, PaymentIntent>("SELECT * FROM payment_intents WHERE id = $1")
+        .bind(intent_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Payment intent {} not found", intent_id)))
+}
+
+pub async fn intent_events(pool: &PgPool, intent_id: i32) -> Result<Vec<PaymentIntentEvent>, (StatusCode, String)> {
+    sqlx::query_as::<_, PaymentIntentEvent>("SELECT * FROM payment_intent_events WHERE intent_id = $1 ORDER BY id")
+        .bind(intent_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Creates an intent raised by `actor` on behalf of `initiated_by` (the same user, except for scheduled payments).
+pub async fn create_intent(
+    pool: &PgPool,
+    req: &CreateIntentRequest,
+    actor: &str,
+    initiated_by: &str,
+) -> Result<PaymentIntent, (StatusCode, String)> {
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let intent = sqlx::query_as::<_, PaymentIntent>(
+        r#"
+        INSERT INTO payment_intents (wallet_id, target_address, amount, token_address, created_by, initiated_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#
+    )
+    .bind(req.wallet_id)
+    .bind(req.target_address.to_lowercase())
+    .bind(req.amount.trim())
+    .bind(req.token_address.as_ref().map(|t| t.to_lowercase()))
+    .bind(actor)
+    .bind(initiated_by)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    sqlx::query("INSERT INTO payment_intent_events (intent_id, from_status, to_status, actor) VALUES ($1, NULL, $2, $3)")
+        .bind(intent.id)
+        .bind(IntentStatus::Created.as_str())
+        .bind(actor)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(intent)
+}
+
+/// Moves an intent from `from` to `to`, recording the event (and the status of the payout item or schedule run it pays) atomically.
+/// The update is conditional on the current status, so concurrent transitions cannot both win.
+/// `note` becomes the failure reason for terminal non-success states; `tx_hash` is stored when given.
+pub async fn transition(
+    pool: &PgPool,
+    intent_id: i32,
+    from: IntentStatus,
+    to: IntentStatus,
+    actor: &str,
+    note: Option<&str>,
+    tx_hash: Option<&str>,
+) -> Result<PaymentIntent, (StatusCode, String)> {
+    if !from.can_transition_to(to) {
+        return Err((StatusCode::CONFLICT, format!("Cannot move a payment from {} to {}", from.as_str(), to.as_str())));
+    }
+
+    let failure_reason = matches!(to, IntentStatus::Failed | IntentStatus::Cancelled | IntentStatus::Rejected | IntentStatus::Expired).then_some(note).flatten();
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let intent = sqlx::query_as::<_, PaymentIntent>(
+        r#"
+        UPDATE payment_intents
+        SET status = $1, updated_at = NOW(),
+            failure_reason = COALESCE($2, failure_reason),
+            tx_hash = COALESCE($3, tx_hash)
+        WHERE id = $4 AND status = $5
+        RETURNING *
+        "#
+    )
+    .bind(to.as_str())
+    .bind(failure_reason)
+    .bind(tx_hash)
+    .bind(intent_id)
+    .bind(from.as_str())
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::CONFLICT, format!("Payment intent {} is no longer {}", intent_id, from.as_str())))?;
+
+    sqlx::query("INSERT INTO payment_intent_events (intent_id, from_status, to_status, actor, note) VALUES ($1, $2, $3, $4, $5)")
+        .bind(intent_id)
+        .bind(from.as_str())
+        .bind(to.as_str())
+        .bind(actor)
+        .bind(note)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    payouts::track_intent(&mut tx, &intent).await?;
+    scheduler::track_intent(&mut tx, &intent).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(intent)
+}
+
+/// Pre-submission checks. `Err` carries the rejection reason recorded on the intent.
+/// Returns the amount in base units.
+pub async fn screen(state: &AppState, intent: &PaymentIntent) -> Result<U256, String> {
+    screen_payment(state, intent.wallet_id, &intent.target_address, &intent.amount, intent.token_address.as_deref(), &intent.initiated_by).await
+}
+
+/// Validates a payment `actor` initiates before any record exists (used for intents and up-front batch validation).
+/// Returns the amount in base units.
+pub async fn screen_payment(
+    state: &AppState,
+    wallet_id: i32,
+    target_address: &str,
+    amount: &str,
+    token_address: Option<&str>,
+    actor: &str,
+) -> Result<U256, String> {
+    let wallet = find_wallet(&state.pool, wallet_id).await.map_err(|(_, e)| e)?;
+
+    target_address.parse::<ethers::types::Address>()
+        .map_err(|_| format!("Invalid target address '{}'", target_address))?;
+
+    freezes::check(&state.pool, &wallet, Some(actor)).await?;
+    counterparties::check(&state.pool, &wallet, target_address).await?;
+    screening::check_payment(&state.pool, &wallet, target_address).await?;
+
+    let amount = match token_address {
+        Some(token_addr) => {
+            let token = find_allowed_token(&state.pool, token_addr, state.chain.chain_id()).await.map_err(|(_, e)| e)?;
+            parse_amount(amount, token.decimals as u8)?
+        }
+        None => parse_amount(amount, NATIVE_DECIMALS)?,
+    };
+
+    if amount.is_zero() {
+        return Err("Amount must be greater than zero".to_string());
+    }
+
+    Ok(amount)
+}
+
+/// Runs a freshly created intent through screening and, unless held, approval and submission.
+/// The initiator's agent grant and off-chain policies are checked after screening; intents matching an approval threshold
+/// stop in `pending_approval` regardless of `hold_for_review`.
+pub async fn process(state: &AppState, intent: PaymentIntent, hold_for_review: bool) -> Result<PaymentIntent, (StatusCode, String)> {
+    let amount = match screen(state, &intent).await {
+        Ok(amount) => amount,
+        Err(reason) => {
+            return transition(&state.pool, intent.id, IntentStatus::Created, IntentStatus::Failed, SYSTEM_ACTOR, Some(&reason), None).await;
+        }
+    };
+    let spend = Spend { token_address: intent.token_address.clone(), amount };
+    if let Err(reason) = agents::authorize(state, &intent.initiated_by, intent.wallet_id, &[spend]).await {
+        return transition(&state.pool, intent.id, IntentStatus::Created, IntentStatus::Failed, SYSTEM_ACTOR, Some(&reason), None).await;
+    }
+    if let Err(reason) = policy::enforce(state, &intent, amount).await {
+        return transition(&state.pool, intent.id, IntentStatus::Created, IntentStatus::Failed, SYSTEM_ACTOR, Some(&reason), None).await;
+    }
+    let intent = transition(&state.pool, intent.id, IntentStatus::Created, IntentStatus::Screened, SYSTEM_ACTOR, None, None).await?;
+    route_screened(state, intent, amount, hold_for_review).await
+}
+
+/// Carries on with an intent interrupted after screening. Screening runs again, since lists and freezes may
+/// have changed, before the intent is routed like a freshly screened one.
+pub async fn resume_screened(state: &AppState, intent: PaymentIntent) -> Result<PaymentIntent, (StatusCode, String)> {
+    match screen(state, &intent).await {
+        Ok(amount) => route_screened(state, intent, amount, false).await,
+        Err(reason) => transition(&state.pool, intent.id, IntentStatus::Screened, IntentStatus::Failed, SYSTEM_ACTOR, Some(&reason), None).await,
+    }
+}
+
+async fn route_screened(state: &AppState, intent: PaymentIntent, amount: U256, hold_for_review: bool) -> Result<PaymentIntent, (StatusCode, String)> {
+    if let Some(threshold) = approvals::matching_threshold(state, &intent, amount).await? {
+        return approvals::request_approval(&state.pool, intent, &threshold).await;
+    }
+
+    if hold_for_review {
+        return Ok(intent);
+    }
+
+    approve_and_submit(state, intent, SYSTEM_ACTOR).await
+}
+
+/// Approves a screened intent and hands it to `ChainClient`, ending in `confirmed` or `failed`.
+pub async fn approve_and_submit(state: &AppState, intent: PaymentIntent, actor: &str) -> Result<PaymentIntent, (StatusCode, String)> {
+    let intent = transition(&state.pool, intent.id, IntentStatus::Screened, IntentStatus::Approved, actor, None, None).await?;
+    submit(state, intent).await
+}
+
+/// Freezes, counterparty lists, sanctions screening and the initiator's agent grant are re-checked here: an
+/// intent approved before a freeze, a blocklisting, a sanctions hit or an agent suspension must not reach
+/// the chain after it.
+/// Once sent, the tx hash is stored before waiting for the receipt; if that wait fails the intent stays
+/// `submitted` and `reconcile` settles it from the chain later.
+pub async fn submit(state: &AppState, intent: PaymentIntent) -> Result<PaymentIntent, (StatusCode, String)> {
+    let wallet = find_wallet(&state.pool, intent.wallet_id).await?;
+    if let Err(reason) = recheck(state, &wallet, &intent).await {
+        return transition(&state.pool, intent.id, IntentStatus::Approved, IntentStatus::Failed, SYSTEM_ACTOR, Some(&reason), None).await;
+    }
+
+    let intent = transition(&state.pool, intent.id, IntentStatus::Approved, IntentStatus::Submitted, SYSTEM_ACTOR, None, None).await?;
+
+    let tx_hash = match broadcast(state, &intent).await {
+        Ok(tx_hash) => tx_hash,
+        Err(reason) => return transition(&state.pool, intent.id, IntentStatus::Submitted, IntentStatus::Failed, SYSTEM_ACTOR, Some(&reason), None).await,
+    };
+    let intent = record_tx_hash(&state.pool, intent.id, &format!("{:?}", tx_hash)).await?;
+
+    match state.chain.wait_for_receipt(tx_hash).await.map_err(|e| e.to_string()) {
+        Ok(status) => settle(&state.pool, intent, status).await,
+        Err(e) => {
+            println!("Receipt for payment intent {} ({:?}) not seen yet: {}", intent.id, tx_hash, e);
+            Ok(intent)
+        }
+    }
+}
+
+/// The checks of `screen_payment` and `agents::authorize` that can change while an intent waits for approval.
+async fn recheck(state: &AppState, wallet: &Wallet, intent: &PaymentIntent) -> Result<(), String> {
+    freezes::check(&state.pool, wallet, Some(&intent.initiated_by)).await?;
+    counterparties::check(&state.pool, wallet, &intent.target_address).await?;
+    screening::check_payment(&state.pool, wallet, &intent.target_address).await?;
+
+    let amount = match &intent.token_address {
+        Some(token_addr) => {
+            let token = find_allowed_token(&state.pool, token_addr, state.chain.chain_id()).await.map_err(|(_, e)| e)?;
+            parse_amount(&intent.amount, token.decimals as u8)?
+        }
+        None => parse_amount(&intent.amount, NATIVE_DECIMALS)?,
+    };
+    agents::reauthorize(state, intent, amount).await
+}
+
+/// Sends the payment and returns its tx hash once the node accepted it.
+async fn broadcast(state: &AppState, intent: &PaymentIntent) -> Result<TxHash, String> {
+    let wallet = find_wallet(&state.pool, intent.wallet_id).await.map_err(|(_, e)| e)?;
+
+    let result = if let Some(token_addr) = &intent.token_address {
+        // ERC20 Flow
+        let token = find_allowed_token(&state.pool, token_addr, state.chain.chain_id()).await.map_err(|(_, e)| e)?;
+        let amount = parse_amount(&intent.amount, token.decimals as u8)?;
+
+        state.chain.send_erc20(
+            &wallet.address,
+            &token.address,
+            &intent.target_address,
+            amount
+        ).await
+    } else {
+        // Native ETH Flow
+        let value = parse_amount(&intent.amount, NATIVE_DECIMALS)?;
+
+        state.chain.send_call(
+            &wallet.address,
+            &intent.target_address,
+            value,
+            Bytes::new()
+        ).await
+    };
+
+    result.map_err(|e| e.to_string())
+}
+
+async fn record_tx_hash(pool: &PgPool, intent_id: i32, tx_hash: &str) -> Result<PaymentIntent, (StatusCode, String)> {
+    sqlx::query_as::<_, PaymentIntent>(
+        "UPDATE payment_intents SET tx_hash = $1, updated_at = NOW() WHERE id = $2 AND status = 'submitted' RETURNING *"
+    )
+    .bind(tx_hash)
+    .bind(intent_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::CONFLICT, format!("Payment intent {} is no longer submitted", intent_id)))
+}
+
+/// Moves a submitted intent to its final state once the chain has an answer; pending transactions are left alone.
+async fn settle(pool: &PgPool, intent: PaymentIntent, status: TxStatus) -> Result<PaymentIntent, (StatusCode, String)> {
+    let tx_hash = intent.tx_hash.clone().unwrap_or_default();
+    match status {
+        TxStatus::Confirmed => transition(pool, intent.id, IntentStatus::Submitted, IntentStatus::Confirmed, SYSTEM_ACTOR, None, None).await,
+        TxStatus::Reverted => {
+            let reason = format!("Transaction {} reverted", tx_hash);
+            transition(pool, intent.id, IntentStatus::Submitted, IntentStatus::Failed, SYSTEM_ACTOR, Some(&reason), None).await
+        }
+        TxStatus::Unknown => {
+            let reason = format!("Transaction {} was dropped before it was mined", tx_hash);
+            transition(pool, intent.id, IntentStatus::Submitted, IntentStatus::Failed, SYSTEM_ACTOR, Some(&reason), None).await
+        }
+        TxStatus::Pending => Ok(intent),
+    }
+}
+
+/// Fails intents left `submitted` without a tx hash, i.e. interrupted between the transition and
+/// `record_tx_hash`. Whether the payment was broadcast is unknown, so compliance is asked to check the signer's
+/// transactions before anyone pays again.
+async fn fail_unrecorded(pool: &PgPool) -> Result<(), (StatusCode, String)> {
+    let interrupted = sqlx::query_as::<_, PaymentIntent>(
+        r#"
+        SELECT * FROM payment_intents
+        WHERE status = 'submitted' AND tx_hash IS NULL AND updated_at < NOW() - make_interval(secs => $1)
+        ORDER BY id
+        "#
+    )
+    .bind(UNRECORDED_SUBMISSION_SECS as f64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for intent in interrupted {
+        let reason = "Submission was interrupted before its transaction hash was recorded; it may have been broadcast";
+        match transition(pool, intent.id, IntentStatus::Submitted, IntentStatus::Failed, SYSTEM_ACTOR, Some(reason), None).await {
+            Ok(_) => {}
+            Err((StatusCode::CONFLICT, _)) => continue,
+            Err(e) => return Err(e),
+        }
+        notifications::notify(
+            pool,
+            "payment.submission_unknown",
+            &format!("payment_intent:{}", intent.id),
+            &format!(
+                "Payment intent {} ({} to {}) was failed after an interrupted submission; check the signer's transactions for it before paying again",
+                intent.id, intent.amount, intent.target_address,
+            ),
+            serde_json::json!({ "intent_id": intent.id, "wallet_id": intent.wallet_id, "target_address": intent.target_address, "amount": intent.amount }),
+        ).await?;
+    }
+    Ok(())
+}
+
+/// Background worker: settles submitted payments whose receipt was not seen when they were sent, and fails
+/// (and reports) those interrupted before their tx hash was recorded.
+pub async fn run_reconciliation(state: AppState) {
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(RECONCILE_INTERVAL_SECS));
+    loop {
+        ticker.tick().await;
+        if let Err((_, e)) = reconcile(&state).await {
+            println!("Payment reconciliation error: {}", e);
+        }
+    }
+}
+
+pub async fn reconcile(state: &AppState) -> Result<(), (StatusCode, String)> {
+    fail_unrecorded(&state.pool).await?;
+
+    let unsettled = sqlx::query_as::<_, PaymentIntent>(
+        r#"
+        SELECT * FROM payment_intents
+        WHERE status = 'submitted' AND tx_hash IS NOT NULL AND updated_at < NOW() - make_interval(secs => $1)
+        ORDER BY id
+        "#
+    )
+    .bind(RECONCILE_INTERVAL_SECS as f64)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for intent in unsettled {
+        let tx_hash = intent.tx_hash.clone().unwrap_or_default();
+        match state.chain.transaction_status(&tx_hash).await.map_err(|e| e.to_string()) {
+            Ok(status) => match settle(&state.pool, intent, status).await {
+                Ok(_) | Err((StatusCode::CONFLICT, _)) => {}
+                Err(e) => return Err(e),
+            },
+            Err(e) => println!("Could not look up transaction {}: {}", tx_hash, e),
+        }
+    }
+
+    Ok(())
+}
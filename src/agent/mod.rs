@@ -1,13 +1,31 @@
 use axum::{
-    routing::post,
+    routing::{get, post},
     Router,
 };
 use crate::state::AppState;
 
+pub mod approvals;
+pub mod calldata;
 pub mod handlers;
+pub mod intents;
 pub mod models;
+pub mod payouts;
+pub mod scheduler;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/pay", post(handlers::execute_transaction))
+        .route("/call", post(handlers::call_contract))
+        .route("/intents", post(handlers::create_intent).get(handlers::list_intents))
+        .route("/intents/:intent_id", get(handlers::get_intent))
+        .route("/intents/:intent_id/approve", post(handlers::approve_intent))
+        .route("/intents/:intent_id/reject", post(handlers::reject_intent))
+        .route("/intents/:intent_id/cancel", post(handlers::cancel_intent))
+        .route("/payouts", post(handlers::create_payout).get(handlers::list_payouts))
+        .route("/payouts/csv", post(handlers::create_payout_csv))
+        .route("/payouts/:batch_id", get(handlers::get_payout))
+        .route("/schedules", post(handlers::create_schedule).get(handlers::list_schedules))
+        .route("/schedules/:schedule_id", get(handlers::get_schedule))
+        .route("/schedules/:schedule_id/pause", post(handlers::pause_schedule))
+        .route("/schedules/:schedule_id/resume", post(handlers::resume_schedule))
 }
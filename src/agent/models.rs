@@ -1,15 +1,230 @@
 use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct TransactionRequest {
-    pub wallet_address: String,
+    pub wallet_id: i32,
     pub target_address: String,
     pub amount: String,
     pub token_address: Option<String>,
 }
 
+/// `tx_hash` is absent while the payment waits for approvals.
 #[derive(Serialize)]
 pub struct TransactionResponse {
+    pub intent_id: i32,
+    pub tx_hash: Option<String>,
+    pub status: String,
+}
+
+/// Arbitrary contract call through `AegisWallet.execute`.
+/// Provide either `function_signature` + `args`, or raw `calldata`.
+#[derive(Deserialize)]
+pub struct ContractCallRequest {
+    pub wallet_id: i32,
+    pub target_address: String,
+    pub value: Option<String>,
+    pub function_signature: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub calldata: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ContractCallResponse {
     pub tx_hash: String,
     pub status: String,
+    /// Decoded return values when a signature with outputs was given, otherwise raw hex
+    pub result: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PaymentIntent {
+    pub id: i32,
+    pub wallet_id: i32,
+    pub target_address: String,
+    pub amount: String,
+    pub token_address: Option<String>,
+    pub status: String,
+    pub tx_hash: Option<String>,
+    pub failure_reason: Option<String>,
+    pub created_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub required_approvals: i32,
+    pub approval_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// The user the payment is made for; differs from `created_by` for scheduled payments
+    pub initiated_by: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PaymentIntentEvent {
+    pub id: i32,
+    pub intent_id: i32,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub actor: String,
+    pub note: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateIntentRequest {
+    pub wallet_id: i32,
+    pub target_address: String,
+    pub amount: String,
+    pub token_address: Option<String>,
+    /// Stop after screening and wait for an explicit approve call
+    #[serde(default)]
+    pub hold_for_review: bool,
+}
+
+impl From<TransactionRequest> for CreateIntentRequest {
+    fn from(req: TransactionRequest) -> Self {
+        Self {
+            wallet_id: req.wallet_id,
+            target_address: req.target_address,
+            amount: req.amount,
+            token_address: req.token_address,
+            hold_for_review: false,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct PaymentIntentDetail {
+    #[serde(flatten)]
+    pub intent: PaymentIntent,
+    pub events: Vec<PaymentIntentEvent>,
+    pub approvals: Vec<PaymentApproval>,
+    pub policy_decisions: Vec<crate::governance::models::PolicyDecision>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PaymentApproval {
+    pub id: i32,
+    pub intent_id: i32,
+    pub approver: String,
+    pub decision: String,
+    pub comment: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Default, Deserialize)]
+pub struct DecisionRequest {
+    pub comment: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct IntentFilter {
+    pub status: Option<String>,
+    pub wallet_id: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct CancelIntentRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PayoutItemRequest {
+    pub target_address: String,
+    pub amount: String,
+    pub token_address: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct CreatePayoutRequest {
+    pub wallet_id: i32,
+    pub items: Vec<PayoutItemRequest>,
+}
+
+#[derive(Deserialize)]
+pub struct CsvPayoutQuery {
+    pub wallet_id: i32,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PayoutBatch {
+    pub id: i32,
+    pub wallet_id: i32,
+    pub status: String,
+    pub item_count: i32,
+    pub created_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PayoutItem {
+    pub id: i32,
+    pub batch_id: i32,
+    pub line_no: i32,
+    pub target_address: String,
+    pub amount: String,
+    pub token_address: Option<String>,
+    pub status: String,
+    pub intent_id: Option<i32>,
+    pub error: Option<String>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Serialize)]
+pub struct PayoutBatchDetail {
+    #[serde(flatten)]
+    pub batch: PayoutBatch,
+    pub items: Vec<PayoutItem>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PaymentSchedule {
+    pub id: i32,
+    pub wallet_id: i32,
+    pub target_address: String,
+    pub amount: String,
+    pub token_address: Option<String>,
+    pub cron_expression: Option<String>,
+    pub interval_seconds: Option<i64>,
+    pub start_at: chrono::DateTime<chrono::Utc>,
+    pub end_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub max_occurrences: Option<i32>,
+    pub occurrences: i32,
+    pub next_run_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub status: String,
+    pub created_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ScheduleRun {
+    pub id: i32,
+    pub schedule_id: i32,
+    pub scheduled_for: chrono::DateTime<chrono::Utc>,
+    pub status: String,
+    pub intent_id: Option<i32>,
+    pub reason: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// `cron_expression` uses the 6/7-field format with seconds (e.g. "0 0 9 * * Mon-Fri");
+/// alternatively `interval_seconds` (minimum 60).
+#[derive(Deserialize)]
+pub struct CreateScheduleRequest {
+    pub wallet_id: i32,
+    pub target_address: String,
+    pub amount: String,
+    pub token_address: Option<String>,
+    pub cron_expression: Option<String>,
+    pub interval_seconds: Option<i64>,
+    pub start_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub end_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub max_occurrences: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct PaymentScheduleDetail {
+    #[serde(flatten)]
+    pub schedule: PaymentSchedule,
+    pub runs: Vec<ScheduleRun>,
 }
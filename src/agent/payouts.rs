@@ -0,0 +1,274 @@
+use axum::http::StatusCode;
+use sqlx::{PgPool, Postgres, Transaction};
+use crate::state::AppState;
+use crate::idempotency::ClaimedKey;
+use crate::finance::registry::find_wallet;
+use crate::governance::agents;
+use crate::governance::limits::{self, Spend};
+use super::intents::{self, IntentStatus};
+use super::models::{CreateIntentRequest, PaymentIntent, PayoutBatch, PayoutBatchDetail, PayoutItem, PayoutItemRequest};
+
+/// Upper bound on items in one batch; each item is its own on-chain transaction.
+pub const MAX_PAYOUT_ITEMS: usize = 500;
+
+/// Parses a CSV upload with a `target_address,amount,token_address` header (token column may be empty).
+pub fn parse_csv(body: &str) -> Result<Vec<PayoutItemRequest>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(body.as_bytes());
+
+    reader.deserialize()
+        .enumerate()
+        .map(|(i, row)| row.map_err(|e| format!("Line {}: {}", i + 2, e)))
+        .collect()
+}
+
+pub async fn find_batch(pool: &PgPool, batch_id: i32) -> Result<PayoutBatchDetail, (StatusCode, String)> {
+    let batch = sqlx::query_as::<_, PayoutBatch>("SELECT * FROM payout_batches WHERE id = $1")
+        .bind(batch_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Payout batch {} not found", batch_id)))?;
+
+    let items = sqlx::query_as::<_, PayoutItem>("SELECT * FROM payout_items WHERE batch_id = $1 ORDER BY line_no")
+        .bind(batch_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(PayoutBatchDetail { batch, items })
+}
+
+/// Validates every item up front and records the batch. Nothing is executed unless all items pass;
+/// a valid batch is then executed in the background and can be polled by id.
+pub async fn create_batch(
+    state: &AppState,
+    wallet_id: i32,
+    items: Vec<PayoutItemRequest>,
+    actor: &str,
+    key: &ClaimedKey,
+) -> Result<PayoutBatchDetail, (StatusCode, String)> {
+    if items.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Batch has no items".to_string()));
+    }
+    if items.len() > MAX_PAYOUT_ITEMS {
+        return Err((StatusCode::BAD_REQUEST, format!("At most {} items per batch", MAX_PAYOUT_ITEMS)));
+    }
+
+    let wallet = find_wallet(&state.pool, wallet_id).await?;
+
+    let mut errors: Vec<Option<String>> = Vec::with_capacity(items.len());
+    let mut spends = Vec::with_capacity(items.len());
+    for item in &items {
+        match intents::screen_payment(state, wallet_id, &item.target_address, &item.amount, item.token_address.as_deref(), actor).await {
+            Ok(amount) => {
+                spends.push(Spend { token_address: item.token_address.clone(), amount });
+                errors.push(None);
+            }
+            Err(reason) => errors.push(Some(reason)),
+        }
+    }
+
+    // The whole batch must fit the wallet's on-chain budgets, per token and in value
+    let limit_error = if errors.iter().all(Option::is_none) {
+        limits::check_budget(state, &wallet.rules_contract, &spends).await.err()
+    } else {
+        None
+    };
+    let over_limit = limit_error.is_some();
+
+    // An agent must be allowed to move the whole batch, not just each item
+    let agent_error = if errors.iter().all(Option::is_none) && !over_limit {
+        agents::authorize(state, actor, wallet_id, &spends).await.err()
+    } else {
+        None
+    };
+
+    let rejected = over_limit || agent_error.is_some() || errors.iter().any(Option::is_some);
+    let batch_status = if rejected { "rejected" } else { "processing" };
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let batch_id: i32 = sqlx::query_scalar(
+        "INSERT INTO payout_batches (wallet_id, status, item_count, created_by) VALUES ($1, $2, $3, $4) RETURNING id"
+    )
+    .bind(wallet_id)
+    .bind(batch_status)
+    .bind(items.len() as i32)
+    .bind(actor)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for (i, (item, error)) in items.iter().zip(errors).enumerate() {
+        let error = match (error, over_limit) {
+            (Some(reason), _) => Some(reason),
+            (None, true) => limit_error.as_ref().map(|e| format!("Batch total: {}", e)),
+            (None, false) => agent_error.clone(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO payout_items (batch_id, line_no, target_address, amount, token_address, status, error)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(batch_id)
+        .bind(i as i32 + 1)
+        .bind(item.target_address.to_lowercase())
+        .bind(item.amount.trim())
+        .bind(item.token_address.as_ref().map(|t| t.to_lowercase()))
+        .bind(if rejected { "rejected" } else { "pending" })
+        .bind(error)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    key.record(&format!("payout_batch:{}", batch_id)).await?;
+
+    if !rejected {
+        let state = state.clone();
+        let actor = actor.to_string();
+        tokio::spawn(async move {
+            if let Err((_, e)) = execute_batch(&state, batch_id, &actor).await {
+                println!("Payout batch {} stopped: {}", batch_id, e);
+            }
+        });
+    }
+
+    find_batch(&state.pool, batch_id).await
+}
+
+/// Executes pending items one at a time (a single signer must not race its own nonces).
+/// Each item goes through the payment intent pipeline; failures are recorded and the batch continues.
+/// Item statuses follow their intent through `track_intent`, including after approval or reconciliation.
+async fn execute_batch(state: &AppState, batch_id: i32, actor: &str) -> Result<(), (StatusCode, String)> {
+    let detail = find_batch(&state.pool, batch_id).await?;
+
+    for item in detail.items.iter().filter(|i| i.status == "pending") {
+        let intent = match item.intent_id {
+            // Interrupted after the intent was created: carry on with it rather than paying twice
+            Some(intent_id) => intents::find_intent(&state.pool, intent_id).await?,
+            None => {
+                let request = CreateIntentRequest {
+                    wallet_id: detail.batch.wallet_id,
+                    target_address: item.target_address.clone(),
+                    amount: item.amount.clone(),
+                    token_address: item.token_address.clone(),
+                    hold_for_review: false,
+                };
+
+                let intent = intents::create_intent(&state.pool, &request, actor, actor).await?;
+                sqlx::query("UPDATE payout_items SET intent_id = $1, updated_at = NOW() WHERE id = $2")
+                    .bind(intent.id)
+                    .bind(item.id)
+                    .execute(&state.pool)
+                    .await
+                    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+                intent
+            }
+        };
+        let result = match IntentStatus::parse(&intent.status) {
+            Some(IntentStatus::Created) => intents::process(state, intent, false).await,
+            // Interrupted mid-pipeline: finish the step it stopped at
+            Some(IntentStatus::Screened) => intents::resume_screened(state, intent).await,
+            Some(IntentStatus::Approved) => intents::submit(state, intent).await,
+            // Reconciliation settles submitted intents, failing those interrupted before their tx hash was
+            // recorded; approvals settle pending ones
+            _ => continue,
+        };
+
+        if let Err((_, e)) = result {
+            sqlx::query("UPDATE payout_items SET status = 'failed', error = $1, updated_at = NOW() WHERE id = $2")
+                .bind(e)
+                .bind(item.id)
+                .execute(&state.pool)
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        }
+    }
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    refresh_batch(&mut tx, batch_id).await?;
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(())
+}
+
+/// Restarts batches left `processing` by a restart; items already turned into intents are not paid again.
+pub async fn resume(state: AppState) {
+    let batches: Result<Vec<(i32, String)>, _> = sqlx::query_as("SELECT id, created_by FROM payout_batches WHERE status = 'processing' ORDER BY id")
+        .fetch_all(&state.pool)
+        .await;
+    match batches {
+        Ok(batches) => {
+            for (batch_id, actor) in batches {
+                if let Err((_, e)) = execute_batch(&state, batch_id, &actor).await {
+                    println!("Payout batch {} stopped: {}", batch_id, e);
+                }
+            }
+        }
+        Err(e) => println!("Payout resume error: {}", e),
+    }
+}
+
+fn item_status(status: Option<IntentStatus>) -> &'static str {
+    match status {
+        Some(IntentStatus::PendingApproval) => "pending_approval",
+        Some(IntentStatus::Submitted) => "submitted",
+        Some(IntentStatus::Confirmed) => "confirmed",
+        Some(IntentStatus::Failed | IntentStatus::Cancelled | IntentStatus::Rejected | IntentStatus::Expired) | None => "failed",
+        Some(IntentStatus::Created | IntentStatus::Screened | IntentStatus::Approved) => "pending",
+    }
+}
+
+/// Mirrors an intent's new status onto the batch item it pays, if any, and refreshes that batch.
+/// Called from `intents::transition` within the same transaction.
+pub async fn track_intent(tx: &mut Transaction<'_, Postgres>, intent: &PaymentIntent) -> Result<(), (StatusCode, String)> {
+    let status = item_status(IntentStatus::parse(&intent.status));
+    let batch_id: Option<i32> = sqlx::query_scalar(
+        "UPDATE payout_items SET status = $1, error = $2, updated_at = NOW() WHERE intent_id = $3 RETURNING batch_id"
+    )
+    .bind(status)
+    .bind(if status == "failed" { intent.failure_reason.as_deref() } else { None })
+    .bind(intent.id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    match batch_id {
+        Some(batch_id) => refresh_batch(tx, batch_id).await,
+        None => Ok(()),
+    }
+}
+
+/// Derives the batch status from its items. It stays `processing` while items are unsent or awaiting a receipt.
+async fn refresh_batch(tx: &mut Transaction<'_, Postgres>, batch_id: i32) -> Result<(), (StatusCode, String)> {
+    sqlx::query(
+        r#"
+        UPDATE payout_batches b SET status = s.status,
+            completed_at = CASE WHEN s.status IN ('completed', 'failed', 'partially_failed') THEN COALESCE(b.completed_at, NOW()) END
+        FROM (
+            SELECT CASE
+                WHEN COUNT(*) FILTER (WHERE status IN ('pending', 'submitted')) > 0 THEN 'processing'
+                WHEN COUNT(*) FILTER (WHERE status = 'pending_approval') > 0 THEN 'awaiting_approval'
+                WHEN COUNT(*) FILTER (WHERE status = 'confirmed') = COUNT(*) THEN 'completed'
+                WHEN COUNT(*) FILTER (WHERE status = 'confirmed') = 0 THEN 'failed'
+                ELSE 'partially_failed'
+            END AS status
+            FROM payout_items WHERE batch_id = $1
+        ) s
+        WHERE b.id = $1 AND b.status <> 'rejected'
+        "#
+    )
+    .bind(batch_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(())
+}
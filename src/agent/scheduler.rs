@@ -0,0 +1,357 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::str::FromStr;
+use crate::state::AppState;
+use crate::auth::{models::Claims, require_role};
+use crate::compliance::audit;
+use crate::governance::ADMIN_ROLES;
+use crate::finance::registry::find_wallet;
+use crate::governance::agents;
+use crate::governance::limits::{self, Spend};
+use super::intents::{self, IntentStatus};
+use super::models::{CreateIntentRequest, CreateScheduleRequest, PaymentIntent, PaymentSchedule, PaymentScheduleDetail, ScheduleRun};
+
+/// How often the worker looks for due schedules.
+const POLL_INTERVAL_SECS: u64 = 30;
+
+/// Longest interval between occurrences (ten years); longer ones overflow date arithmetic.
+const MAX_INTERVAL_SECS: i64 = 10 * 366 * 24 * 3600;
+
+pub enum Cadence {
+    Cron(Box<cron::Schedule>),
+    Interval(Duration),
+}
+
+impl Cadence {
+    pub fn parse(cron_expression: Option<&str>, interval_seconds: Option<i64>) -> Result<Self, String> {
+        match (cron_expression, interval_seconds) {
+            (Some(expr), None) => cron::Schedule::from_str(expr)
+                .map(|s| Cadence::Cron(Box::new(s)))
+                .map_err(|e| format!("Invalid cron expression: {}", e)),
+            (None, Some(secs)) => (60..=MAX_INTERVAL_SECS).contains(&secs)
+                .then(|| Duration::try_seconds(secs))
+                .flatten()
+                .map(Cadence::Interval)
+                .ok_or(format!("interval_seconds must be between 60 and {}", MAX_INTERVAL_SECS)),
+            _ => Err("Provide exactly one of cron_expression or interval_seconds".to_string()),
+        }
+    }
+}
+
+/// First occurrence strictly after `after`, honouring start/end dates and `max_occurrences`.
+/// `None` means the schedule is exhausted.
+pub fn next_occurrence(schedule: &PaymentSchedule, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+    if schedule.max_occurrences.is_some_and(|max| schedule.occurrences >= max) {
+        return Ok(None);
+    }
+
+    let floor = after.max(schedule.start_at - Duration::seconds(1));
+    let next = match Cadence::parse(schedule.cron_expression.as_deref(), schedule.interval_seconds)? {
+        Cadence::Cron(cron) => cron.after(&floor).next(),
+        Cadence::Interval(step) => {
+            let elapsed = (floor - schedule.start_at).num_seconds();
+            let periods = if elapsed < 0 { 0 } else { elapsed / step.num_seconds() + 1 };
+            i32::try_from(periods).ok()
+                .and_then(|periods| step.checked_mul(periods))
+                .and_then(|offset| schedule.start_at.checked_add_signed(offset))
+        }
+    };
+
+    Ok(next.filter(|at| schedule.end_at.is_none_or(|end| *at <= end)))
+}
+
+pub async fn find_schedule(pool: &PgPool, schedule_id: i32) -> Result<PaymentScheduleDetail, (StatusCode, String)> {
+    let schedule = sqlx::query_as::<_, PaymentSchedule>("SELECT * FROM payment_schedules WHERE id = $1")
+        .bind(schedule_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Payment schedule {} not found", schedule_id)))?;
+
+    let runs = sqlx::query_as::<_, ScheduleRun>("SELECT * FROM schedule_runs WHERE schedule_id = $1 ORDER BY id DESC")
+        .bind(schedule_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(PaymentScheduleDetail { schedule, runs })
+}
+
+/// Validates the payment and cadence up front and stores the schedule with its first run time.
+pub async fn create_schedule(
+    state: &AppState,
+    req: &CreateScheduleRequest,
+    actor: &str,
+) -> Result<PaymentScheduleDetail, (StatusCode, String)> {
+    find_wallet(&state.pool, req.wallet_id).await?;
+    let amount = intents::screen_payment(state, req.wallet_id, &req.target_address, &req.amount, req.token_address.as_deref(), actor)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let spend = Spend { token_address: req.token_address.clone(), amount };
+    agents::authorize(state, actor, req.wallet_id, &[spend]).await.map_err(|e| (StatusCode::FORBIDDEN, e))?;
+    Cadence::parse(req.cron_expression.as_deref(), req.interval_seconds).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    if req.max_occurrences.is_some_and(|max| max <= 0) {
+        return Err((StatusCode::BAD_REQUEST, "max_occurrences must be positive".to_string()));
+    }
+
+    let now = Utc::now();
+    let start_at = req.start_at.unwrap_or(now);
+    if req.end_at.is_some_and(|end| end <= start_at) {
+        return Err((StatusCode::BAD_REQUEST, "end_at must be after start_at".to_string()));
+    }
+
+    let draft = PaymentSchedule {
+        id: 0,
+        wallet_id: req.wallet_id,
+        target_address: req.target_address.to_lowercase(),
+        amount: req.amount.trim().to_string(),
+        token_address: req.token_address.as_ref().map(|t| t.to_lowercase()),
+        cron_expression: req.cron_expression.clone(),
+        interval_seconds: req.interval_seconds,
+        start_at,
+        end_at: req.end_at,
+        max_occurrences: req.max_occurrences,
+        occurrences: 0,
+        next_run_at: None,
+        status: "active".to_string(),
+        created_by: actor.to_string(),
+        created_at: now,
+        updated_at: now,
+    };
+    let next_run_at = next_occurrence(&draft, now - Duration::seconds(1))
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?
+        .ok_or((StatusCode::BAD_REQUEST, "Schedule has no occurrence before end_at".to_string()))?;
+
+    let schedule_id: i32 = sqlx::query_scalar(
+        r#"
+        INSERT INTO payment_schedules (wallet_id, target_address, amount, token_address, cron_expression,
+            interval_seconds, start_at, end_at, max_occurrences, next_run_at, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING id
+        "#
+    )
+    .bind(draft.wallet_id)
+    .bind(&draft.target_address)
+    .bind(&draft.amount)
+    .bind(&draft.token_address)
+    .bind(&draft.cron_expression)
+    .bind(draft.interval_seconds)
+    .bind(draft.start_at)
+    .bind(draft.end_at)
+    .bind(draft.max_occurrences)
+    .bind(next_run_at)
+    .bind(actor)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    find_schedule(&state.pool, schedule_id).await
+}
+
+/// Only the schedule's creator or an admin may pause or resume it.
+fn check_owner(claims: &Claims, schedule: &PaymentSchedule) -> Result<(), (StatusCode, String)> {
+    if schedule.created_by != claims.sub {
+        require_role(claims, ADMIN_ROLES)
+            .map_err(|_| (StatusCode::FORBIDDEN, format!("Payment schedule {} belongs to {}", schedule.id, schedule.created_by)))?;
+    }
+    Ok(())
+}
+
+pub async fn pause_schedule(pool: &PgPool, schedule_id: i32, claims: &Claims) -> Result<PaymentScheduleDetail, (StatusCode, String)> {
+    check_owner(claims, &find_schedule(pool, schedule_id).await?.schedule)?;
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let paused = sqlx::query("UPDATE payment_schedules SET status = 'paused', updated_at = NOW() WHERE id = $1 AND status = 'active'")
+        .bind(schedule_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if paused.rows_affected() == 0 {
+        let detail = find_schedule(pool, schedule_id).await?;
+        return Err((StatusCode::CONFLICT, format!("Payment schedule {} is {}", schedule_id, detail.schedule.status)));
+    }
+
+    audit::record(&mut *tx, &claims.sub, "schedule.pause", &format!("payment_schedule:{}", schedule_id), serde_json::json!({})).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    find_schedule(pool, schedule_id).await
+}
+
+/// Resumes from now: occurrences that fell due while paused are not made up.
+pub async fn resume_schedule(pool: &PgPool, schedule_id: i32, claims: &Claims) -> Result<PaymentScheduleDetail, (StatusCode, String)> {
+    let detail = find_schedule(pool, schedule_id).await?;
+    check_owner(claims, &detail.schedule)?;
+    if detail.schedule.status != "paused" {
+        return Err((StatusCode::CONFLICT, format!("Payment schedule {} is {}", schedule_id, detail.schedule.status)));
+    }
+
+    let next = next_occurrence(&detail.schedule, Utc::now()).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let status = if next.is_some() { "active" } else { "completed" };
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let resumed = sqlx::query("UPDATE payment_schedules SET status = $1, next_run_at = $2, updated_at = NOW() WHERE id = $3 AND status = 'paused'")
+        .bind(status)
+        .bind(next)
+        .bind(schedule_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if resumed.rows_affected() == 0 {
+        return Err((StatusCode::CONFLICT, format!("Payment schedule {} is no longer paused", schedule_id)));
+    }
+
+    audit::record(&mut *tx, &claims.sub, "schedule.resume", &format!("payment_schedule:{}", schedule_id), serde_json::json!({
+        "status": status,
+        "next_run_at": next,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    find_schedule(pool, schedule_id).await
+}
+
+/// Background worker: runs due schedules through the same payment intent path as `/api/agent/pay`.
+pub async fn run(state: AppState) {
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(POLL_INTERVAL_SECS));
+    loop {
+        ticker.tick().await;
+        if let Err((_, e)) = run_due(&state).await {
+            println!("Scheduler error: {}", e);
+        }
+    }
+}
+
+async fn run_due(state: &AppState) -> Result<(), (StatusCode, String)> {
+    let due = sqlx::query_as::<_, PaymentSchedule>(
+        "SELECT * FROM payment_schedules WHERE status = 'active' AND next_run_at <= NOW() ORDER BY next_run_at"
+    )
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for schedule in due {
+        if let Err((_, e)) = run_occurrence(state, schedule).await {
+            println!("Scheduler error: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_occurrence(state: &AppState, schedule: PaymentSchedule) -> Result<(), (StatusCode, String)> {
+    let Some(scheduled_for) = schedule.next_run_at else { return Ok(()) };
+
+    // Occurrences missed while the service was down are not replayed; the next run is computed from now
+    let advanced = PaymentSchedule { occurrences: schedule.occurrences + 1, ..schedule.clone() };
+    let next = next_occurrence(&advanced, Utc::now()).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    // Claim the occurrence; a concurrent worker or a pause in between makes this a no-op
+    let claimed = sqlx::query(
+        r#"
+        UPDATE payment_schedules
+        SET next_run_at = $1, occurrences = occurrences + 1, updated_at = NOW(),
+            status = CASE WHEN $1::TIMESTAMPTZ IS NULL THEN 'completed' ELSE status END
+        WHERE id = $2 AND status = 'active' AND next_run_at = $3
+        "#
+    )
+    .bind(next)
+    .bind(schedule.id)
+    .bind(scheduled_for)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if claimed.rows_affected() == 0 {
+        return Ok(());
+    }
+
+    // The occurrence is claimed, so every outcome from here on is recorded as a run
+    let (status, intent_id, reason) = match precheck(state, &schedule).await {
+        Err(reason) => ("skipped", None, Some(reason)),
+        Ok(()) => pay(state, &schedule).await,
+    };
+
+    sqlx::query("INSERT INTO schedule_runs (schedule_id, scheduled_for, status, intent_id, reason) VALUES ($1, $2, $3, $4, $5)")
+        .bind(schedule.id)
+        .bind(scheduled_for)
+        .bind(status)
+        .bind(intent_id)
+        .bind(reason)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(())
+}
+
+async fn pay(state: &AppState, schedule: &PaymentSchedule) -> (&'static str, Option<i32>, Option<String>) {
+    let request = CreateIntentRequest {
+        wallet_id: schedule.wallet_id,
+        target_address: schedule.target_address.clone(),
+        amount: schedule.amount.clone(),
+        token_address: schedule.token_address.clone(),
+        hold_for_review: false,
+    };
+    let actor = format!("schedule:{}", schedule.id);
+    let intent = match intents::create_intent(&state.pool, &request, &actor, &schedule.created_by).await {
+        Ok(intent) => intent,
+        Err((_, e)) => return ("failed", None, Some(e)),
+    };
+
+    let intent_id = intent.id;
+    match intents::process(state, intent, false).await {
+        Ok(intent) => (run_status(IntentStatus::parse(&intent.status)), Some(intent_id), intent.failure_reason),
+        Err((_, e)) => ("failed", Some(intent_id), Some(e)),
+    }
+}
+
+fn run_status(status: Option<IntentStatus>) -> &'static str {
+    match status {
+        Some(IntentStatus::Confirmed) => "confirmed",
+        Some(IntentStatus::PendingApproval) => "pending_approval",
+        Some(IntentStatus::Submitted) => "submitted",
+        _ => "failed",
+    }
+}
+
+/// Mirrors an intent's new status onto the schedule run it was created for, if any.
+/// Called from `intents::transition` within the same transaction.
+pub async fn track_intent(tx: &mut Transaction<'_, Postgres>, intent: &PaymentIntent) -> Result<(), (StatusCode, String)> {
+    let status = match IntentStatus::parse(&intent.status) {
+        Some(IntentStatus::Created | IntentStatus::Screened | IntentStatus::Approved) => return Ok(()),
+        status => run_status(status),
+    };
+
+    sqlx::query("UPDATE schedule_runs SET status = $1, reason = COALESCE($2, reason) WHERE intent_id = $3")
+        .bind(status)
+        .bind(intent.failure_reason.as_deref())
+        .bind(intent.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(())
+}
+
+/// Skips the run up front instead of letting `AegisRules` revert it on-chain.
+/// The schedule's creator must still be allowed to make the payment (e.g. not a suspended or frozen agent).
+async fn precheck(state: &AppState, schedule: &PaymentSchedule) -> Result<(), String> {
+    let amount = intents::screen_payment(
+        state,
+        schedule.wallet_id,
+        &schedule.target_address,
+        &schedule.amount,
+        schedule.token_address.as_deref(),
+        &schedule.created_by,
+    ).await?;
+    let spends = [Spend { token_address: schedule.token_address.clone(), amount }];
+    agents::authorize(state, &schedule.created_by, schedule.wallet_id, &spends).await?;
+
+    let wallet = find_wallet(&state.pool, schedule.wallet_id).await.map_err(|(_, e)| e)?;
+    limits::check_budget(state, &wallet.rules_contract, &spends).await
+}
@@ -1,4 +1,4 @@
-use axum::{extract::State, http::StatusCode, Json, response::{IntoResponse, Response}};
+use axum::{extract::State, http::StatusCode, Json};
 use sqlx::Row;
 use crate::state::AppState;
 use super::models::{AuthRequest, AuthResponse};
@@ -17,6 +17,15 @@ pub fn router() -> Router<AppState> {
         .route("/register", post(handlers::register_admin))
 }
 
+/// Rejects the request unless the caller holds one of `roles`.
+pub fn require_role(claims: &models::Claims, roles: &[&str]) -> Result<(), (StatusCode, String)> {
+    if roles.contains(&claims.role.as_str()) {
+        Ok(())
+    } else {
+        Err((StatusCode::FORBIDDEN, format!("Requires one of the roles: {}", roles.join(", "))))
+    }
+}
+
 // Extractor for JWT
 #[derive(Clone)]
 pub struct Claims(pub models::Claims);
@@ -0,0 +1,107 @@
+use ethers::prelude::*;
+use ethers::abi::{Detokenize, Tokenizable};
+use super::{ChainClient, AegisRulesContract, ERC20Contract};
+
+type Client = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+/// Calls per `aggregate3` round-trip; keeps each `eth_call` well under node gas caps.
+const MULTICALL_CHUNK: usize = 250;
+
+/// Upper bound on addresses accepted by a single bulk read endpoint.
+pub const MAX_BATCH_READ: usize = 1000;
+
+/// `AegisRules.agentRules` entry for one agent.
+pub struct AgentLimit {
+    pub daily_limit: U256,
+    pub spent_today: U256,
+    pub last_reset_time: U256,
+}
+
+impl AgentLimit {
+    /// Spend that still counts at unix time `now`, mirroring the 24h reset in `AegisRules.checkTransaction`.
+    pub fn spent_at(&self, now: u64) -> U256 {
+        if U256::from(now) >= self.last_reset_time + U256::from(86_400) {
+            U256::zero()
+        } else {
+            self.spent_today
+        }
+    }
+
+    pub fn remaining_at(&self, now: u64) -> U256 {
+        self.daily_limit.saturating_sub(self.spent_at(now))
+    }
+}
+
+/// Parses and bounds a list of addresses for a bulk read.
+pub fn parse_addresses(addresses: &[String]) -> Result<Vec<Address>, String> {
+    if addresses.len() > MAX_BATCH_READ {
+        return Err(format!("At most {} addresses per request", MAX_BATCH_READ));
+    }
+
+    addresses.iter()
+        .map(|a| a.parse::<Address>().map_err(|_| format!("Invalid address '{}'", a)))
+        .collect()
+}
+
+impl ChainClient {
+    async fn new_multicall(&self) -> Result<Multicall<Client>, Box<dyn std::error::Error>> {
+        Ok(Multicall::new(self.client(), self.multicall).await?)
+    }
+
+    /// Runs `calls` through Multicall3 in chunks. Individual failures yield `None` instead of failing the batch.
+    async fn aggregate<D: Detokenize>(&self, calls: Vec<ContractCall<Client, D>>) -> Result<Vec<Option<abi::Token>>, Box<dyn std::error::Error>> {
+        let mut multicall = self.new_multicall().await?;
+        let mut results = Vec::with_capacity(calls.len());
+        let mut calls = calls.into_iter().peekable();
+
+        while calls.peek().is_some() {
+            multicall.clear_calls();
+            multicall.add_calls(true, calls.by_ref().take(MULTICALL_CHUNK));
+            results.extend(multicall.call_raw().await?.into_iter().map(Result::ok));
+        }
+
+        Ok(results)
+    }
+
+    pub async fn batch_native_balances(&self, holders: &[Address]) -> Result<Vec<Option<U256>>, Box<dyn std::error::Error>> {
+        let mut multicall = self.new_multicall().await?;
+        let mut results = Vec::with_capacity(holders.len());
+
+        for chunk in holders.chunks(MULTICALL_CHUNK) {
+            multicall.clear_calls();
+            for holder in chunk {
+                multicall.add_get_eth_balance(*holder, true);
+            }
+            results.extend(multicall.call_raw().await?.into_iter().map(|r| r.ok().and_then(|t| t.into_uint())));
+        }
+
+        Ok(results)
+    }
+
+    pub async fn batch_token_balances(&self, token: Address, holders: &[Address]) -> Result<Vec<Option<U256>>, Box<dyn std::error::Error>> {
+        let contract = ERC20Contract::new(token, self.client());
+        let calls = holders.iter().map(|holder| contract.balance_of(*holder)).collect();
+
+        Ok(self.aggregate(calls).await?.into_iter().map(|t| t.and_then(|t| t.into_uint())).collect())
+    }
+
+    /// AegisID `balanceOf` for each holder (non-zero means the holder is a verified identity).
+    pub async fn batch_identity_balances(&self, holders: &[Address]) -> Result<Vec<Option<U256>>, Box<dyn std::error::Error>> {
+        let calls = holders.iter().map(|holder| self.contract.balance_of(*holder)).collect();
+
+        Ok(self.aggregate(calls).await?.into_iter().map(|t| t.and_then(|t| t.into_uint())).collect())
+    }
+
+    pub async fn batch_agent_limits(&self, rules: Address, agents: &[Address]) -> Result<Vec<Option<AgentLimit>>, Box<dyn std::error::Error>> {
+        let contract = AegisRulesContract::new(rules, self.client());
+        let calls = agents.iter().map(|agent| contract.agent_rules(*agent)).collect();
+
+        Ok(self.aggregate(calls).await?
+            .into_iter()
+            .map(|t| {
+                t.and_then(|t| <(U256, U256, U256)>::from_token(t).ok())
+                    .map(|(daily_limit, spent_today, last_reset_time)| AgentLimit { daily_limit, spent_today, last_reset_time })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,110 @@
+use ethers::prelude::*;
+use ethers::contract::parse_log;
+use super::{aegis_wallet_contract, erc20_contract, ChainClient};
+
+/// A balance change of one wallet read from a log. `token` is `None` for the native asset.
+pub struct WalletLog {
+    pub wallet: Address,
+    pub block_number: u64,
+    pub tx_hash: H256,
+    pub log_index: u64,
+    /// Name of the event the change was read from.
+    pub event: &'static str,
+    pub token: Option<Address>,
+    pub credit: bool,
+    pub amount: U256,
+    pub counterparty: Address,
+}
+
+impl ChainClient {
+    pub async fn block_number(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        Ok(self.client().get_block_number().await?.as_u64())
+    }
+
+    /// Unix timestamp of a mined block.
+    pub async fn block_timestamp(&self, number: u64) -> Result<u64, Box<dyn std::error::Error>> {
+        let block = self.client().get_block(number).await?.ok_or(format!("Block {} not found", number))?;
+        Ok(block.timestamp.as_u64())
+    }
+
+    /// Block a transaction was mined in, e.g. to find where a wallet's history starts.
+    pub async fn transaction_block(&self, tx_hash: &str) -> Result<u64, Box<dyn std::error::Error>> {
+        let receipt = self.client().get_transaction_receipt(tx_hash.parse::<TxHash>()?).await?
+            .ok_or(format!("Transaction {} not mined", tx_hash))?;
+        Ok(receipt.block_number.ok_or(format!("Transaction {} not mined", tx_hash))?.as_u64())
+    }
+
+    /// Balance changes of `wallets` in blocks `from..=to`, in chain order.
+    /// Native movements come from `AegisWallet.Received` and the value of `AegisWallet.Executed`; token movements
+    /// come from the ERC-20 `Transfer` logs of `tokens`, which also cover transfers that bypass `execute`
+    /// (`withdrawToken`, tokens sent to the wallet).
+    pub async fn wallet_logs(&self, wallets: &[Address], tokens: &[Address], from: u64, to: u64) -> Result<Vec<WalletLog>, Box<dyn std::error::Error>> {
+        if wallets.is_empty() {
+            return Ok(Vec::new());
+        }
+        let client = self.client();
+        let range = Filter::new().from_block(from).to_block(to);
+        let mut movements = Vec::new();
+
+        let wallet_events = range.clone()
+            .address(wallets.to_vec())
+            .topic0(ValueOrArray::Array(vec![
+                aegis_wallet_contract::ReceivedFilter::signature(),
+                aegis_wallet_contract::ExecutedFilter::signature(),
+            ]));
+        for log in client.get_logs(&wallet_events).await? {
+            let (wallet, Some((block_number, tx_hash, log_index))) = (log.address, position(&log)) else { continue };
+            match parse_log::<aegis_wallet_contract::AegisWalletContractEvents>(log)? {
+                aegis_wallet_contract::AegisWalletContractEvents::ReceivedFilter(event) => movements.push(WalletLog {
+                    wallet, block_number, tx_hash, log_index,
+                    event: "Received",
+                    token: None,
+                    credit: true,
+                    amount: event.amount,
+                    counterparty: event.sender,
+                }),
+                // Token calls carry no value; the token's own Transfer log records them
+                aegis_wallet_contract::AegisWalletContractEvents::ExecutedFilter(event) if !event.value.is_zero() => movements.push(WalletLog {
+                    wallet, block_number, tx_hash, log_index,
+                    event: "Executed",
+                    token: None,
+                    credit: false,
+                    amount: event.value,
+                    counterparty: event.target,
+                }),
+                _ => {}
+            }
+        }
+
+        if !tokens.is_empty() {
+            let holders: Vec<H256> = wallets.iter().map(|w| H256::from(*w)).collect();
+            let transfers = range.address(tokens.to_vec()).topic0(erc20_contract::TransferFilter::signature());
+            let outgoing = transfers.clone().topic1(ValueOrArray::Array(holders.clone()));
+            let incoming = transfers.topic2(ValueOrArray::Array(holders));
+
+            for (filter, credit) in [(outgoing, false), (incoming, true)] {
+                for log in client.get_logs(&filter).await? {
+                    let (token, Some((block_number, tx_hash, log_index))) = (log.address, position(&log)) else { continue };
+                    let event = parse_log::<erc20_contract::TransferFilter>(log)?;
+                    let (wallet, counterparty) = if credit { (event.to, event.from) } else { (event.from, event.to) };
+                    movements.push(WalletLog {
+                        wallet, block_number, tx_hash, log_index,
+                        event: "Transfer",
+                        token: Some(token),
+                        credit,
+                        amount: event.value,
+                        counterparty,
+                    });
+                }
+            }
+        }
+
+        movements.sort_by_key(|m| (m.block_number, m.log_index));
+        Ok(movements)
+    }
+}
+
+/// Block number, transaction hash and log index of a mined log.
+fn position(log: &Log) -> Option<(u64, H256, u64)> {
+    Some((log.block_number?.as_u64(), log.transaction_hash?, log.log_index?.as_u64()))
+}
@@ -1,7 +1,14 @@
 use ethers::prelude::*;
+use ethers::contract::{parse_log, EthError};
+use ethers::types::transaction::eip2718::TypedTransaction;
 use std::sync::Arc;
 use std::convert::TryFrom;
 
+mod batch;
+mod events;
+pub use batch::{parse_addresses, AgentLimit, MAX_BATCH_READ};
+pub use events::WalletLog;
+
 // Generate type-safe bindings
 abigen!(
     AegisIDContract,
@@ -18,10 +25,77 @@ abigen!(
     "artifacts/contracts/AegisRules.sol/AegisRules.json"
 );
 
+// Minimal ERC-20 surface used for the token registry, balances and movement indexing
+abigen!(
+    ERC20Contract,
+    r#"[
+        function symbol() external view returns (string)
+        function decimals() external view returns (uint8)
+        function balanceOf(address account) external view returns (uint256)
+        event Transfer(address indexed from, address indexed to, uint256 value)
+    ]"#
+);
+
+// Price source behind value-denominated limits (contracts/interfaces/IPriceOracle.sol)
+abigen!(
+    PriceOracleContract,
+    r#"[
+        function getPrice(address asset) external view returns (uint256)
+    ]"#
+);
+
+/// Result of dry-running a call through an AegisWallet.
+pub enum Simulation {
+    /// Raw return data of the target call.
+    Success(Bytes),
+    /// Decoded revert reason (or hex revert data when it isn't an `Error(string)`).
+    Reverted(String),
+}
+
+/// Decodes Solidity `Error(string)` revert data, falling back to hex.
+pub fn revert_reason(data: &Bytes) -> String {
+    String::decode_with_selector(data).unwrap_or_else(|| format!("{}", data))
+}
+
+/// A send rejected by the node with revert data (e.g. `AegisRules: Daily limit exceeded`) is reported by its reason.
+fn send_error<M: Middleware + 'static>(e: ContractError<M>) -> Box<dyn std::error::Error> {
+    match e.as_revert() {
+        Some(revert) => format!("Reverted: {}", revert_reason(revert)).into(),
+        None => e.into(),
+    }
+}
+
+fn receipt_status(receipt: &TransactionReceipt) -> TxStatus {
+    if receipt.status == Some(U64::zero()) {
+        TxStatus::Reverted
+    } else {
+        TxStatus::Confirmed
+    }
+}
+
+/// Mined-but-reverted transactions come back as a receipt with status 0.
+fn ensure_success(receipt: &TransactionReceipt) -> Result<(), Box<dyn std::error::Error>> {
+    if receipt.status == Some(U64::zero()) {
+        return Err(format!("Transaction {:?} reverted", receipt.transaction_hash).into());
+    }
+    Ok(())
+}
+
+/// Outcome of a sent transaction as far as the node knows it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxStatus {
+    Confirmed,
+    Reverted,
+    /// Still in the mempool.
+    Pending,
+    /// Neither mined nor pending: dropped or replaced.
+    Unknown,
+}
+
 #[derive(Clone)]
 pub struct ChainClient {
     contract: AegisIDContract<SignerMiddleware<Provider<Http>, LocalWallet>>,
-    wallet_contract_template: AegisWalletContract<SignerMiddleware<Provider<Http>, LocalWallet>>, // Template to attach to dynamic addresses
+    multicall: Option<Address>, // None uses the canonical Multicall3 address on supported chains
 }
 
 impl ChainClient {
@@ -41,51 +115,115 @@ impl ChainClient {
         let address: Address = contract_address.parse()?;
         let contract = AegisIDContract::new(address, client);
 
-        let address: Address = contract_address.parse()?;
-        let contract = AegisIDContract::new(address, client.clone());
-        
-        // We create a template instance just to have the type, 
-        // effectively we will just use the client to connect to dynamic addresses later
-        // or we can just instantiate new contracts on the fly in methods.
-        // Let's use the second approach for cleaner code, but we need to keep the client accessible.
-        // Actually, let's keep the client in the struct if we want.
-        // A better way is to store the client in the struct.
-        let wallet_contract_template = AegisWalletContract::new(Address::zero(), client);
+        // AegisWallet instances are attached on the fly per call (see `execute_native`),
+        // or deployed through `deploy_wallet`.
+        Ok(Self { contract, multicall: None })
+    }
 
-        Ok(Self { contract, wallet_contract_template })
+    /// Uses a locally deployed Multicall3 (e.g. on Hardhat) for batched reads.
+    pub fn with_multicall(mut self, multicall_address: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        self.multicall = Some(multicall_address.parse()?);
+        Ok(self)
     }
 
     pub fn client(&self) -> Arc<SignerMiddleware<Provider<Http>, LocalWallet>> {
         self.contract.client()
     }
 
-    pub async fn get_wallet_balance(&self, wallet_addr: &str) -> Result<String, Box<dyn std::error::Error>> {
+    /// Address of the backend signer, which owns every wallet it deploys.
+    pub fn signer_address(&self) -> Address {
+        self.client().address()
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.client().signer().chain_id()
+    }
+
+    /// Deploys a new AegisWallet bound to our AegisID registry; link its rules with `set_wallet_rules`.
+    /// Returns `(wallet_address, deploy_tx_hash)`.
+    pub async fn deploy_wallet(&self) -> Result<(String, String), Box<dyn std::error::Error>> {
+        let (wallet, receipt) = AegisWalletContract::deploy(self.client(), self.contract.address())?
+            .send_with_receipt()
+            .await?;
+
+        Ok((format!("{:?}", wallet.address()), format!("{:?}", receipt.transaction_hash)))
+    }
+
+    pub async fn set_wallet_rules(&self, wallet_addr: &str, rules_addr: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let wallet: Address = wallet_addr.parse()?;
+        let rules: Address = rules_addr.parse()?;
+
+        let contract = AegisWalletContract::new(wallet, self.client());
+        let call = contract.set_rules_contract(rules);
+        let pending = call.send().await?;
+        let receipt = pending.await?.ok_or("Transaction dropped")?;
+
+        Ok(format!("{:?}", receipt.transaction_hash))
+    }
+
+    /// Emergency stop on an AegisWallet (blocks `execute`/`executeERC20`). Returns the tx hash.
+    pub async fn pause_wallet(&self, wallet_addr: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let wallet: Address = wallet_addr.parse()?;
+        let contract = AegisWalletContract::new(wallet, self.client());
+        let call = contract.pause();
+        let pending = call.send().await?;
+        let receipt = pending.await?.ok_or("Transaction dropped")?;
+        ensure_success(&receipt)?;
+
+        Ok(format!("{:?}", receipt.transaction_hash))
+    }
+
+    pub async fn unpause_wallet(&self, wallet_addr: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let wallet: Address = wallet_addr.parse()?;
+        let contract = AegisWalletContract::new(wallet, self.client());
+        let call = contract.unpause();
+        let pending = call.send().await?;
+        let receipt = pending.await?.ok_or("Transaction dropped")?;
+        ensure_success(&receipt)?;
+
+        Ok(format!("{:?}", receipt.transaction_hash))
+    }
+
+    pub async fn get_wallet_balance(&self, wallet_addr: &str) -> Result<U256, Box<dyn std::error::Error>> {
         let addr: Address = wallet_addr.parse()?;
         // Use provider directly for ETH balance
         let balance = self.client().provider().get_balance(addr, None).await?;
-        Ok(balance.to_string())
+        Ok(balance)
     }
 
-    pub async fn fund_wallet(&self, wallet_addr: &str, amount_eth: &str) -> Result<String, Box<dyn std::error::Error>> {
-         let to_addr: Address = wallet_addr.parse()?;
-         let value = amount_eth.parse::<U256>()?; // Assuming amount is in wei for simplicity or use ethers utils
-         // Actually let's assume input is standard decimal string, we might need parse_ether
-         // For MVP, allow raw value or standard eth. Let's use parse_ether.
-         let val_wei = ethers::utils::parse_ether(amount_eth)?;
+    /// Reads `(symbol, decimals)` from an ERC-20 contract.
+    pub async fn get_token_info(&self, token_addr: &str) -> Result<(String, u8), Box<dyn std::error::Error>> {
+        let token: Address = token_addr.parse()?;
+        let contract = ERC20Contract::new(token, self.client());
+        let symbol = contract.symbol().call().await?;
+        let decimals = contract.decimals().call().await?;
+        Ok((symbol, decimals))
+    }
 
-         let tx = TransactionRequest::new()
+    pub async fn get_token_balance(&self, token_addr: &str, holder_addr: &str) -> Result<U256, Box<dyn std::error::Error>> {
+        let token: Address = token_addr.parse()?;
+        let holder: Address = holder_addr.parse()?;
+        let contract = ERC20Contract::new(token, self.client());
+        Ok(contract.balance_of(holder).call().await?)
+    }
+
+    pub async fn fund_wallet(&self, wallet_addr: &str, value: U256) -> Result<String, Box<dyn std::error::Error>> {
+        let to_addr: Address = wallet_addr.parse()?;
+
+        let tx = TransactionRequest::new()
             .to(to_addr)
-            .value(val_wei);
+            .value(value);
         
-        let pending = self.client().send_transaction(tx, None).await?;
+        let client = self.client();
+        let pending = client.send_transaction(tx, None).await?;
         let receipt = pending.await?.ok_or("Transaction dropped")?;
+        ensure_success(&receipt)?;
         Ok(format!("{:?}", receipt.transaction_hash))
     }
 
-    pub async fn set_agent_limit(&self, rules_addr: &str, agent_addr: &str, limit_eth: &str) -> Result<String, Box<dyn std::error::Error>> {
+    pub async fn set_agent_limit(&self, rules_addr: &str, agent_addr: &str, limit: U256) -> Result<String, Box<dyn std::error::Error>> {
         let rules: Address = rules_addr.parse()?;
         let agent: Address = agent_addr.parse()?;
-        let limit = ethers::utils::parse_ether(limit_eth)?;
         
         let contract = AegisRulesContract::new(rules, self.client());
         let call = contract.set_limit(agent, limit);
@@ -95,43 +233,257 @@ impl ChainClient {
         Ok(format!("{:?}", receipt.transaction_hash))
     }
 
-    pub async fn execute_erc20(&self, wallet_addr: &str, token_addr: &str, to_addr: &str, amount: &str) -> Result<String, Box<dyn std::error::Error>> {
+    /// Bars (or re-admits) `agent_addr` on the given rules contract, whatever its limits. Returns the tx hash.
+    pub async fn set_agent_blocked(&self, rules_addr: &str, agent_addr: &str, blocked: bool) -> Result<String, Box<dyn std::error::Error>> {
+        let rules: Address = rules_addr.parse()?;
+        let agent: Address = agent_addr.parse()?;
+
+        let contract = AegisRulesContract::new(rules, self.client());
+        let call = contract.set_blocked(agent, blocked);
+        let pending = call.send().await?;
+        let receipt = pending.await?.ok_or("Transaction dropped")?;
+        ensure_success(&receipt)?;
+
+        Ok(format!("{:?}", receipt.transaction_hash))
+    }
+
+    /// Current `AegisRules` budget of `agent_addr` on the given rules contract.
+    pub async fn get_agent_limit(&self, rules_addr: &str, agent_addr: Address) -> Result<AgentLimit, Box<dyn std::error::Error>> {
+        let rules: Address = rules_addr.parse()?;
+        let contract = AegisRulesContract::new(rules, self.client());
+        let (daily_limit, spent_today, last_reset_time) = contract.agent_rules(agent_addr).call().await?;
+        Ok(AgentLimit { daily_limit, spent_today, last_reset_time })
+    }
+
+    /// Per-token budget (`token` zero for native ETH), in the token's base units.
+    pub async fn set_token_limit(&self, rules_addr: &str, agent_addr: &str, token: Address, limit: U256) -> Result<String, Box<dyn std::error::Error>> {
+        let rules: Address = rules_addr.parse()?;
+        let agent: Address = agent_addr.parse()?;
+
+        let contract = AegisRulesContract::new(rules, self.client());
+        let call = contract.set_token_limit(agent, token, limit);
+        let pending = call.send().await?;
+        let receipt = pending.await?.ok_or("Transaction dropped")?;
+        ensure_success(&receipt)?;
+
+        Ok(format!("{:?}", receipt.transaction_hash))
+    }
+
+    pub async fn remove_token_limit(&self, rules_addr: &str, agent_addr: &str, token: Address) -> Result<String, Box<dyn std::error::Error>> {
+        let rules: Address = rules_addr.parse()?;
+        let agent: Address = agent_addr.parse()?;
+
+        let contract = AegisRulesContract::new(rules, self.client());
+        let call = contract.remove_token_limit(agent, token);
+        let pending = call.send().await?;
+        let receipt = pending.await?.ok_or("Transaction dropped")?;
+        ensure_success(&receipt)?;
+
+        Ok(format!("{:?}", receipt.transaction_hash))
+    }
+
+    /// Budget across all assets in the price oracle's reference currency (18 decimals).
+    pub async fn set_value_limit(&self, rules_addr: &str, agent_addr: &str, limit: U256) -> Result<String, Box<dyn std::error::Error>> {
+        let rules: Address = rules_addr.parse()?;
+        let agent: Address = agent_addr.parse()?;
+
+        let contract = AegisRulesContract::new(rules, self.client());
+        let call = contract.set_value_limit(agent, limit);
+        let pending = call.send().await?;
+        let receipt = pending.await?.ok_or("Transaction dropped")?;
+        ensure_success(&receipt)?;
+
+        Ok(format!("{:?}", receipt.transaction_hash))
+    }
+
+    pub async fn remove_value_limit(&self, rules_addr: &str, agent_addr: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let rules: Address = rules_addr.parse()?;
+        let agent: Address = agent_addr.parse()?;
+
+        let contract = AegisRulesContract::new(rules, self.client());
+        let call = contract.remove_value_limit(agent);
+        let pending = call.send().await?;
+        let receipt = pending.await?.ok_or("Transaction dropped")?;
+        ensure_success(&receipt)?;
+
+        Ok(format!("{:?}", receipt.transaction_hash))
+    }
+
+    pub async fn set_price_oracle(&self, rules_addr: &str, oracle_addr: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let rules: Address = rules_addr.parse()?;
+        let oracle: Address = oracle_addr.parse()?;
+
+        let contract = AegisRulesContract::new(rules, self.client());
+        let call = contract.set_price_oracle(oracle);
+        let pending = call.send().await?;
+        let receipt = pending.await?.ok_or("Transaction dropped")?;
+        ensure_success(&receipt)?;
+
+        Ok(format!("{:?}", receipt.transaction_hash))
+    }
+
+    /// `AegisRules.tokenRules` entry, or `None` when no per-token limit is configured.
+    pub async fn get_token_limit(&self, rules_addr: &str, agent_addr: Address, token: Address) -> Result<Option<AgentLimit>, Box<dyn std::error::Error>> {
+        let rules: Address = rules_addr.parse()?;
+        let contract = AegisRulesContract::new(rules, self.client());
+        if !contract.has_token_limit(agent_addr, token).call().await? {
+            return Ok(None);
+        }
+        let (daily_limit, spent_today, last_reset_time) = contract.token_rules(agent_addr, token).call().await?;
+        Ok(Some(AgentLimit { daily_limit, spent_today, last_reset_time }))
+    }
+
+    /// `AegisRules.valueRules` entry, or `None` when no value limit is configured.
+    pub async fn get_value_limit(&self, rules_addr: &str, agent_addr: Address) -> Result<Option<AgentLimit>, Box<dyn std::error::Error>> {
+        let rules: Address = rules_addr.parse()?;
+        let contract = AegisRulesContract::new(rules, self.client());
+        if !contract.has_value_limit(agent_addr).call().await? {
+            return Ok(None);
+        }
+        let (daily_limit, spent_today, last_reset_time) = contract.value_rules(agent_addr).call().await?;
+        Ok(Some(AgentLimit { daily_limit, spent_today, last_reset_time }))
+    }
+
+    /// Oracle configured on a rules contract (zero when unset).
+    pub async fn get_price_oracle(&self, rules_addr: &str) -> Result<Address, Box<dyn std::error::Error>> {
+        let rules: Address = rules_addr.parse()?;
+        let contract = AegisRulesContract::new(rules, self.client());
+        Ok(contract.price_oracle().call().await?)
+    }
+
+    /// `IPriceOracle.getPrice`: one whole unit of `asset` (zero for native ETH) in the reference currency, 18 decimals.
+    pub async fn get_oracle_price(&self, oracle: Address, asset: Address) -> Result<U256, Box<dyn std::error::Error>> {
+        let contract = PriceOracleContract::new(oracle, self.client());
+        Ok(contract.get_price(asset).call().await?)
+    }
+
+    /// `amount` is in the token's base units; callers convert from decimals via `finance::amounts`.
+    pub async fn execute_erc20(&self, wallet_addr: &str, token_addr: &str, to_addr: &str, amount: U256) -> Result<String, Box<dyn std::error::Error>> {
+        let tx_hash = self.send_erc20(wallet_addr, token_addr, to_addr, amount).await?;
+        self.confirm(tx_hash).await
+    }
+
+    /// Sends `AegisWallet.executeERC20` and returns the tx hash as soon as the node accepts it.
+    pub async fn send_erc20(&self, wallet_addr: &str, token_addr: &str, to_addr: &str, amount: U256) -> Result<TxHash, Box<dyn std::error::Error>> {
         let wallet: Address = wallet_addr.parse()?;
         let token: Address = token_addr.parse()?;
         let to: Address = to_addr.parse()?;
-        // Assuming conversion is done upstream or raw value passed. 
-        // Let's assume raw string value for tokens (decimals vary).
-        let val = amount.parse::<U256>()?;
 
         // We need to attach AegisWallet template to the specific wallet address
         let contract = AegisWalletContract::new(wallet, self.client());
-        let call = contract.execute_erc_20(token, to, val);
-        let pending = call.send().await?;
-        let receipt = pending.await?.ok_or("Transaction dropped")?;
+        let call = contract.execute_erc20(token, to, amount);
+        let pending = call.send().await.map_err(send_error)?;
+        Ok(pending.tx_hash())
+    }
 
-        Ok(format!("{:?}", receipt.transaction_hash))
+    pub async fn execute_native(&self, wallet_addr: &str, target_addr: &str, value: U256) -> Result<String, Box<dyn std::error::Error>> {
+        // Empty data for simple transfer
+        self.execute_call(wallet_addr, target_addr, value, Bytes::new()).await
     }
 
-    pub async fn execute_native(&self, wallet_addr: &str, target_addr: &str, amount_eth: &str) -> Result<String, Box<dyn std::error::Error>> {
+    /// Sends `AegisWallet.execute(target, value, data)` and returns the tx hash.
+    pub async fn execute_call(&self, wallet_addr: &str, target_addr: &str, value: U256, data: Bytes) -> Result<String, Box<dyn std::error::Error>> {
+        let tx_hash = self.send_call(wallet_addr, target_addr, value, data).await?;
+        self.confirm(tx_hash).await
+    }
+
+    /// Like `execute_call`, but returns the tx hash as soon as the node accepts the transaction.
+    pub async fn send_call(&self, wallet_addr: &str, target_addr: &str, value: U256, data: Bytes) -> Result<TxHash, Box<dyn std::error::Error>> {
         let wallet: Address = wallet_addr.parse()?;
         let target: Address = target_addr.parse()?;
-        let val = ethers::utils::parse_ether(amount_eth)?;
-        let data = ethers::types::Bytes::new(); // Empty data for simple transfer
 
         let contract = AegisWalletContract::new(wallet, self.client());
-        let call = contract.execute(target, val, data);
-        let pending = call.send().await?;
-        let receipt = pending.await?.ok_or("Transaction dropped")?;
+        let call = contract.execute(target, value, data);
+        let pending = call.send().await.map_err(send_error)?;
+        Ok(pending.tx_hash())
+    }
 
-        Ok(format!("{:?}", receipt.transaction_hash))
+    /// Waits for a sent transaction to be mined. `Err` means the outcome is not known yet
+    /// (RPC failure, or the transaction left the mempool), not that it failed.
+    pub async fn wait_for_receipt(&self, tx_hash: TxHash) -> Result<TxStatus, Box<dyn std::error::Error>> {
+        let client = self.client();
+        let receipt = PendingTransaction::new(tx_hash, client.provider()).await?.ok_or("Transaction dropped")?;
+        Ok(receipt_status(&receipt))
+    }
+
+    /// Looks up a previously sent transaction, e.g. to settle payments whose receipt was never seen.
+    pub async fn transaction_status(&self, tx_hash: &str) -> Result<TxStatus, Box<dyn std::error::Error>> {
+        let tx_hash: TxHash = tx_hash.parse()?;
+        let provider = self.client().provider().clone();
+        if let Some(receipt) = provider.get_transaction_receipt(tx_hash).await? {
+            return Ok(receipt_status(&receipt));
+        }
+        Ok(match provider.get_transaction(tx_hash).await? {
+            Some(_) => TxStatus::Pending,
+            None => TxStatus::Unknown,
+        })
+    }
+
+    async fn confirm(&self, tx_hash: TxHash) -> Result<String, Box<dyn std::error::Error>> {
+        match self.wait_for_receipt(tx_hash).await? {
+            TxStatus::Confirmed => Ok(format!("{:?}", tx_hash)),
+            _ => Err(format!("Transaction {:?} reverted", tx_hash).into()),
+        }
+    }
+
+    /// Dry-runs a wallet call without broadcasting it.
+    /// The target is first called with the wallet as `msg.sender` to capture its return data or revert reason,
+    /// then `AegisWallet.execute` is simulated to surface identity and rule failures.
+    pub async fn simulate_execute(&self, wallet_addr: &str, target_addr: &str, value: U256, data: Bytes) -> Result<Simulation, Box<dyn std::error::Error>> {
+        let wallet: Address = wallet_addr.parse()?;
+        let target: Address = target_addr.parse()?;
+
+        let inner: TypedTransaction = TransactionRequest::new()
+            .from(wallet)
+            .to(target)
+            .value(value)
+            .data(data.clone())
+            .into();
+
+        let output = match self.client().provider().call(&inner, None).await {
+            Ok(output) => output,
+            Err(e) => match RpcError::as_error_response(&e).and_then(|r| r.as_revert_data()) {
+                Some(revert) => return Ok(Simulation::Reverted(revert_reason(&revert))),
+                None => return Err(e.into()),
+            },
+        };
+
+        let contract = AegisWalletContract::new(wallet, self.client());
+        if let Err(e) = contract.execute(target, value, data).call().await {
+            return match e.as_revert() {
+                Some(revert) => Ok(Simulation::Reverted(revert_reason(revert))),
+                None => Err(e.into()),
+            };
+        }
+
+        Ok(Simulation::Success(output))
     }
     
-    pub async fn mint(&self, to: &str, uri: &str) -> Result<String, Box<dyn std::error::Error>> {
+    /// Mints an AegisID and returns `(tx_hash, token_id)`.
+    /// The token id is read back from the ERC-721 `Transfer` event emitted by `_safeMint`.
+    pub async fn mint(&self, to: &str, uri: &str) -> Result<(String, String), Box<dyn std::error::Error>> {
         let to_addr: Address = to.parse()?;
         let call = self.contract.mint(to_addr, uri.to_string());
         let pending_tx = call.send().await?;
         let receipt = pending_tx.await?.ok_or("Transaction dropped")?;
+
+        let token_id = receipt.logs.iter()
+            .filter(|log| log.address == self.contract.address())
+            .find_map(|log| parse_log::<aegis_id_contract::TransferFilter>(log.clone()).ok())
+            .map(|event| event.token_id.to_string())
+            .ok_or("Mint receipt has no Transfer event")?;
         
+        Ok((format!("{:?}", receipt.transaction_hash), token_id))
+    }
+
+    /// Burns an AegisID (`token_id` in decimal), recording `reason` in the `Revoked` event. Returns the tx hash.
+    pub async fn revoke_identity(&self, token_id: &str, reason: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let token_id = U256::from_dec_str(token_id)?;
+        let call = self.contract.revoke(token_id, reason.to_string());
+        let pending = call.send().await.map_err(send_error)?;
+        let receipt = pending.await?.ok_or("Transaction dropped")?;
+        ensure_success(&receipt)?;
+
         Ok(format!("{:?}", receipt.transaction_hash))
     }
 }
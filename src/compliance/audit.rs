@@ -0,0 +1,25 @@
+use axum::http::StatusCode;
+use sqlx::{Executor, Postgres};
+
+/// Appends an entry to `compliance_audit_log`. Pass a transaction to record it atomically with the change.
+pub async fn record<'e, E>(
+    executor: E,
+    actor: &str,
+    action: &str,
+    subject: &str,
+    details: serde_json::Value,
+) -> Result<(), (StatusCode, String)>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("INSERT INTO compliance_audit_log (actor, action, subject, details) VALUES ($1, $2, $3, $4)")
+        .bind(actor)
+        .bind(action)
+        .bind(subject)
+        .bind(details)
+        .execute(executor)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(())
+}
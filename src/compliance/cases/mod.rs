@@ -0,0 +1,620 @@
+pub mod report;
+pub mod sla;
+
+use axum::http::StatusCode;
+use sqlx::{PgPool, Postgres, Transaction};
+use super::documents::DocumentStore;
+use super::kyc::clean_file_name;
+use super::metadata;
+use super::models::{
+    AddCaseCommentRequest, CaseAttachmentUpload, CaseLinkRequest, ComplianceCase, ComplianceCaseAttachment, ComplianceCaseComment,
+    ComplianceCaseDetail, ComplianceCaseEvent, ComplianceCaseLink, CreateCaseRequest, UpdateCaseRequest,
+};
+use super::{audit, COMPLIANCE_ROLES};
+
+pub const PRIORITIES: &[&str] = &["low", "medium", "high", "critical"];
+
+pub const LINK_ENTITY: &str = "entity";
+pub const LINK_WALLET: &str = "wallet";
+pub const LINK_INTENT: &str = "intent";
+pub const LINK_MOVEMENT: &str = "movement";
+pub const LINK_MONITORING_ALERT: &str = "monitoring_alert";
+pub const LINK_SANCTIONS_HIT: &str = "sanctions_hit";
+pub const LINK_KYC_CASE: &str = "kyc_case";
+
+pub const LINK_TYPES: &[&str] = &[
+    LINK_ENTITY,
+    LINK_WALLET,
+    LINK_INTENT,
+    LINK_MOVEMENT,
+    LINK_MONITORING_ALERT,
+    LINK_SANCTIONS_HIT,
+    LINK_KYC_CASE,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseStatus {
+    Open,
+    Investigating,
+    Escalated,
+    Reported,
+    Closed,
+}
+
+impl CaseStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaseStatus::Open => "open",
+            CaseStatus::Investigating => "investigating",
+            CaseStatus::Escalated => "escalated",
+            CaseStatus::Reported => "reported",
+            CaseStatus::Closed => "closed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "open" => Some(CaseStatus::Open),
+            "investigating" => Some(CaseStatus::Investigating),
+            "escalated" => Some(CaseStatus::Escalated),
+            "reported" => Some(CaseStatus::Reported),
+            "closed" => Some(CaseStatus::Closed),
+            _ => None,
+        }
+    }
+
+    /// Cases still awaiting a decision run against their SLA deadline.
+    pub fn is_pending(&self) -> bool {
+        matches!(self, CaseStatus::Open | CaseStatus::Investigating | CaseStatus::Escalated)
+    }
+
+    /// Allowed edges of the case workflow. An escalated case is either filed, closed without a report,
+    /// or sent back for further investigation.
+    pub fn can_transition_to(&self, next: CaseStatus) -> bool {
+        use CaseStatus::*;
+        matches!(
+            (self, next),
+            (Open, Investigating) | (Open, Closed)
+                | (Investigating, Escalated) | (Investigating, Closed)
+                | (Escalated, Investigating) | (Escalated, Reported) | (Escalated, Closed)
+                | (Reported, Closed)
+        )
+    }
+
+    /// Whether `actor` may take a case in this status elsewhere. The assignee drives the investigation;
+    /// the decision on an escalated case is made by someone else (four-eyes).
+    pub fn can_decide(&self, assigned_to: Option<&str>, actor: &str) -> bool {
+        match self {
+            CaseStatus::Investigating => assigned_to == Some(actor),
+            CaseStatus::Escalated => assigned_to != Some(actor),
+            CaseStatus::Open | CaseStatus::Reported => true,
+            CaseStatus::Closed => false,
+        }
+    }
+}
+
+pub async fn find_case(pool: &PgPool, case_id: i32) -> Result<ComplianceCase, (StatusCode, String)> {
+    sqlx::query_as::<_, ComplianceCase>("SELECT * FROM compliance_cases WHERE id = $1")
+        .bind(case_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Case {} not found", case_id)))
+}
+
+pub async fn case_links(pool: &PgPool, case_id: i32) -> Result<Vec<ComplianceCaseLink>, (StatusCode, String)> {
+    sqlx::query_as::<_, ComplianceCaseLink>("SELECT * FROM compliance_case_links WHERE case_id = $1 ORDER BY id")
+        .bind(case_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+pub async fn case_attachments(pool: &PgPool, case_id: i32) -> Result<Vec<ComplianceCaseAttachment>, (StatusCode, String)> {
+    sqlx::query_as::<_, ComplianceCaseAttachment>("SELECT * FROM compliance_case_attachments WHERE case_id = $1 ORDER BY id")
+        .bind(case_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+pub async fn case_detail(pool: &PgPool, case_id: i32) -> Result<ComplianceCaseDetail, (StatusCode, String)> {
+    let case = find_case(pool, case_id).await?;
+    let links = case_links(pool, case.id).await?;
+
+    let events = sqlx::query_as::<_, ComplianceCaseEvent>("SELECT * FROM compliance_case_events WHERE case_id = $1 ORDER BY id")
+        .bind(case.id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let comments = sqlx::query_as::<_, ComplianceCaseComment>("SELECT * FROM compliance_case_comments WHERE case_id = $1 ORDER BY id")
+        .bind(case.id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let attachments = case_attachments(pool, case.id).await?;
+
+    Ok(ComplianceCaseDetail { case, links, events, comments, attachments })
+}
+
+/// Opens a case with its initial links. The SLA deadline defaults to the one of its priority.
+pub async fn open(pool: &PgPool, payload: CreateCaseRequest, actor: &str) -> Result<ComplianceCase, (StatusCode, String)> {
+    let title = payload.title.trim();
+    if title.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "title is required".to_string()));
+    }
+    validate_priority(&payload.priority)?;
+    let now = chrono::Utc::now();
+    let due_at = payload.due_at.unwrap_or_else(|| sla::due_at(&payload.priority, now));
+    if due_at <= now {
+        return Err((StatusCode::BAD_REQUEST, "due_at must be in the future".to_string()));
+    }
+    for link in &payload.links {
+        check_link_target(pool, &link.link_type, link.link_id).await?;
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let case = sqlx::query_as::<_, ComplianceCase>(
+        r#"
+        INSERT INTO compliance_cases (title, priority, narrative, opened_by, due_at)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#
+    )
+    .bind(title)
+    .bind(&payload.priority)
+    .bind(payload.narrative.as_deref().map(str::trim).filter(|n| !n.is_empty()))
+    .bind(actor)
+    .bind(due_at)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    record_event(&mut tx, case.id, None, CaseStatus::Open, actor, None).await?;
+    for link in &payload.links {
+        insert_link(&mut tx, case.id, link, actor).await?;
+    }
+
+    audit::record(&mut *tx, actor, "case.open", &format!("compliance_case:{}", case.id), serde_json::json!({
+        "title": case.title,
+        "priority": case.priority,
+        "due_at": case.due_at,
+        "links": payload.links.iter().map(|l| format!("{}:{}", l.link_type, l.link_id)).collect::<Vec<_>>(),
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(case)
+}
+
+/// Edits the title, narrative, priority or deadline of a case that is not closed. A new priority moves the
+/// deadline to the priority's SLA from opening, or to now if that has passed. A later deadline re-arms the SLA
+/// warning and breach.
+pub async fn update(pool: &PgPool, case: ComplianceCase, payload: UpdateCaseRequest, actor: &str) -> Result<ComplianceCase, (StatusCode, String)> {
+    check_not_closed(&case)?;
+    let title = match payload.title.as_deref().map(str::trim) {
+        Some("") => return Err((StatusCode::BAD_REQUEST, "title is required".to_string())),
+        title => title,
+    };
+    if let Some(priority) = &payload.priority {
+        validate_priority(priority)?;
+    }
+    let now = chrono::Utc::now();
+    let due_at = match (payload.due_at, &payload.priority) {
+        (Some(due_at), _) if due_at <= now => return Err((StatusCode::BAD_REQUEST, "due_at must be in the future".to_string())),
+        (Some(due_at), _) => Some(due_at),
+        // A raised priority can put the new deadline behind us; the SLA job then reports the case as breached
+        (None, Some(priority)) if *priority != case.priority => Some(sla::due_at(priority, case.created_at).max(now)),
+        _ => None,
+    };
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let updated = sqlx::query_as::<_, ComplianceCase>(
+        r#"
+        UPDATE compliance_cases
+        SET title = COALESCE($1, title), priority = COALESCE($2, priority), narrative = COALESCE($3, narrative),
+            due_at = COALESCE($4, due_at),
+            sla_warned_at = CASE WHEN $4 IS NULL THEN sla_warned_at END,
+            sla_breached_at = CASE WHEN $4 IS NULL THEN sla_breached_at END,
+            updated_at = NOW()
+        WHERE id = $5 AND status <> 'closed'
+        RETURNING *
+        "#
+    )
+    .bind(title)
+    .bind(&payload.priority)
+    .bind(payload.narrative.as_deref().map(str::trim))
+    .bind(due_at)
+    .bind(case.id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::CONFLICT, format!("Case {} is closed", case.id)))?;
+
+    audit::record(&mut *tx, actor, "case.update", &format!("compliance_case:{}", case.id), serde_json::json!({
+        "title": title,
+        "priority": payload.priority,
+        "narrative_changed": payload.narrative.is_some(),
+        "due_at": due_at,
+        "previous_due_at": case.due_at,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(updated)
+}
+
+/// Assigns a pending case to a compliance user; an open case moves to `investigating`.
+pub async fn assign(pool: &PgPool, case: ComplianceCase, assignee: &str, actor: &str) -> Result<ComplianceCase, (StatusCode, String)> {
+    let status = parse_status(&case)?;
+    if !status.is_pending() {
+        return Err((StatusCode::CONFLICT, format!("Case {} is {}", case.id, case.status)));
+    }
+    let role: Option<String> = sqlx::query_scalar("SELECT role FROM users WHERE username = $1")
+        .bind(assignee)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !role.is_some_and(|r| COMPLIANCE_ROLES.contains(&r.as_str())) {
+        return Err((StatusCode::BAD_REQUEST, format!("{} is not a compliance user", assignee)));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let updated = sqlx::query_as::<_, ComplianceCase>(
+        "UPDATE compliance_cases SET assigned_to = $1, updated_at = NOW() WHERE id = $2 AND status = $3 RETURNING *"
+    )
+    .bind(assignee)
+    .bind(case.id)
+    .bind(status.as_str())
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::CONFLICT, format!("Case {} is no longer {}", case.id, status.as_str())))?;
+
+    let note = format!("Assigned to {}", assignee);
+    let case = match status {
+        CaseStatus::Open => transition(&mut tx, case.id, status, CaseStatus::Investigating, actor, Some(&note)).await?,
+        _ => {
+            record_event(&mut tx, case.id, Some(status), status, actor, Some(&note)).await?;
+            updated
+        }
+    };
+
+    audit::record(&mut *tx, actor, "case.assign", &format!("compliance_case:{}", case.id), serde_json::json!({
+        "assignee": assignee,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(case)
+}
+
+/// Escalates, returns or closes a case (see `CaseStatus::can_decide` for who may). Closing needs a reason.
+pub async fn decide(
+    pool: &PgPool,
+    case: ComplianceCase,
+    to: CaseStatus,
+    actor: &str,
+    reason: Option<&str>,
+) -> Result<ComplianceCase, (StatusCode, String)> {
+    let status = check_decision(&case, to, actor)?;
+    if to == CaseStatus::Closed && reason.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "A reason is required".to_string()));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let case = transition(&mut tx, case.id, status, to, actor, reason).await?;
+    audit::record(&mut *tx, actor, &format!("case.{}", to.as_str()), &format!("compliance_case:{}", case.id), serde_json::json!({
+        "from": status.as_str(),
+        "reason": reason,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(case)
+}
+
+/// Records that the report on an escalated case was filed, with the reference the regulator or FIU gave it.
+pub async fn file_report(
+    pool: &PgPool,
+    case: ComplianceCase,
+    filing_reference: &str,
+    note: Option<&str>,
+    actor: &str,
+) -> Result<ComplianceCase, (StatusCode, String)> {
+    let filing_reference = filing_reference.trim();
+    if filing_reference.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "filing_reference is required".to_string()));
+    }
+    let status = check_decision(&case, CaseStatus::Reported, actor)?;
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    transition(&mut tx, case.id, status, CaseStatus::Reported, actor, note).await?;
+    let case = sqlx::query_as::<_, ComplianceCase>(
+        "UPDATE compliance_cases SET filing_reference = $1, filed_by = $2, filed_at = NOW() WHERE id = $3 RETURNING *"
+    )
+    .bind(filing_reference)
+    .bind(actor)
+    .bind(case.id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit::record(&mut *tx, actor, "case.reported", &format!("compliance_case:{}", case.id), serde_json::json!({
+        "filing_reference": filing_reference,
+        "note": note,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(case)
+}
+
+pub async fn add_link(pool: &PgPool, case: &ComplianceCase, link: &CaseLinkRequest, actor: &str) -> Result<ComplianceCaseLink, (StatusCode, String)> {
+    check_not_closed(case)?;
+    check_link_target(pool, &link.link_type, link.link_id).await?;
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let added = insert_link(&mut tx, case.id, link, actor).await?;
+    audit::record(&mut *tx, actor, "case.link", &format!("compliance_case:{}", case.id), serde_json::json!({
+        "link_type": added.link_type,
+        "link_id": added.link_id,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(added)
+}
+
+pub async fn remove_link(pool: &PgPool, case: &ComplianceCase, link_id: i32, actor: &str) -> Result<(), (StatusCode, String)> {
+    check_not_closed(case)?;
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let removed = sqlx::query_as::<_, ComplianceCaseLink>("DELETE FROM compliance_case_links WHERE id = $1 AND case_id = $2 RETURNING *")
+        .bind(link_id)
+        .bind(case.id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Link {} not found on case {}", link_id, case.id)))?;
+
+    audit::record(&mut *tx, actor, "case.unlink", &format!("compliance_case:{}", case.id), serde_json::json!({
+        "link_type": removed.link_type,
+        "link_id": removed.link_id,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(())
+}
+
+pub async fn add_comment(pool: &PgPool, case: &ComplianceCase, payload: &AddCaseCommentRequest, author: &str) -> Result<ComplianceCaseComment, (StatusCode, String)> {
+    check_not_closed(case)?;
+    if payload.body.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Comment is empty".to_string()));
+    }
+
+    sqlx::query_as::<_, ComplianceCaseComment>("INSERT INTO compliance_case_comments (case_id, author, body) VALUES ($1, $2, $3) RETURNING *")
+        .bind(case.id)
+        .bind(author)
+        .bind(payload.body.trim())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Encrypts an uploaded file to the document store and attaches it to a case that is not closed.
+pub async fn add_attachment(
+    pool: &PgPool,
+    store: &DocumentStore,
+    case: &ComplianceCase,
+    upload: &CaseAttachmentUpload,
+    content_type: &str,
+    content: &[u8],
+    actor: &str,
+) -> Result<ComplianceCaseAttachment, (StatusCode, String)> {
+    check_not_closed(case)?;
+    if content.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Attachment is empty".to_string()));
+    }
+    let file_name = clean_file_name(&upload.file_name).ok_or((StatusCode::BAD_REQUEST, "Invalid file_name".to_string()))?;
+
+    let storage_key = store.put(content).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let attachment = sqlx::query_as::<_, ComplianceCaseAttachment>(
+        r#"
+        INSERT INTO compliance_case_attachments (case_id, file_name, content_type, size_bytes, content_hash, storage_key, uploaded_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#
+    )
+    .bind(case.id)
+    .bind(&file_name)
+    .bind(content_type)
+    .bind(content.len() as i64)
+    .bind(metadata::content_hash_bytes(content))
+    .bind(&storage_key)
+    .bind(actor)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit::record(&mut *tx, actor, "case.attachment.upload", &format!("compliance_case:{}", case.id), serde_json::json!({
+        "attachment_id": attachment.id,
+        "file_name": attachment.file_name,
+        "content_hash": attachment.content_hash,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(attachment)
+}
+
+/// Decrypts an attachment for download, refusing one that no longer matches the hash taken at upload.
+pub async fn read_attachment(
+    pool: &PgPool,
+    store: &DocumentStore,
+    attachment_id: i32,
+    actor: &str,
+) -> Result<(ComplianceCaseAttachment, Vec<u8>), (StatusCode, String)> {
+    let attachment = sqlx::query_as::<_, ComplianceCaseAttachment>("SELECT * FROM compliance_case_attachments WHERE id = $1")
+        .bind(attachment_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Attachment not found".to_string()))?;
+
+    let content = store.get(&attachment.storage_key).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    if metadata::content_hash_bytes(&content) != attachment.content_hash {
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, "Attachment integrity check failed".to_string()));
+    }
+
+    audit::record(pool, actor, "case.attachment.download", &format!("compliance_case:{}", attachment.case_id), serde_json::json!({
+        "attachment_id": attachment.id,
+    })).await?;
+
+    Ok((attachment, content))
+}
+
+fn validate_priority(priority: &str) -> Result<(), (StatusCode, String)> {
+    if !PRIORITIES.contains(&priority) {
+        return Err((StatusCode::BAD_REQUEST, format!("priority must be one of {}", PRIORITIES.join(", "))));
+    }
+    Ok(())
+}
+
+fn parse_status(case: &ComplianceCase) -> Result<CaseStatus, (StatusCode, String)> {
+    CaseStatus::parse(&case.status).ok_or((StatusCode::INTERNAL_SERVER_ERROR, format!("Unknown case status {}", case.status)))
+}
+
+fn check_not_closed(case: &ComplianceCase) -> Result<(), (StatusCode, String)> {
+    if parse_status(case)? == CaseStatus::Closed {
+        return Err((StatusCode::CONFLICT, format!("Case {} is closed", case.id)));
+    }
+    Ok(())
+}
+
+fn check_decision(case: &ComplianceCase, to: CaseStatus, actor: &str) -> Result<CaseStatus, (StatusCode, String)> {
+    let status = parse_status(case)?;
+    if !status.can_transition_to(to) {
+        return Err((StatusCode::CONFLICT, format!("Cannot move case {} from {} to {}", case.id, status.as_str(), to.as_str())));
+    }
+    if !status.can_decide(case.assigned_to.as_deref(), actor) {
+        let reason = match status {
+            CaseStatus::Escalated => format!("Case {} was escalated by its assignee and needs a second reviewer", case.id),
+            _ => format!("Case {} is not assigned to {}", case.id, actor),
+        };
+        return Err((StatusCode::FORBIDDEN, reason));
+    }
+    Ok(status)
+}
+
+/// Ensures the record a link points to exists.
+async fn check_link_target(pool: &PgPool, link_type: &str, link_id: i32) -> Result<(), (StatusCode, String)> {
+    let table = match link_type {
+        LINK_ENTITY => "legal_entities",
+        LINK_WALLET => "wallets",
+        LINK_INTENT => "payment_intents",
+        LINK_MOVEMENT => "wallet_movements",
+        LINK_MONITORING_ALERT => "monitoring_alerts",
+        LINK_SANCTIONS_HIT => "sanctions_hits",
+        LINK_KYC_CASE => "kyc_cases",
+        _ => return Err((StatusCode::BAD_REQUEST, format!("link_type must be one of {}", LINK_TYPES.join(", ")))),
+    };
+
+    let exists: bool = sqlx::query_scalar(&format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = $1)", table))
+        .bind(link_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !exists {
+        return Err((StatusCode::NOT_FOUND, format!("{} {} not found", link_type, link_id)));
+    }
+    Ok(())
+}
+
+async fn insert_link(
+    tx: &mut Transaction<'_, Postgres>,
+    case_id: i32,
+    link: &CaseLinkRequest,
+    actor: &str,
+) -> Result<ComplianceCaseLink, (StatusCode, String)> {
+    sqlx::query_as::<_, ComplianceCaseLink>(
+        "INSERT INTO compliance_case_links (case_id, link_type, link_id, note, added_by) VALUES ($1, $2, $3, $4, $5) RETURNING *"
+    )
+    .bind(case_id)
+    .bind(&link.link_type)
+    .bind(link.link_id)
+    .bind(link.note.as_deref().map(str::trim).filter(|n| !n.is_empty()))
+    .bind(actor)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            (StatusCode::CONFLICT, format!("{} {} is already linked to case {}", link.link_type, link.link_id, case_id))
+        }
+        e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })
+}
+
+async fn transition(
+    tx: &mut Transaction<'_, Postgres>,
+    case_id: i32,
+    from: CaseStatus,
+    to: CaseStatus,
+    actor: &str,
+    note: Option<&str>,
+) -> Result<ComplianceCase, (StatusCode, String)> {
+    if !from.can_transition_to(to) {
+        return Err((StatusCode::CONFLICT, format!("Cannot move a case from {} to {}", from.as_str(), to.as_str())));
+    }
+
+    let closed = to == CaseStatus::Closed;
+    let case = sqlx::query_as::<_, ComplianceCase>(
+        r#"
+        UPDATE compliance_cases
+        SET status = $1, updated_at = NOW(),
+            closed_by = CASE WHEN $2 THEN $3 ELSE closed_by END,
+            closed_at = CASE WHEN $2 THEN NOW() ELSE closed_at END,
+            close_reason = CASE WHEN $2 THEN $4 ELSE close_reason END
+        WHERE id = $5 AND status = $6
+        RETURNING *
+        "#
+    )
+    .bind(to.as_str())
+    .bind(closed)
+    .bind(actor)
+    .bind(note)
+    .bind(case_id)
+    .bind(from.as_str())
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::CONFLICT, format!("Case {} is no longer {}", case_id, from.as_str())))?;
+
+    record_event(tx, case_id, Some(from), to, actor, note).await?;
+    Ok(case)
+}
+
+async fn record_event(
+    tx: &mut Transaction<'_, Postgres>,
+    case_id: i32,
+    from: Option<CaseStatus>,
+    to: CaseStatus,
+    actor: &str,
+    note: Option<&str>,
+) -> Result<(), (StatusCode, String)> {
+    sqlx::query("INSERT INTO compliance_case_events (case_id, from_status, to_status, actor, note) VALUES ($1, $2, $3, $4, $5)")
+        .bind(case_id)
+        .bind(from.map(|s| s.as_str()))
+        .bind(to.as_str())
+        .bind(actor)
+        .bind(note)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(())
+}
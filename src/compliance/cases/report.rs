@@ -0,0 +1,412 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, SecondsFormat, Utc};
+use ethers::types::U256;
+use sqlx::{FromRow, PgPool};
+use std::collections::BTreeMap;
+use crate::compliance::iso20022::xml::XmlBuilder;
+use crate::compliance::models::{
+    KycCase, LegalEntity, MonitoringAlert, SanctionsHit, SarActivity, SarAttachment, SarCase, SarDraft, SarIndicator, SarSubject,
+    SarTotal, SarTransaction,
+};
+use crate::compliance::{audit, monitoring, sanctions};
+use crate::finance::amounts::{format_amount, parse_amount};
+use super::{case_attachments, find_case};
+
+pub const REPORT_SAR: &str = "sar";
+pub const REPORT_STR: &str = "str";
+
+/// Namespace of the XML rendering, which carries the same content as the JSON draft with every value
+/// already formatted for display, so it can be laid out as a PDF without further lookups.
+pub const XML_NAMESPACE: &str = "urn:aegis:compliance:sar-draft:1";
+
+/// Drafts a report from everything linked to the case: subjects from linked entities, wallets, payments,
+/// alerts, hits and KYC cases; transactions from linked payments and movements and the flows behind linked alerts.
+pub async fn draft(pool: &PgPool, case_id: i32, report_type: &str, actor: &str) -> Result<SarDraft, (StatusCode, String)> {
+    if report_type != REPORT_SAR && report_type != REPORT_STR {
+        return Err((StatusCode::BAD_REQUEST, format!("report_type must be {} or {}", REPORT_SAR, REPORT_STR)));
+    }
+    let case = find_case(pool, case_id).await?;
+
+    let subjects = subjects(pool, case.id).await?;
+    let (transactions, totals) = transactions(pool, case.id).await?;
+    let indicators = indicators(pool, case.id).await?;
+    let attachments = case_attachments(pool, case.id).await?
+        .into_iter()
+        .map(|a| SarAttachment { id: a.id, file_name: a.file_name, content_type: a.content_type, content_hash: a.content_hash })
+        .collect();
+
+    let mut draft = SarDraft {
+        report_type: report_type.to_uppercase(),
+        reference: format!("CASE-{:06}-{}", case.id, report_type.to_uppercase()),
+        generated_at: Utc::now(),
+        generated_by: actor.to_string(),
+        activity: SarActivity {
+            from: transactions.first().map(|t| t.at),
+            to: transactions.last().map(|t| t.at),
+            totals,
+        },
+        case: SarCase {
+            id: case.id,
+            title: case.title,
+            priority: case.priority,
+            status: case.status,
+            opened_by: case.opened_by,
+            assigned_to: case.assigned_to,
+            opened_at: case.created_at,
+            due_at: case.due_at,
+            filing_reference: case.filing_reference,
+        },
+        subjects,
+        transactions,
+        indicators,
+        narrative: case.narrative.filter(|n| !n.trim().is_empty()),
+        attachments,
+        missing: Vec::new(),
+    };
+    draft.missing = missing_information(&draft);
+
+    audit::record(pool, actor, "case.report.export", &format!("compliance_case:{}", case_id), serde_json::json!({
+        "report_type": report_type,
+        "reference": draft.reference,
+        "transactions": draft.transactions.len(),
+        "missing": draft.missing,
+    })).await?;
+
+    Ok(draft)
+}
+
+/// What a reviewer must still complete before the draft can be filed.
+pub fn missing_information(draft: &SarDraft) -> Vec<String> {
+    let mut missing = Vec::new();
+    if draft.subjects.is_empty() {
+        missing.push("subjects: link the entities or wallets involved".to_string());
+    }
+    for subject in draft.subjects.iter().filter(|s| s.legal_name.is_none()) {
+        missing.push(format!("subjects: legal name of entity {}", subject.entity_id));
+    }
+    if draft.transactions.is_empty() {
+        missing.push("transactions: link the payments, movements or alerts involved".to_string());
+    }
+    if draft.narrative.is_none() {
+        missing.push("narrative: describe the suspicious activity and why it is suspicious".to_string());
+    }
+    missing
+}
+
+/// Renders a draft as XML. Amounts and dates are written as they appear in the JSON draft.
+pub fn to_xml(draft: &SarDraft) -> String {
+    let mut xml = XmlBuilder::document(XML_NAMESPACE);
+    let root = match draft.report_type.as_str() {
+        "STR" => "SuspiciousTransactionReport",
+        _ => "SuspiciousActivityReport",
+    };
+    xml.start_with(root, &[("Type", &draft.report_type), ("Status", "draft")]);
+    xml.text("Reference", &draft.reference);
+    xml.text("GeneratedAt", &timestamp(draft.generated_at));
+    xml.text("GeneratedBy", &draft.generated_by);
+
+    xml.start("Case");
+    xml.text("Id", &draft.case.id.to_string());
+    xml.text("Title", &draft.case.title);
+    xml.text("Priority", &draft.case.priority);
+    xml.text("Status", &draft.case.status);
+    xml.text("OpenedBy", &draft.case.opened_by);
+    xml.optional("AssignedTo", draft.case.assigned_to.as_deref());
+    xml.text("OpenedAt", &timestamp(draft.case.opened_at));
+    xml.text("DueAt", &timestamp(draft.case.due_at));
+    xml.optional("FilingReference", draft.case.filing_reference.as_deref());
+    xml.end("Case");
+
+    xml.start("Subjects");
+    for subject in &draft.subjects {
+        xml.start("Subject");
+        xml.text("EntityId", &subject.entity_id.to_string());
+        xml.optional("LegalName", subject.legal_name.as_deref());
+        xml.optional("Lei", subject.lei.as_deref());
+        xml.optional("Bic", subject.bic.as_deref());
+        xml.optional("Iban", subject.iban.as_deref());
+        xml.text("Jurisdiction", &subject.jurisdiction);
+        xml.text("KycLevel", &subject.kyc_level.to_string());
+        xml.text("KycStatus", &subject.kyc_status);
+        xml.start("Wallets");
+        for wallet in &subject.wallets {
+            xml.text("Wallet", wallet);
+        }
+        xml.end("Wallets");
+        xml.end("Subject");
+    }
+    xml.end("Subjects");
+
+    xml.start("Activity");
+    xml.optional("From", draft.activity.from.map(timestamp).as_deref());
+    xml.optional("To", draft.activity.to.map(timestamp).as_deref());
+    xml.start("Totals");
+    for total in &draft.activity.totals {
+        let count = total.count.to_string();
+        xml.text_with("Total", &[("Direction", &total.direction), ("Asset", &total.asset), ("Count", &count)], &total.amount);
+    }
+    xml.end("Totals");
+    xml.end("Activity");
+
+    xml.start("Transactions");
+    for transaction in &draft.transactions {
+        let id = transaction.id.to_string();
+        xml.start_with("Transaction", &[("Type", &transaction.flow_type), ("Id", &id)]);
+        xml.text("At", &timestamp(transaction.at));
+        xml.text("Direction", &transaction.direction);
+        xml.text("Wallet", &transaction.wallet);
+        xml.text("Counterparty", &transaction.counterparty);
+        xml.text("Asset", &transaction.asset);
+        xml.text("Amount", &transaction.amount);
+        xml.optional("TxHash", transaction.tx_hash.as_deref());
+        xml.end("Transaction");
+    }
+    xml.end("Transactions");
+
+    xml.start("Indicators");
+    for indicator in &draft.indicators {
+        let id = indicator.id.to_string();
+        xml.start_with("Indicator", &[("Source", &indicator.source), ("Id", &id)]);
+        xml.text("Kind", &indicator.kind);
+        xml.text("Status", &indicator.status);
+        xml.text("Description", &indicator.description);
+        xml.text("RaisedAt", &timestamp(indicator.raised_at));
+        xml.end("Indicator");
+    }
+    xml.end("Indicators");
+
+    xml.optional("Narrative", draft.narrative.as_deref());
+
+    xml.start("Attachments");
+    for attachment in &draft.attachments {
+        let id = attachment.id.to_string();
+        xml.start_with("Attachment", &[("Id", &id)]);
+        xml.text("FileName", &attachment.file_name);
+        xml.text("ContentType", &attachment.content_type);
+        xml.text("ContentHash", &attachment.content_hash);
+        xml.end("Attachment");
+    }
+    xml.end("Attachments");
+
+    xml.start("MissingInformation");
+    for item in &draft.missing {
+        xml.text("Item", item);
+    }
+    xml.end("MissingInformation");
+
+    xml.end(root);
+    xml.finish()
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+async fn subjects(pool: &PgPool, case_id: i32) -> Result<Vec<SarSubject>, (StatusCode, String)> {
+    let entity_ids: Vec<i32> = sqlx::query_scalar(
+        r#"
+        WITH links AS (SELECT link_type, link_id FROM compliance_case_links WHERE case_id = $1)
+        SELECT DISTINCT entity_id FROM (
+            SELECT link_id AS entity_id FROM links WHERE link_type = 'entity'
+            UNION SELECT w.entity_id FROM links l JOIN wallets w ON l.link_type = 'wallet' AND w.id = l.link_id
+            UNION SELECT w.entity_id FROM links l
+                JOIN payment_intents i ON l.link_type = 'intent' AND i.id = l.link_id JOIN wallets w ON w.id = i.wallet_id
+            UNION SELECT w.entity_id FROM links l
+                JOIN wallet_movements m ON l.link_type = 'movement' AND m.id = l.link_id JOIN wallets w ON w.id = m.wallet_id
+            UNION SELECT a.entity_id FROM links l JOIN monitoring_alerts a ON l.link_type = 'monitoring_alert' AND a.id = l.link_id
+            UNION SELECT h.entity_id FROM links l JOIN sanctions_hits h ON l.link_type = 'sanctions_hit' AND h.id = l.link_id
+            UNION SELECT k.entity_id FROM links l JOIN kyc_cases k ON l.link_type = 'kyc_case' AND k.id = l.link_id
+        ) s
+        WHERE entity_id IS NOT NULL
+        "#
+    )
+    .bind(case_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let entities = sqlx::query_as::<_, LegalEntity>("SELECT * FROM legal_entities WHERE id = ANY($1) ORDER BY id")
+        .bind(&entity_ids)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let wallets: Vec<(i32, String)> = sqlx::query_as("SELECT entity_id, address FROM wallets WHERE entity_id = ANY($1) ORDER BY id")
+        .bind(&entity_ids)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(entities.into_iter().map(|entity| SarSubject {
+        wallets: wallets.iter().filter(|(id, _)| *id == entity.id).map(|(_, address)| address.clone()).collect(),
+        entity_id: entity.id,
+        legal_name: entity.legal_name,
+        lei: entity.lei,
+        bic: entity.bic,
+        iban: entity.iban,
+        jurisdiction: entity.jurisdiction,
+        kyc_level: entity.kyc_level,
+        kyc_status: entity.kyc_status,
+    }).collect())
+}
+
+#[derive(FromRow)]
+struct TransactionRow {
+    flow_type: String,
+    id: i32,
+    at: DateTime<Utc>,
+    direction: String,
+    wallet: String,
+    chain_id: i64,
+    token_address: Option<String>,
+    amount: String,
+    counterparty: String,
+    tx_hash: Option<String>,
+}
+
+/// Linked transactions oldest first, with totals per direction and asset. Payment amounts are stored in
+/// decimal units, movements in base units.
+async fn transactions(pool: &PgPool, case_id: i32) -> Result<(Vec<SarTransaction>, Vec<SarTotal>), (StatusCode, String)> {
+    let rows = sqlx::query_as::<_, TransactionRow>(
+        r#"
+        WITH flows AS (
+            SELECT link_type AS flow_type, link_id AS flow_id FROM compliance_case_links
+            WHERE case_id = $1 AND link_type IN ('intent', 'movement')
+            UNION
+            SELECT f.flow_type, f.flow_id FROM monitoring_alert_flows f
+            JOIN compliance_case_links l ON l.link_type = 'monitoring_alert' AND l.link_id = f.alert_id
+            WHERE l.case_id = $1
+        )
+        SELECT 'intent' AS flow_type, i.id, i.updated_at AS at, 'debit' AS direction, w.address AS wallet, w.chain_id,
+               LOWER(i.token_address) AS token_address, i.amount, i.target_address AS counterparty, i.tx_hash
+        FROM flows f JOIN payment_intents i ON f.flow_type = 'intent' AND i.id = f.flow_id JOIN wallets w ON w.id = i.wallet_id
+        UNION ALL
+        SELECT 'movement', m.id, m.block_time, m.direction, w.address, m.chain_id,
+               LOWER(m.token_address), m.amount, m.counterparty, m.tx_hash
+        FROM flows f JOIN wallet_movements m ON f.flow_type = 'movement' AND m.id = f.flow_id JOIN wallets w ON w.id = m.wallet_id
+        ORDER BY at, flow_type, id
+        "#
+    )
+    .bind(case_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let tokens = monitoring::token_index(pool).await?;
+    let mut totals: BTreeMap<(String, String), (usize, U256, u8)> = BTreeMap::new();
+    let mut transactions = Vec::with_capacity(rows.len());
+    for row in rows {
+        let intent = row.flow_type == monitoring::scenarios::FLOW_INTENT;
+        let (asset, decimals) = monitoring::asset(&tokens, row.chain_id, row.token_address.as_deref())
+            .unwrap_or_else(|| (row.token_address.clone().unwrap_or_default(), 0));
+        let amount = match intent {
+            true => parse_amount(&row.amount, decimals).ok(),
+            false => U256::from_dec_str(&row.amount).ok(),
+        };
+
+        let display = match amount {
+            Some(amount) => {
+                let total = totals.entry((row.direction.clone(), asset.clone())).or_insert((0, U256::zero(), decimals));
+                total.0 += 1;
+                total.1 += amount;
+                format_amount(amount, decimals)
+            }
+            // Kept as stored rather than dropped from the report
+            None => row.amount.clone(),
+        };
+
+        transactions.push(SarTransaction {
+            flow_type: row.flow_type,
+            id: row.id,
+            at: row.at,
+            direction: row.direction,
+            wallet: row.wallet,
+            counterparty: row.counterparty,
+            asset,
+            amount: display,
+            tx_hash: row.tx_hash,
+        });
+    }
+
+    let totals = totals.into_iter()
+        .map(|((direction, asset), (count, amount, decimals))| SarTotal { direction, asset, count, amount: format_amount(amount, decimals) })
+        .collect();
+    Ok((transactions, totals))
+}
+
+async fn indicators(pool: &PgPool, case_id: i32) -> Result<Vec<SarIndicator>, (StatusCode, String)> {
+    let alerts = sqlx::query_as::<_, MonitoringAlert>(
+        r#"
+        SELECT a.* FROM monitoring_alerts a
+        JOIN compliance_case_links l ON l.link_type = 'monitoring_alert' AND l.link_id = a.id
+        WHERE l.case_id = $1
+        ORDER BY a.created_at
+        "#
+    )
+    .bind(case_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let hits = sqlx::query_as::<_, SanctionsHit>(
+        r#"
+        SELECT h.* FROM sanctions_hits h
+        JOIN compliance_case_links l ON l.link_type = 'sanctions_hit' AND l.link_id = h.id
+        WHERE l.case_id = $1
+        ORDER BY h.created_at
+        "#
+    )
+    .bind(case_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let kyc_cases = sqlx::query_as::<_, KycCase>(
+        r#"
+        SELECT k.* FROM kyc_cases k
+        JOIN compliance_case_links l ON l.link_type = 'kyc_case' AND l.link_id = k.id
+        WHERE l.case_id = $1
+        ORDER BY k.created_at
+        "#
+    )
+    .bind(case_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut indicators: Vec<SarIndicator> = alerts.into_iter().map(|alert| SarIndicator {
+        source: super::LINK_MONITORING_ALERT.to_string(),
+        id: alert.id,
+        kind: alert.kind,
+        status: alert.status,
+        description: format!("{} ({} severity)", alert.summary, alert.severity),
+        raised_at: alert.created_at,
+    }).collect();
+
+    indicators.extend(hits.into_iter().map(|hit| SarIndicator {
+        source: super::LINK_SANCTIONS_HIT.to_string(),
+        id: hit.id,
+        kind: format!("sanctions_{}_match", hit.match_type),
+        description: format!(
+            "{} {} matches {} on the {} (entry {}, score {:.2})",
+            hit.subject_type, hit.matched_value, hit.listed_name, sanctions::source_label(&hit.source), hit.source_uid, hit.score,
+        ),
+        status: hit.status,
+        raised_at: hit.created_at,
+    }));
+
+    indicators.extend(kyc_cases.into_iter().map(|kyc| SarIndicator {
+        source: super::LINK_KYC_CASE.to_string(),
+        id: kyc.id,
+        kind: "kyc".to_string(),
+        description: match &kyc.decision_reason {
+            Some(reason) => format!("KYC case for level {}: {}", kyc.requested_level, reason),
+            None => format!("KYC case for level {}", kyc.requested_level),
+        },
+        status: kyc.status,
+        raised_at: kyc.created_at,
+    }));
+
+    indicators.sort_by_key(|i| i.raised_at);
+    Ok(indicators)
+}
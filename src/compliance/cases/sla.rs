@@ -0,0 +1,99 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use crate::compliance::models::ComplianceCase;
+use crate::compliance::notifications;
+
+/// How often pending cases are checked against their deadlines.
+const POLL_INTERVAL_SECS: u64 = 900;
+
+/// Days to reach a decision on a case of each priority.
+pub fn sla_days(priority: &str) -> i64 {
+    match priority {
+        "critical" => 1,
+        "high" => 7,
+        "medium" => 14,
+        _ => 30,
+    }
+}
+
+pub fn due_at(priority: &str, opened_at: DateTime<Utc>) -> DateTime<Utc> {
+    opened_at + Duration::days(sla_days(priority))
+}
+
+/// When assignees are warned: once four fifths of the time to the deadline have passed.
+pub fn warn_at(opened_at: DateTime<Utc>, due_at: DateTime<Utc>) -> DateTime<Utc> {
+    due_at - (due_at - opened_at) / 5
+}
+
+/// Warns about pending cases nearing their deadline and flags those past it.
+pub async fn run(pool: PgPool) {
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(POLL_INTERVAL_SECS));
+    loop {
+        ticker.tick().await;
+        if let Err((_, e)) = sweep(&pool).await {
+            println!("Case SLA error: {}", e);
+        }
+    }
+}
+
+pub async fn sweep(pool: &PgPool) -> Result<(), (StatusCode, String)> {
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    // Matches `warn_at`
+    let warned = sqlx::query_as::<_, ComplianceCase>(
+        r#"
+        UPDATE compliance_cases SET sla_warned_at = NOW()
+        WHERE status IN ('open', 'investigating', 'escalated') AND sla_warned_at IS NULL
+          AND NOW() >= due_at - (due_at - created_at) / 5 AND NOW() < due_at
+        RETURNING *
+        "#
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for case in &warned {
+        notifications::notify(
+            &mut *tx,
+            "case.sla_warning",
+            &format!("compliance_case:{}", case.id),
+            &format!("Case {} ({}) is due {}", case.id, case.title, case.due_at.format("%Y-%m-%d %H:%M UTC")),
+            sla_details(case),
+        ).await?;
+    }
+
+    let breached = sqlx::query_as::<_, ComplianceCase>(
+        r#"
+        UPDATE compliance_cases SET sla_breached_at = NOW()
+        WHERE status IN ('open', 'investigating', 'escalated') AND sla_breached_at IS NULL AND NOW() >= due_at
+        RETURNING *
+        "#
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for case in &breached {
+        notifications::notify(
+            &mut *tx,
+            "case.sla_breached",
+            &format!("compliance_case:{}", case.id),
+            &format!("Case {} ({}) missed its deadline of {}", case.id, case.title, case.due_at.format("%Y-%m-%d %H:%M UTC")),
+            sla_details(case),
+        ).await?;
+    }
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(())
+}
+
+fn sla_details(case: &ComplianceCase) -> serde_json::Value {
+    serde_json::json!({
+        "case_id": case.id,
+        "priority": case.priority,
+        "status": case.status,
+        "assigned_to": case.assigned_to,
+        "due_at": case.due_at,
+    })
+}
@@ -0,0 +1,43 @@
+use axum::http::StatusCode;
+use sqlx::PgPool;
+use crate::finance::models::Wallet;
+use super::models::CounterpartyEntry;
+
+pub const LIST_ALLOW: &str = "allow";
+pub const LIST_BLOCK: &str = "block";
+
+pub const MODE_BLOCK: &str = "block";
+pub const MODE_ALLOW_ONLY: &str = "allow_only";
+
+/// Entries that apply to payments from `wallet_id` to `address`: the wallet's own and the global ones.
+pub async fn entries_for(pool: &PgPool, wallet_id: i32, address: &str) -> Result<Vec<CounterpartyEntry>, (StatusCode, String)> {
+    sqlx::query_as::<_, CounterpartyEntry>(
+        "SELECT * FROM counterparty_entries WHERE address = $1 AND (wallet_id IS NULL OR wallet_id = $2) ORDER BY id"
+    )
+    .bind(address.to_lowercase())
+    .bind(wallet_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Blocklists always win; in `allow_only` mode the address must also be allowlisted (globally or for the wallet).
+/// `Err` carries the rejection reason recorded on the payment.
+pub async fn check(pool: &PgPool, wallet: &Wallet, address: &str) -> Result<(), String> {
+    let entries = entries_for(pool, wallet.id, address).await.map_err(|(_, e)| e)?;
+
+    if let Some(blocked) = entries.iter().find(|e| e.list_type == LIST_BLOCK) {
+        return Err(format!(
+            "Counterparty {}{} is on the {} blocklist",
+            address,
+            blocked.label.as_ref().map(|l| format!(" ({})", l)).unwrap_or_default(),
+            if blocked.wallet_id.is_some() { "wallet" } else { "global" },
+        ));
+    }
+
+    if wallet.counterparty_mode == MODE_ALLOW_ONLY && !entries.iter().any(|e| e.list_type == LIST_ALLOW) {
+        return Err(format!("Counterparty {} is not on the allowlist of wallet {}", address, wallet.id));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+use aes_gcm::{AeadCore, Aes256Gcm, Key, Nonce};
+use rand::RngCore;
+use std::path::PathBuf;
+
+/// Bytes of the random nonce stored in front of each file's ciphertext.
+const NONCE_LEN: usize = 12;
+
+/// Largest document accepted for upload.
+pub const MAX_DOCUMENT_BYTES: usize = 20 * 1024 * 1024;
+
+/// KYC evidence and compliance case attachments on local disk, AES-256-GCM encrypted. Each file is
+/// `nonce || ciphertext` under a random storage key, which is also the associated data so a file renamed
+/// onto another key fails to decrypt.
+pub struct DocumentStore {
+    dir: PathBuf,
+    cipher: Aes256Gcm,
+}
+
+impl DocumentStore {
+    /// `key_hex` is the 32-byte key as 64 hex characters (optionally `0x`-prefixed).
+    pub fn new(dir: impl Into<PathBuf>, key_hex: &str) -> Result<Self, String> {
+        let key = ethers::utils::hex::decode(key_hex.trim().trim_start_matches("0x"))
+            .map_err(|e| format!("Invalid document key: {}", e))?;
+        if key.len() != 32 {
+            return Err(format!("Document key must be 32 bytes, got {}", key.len()));
+        }
+
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Cannot create document directory {}: {}", dir.display(), e))?;
+
+        Ok(Self { dir, cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)) })
+    }
+
+    /// Encrypts and writes a document, returning the storage key to keep alongside its metadata.
+    pub async fn put(&self, content: &[u8]) -> Result<String, String> {
+        let mut random = [0u8; 16];
+        OsRng.fill_bytes(&mut random);
+        let storage_key = ethers::utils::hex::encode(random);
+
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self.cipher
+            .encrypt(&nonce, Payload { msg: content, aad: storage_key.as_bytes() })
+            .map_err(|_| "Document encryption failed".to_string())?;
+
+        let mut file = nonce.to_vec();
+        file.extend_from_slice(&ciphertext);
+
+        // Write under a temporary name first so a crash never leaves a truncated document behind
+        let path = self.path(&storage_key);
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, &file).await.map_err(|e| e.to_string())?;
+        tokio::fs::rename(&partial, &path).await.map_err(|e| e.to_string())?;
+
+        Ok(storage_key)
+    }
+
+    /// Reads and decrypts a stored document.
+    pub async fn get(&self, storage_key: &str) -> Result<Vec<u8>, String> {
+        let file = tokio::fs::read(self.path(storage_key)).await.map_err(|e| e.to_string())?;
+        if file.len() < NONCE_LEN {
+            return Err(format!("Document {} is truncated", storage_key));
+        }
+
+        let (nonce, ciphertext) = file.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: storage_key.as_bytes() })
+            .map_err(|_| format!("Document {} failed to decrypt", storage_key))
+    }
+
+    fn path(&self, storage_key: &str) -> PathBuf {
+        self.dir.join(format!("{}.bin", storage_key))
+    }
+}
@@ -1,55 +1,176 @@
 use axum::{
-    extract::State,
-    http::StatusCode,
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use crate::state::AppState;
-use super::models::{RegisterEntityRequest, LegalEntity};
-use serde::Deserialize;
+use super::models::{
+    RegisterEntityRequest, LegalEntity, MintRequest, MintResponse, BulkIdentityRequest, IdentityStatus,
+    CounterpartyEntry, AddCounterpartyRequest, CounterpartyFilter, CounterpartyModeRequest, AuditEntry, AuditFilter,
+    Iso20022Message, Iso20022Filter, StatementQuery, InboundMessage, InboundMessageDetail, InboundFilter, IncomingFunds, IncomingFundsFilter,
+    KycCase, KycCaseDetail, KycCaseFilter, SubmitKycCaseRequest, AssignKycCaseRequest, KycDecisionRequest, KycDocument, KycDocumentUpload,
+    KycNote, AddKycNoteRequest, KycReviewPeriod, CreateReviewPeriodRequest, EntityReview, EntityReviewFilter, KycReviewAction,
+    ComplianceNotification, NotificationFilter, SanctionsList, SanctionsImportQuery, SanctionsHit, SanctionsHitFilter,
+    SanctionsHitReviewRequest, MonitoringScenario, CreateScenarioRequest, UpdateScenarioRequest, MonitoringAlert, MonitoringAlertDetail,
+    MonitoringAlertFilter, MonitoringAlertNote, AssignAlertRequest, AlertDecisionRequest, AddAlertNoteRequest, ComplianceCase,
+    ComplianceCaseDetail, ComplianceCaseLink, ComplianceCaseComment, ComplianceCaseAttachment, CreateCaseRequest, UpdateCaseRequest,
+    CaseFilter, CaseLinkRequest, AssignCaseRequest, CaseDecisionRequest, FileCaseReportRequest, AddCaseCommentRequest,
+    CaseAttachmentUpload, CaseReportQuery,
+};
+use crate::chain::parse_addresses;
+use crate::finance::models::Wallet;
+use crate::finance::registry::find_wallet;
+use super::metadata::{self, TokenMetadata};
+use super::{audit, cases, counterparties, iso20022, kyc, monitoring, reverification, sanctions, COMPLIANCE_ROLES};
+use super::cases::CaseStatus;
+use super::monitoring::alerts::{self, AlertStatus};
+use super::sanctions::screening;
+use super::iso20022::identifiers;
 
 pub async fn register_entity(
     State(state): State<AppState>,
     Json(payload): Json<RegisterEntityRequest>,
 ) -> Result<Json<LegalEntity>, (StatusCode, String)> {
-    if payload.kyc_level <= 0 {
-        return Err((StatusCode::BAD_REQUEST, "Invalid KYC Level".to_string()));
-    }
+    let bad_request = |e| (StatusCode::BAD_REQUEST, e);
+    payload.lei.as_deref().map(identifiers::validate_lei).transpose().map_err(bad_request)?;
+    payload.bic.as_deref().map(identifiers::validate_bic).transpose().map_err(bad_request)?;
+    payload.iban.as_deref().map(identifiers::validate_iban).transpose().map_err(bad_request)?;
 
     let entity = sqlx::query_as::<_, LegalEntity>(
         r#"
-        INSERT INTO legal_entities (hash_id, jurisdiction, kyc_level)
-        VALUES ($1, $2, $3)
-        RETURNING id, hash_id, jurisdiction, kyc_level, on_chain_id, created_at
+        INSERT INTO legal_entities (hash_id, jurisdiction, kyc_level, legal_name, lei, bic, iban)
+        VALUES ($1, $2, 0, $3, $4, $5, $6)
+        RETURNING *
         "#
     )
     .bind(payload.hash_id)
     .bind(payload.jurisdiction)
-    .bind(payload.kyc_level)
+    .bind(payload.legal_name)
+    .bind(payload.lei)
+    .bind(payload.bic)
+    .bind(payload.iban)
     .fetch_one(&state.pool)
     .await
     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    Ok(Json(entity))
-}
+    // Hits do not block registration; they are raised for review and hold the entity's payments
+    screening::screen_entity(&state.pool, &entity, screening::CONTEXT_REGISTRATION).await?;
 
-#[derive(Deserialize)]
-pub struct MintRequest {
-    pub wallet_address: String,
-    pub uri: String,
+    Ok(Json(entity))
 }
 
-use crate::auth::Claims;
+use crate::auth::{require_role, Claims};
+use crate::idempotency::{idempotent, resource_id, ClaimedKey};
 
+/// Honours `Idempotency-Key` so a retried mint never issues a second identity.
 pub async fn mint_token(
     State(state): State<AppState>,
-    _: Claims, // Requires valid JWT
+    Claims(claims): Claims, // Requires valid JWT
+    headers: HeaderMap,
     Json(payload): Json<MintRequest>,
-) -> Result<Json<String>, (StatusCode, String)> {
-    let tx_hash = state.chain.mint(&payload.wallet_address, &payload.uri)
+) -> Response {
+    idempotent(
+        &state.pool, &headers, &claims.sub, "compliance.mint", &payload,
+        |key| mint_identity(&state, &payload, key),
+        |resource| interrupted_mint(&state, resource),
+    ).await
+}
+
+async fn mint_identity(
+    state: &AppState,
+    payload: &MintRequest,
+    key: ClaimedKey,
+) -> Result<Json<MintResponse>, (StatusCode, String)> {
+    let entity = sqlx::query_as::<_, LegalEntity>("SELECT * FROM legal_entities WHERE id = $1")
+        .bind(payload.entity_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Entity not found".to_string()))?;
+    if entity.kyc_level == 0 {
+        return Err((StatusCode::CONFLICT, format!("Entity {} has no approved KYC case", entity.id)));
+    }
+
+    // Build the metadata from the verified entity record, never from caller input
+    let issued_at = chrono::Utc::now();
+    let verified_at = entity.kyc_verified_at.unwrap_or(entity.created_at);
+    let expires_at = reverification::deadline(&state.pool, &entity.jurisdiction, entity.kyc_level, verified_at).await?;
+    let (document, content_hash) = TokenMetadata::for_entity(&entity, issued_at, expires_at)
+        .seal()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    sqlx::query(
+        "INSERT INTO identity_metadata (content_hash, entity_id, document) VALUES ($1, $2, $3) ON CONFLICT (content_hash) DO NOTHING"
+    )
+    .bind(&content_hash)
+    .bind(entity.id)
+    .bind(&document)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    key.record(&format!("identity_metadata:{}", content_hash)).await?;
+
+    let token_uri = metadata::token_uri(&state.metadata_base_url, &content_hash);
+    let (tx_hash, token_id) = state.chain.mint(&payload.wallet_address, &token_uri)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Minting failed: {}", e)))?;
 
-    Ok(Json(tx_hash))
+    sqlx::query("UPDATE identity_metadata SET token_id = $1 WHERE content_hash = $2")
+        .bind(&token_id)
+        .bind(&content_hash)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    sqlx::query("UPDATE legal_entities SET on_chain_id = $1 WHERE id = $2")
+        .bind(&token_id)
+        .bind(entity.id)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(MintResponse {
+        tx_hash,
+        token_id,
+        token_uri,
+    }))
+}
+
+/// Reports the mint an interrupted request started. Its tx hash is not kept, so a completed mint is
+/// reported by token id rather than replayed.
+async fn interrupted_mint(state: &AppState, resource: String) -> Result<Json<MintResponse>, (StatusCode, String)> {
+    let content_hash: String = resource_id(&resource, "identity_metadata")?;
+    let token_id: Option<String> = sqlx::query_scalar("SELECT token_id FROM identity_metadata WHERE content_hash = $1")
+        .bind(&content_hash)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Err((StatusCode::CONFLICT, match token_id {
+        Some(token_id) => format!("The interrupted request minted AegisID {}", token_id),
+        None => format!("The interrupted request may have minted an AegisID for metadata {}; check the chain before minting again", content_hash),
+    }))
+}
+
+pub async fn get_metadata(
+    State(state): State<AppState>,
+    Path(content_hash): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let document: String = sqlx::query_scalar("SELECT document FROM identity_metadata WHERE content_hash = $1")
+        .bind(content_hash.to_lowercase())
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Metadata not found".to_string()))?;
+
+    // Refuse to serve a document that no longer matches its address
+    if metadata::content_hash(&document) != content_hash.to_lowercase() {
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, "Metadata integrity check failed".to_string()));
+    }
+
+    Ok(([(header::CONTENT_TYPE, "application/json")], document))
 }
 
 pub async fn list_entities(
@@ -66,4 +187,1103 @@ pub async fn list_entities(
     Ok(Json(entities))
 }
 
+/// AegisID holdings for many addresses in a single Multicall3 round-trip.
+pub async fn get_identities_bulk(
+    State(state): State<AppState>,
+    Json(payload): Json<BulkIdentityRequest>,
+) -> Result<Json<Vec<IdentityStatus>>, (StatusCode, String)> {
+    let holders = parse_addresses(&payload.addresses).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let balances = state.chain.batch_identity_balances(&holders)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let statuses = holders.iter()
+        .zip(balances)
+        .map(|(holder, balance)| IdentityStatus {
+            address: format!("{:?}", holder),
+            verified: balance.map(|b| !b.is_zero()).unwrap_or(false),
+            token_count: balance.map(|b| b.to_string()),
+        })
+        .collect();
+
+    Ok(Json(statuses))
+}
+
+pub async fn add_counterparty(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<AddCounterpartyRequest>,
+) -> Result<(StatusCode, Json<CounterpartyEntry>), (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let address: ethers::types::Address = payload.address.parse()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid counterparty address".to_string()))?;
+    if payload.list_type != counterparties::LIST_ALLOW && payload.list_type != counterparties::LIST_BLOCK {
+        return Err((StatusCode::BAD_REQUEST, "list_type must be 'allow' or 'block'".to_string()));
+    }
+    if let Some(wallet_id) = payload.wallet_id {
+        find_wallet(&state.pool, wallet_id).await?;
+    }
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let entry = sqlx::query_as::<_, CounterpartyEntry>(
+        r#"
+        INSERT INTO counterparty_entries (wallet_id, address, list_type, label, created_by)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT DO NOTHING
+        RETURNING *
+        "#
+    )
+    .bind(payload.wallet_id)
+    .bind(format!("{:?}", address))
+    .bind(&payload.list_type)
+    .bind(&payload.label)
+    .bind(&claims.sub)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::CONFLICT, format!("{:?} is already on that {}list", address, payload.list_type)))?;
+
+    audit::record(&mut *tx, &claims.sub, "counterparty.add", &entry.address, serde_json::json!({
+        "entry_id": entry.id,
+        "wallet_id": entry.wallet_id,
+        "list_type": entry.list_type,
+        "label": entry.label,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+pub async fn list_counterparties(
+    State(state): State<AppState>,
+    Query(filter): Query<CounterpartyFilter>,
+) -> Result<Json<Vec<CounterpartyEntry>>, (StatusCode, String)> {
+    let entries = sqlx::query_as::<_, CounterpartyEntry>(
+        r#"
+        SELECT * FROM counterparty_entries
+        WHERE ($1::INTEGER IS NULL OR wallet_id = $1)
+          AND ($2::VARCHAR IS NULL OR list_type = $2)
+          AND ($3::VARCHAR IS NULL OR address = $3)
+        ORDER BY created_at DESC
+        "#
+    )
+    .bind(filter.wallet_id)
+    .bind(filter.list_type)
+    .bind(filter.address.map(|a| a.to_lowercase()))
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(entries))
+}
+
+pub async fn remove_counterparty(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(entry_id): Path<i32>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let entry = sqlx::query_as::<_, CounterpartyEntry>("DELETE FROM counterparty_entries WHERE id = $1 RETURNING *")
+        .bind(entry_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Counterparty entry not found".to_string()))?;
+
+    audit::record(&mut *tx, &claims.sub, "counterparty.remove", &entry.address, serde_json::json!({
+        "entry_id": entry.id,
+        "wallet_id": entry.wallet_id,
+        "list_type": entry.list_type,
+        "label": entry.label,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn set_counterparty_mode(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(wallet_id): Path<i32>,
+    Json(payload): Json<CounterpartyModeRequest>,
+) -> Result<Json<Wallet>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    if payload.mode != counterparties::MODE_BLOCK && payload.mode != counterparties::MODE_ALLOW_ONLY {
+        return Err((StatusCode::BAD_REQUEST, "mode must be 'block' or 'allow_only'".to_string()));
+    }
+    let previous = find_wallet(&state.pool, wallet_id).await?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let wallet = sqlx::query_as::<_, Wallet>("UPDATE wallets SET counterparty_mode = $1 WHERE id = $2 RETURNING *")
+        .bind(&payload.mode)
+        .bind(wallet_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit::record(&mut *tx, &claims.sub, "counterparty.mode", &wallet.address, serde_json::json!({
+        "wallet_id": wallet.id,
+        "from": previous.counterparty_mode,
+        "to": wallet.counterparty_mode,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(wallet))
+}
+
+pub async fn list_audit_log(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Query(filter): Query<AuditFilter>,
+) -> Result<Json<Vec<AuditEntry>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let entries = sqlx::query_as::<_, AuditEntry>(
+        r#"
+        SELECT * FROM compliance_audit_log
+        WHERE ($1::VARCHAR IS NULL OR subject = $1) AND ($2::VARCHAR IS NULL OR action = $2)
+        ORDER BY id DESC
+        "#
+    )
+    .bind(filter.subject.map(|s| s.to_lowercase()))
+    .bind(filter.action)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(entries))
+}
+
+/// Serves a stored ISO 20022 message as an XML download.
+fn xml_download(message: Iso20022Message) -> impl IntoResponse {
+    (
+        [
+            (header::CONTENT_TYPE, "application/xml".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.xml\"", message.msg_id)),
+        ],
+        message.document,
+    )
+}
 
+pub async fn get_intent_pacs008(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(intent_id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let message = iso20022::pacs008_for_intent(&state, intent_id, &claims.sub).await?;
+    Ok(xml_download(message))
+}
+
+pub async fn get_funding_pacs008(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(funding_id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let message = iso20022::pacs008_for_funding(&state, funding_id, &claims.sub).await?;
+    Ok(xml_download(message))
+}
+
+pub async fn get_intent_pacs002(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(intent_id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let message = iso20022::pacs002_for_intent(&state, intent_id, &claims.sub).await?;
+    Ok(xml_download(message))
+}
+
+pub async fn get_inbound_pacs002(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(message_id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let message = iso20022::pacs002_for_inbound(&state, message_id, &claims.sub).await?;
+    Ok(xml_download(message))
+}
+
+/// The wallet's end-of-day camt.053 for `?date=YYYY-MM-DD`; 409 until the indexer has covered the whole day.
+pub async fn get_wallet_camt053(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(wallet_id): Path<i32>,
+    Query(query): Query<StatementQuery>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let message = iso20022::statements::camt053_for_wallet(&state, wallet_id, query.date, &claims.sub).await?;
+    Ok(xml_download(message))
+}
+
+pub async fn get_movement_camt054(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(movement_id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let message = iso20022::statements::camt054_for_movement(&state, movement_id, &claims.sub).await?;
+    Ok(xml_download(message))
+}
+
+pub async fn list_iso20022_messages(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Query(filter): Query<Iso20022Filter>,
+) -> Result<Json<Vec<Iso20022Message>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let messages = sqlx::query_as::<_, Iso20022Message>(
+        r#"
+        SELECT * FROM iso20022_messages
+        WHERE ($1::INTEGER IS NULL OR intent_id = $1) AND ($2::INTEGER IS NULL OR funding_id = $2)
+          AND ($3::INTEGER IS NULL OR inbound_message_id = $3) AND ($4::VARCHAR IS NULL OR message_type = $4)
+          AND ($5::INTEGER IS NULL OR wallet_id = $5 OR movement_id IN (SELECT id FROM wallet_movements WHERE wallet_id = $5))
+          AND ($6::INTEGER IS NULL OR movement_id = $6)
+        ORDER BY id DESC
+        "#
+    )
+    .bind(filter.intent_id)
+    .bind(filter.funding_id)
+    .bind(filter.inbound_message_id)
+    .bind(filter.message_type)
+    .bind(filter.wallet_id)
+    .bind(filter.movement_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(messages))
+}
+
+pub async fn get_iso20022_message(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(message_id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let message = sqlx::query_as::<_, Iso20022Message>("SELECT * FROM iso20022_messages WHERE id = $1")
+        .bind(message_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Message {} not found", message_id)))?;
+
+    Ok(xml_download(message))
+}
+
+/// Accepts a raw pain.001 or pacs.008 document (the namespace decides which) and returns how each transfer was mapped.
+pub async fn receive_iso20022_message(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    body: String,
+) -> Result<Json<InboundMessageDetail>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let detail = iso20022::inbound::ingest(&state, &body, &claims.sub).await?;
+    Ok(Json(detail))
+}
+
+pub async fn list_inbound_messages(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Query(filter): Query<InboundFilter>,
+) -> Result<Json<Vec<InboundMessage>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let messages = sqlx::query_as::<_, InboundMessage>(
+        r#"
+        SELECT * FROM iso20022_inbound_messages
+        WHERE ($1::VARCHAR IS NULL OR message_type = $1)
+        ORDER BY id DESC
+        "#
+    )
+    .bind(filter.message_type)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(messages))
+}
+
+pub async fn get_inbound_message(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(message_id): Path<i32>,
+) -> Result<Json<InboundMessageDetail>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let detail = iso20022::inbound::find_inbound(&state.pool, message_id).await?;
+    Ok(Json(detail))
+}
+
+pub async fn list_incoming_funds(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Query(filter): Query<IncomingFundsFilter>,
+) -> Result<Json<Vec<IncomingFunds>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let funds = sqlx::query_as::<_, IncomingFunds>(
+        r#"
+        SELECT * FROM incoming_funds
+        WHERE ($1::INTEGER IS NULL OR wallet_id = $1)
+        ORDER BY id DESC
+        "#
+    )
+    .bind(filter.wallet_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(funds))
+}
+
+/// Opens a KYC case for an entity. Any authenticated user may submit; compliance reviews it.
+pub async fn submit_kyc_case(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<SubmitKycCaseRequest>,
+) -> Result<(StatusCode, Json<KycCase>), (StatusCode, String)> {
+    let case = kyc::submit(&state.pool, payload.entity_id, payload.requested_level, &claims.sub).await?;
+    Ok((StatusCode::CREATED, Json(case)))
+}
+
+pub async fn list_kyc_cases(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Query(filter): Query<KycCaseFilter>,
+) -> Result<Json<Vec<KycCase>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let cases = sqlx::query_as::<_, KycCase>(
+        r#"
+        SELECT * FROM kyc_cases
+        WHERE ($1::INTEGER IS NULL OR entity_id = $1)
+          AND ($2::VARCHAR IS NULL OR status = $2)
+          AND ($3::VARCHAR IS NULL OR assigned_to = $3)
+        ORDER BY created_at DESC
+        "#
+    )
+    .bind(filter.entity_id)
+    .bind(filter.status)
+    .bind(filter.assigned_to)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(cases))
+}
+
+/// A case with its history, document list and notes. Reviewer notes are only shown to compliance.
+pub async fn get_kyc_case(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+) -> Result<Json<KycCaseDetail>, (StatusCode, String)> {
+    let case = kyc::find_case(&state.pool, case_id).await?;
+    let reviewer = COMPLIANCE_ROLES.contains(&claims.role.as_str());
+    if !reviewer && case.submitted_by != claims.sub {
+        return Err((StatusCode::FORBIDDEN, "Only the submitter or compliance can view a KYC case".to_string()));
+    }
+
+    let events = kyc::case_events(&state.pool, case_id).await?;
+    let documents = kyc::case_documents(&state.pool, case_id).await?;
+    let notes = match reviewer {
+        true => kyc::case_notes(&state.pool, case_id).await?,
+        false => Vec::new(),
+    };
+
+    Ok(Json(KycCaseDetail { case, events, documents, notes }))
+}
+
+pub async fn assign_kyc_case(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    Json(payload): Json<AssignKycCaseRequest>,
+) -> Result<Json<KycCase>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let case = kyc::find_case(&state.pool, case_id).await?;
+    Ok(Json(kyc::assign(&state.pool, case, &payload.reviewer, &claims.sub).await?))
+}
+
+/// Approves the case and sets the entity's KYC level to the one requested.
+pub async fn approve_kyc_case(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    payload: Option<Json<KycDecisionRequest>>,
+) -> Result<Json<KycCase>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let case = kyc::find_case(&state.pool, case_id).await?;
+    let Json(payload) = payload.unwrap_or_default();
+    Ok(Json(kyc::approve(&state.pool, case, &claims.sub, payload.reason.as_deref()).await?))
+}
+
+pub async fn reject_kyc_case(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    Json(payload): Json<KycDecisionRequest>,
+) -> Result<Json<KycCase>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let reason = payload.reason.filter(|r| !r.trim().is_empty()).ok_or((StatusCode::BAD_REQUEST, "A reason is required".to_string()))?;
+    let case = kyc::find_case(&state.pool, case_id).await?;
+    Ok(Json(kyc::reject(&state.pool, case, &claims.sub, &reason).await?))
+}
+
+pub async fn expire_kyc_case(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    Json(payload): Json<KycDecisionRequest>,
+) -> Result<Json<KycCase>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let reason = payload.reason.filter(|r| !r.trim().is_empty()).ok_or((StatusCode::BAD_REQUEST, "A reason is required".to_string()))?;
+    let case = kyc::find_case(&state.pool, case_id).await?;
+    Ok(Json(kyc::expire(&state.pool, case, &claims.sub, &reason).await?))
+}
+
+/// Uploads one document to an open case. The body is the raw file; its `Content-Type` is kept for download.
+pub async fn upload_kyc_document(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    Query(upload): Query<KycDocumentUpload>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<KycDocument>), (StatusCode, String)> {
+    let case = kyc::find_case(&state.pool, case_id).await?;
+    if !COMPLIANCE_ROLES.contains(&claims.role.as_str()) && case.submitted_by != claims.sub {
+        return Err((StatusCode::FORBIDDEN, "Only the submitter or compliance can add documents".to_string()));
+    }
+    let content_type = headers.get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("application/octet-stream");
+
+    let document = kyc::add_document(&state.pool, &state.documents, &case, &upload, content_type, &body, &claims.sub).await?;
+    Ok((StatusCode::CREATED, Json(document)))
+}
+
+pub async fn download_kyc_document(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(document_id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let (document, content) = kyc::read_document(&state.pool, &state.documents, document_id, &claims.sub).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, document.content_type),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", document.file_name)),
+        ],
+        content,
+    ))
+}
+
+pub async fn add_kyc_note(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    Json(payload): Json<AddKycNoteRequest>,
+) -> Result<(StatusCode, Json<KycNote>), (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let case = kyc::find_case(&state.pool, case_id).await?;
+    let note = kyc::add_note(&state.pool, &case, &payload.body, &claims.sub).await?;
+    Ok((StatusCode::CREATED, Json(note)))
+}
+
+pub async fn create_review_period(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<CreateReviewPeriodRequest>,
+) -> Result<(StatusCode, Json<KycReviewPeriod>), (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let period = reverification::create_period(&state.pool, &payload, &claims.sub).await?;
+    Ok((StatusCode::CREATED, Json(period)))
+}
+
+pub async fn list_review_periods(
+    State(state): State<AppState>,
+    _: Claims,
+) -> Result<Json<Vec<KycReviewPeriod>>, (StatusCode, String)> {
+    Ok(Json(reverification::periods(&state.pool).await?))
+}
+
+pub async fn remove_review_period(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(period_id): Path<i32>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    reverification::remove_period(&state.pool, period_id, &claims.sub).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Review deadlines of verified entities, soonest first.
+pub async fn list_entity_reviews(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Query(filter): Query<EntityReviewFilter>,
+) -> Result<Json<Vec<EntityReview>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let periods = reverification::periods(&state.pool).await?;
+    let horizon = filter.within_days.map(|days| chrono::Utc::now() + chrono::Duration::days(days));
+
+    let mut reviews: Vec<EntityReview> = reverification::under_review(&state.pool).await?
+        .iter()
+        .filter_map(|entity| reverification::review(entity, &periods))
+        .filter(|review| horizon.is_none_or(|h| review.due_at <= h))
+        .collect();
+    reviews.sort_by_key(|review| review.due_at);
+
+    Ok(Json(reviews))
+}
+
+/// What the review job did to an entity: flags, lapses and their on-chain follow-up.
+pub async fn list_entity_review_actions(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(entity_id): Path<i32>,
+) -> Result<Json<Vec<KycReviewAction>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let actions = sqlx::query_as::<_, KycReviewAction>("SELECT * FROM kyc_review_actions WHERE entity_id = $1 ORDER BY id")
+        .bind(entity_id)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(actions))
+}
+
+pub async fn list_notifications(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Query(filter): Query<NotificationFilter>,
+) -> Result<Json<Vec<ComplianceNotification>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let notifications = sqlx::query_as::<_, ComplianceNotification>(
+        r#"
+        SELECT * FROM compliance_notifications
+        WHERE ($1 OR acknowledged_at IS NULL) AND ($2::VARCHAR IS NULL OR kind = $2)
+        ORDER BY created_at DESC
+        LIMIT 500
+        "#
+    )
+    .bind(filter.all)
+    .bind(filter.kind)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(notifications))
+}
+
+pub async fn acknowledge_notification(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(notification_id): Path<i32>,
+) -> Result<Json<ComplianceNotification>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let notification = sqlx::query_as::<_, ComplianceNotification>(
+        r#"
+        UPDATE compliance_notifications
+        SET acknowledged_by = COALESCE(acknowledged_by, $1), acknowledged_at = COALESCE(acknowledged_at, NOW())
+        WHERE id = $2
+        RETURNING *
+        "#
+    )
+    .bind(&claims.sub)
+    .bind(notification_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::NOT_FOUND, "Notification not found".to_string()))?;
+
+    Ok(Json(notification))
+}
+
+/// Imports a sanctions list file (the raw body) and re-screens the population against it in the background.
+pub async fn import_sanctions_list(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Query(query): Query<SanctionsImportQuery>,
+    body: String,
+) -> Result<(StatusCode, Json<SanctionsList>), (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let list = sanctions::import(&state.pool, &query, body, &claims.sub).await?;
+    tokio::spawn(screening::run_rescreen(state.pool.clone(), list.id));
+    Ok((StatusCode::CREATED, Json(list)))
+}
+
+pub async fn list_sanctions_lists(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+) -> Result<Json<Vec<SanctionsList>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let lists = sqlx::query_as::<_, SanctionsList>("SELECT * FROM sanctions_lists ORDER BY id DESC")
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(lists))
+}
+
+pub async fn list_sanctions_hits(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Query(filter): Query<SanctionsHitFilter>,
+) -> Result<Json<Vec<SanctionsHit>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let hits = sqlx::query_as::<_, SanctionsHit>(
+        r#"
+        SELECT * FROM sanctions_hits
+        WHERE ($1::VARCHAR IS NULL OR status = $1)
+          AND ($2::VARCHAR IS NULL OR subject_type = $2)
+          AND ($3::INTEGER IS NULL OR entity_id = $3)
+        ORDER BY created_at DESC
+        LIMIT 500
+        "#
+    )
+    .bind(filter.status)
+    .bind(filter.subject_type)
+    .bind(filter.entity_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(hits))
+}
+
+pub async fn get_sanctions_hit(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(hit_id): Path<i32>,
+) -> Result<Json<SanctionsHit>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    Ok(Json(screening::find_hit(&state.pool, hit_id).await?))
+}
+
+/// Confirms a true match; an entity's hit also freezes the entity.
+pub async fn confirm_sanctions_hit(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(hit_id): Path<i32>,
+    payload: Option<Json<SanctionsHitReviewRequest>>,
+) -> Result<Json<SanctionsHit>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let Json(payload) = payload.unwrap_or_default();
+    Ok(Json(screening::review(&state, hit_id, screening::HIT_CONFIRMED, &payload, &claims.sub).await?))
+}
+
+/// Dismisses a false positive; it stays dismissed when later list versions still contain the entry.
+pub async fn dismiss_sanctions_hit(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(hit_id): Path<i32>,
+    Json(payload): Json<SanctionsHitReviewRequest>,
+) -> Result<Json<SanctionsHit>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    Ok(Json(screening::review(&state, hit_id, screening::HIT_DISMISSED, &payload, &claims.sub).await?))
+}
+
+pub async fn create_monitoring_scenario(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<CreateScenarioRequest>,
+) -> Result<(StatusCode, Json<MonitoringScenario>), (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let scenario = monitoring::create_scenario(&state, payload, &claims.sub).await?;
+    Ok((StatusCode::CREATED, Json(scenario)))
+}
+
+pub async fn list_monitoring_scenarios(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+) -> Result<Json<Vec<MonitoringScenario>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let scenarios = sqlx::query_as::<_, MonitoringScenario>("SELECT * FROM monitoring_scenarios ORDER BY id")
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(scenarios))
+}
+
+pub async fn update_monitoring_scenario(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(scenario_id): Path<i32>,
+    Json(payload): Json<UpdateScenarioRequest>,
+) -> Result<Json<MonitoringScenario>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let scenario = monitoring::find_scenario(&state.pool, scenario_id).await?;
+    Ok(Json(monitoring::update_scenario(&state, scenario, payload, &claims.sub).await?))
+}
+
+/// Evaluates the enabled scenarios now instead of waiting for the hourly run; returns the alerts raised.
+pub async fn run_monitoring(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+) -> Result<Json<Vec<MonitoringAlert>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    Ok(Json(monitoring::evaluate(&state.pool).await?))
+}
+
+pub async fn list_monitoring_alerts(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Query(filter): Query<MonitoringAlertFilter>,
+) -> Result<Json<Vec<MonitoringAlert>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let alerts = sqlx::query_as::<_, MonitoringAlert>(
+        r#"
+        SELECT * FROM monitoring_alerts
+        WHERE ($1::VARCHAR IS NULL OR status = $1)
+          AND ($2::INTEGER IS NULL OR scenario_id = $2)
+          AND ($3::INTEGER IS NULL OR wallet_id = $3)
+          AND ($4::VARCHAR IS NULL OR severity = $4)
+          AND ($5::VARCHAR IS NULL OR assigned_to = $5)
+        ORDER BY created_at DESC
+        LIMIT 500
+        "#
+    )
+    .bind(filter.status)
+    .bind(filter.scenario_id)
+    .bind(filter.wallet_id)
+    .bind(filter.severity)
+    .bind(filter.assigned_to)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(alerts))
+}
+
+pub async fn get_monitoring_alert(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(alert_id): Path<i32>,
+) -> Result<Json<MonitoringAlertDetail>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    Ok(Json(alerts::alert_detail(&state.pool, alert_id).await?))
+}
+
+pub async fn assign_monitoring_alert(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(alert_id): Path<i32>,
+    Json(payload): Json<AssignAlertRequest>,
+) -> Result<Json<MonitoringAlert>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let alert = alerts::find_alert(&state.pool, alert_id).await?;
+    Ok(Json(alerts::assign(&state.pool, alert, &payload.assignee, &claims.sub).await?))
+}
+
+pub async fn escalate_monitoring_alert(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(alert_id): Path<i32>,
+    Json(payload): Json<AlertDecisionRequest>,
+) -> Result<Json<MonitoringAlert>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    decide_monitoring_alert(&state, alert_id, AlertStatus::Escalated, payload, &claims.sub).await
+}
+
+/// Dismisses a false positive. The flows behind it will not trigger the same scenario again.
+pub async fn dismiss_monitoring_alert(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(alert_id): Path<i32>,
+    Json(payload): Json<AlertDecisionRequest>,
+) -> Result<Json<MonitoringAlert>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    decide_monitoring_alert(&state, alert_id, AlertStatus::Dismissed, payload, &claims.sub).await
+}
+
+pub async fn resolve_monitoring_alert(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(alert_id): Path<i32>,
+    Json(payload): Json<AlertDecisionRequest>,
+) -> Result<Json<MonitoringAlert>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    decide_monitoring_alert(&state, alert_id, AlertStatus::Resolved, payload, &claims.sub).await
+}
+
+async fn decide_monitoring_alert(
+    state: &AppState,
+    alert_id: i32,
+    to: AlertStatus,
+    payload: AlertDecisionRequest,
+    actor: &str,
+) -> Result<Json<MonitoringAlert>, (StatusCode, String)> {
+    let reason = payload.reason.filter(|r| !r.trim().is_empty()).ok_or((StatusCode::BAD_REQUEST, "A reason is required".to_string()))?;
+    let alert = alerts::find_alert(&state.pool, alert_id).await?;
+    Ok(Json(alerts::decide(&state.pool, alert, to, actor, reason.trim()).await?))
+}
+
+pub async fn add_monitoring_alert_note(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(alert_id): Path<i32>,
+    Json(payload): Json<AddAlertNoteRequest>,
+) -> Result<(StatusCode, Json<MonitoringAlertNote>), (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let alert = alerts::find_alert(&state.pool, alert_id).await?;
+    let note = alerts::add_note(&state.pool, &alert, &payload.body, &claims.sub).await?;
+    Ok((StatusCode::CREATED, Json(note)))
+}
+
+pub async fn open_case(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<CreateCaseRequest>,
+) -> Result<(StatusCode, Json<ComplianceCase>), (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let case = cases::open(&state.pool, payload, &claims.sub).await?;
+    Ok((StatusCode::CREATED, Json(case)))
+}
+
+pub async fn list_cases(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Query(filter): Query<CaseFilter>,
+) -> Result<Json<Vec<ComplianceCase>>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let cases = sqlx::query_as::<_, ComplianceCase>(
+        r#"
+        SELECT * FROM compliance_cases c
+        WHERE ($1::VARCHAR IS NULL OR status = $1)
+          AND ($2::VARCHAR IS NULL OR priority = $2)
+          AND ($3::VARCHAR IS NULL OR assigned_to = $3)
+          AND (NOT $4 OR (status IN ('open', 'investigating', 'escalated') AND due_at <= NOW()))
+          AND ($5::VARCHAR IS NULL OR EXISTS (
+              SELECT 1 FROM compliance_case_links l
+              WHERE l.case_id = c.id AND l.link_type = $5 AND ($6::INTEGER IS NULL OR l.link_id = $6)
+          ))
+        ORDER BY due_at
+        LIMIT 500
+        "#
+    )
+    .bind(filter.status)
+    .bind(filter.priority)
+    .bind(filter.assigned_to)
+    .bind(filter.overdue.unwrap_or(false))
+    .bind(filter.link_type)
+    .bind(filter.link_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(cases))
+}
+
+pub async fn get_case(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+) -> Result<Json<ComplianceCaseDetail>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    Ok(Json(cases::case_detail(&state.pool, case_id).await?))
+}
+
+pub async fn update_case(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    Json(payload): Json<UpdateCaseRequest>,
+) -> Result<Json<ComplianceCase>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let case = cases::find_case(&state.pool, case_id).await?;
+    Ok(Json(cases::update(&state.pool, case, payload, &claims.sub).await?))
+}
+
+pub async fn assign_case(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    Json(payload): Json<AssignCaseRequest>,
+) -> Result<Json<ComplianceCase>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let case = cases::find_case(&state.pool, case_id).await?;
+    Ok(Json(cases::assign(&state.pool, case, &payload.assignee, &claims.sub).await?))
+}
+
+/// Hands an investigated case to a second reviewer to decide on filing.
+pub async fn escalate_case(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    payload: Option<Json<CaseDecisionRequest>>,
+) -> Result<Json<ComplianceCase>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let Json(payload) = payload.unwrap_or_default();
+    decide_case(&state, case_id, CaseStatus::Escalated, payload, &claims.sub).await
+}
+
+/// Sends an escalated case back to its assignee for further investigation.
+pub async fn return_case(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    payload: Option<Json<CaseDecisionRequest>>,
+) -> Result<Json<ComplianceCase>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let Json(payload) = payload.unwrap_or_default();
+    decide_case(&state, case_id, CaseStatus::Investigating, payload, &claims.sub).await
+}
+
+pub async fn close_case(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    Json(payload): Json<CaseDecisionRequest>,
+) -> Result<Json<ComplianceCase>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    decide_case(&state, case_id, CaseStatus::Closed, payload, &claims.sub).await
+}
+
+async fn decide_case(
+    state: &AppState,
+    case_id: i32,
+    to: CaseStatus,
+    payload: CaseDecisionRequest,
+    actor: &str,
+) -> Result<Json<ComplianceCase>, (StatusCode, String)> {
+    let reason = payload.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    let case = cases::find_case(&state.pool, case_id).await?;
+    Ok(Json(cases::decide(&state.pool, case, to, actor, reason).await?))
+}
+
+/// Records the filing of the case's report with the regulator or FIU.
+pub async fn file_case_report(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    Json(payload): Json<FileCaseReportRequest>,
+) -> Result<Json<ComplianceCase>, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let case = cases::find_case(&state.pool, case_id).await?;
+    let note = payload.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    Ok(Json(cases::file_report(&state.pool, case, &payload.filing_reference, note, &claims.sub).await?))
+}
+
+pub async fn add_case_link(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    Json(payload): Json<CaseLinkRequest>,
+) -> Result<(StatusCode, Json<ComplianceCaseLink>), (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let case = cases::find_case(&state.pool, case_id).await?;
+    let link = cases::add_link(&state.pool, &case, &payload, &claims.sub).await?;
+    Ok((StatusCode::CREATED, Json(link)))
+}
+
+pub async fn remove_case_link(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path((case_id, link_id)): Path<(i32, i32)>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let case = cases::find_case(&state.pool, case_id).await?;
+    cases::remove_link(&state.pool, &case, link_id, &claims.sub).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn add_case_comment(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    Json(payload): Json<AddCaseCommentRequest>,
+) -> Result<(StatusCode, Json<ComplianceCaseComment>), (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let case = cases::find_case(&state.pool, case_id).await?;
+    let comment = cases::add_comment(&state.pool, &case, &payload, &claims.sub).await?;
+    Ok((StatusCode::CREATED, Json(comment)))
+}
+
+/// Attaches one file to a case. The body is the raw file; its `Content-Type` is kept for download.
+pub async fn upload_case_attachment(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    Query(upload): Query<CaseAttachmentUpload>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<ComplianceCaseAttachment>), (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let case = cases::find_case(&state.pool, case_id).await?;
+    let content_type = headers.get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("application/octet-stream");
+
+    let attachment = cases::add_attachment(&state.pool, &state.documents, &case, &upload, content_type, &body, &claims.sub).await?;
+    Ok((StatusCode::CREATED, Json(attachment)))
+}
+
+pub async fn download_case_attachment(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(attachment_id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let (attachment, content) = cases::read_attachment(&state.pool, &state.documents, attachment_id, &claims.sub).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", attachment.file_name)),
+        ],
+        content,
+    ))
+}
+
+/// Drafts a SAR/STR from the case as JSON, or as XML ready to be laid out as a PDF.
+pub async fn export_case_report(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(case_id): Path<i32>,
+    Query(query): Query<CaseReportQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+    let format = query.format.as_deref().unwrap_or("json");
+    if format != "json" && format != "xml" {
+        return Err((StatusCode::BAD_REQUEST, "format must be json or xml".to_string()));
+    }
+    let report_type = query.report_type.as_deref().unwrap_or(cases::report::REPORT_SAR).to_lowercase();
+    let draft = cases::report::draft(&state.pool, case_id, &report_type, &claims.sub).await?;
+
+    match format {
+        "xml" => Ok((
+            [
+                (header::CONTENT_TYPE, "application/xml".to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.xml\"", draft.reference)),
+            ],
+            cases::report::to_xml(&draft),
+        ).into_response()),
+        _ => Ok(Json(draft).into_response()),
+    }
+}
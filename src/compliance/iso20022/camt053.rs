@@ -0,0 +1,233 @@
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use std::sync::OnceLock;
+use crate::finance::amounts::{format_amount, parse_amount};
+use super::pacs008::{write_account, write_party, Account, Amount, Party, MAX_FRACTION_DIGITS};
+use super::xml::{truncate, XmlBuilder};
+use super::xsd::Schema;
+
+pub const NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:camt.053.001.08";
+const XSD: &str = include_str!("../../../schemas/iso20022/camt.053.001.08.xsd");
+
+/// Issuer of the proprietary bank transaction codes, which name the on-chain event behind an entry.
+const CODE_ISSUER: &str = "AEGIS";
+
+/// BankToCustomerStatementV08.
+pub struct Camt053 {
+    pub msg_id: String,
+    pub created_at: DateTime<Utc>,
+    pub statements: Vec<Statement>,
+}
+
+/// AccountStatement9: one asset held by a wallet over the reporting period.
+pub struct Statement {
+    pub id: String,
+    pub sequence: Option<u64>,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub account: Account,
+    pub owner: Party,
+    pub opening: Balance,
+    pub closing: Balance,
+    pub entries: Vec<Entry>,
+    pub info: Option<String>,
+}
+
+/// CashBalance8. A wallet indexed after it was first funded can show a debit balance.
+pub struct Balance {
+    pub amount: Amount,
+    pub direction: Direction,
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Credit,
+    Debit,
+}
+
+impl Direction {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Direction::Credit => "CRDT",
+            Direction::Debit => "DBIT",
+        }
+    }
+}
+
+/// ReportEntry10 carrying a single transaction: one indexed movement of the wallet.
+pub struct Entry {
+    pub reference: String,
+    pub amount: Amount,
+    pub direction: Direction,
+    pub booked_at: DateTime<Utc>,
+    /// On-chain event the movement was read from, reported as the proprietary transaction code.
+    pub event: String,
+    /// End-to-end id of the agent payment or funding that caused the movement, when there is one.
+    pub end_to_end_id: Option<String>,
+    pub counterparty: Party,
+    pub counterparty_account: Account,
+    pub details: Option<String>,
+}
+
+pub fn schema() -> &'static Schema {
+    static SCHEMA: OnceLock<Schema> = OnceLock::new();
+    SCHEMA.get_or_init(|| Schema::parse(XSD).expect("bundled camt.053 schema is valid"))
+}
+
+impl Camt053 {
+    /// Serializes the statement and validates it against the bundled XSD.
+    pub fn to_xml(&self) -> Result<String, String> {
+        if self.statements.is_empty() {
+            return Err("A camt.053 needs at least one statement".to_string());
+        }
+
+        let mut xml = XmlBuilder::document(NAMESPACE);
+        xml.start("BkToCstmrStmt");
+
+        xml.start("GrpHdr");
+        xml.text("MsgId", &self.msg_id);
+        xml.text("CreDtTm", &self.created_at.to_rfc3339_opts(SecondsFormat::Secs, true));
+        xml.end("GrpHdr");
+
+        for statement in &self.statements {
+            statement.write(&mut xml, self.created_at)?;
+        }
+
+        xml.end("BkToCstmrStmt");
+        let document = xml.finish();
+
+        schema().validate(&document).map_err(|errors| format!("camt.053 failed schema validation: {}", errors.join("; ")))?;
+        Ok(document)
+    }
+}
+
+impl Statement {
+    fn write(&self, xml: &mut XmlBuilder, created_at: DateTime<Utc>) -> Result<(), String> {
+        xml.start("Stmt");
+        xml.text("Id", &self.id);
+        xml.optional("ElctrncSeqNb", self.sequence.map(|n| n.to_string()).as_deref());
+        xml.text("CreDtTm", &created_at.to_rfc3339_opts(SecondsFormat::Secs, true));
+        xml.start("FrToDt");
+        xml.text("FrDtTm", &self.from.to_rfc3339_opts(SecondsFormat::Secs, true));
+        xml.text("ToDtTm", &self.to.to_rfc3339_opts(SecondsFormat::Secs, true));
+        xml.end("FrToDt");
+        write_account(xml, "Acct", &self.account);
+
+        // Opening and closing booked balances
+        write_balance(xml, "OPBD", &self.opening);
+        write_balance(xml, "CLBD", &self.closing);
+
+        if !self.entries.is_empty() {
+            xml.start("TxsSummry");
+            for (tag, direction) in [("TtlCdtNtries", Direction::Credit), ("TtlDbtNtries", Direction::Debit)] {
+                let amounts: Vec<&Amount> = self.entries.iter().filter(|e| e.direction == direction).map(|e| &e.amount).collect();
+                if !amounts.is_empty() {
+                    xml.start(tag);
+                    xml.text("NbOfNtries", &amounts.len().to_string());
+                    xml.text("Sum", &sum(&amounts)?);
+                    xml.end(tag);
+                }
+            }
+            xml.end("TxsSummry");
+        }
+
+        for entry in &self.entries {
+            write_entry(xml, entry, &self.owner, &self.account);
+        }
+
+        xml.optional("AddtlStmtInf", self.info.as_deref().map(|i| truncate(i, 500)).as_deref());
+        xml.end("Stmt");
+        Ok(())
+    }
+}
+
+fn write_balance(xml: &mut XmlBuilder, code: &str, balance: &Balance) {
+    xml.start("Bal");
+    xml.start("Tp");
+    xml.start("CdOrPrtry");
+    xml.text("Cd", code);
+    xml.end("CdOrPrtry");
+    xml.end("Tp");
+    xml.text_with("Amt", &[("Ccy", balance.amount.currency())], balance.amount.value());
+    xml.text("CdtDbtInd", balance.direction.code());
+    xml.start("Dt");
+    xml.text("Dt", &balance.date.to_string());
+    xml.end("Dt");
+    xml.end("Bal");
+}
+
+/// Writes a booked entry. The wallet's owner is the creditor of credits and the debtor of debits.
+pub(super) fn write_entry(xml: &mut XmlBuilder, entry: &Entry, owner: &Party, account: &Account) {
+    let booked_at = entry.booked_at.to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    xml.start("Ntry");
+    xml.text("NtryRef", &entry.reference);
+    xml.text_with("Amt", &[("Ccy", entry.amount.currency())], entry.amount.value());
+    xml.text("CdtDbtInd", entry.direction.code());
+    xml.start("Sts");
+    xml.text("Cd", "BOOK");
+    xml.end("Sts");
+    xml.start("BookgDt");
+    xml.text("DtTm", &booked_at);
+    xml.end("BookgDt");
+    xml.start("ValDt");
+    xml.text("Dt", &entry.booked_at.date_naive().to_string());
+    xml.end("ValDt");
+    xml.text("AcctSvcrRef", &entry.reference);
+
+    // Received and issued credit transfers
+    xml.start("BkTxCd");
+    xml.start("Domn");
+    xml.text("Cd", "PMNT");
+    xml.start("Fmly");
+    xml.text("Cd", match entry.direction {
+        Direction::Credit => "RCDT",
+        Direction::Debit => "ICDT",
+    });
+    xml.text("SubFmlyCd", "OTHR");
+    xml.end("Fmly");
+    xml.end("Domn");
+    xml.start("Prtry");
+    xml.text("Cd", &entry.event);
+    xml.text("Issr", CODE_ISSUER);
+    xml.end("Prtry");
+    xml.end("BkTxCd");
+
+    xml.start("NtryDtls");
+    xml.start("TxDtls");
+    xml.start("Refs");
+    xml.text("AcctSvcrRef", &entry.reference);
+    xml.optional("EndToEndId", entry.end_to_end_id.as_deref());
+    xml.end("Refs");
+    xml.text_with("Amt", &[("Ccy", entry.amount.currency())], entry.amount.value());
+    xml.text("CdtDbtInd", entry.direction.code());
+
+    let (debtor, debtor_account, creditor, creditor_account) = match entry.direction {
+        Direction::Credit => (&entry.counterparty, &entry.counterparty_account, owner, account),
+        Direction::Debit => (owner, account, &entry.counterparty, &entry.counterparty_account),
+    };
+    xml.start("RltdPties");
+    xml.start("Dbtr");
+    write_party(xml, "Pty", debtor);
+    xml.end("Dbtr");
+    write_account(xml, "DbtrAcct", &Account { currency: None, ..debtor_account.clone() });
+    xml.start("Cdtr");
+    write_party(xml, "Pty", creditor);
+    xml.end("Cdtr");
+    write_account(xml, "CdtrAcct", &Account { currency: None, ..creditor_account.clone() });
+    xml.end("RltdPties");
+
+    xml.optional("AddtlTxInf", entry.details.as_deref().map(|d| truncate(d, 500)).as_deref());
+    xml.end("TxDtls");
+    xml.end("NtryDtls");
+    xml.end("Ntry");
+}
+
+/// Total of entry amounts as a `DecimalNumber`.
+fn sum(amounts: &[&Amount]) -> Result<String, String> {
+    let decimals = MAX_FRACTION_DIGITS as u8;
+    let total = amounts.iter().try_fold(ethers::types::U256::zero(), |total, amount| {
+        parse_amount(amount.value(), decimals).map(|value| total.saturating_add(value))
+    })?;
+    Ok(format_amount(total, decimals))
+}
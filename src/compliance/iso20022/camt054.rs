@@ -0,0 +1,66 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::sync::OnceLock;
+use super::camt053::{write_entry, Entry};
+use super::pacs008::{write_account, Account, Party};
+use super::xml::{truncate, XmlBuilder};
+use super::xsd::Schema;
+
+pub const NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:camt.054.001.08";
+const XSD: &str = include_str!("../../../schemas/iso20022/camt.054.001.08.xsd");
+
+/// BankToCustomerDebitCreditNotificationV08.
+pub struct Camt054 {
+    pub msg_id: String,
+    pub created_at: DateTime<Utc>,
+    pub notifications: Vec<Notification>,
+}
+
+/// AccountNotification17: movements of one asset held by a wallet.
+pub struct Notification {
+    pub id: String,
+    pub account: Account,
+    pub owner: Party,
+    pub entries: Vec<Entry>,
+    pub info: Option<String>,
+}
+
+pub fn schema() -> &'static Schema {
+    static SCHEMA: OnceLock<Schema> = OnceLock::new();
+    SCHEMA.get_or_init(|| Schema::parse(XSD).expect("bundled camt.054 schema is valid"))
+}
+
+impl Camt054 {
+    /// Serializes the notification and validates it against the bundled XSD.
+    pub fn to_xml(&self) -> Result<String, String> {
+        if self.notifications.iter().all(|n| n.entries.is_empty()) {
+            return Err("A camt.054 needs at least one entry".to_string());
+        }
+
+        let created_at = self.created_at.to_rfc3339_opts(SecondsFormat::Secs, true);
+        let mut xml = XmlBuilder::document(NAMESPACE);
+        xml.start("BkToCstmrDbtCdtNtfctn");
+
+        xml.start("GrpHdr");
+        xml.text("MsgId", &self.msg_id);
+        xml.text("CreDtTm", &created_at);
+        xml.end("GrpHdr");
+
+        for notification in &self.notifications {
+            xml.start("Ntfctn");
+            xml.text("Id", &notification.id);
+            xml.text("CreDtTm", &created_at);
+            write_account(&mut xml, "Acct", &notification.account);
+            for entry in &notification.entries {
+                write_entry(&mut xml, entry, &notification.owner, &notification.account);
+            }
+            xml.optional("AddtlNtfctnInf", notification.info.as_deref().map(|i| truncate(i, 500)).as_deref());
+            xml.end("Ntfctn");
+        }
+
+        xml.end("BkToCstmrDbtCdtNtfctn");
+        let document = xml.finish();
+
+        schema().validate(&document).map_err(|errors| format!("camt.054 failed schema validation: {}", errors.join("; ")))?;
+        Ok(document)
+    }
+}
@@ -0,0 +1,65 @@
+/// `BICFIDec2014Identifier` / `AnyBICDec2014Identifier`: 8 or 11 characters.
+pub fn validate_bic(bic: &str) -> Result<(), String> {
+    let valid = matches!(bic.len(), 8 | 11)
+        && bic[..4].chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        && bic[4..6].chars().all(|c| c.is_ascii_uppercase())
+        && bic[6..].chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    if valid { Ok(()) } else { Err(format!("Invalid BIC '{}'", bic)) }
+}
+
+/// `IBAN2007Identifier` with valid ISO 13616 check digits (the XSD pattern alone accepts any digits).
+pub fn validate_iban(iban: &str) -> Result<(), String> {
+    let valid_shape = (5..=34).contains(&iban.len())
+        && iban[..2].chars().all(|c| c.is_ascii_uppercase())
+        && iban[2..4].chars().all(|c| c.is_ascii_digit())
+        && iban[4..].chars().all(|c| c.is_ascii_alphanumeric());
+    if !valid_shape {
+        return Err(format!("Invalid IBAN '{}'", iban));
+    }
+
+    let rearranged = format!("{}{}", &iban[4..], &iban[..4]);
+    if mod97(&rearranged) != 1 {
+        return Err(format!("IBAN '{}' has an invalid check digit", iban));
+    }
+    Ok(())
+}
+
+/// `LEIIdentifier`: 18 alphanumerics and two ISO 17442 check digits.
+pub fn validate_lei(lei: &str) -> Result<(), String> {
+    let valid_shape = lei.len() == 20
+        && lei[..18].chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        && lei[18..].chars().all(|c| c.is_ascii_digit());
+    if !valid_shape {
+        return Err(format!("Invalid LEI '{}'", lei));
+    }
+    if mod97(lei) != 1 {
+        return Err(format!("LEI '{}' has an invalid check digit", lei));
+    }
+    Ok(())
+}
+
+/// `ActiveCurrencyCode` / `ActiveOrHistoricCurrencyCode`: three uppercase letters.
+pub fn validate_currency(code: &str) -> Result<(), String> {
+    if code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase()) {
+        Ok(())
+    } else {
+        Err(format!("Invalid currency code '{}', expected three uppercase letters", code))
+    }
+}
+
+/// `CountryCode`: two uppercase letters.
+pub fn is_country_code(code: &str) -> bool {
+    code.len() == 2 && code.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// MOD 97-10 over alphanumerics, letters expanded to 10..35.
+fn mod97(s: &str) -> u32 {
+    s.chars().fold(0u32, |acc, c| {
+        let value = c.to_digit(36).unwrap_or(0);
+        if value >= 10 {
+            (acc * 100 + value) % 97
+        } else {
+            (acc * 10 + value) % 97
+        }
+    })
+}
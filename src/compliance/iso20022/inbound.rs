@@ -0,0 +1,359 @@
+use axum::http::StatusCode;
+use sqlx::PgPool;
+use crate::state::AppState;
+use crate::agent::intents;
+use crate::agent::models::CreateIntentRequest;
+use crate::finance::amounts::NATIVE_SYMBOL;
+use crate::finance::models::{Token, Wallet};
+use crate::compliance::audit;
+use crate::compliance::models::{InboundItem, InboundMessage, InboundMessageDetail, IncomingFunds, LegalEntity};
+use super::pacs008::{Account, AccountId, CreditTransfer, Pacs008, Party};
+use super::pain001::{Pain001, PaymentInstruction, TransferInstruction, CREDIT_TRANSFER};
+use super::{pacs008, pain001, ADDRESS_PROXY, PACS_008, PAIN_001, WALLET_SCHEME};
+
+/// Either the mapped value or the reason the transfer is rejected.
+type Mapping<T> = Result<T, String>;
+
+/// What an inbound credit transfer turned into.
+enum Outcome {
+    Intent(i32),
+    Funds(i32),
+    Rejected(String),
+}
+
+/// Stores a received pain.001 or pacs.008 and maps each of its credit transfers.
+///
+/// A pain.001 asks us to pay: each transfer from a mapped debtor wallet becomes an agent payment intent,
+/// created by the caller and held after screening so it still needs an explicit approval.
+/// A pacs.008 tells us we were paid: each transfer to a mapped creditor wallet becomes an incoming-funds record.
+/// Transfers whose parties cannot be mapped, or whose processing fails, are kept as rejected items with the reason,
+/// so a message is never left stored with only some of its transfers recorded.
+pub async fn ingest(state: &AppState, xml: &str, actor: &str) -> Result<InboundMessageDetail, (StatusCode, String)> {
+    let namespace = roxmltree::Document::parse(xml)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Malformed XML: {}", e)))?
+        .root_element()
+        .tag_name()
+        .namespace()
+        .map(str::to_string);
+
+    match namespace.as_deref() {
+        Some(pain001::NAMESPACE) => {
+            let message = Pain001::from_xml(xml).map_err(unprocessable)?;
+            let stored = store(&state.pool, PAIN_001, &message.msg_id, xml, actor).await?;
+            for payment in &message.payments {
+                for transfer in &payment.transactions {
+                    let outcome = initiate(state, payment, transfer, actor).await.unwrap_or_else(|(_, e)| Outcome::Rejected(e));
+                    record_item(&state.pool, stored.id, Some(&payment.payment_info_id), &transfer.end_to_end_id, &transfer.amount, outcome).await?;
+                }
+            }
+            finish(&state.pool, stored, actor).await
+        }
+        Some(pacs008::NAMESPACE) => {
+            let message = Pacs008::from_xml(xml).map_err(unprocessable)?;
+            let stored = store(&state.pool, PACS_008, &message.msg_id, xml, actor).await?;
+            for transfer in &message.transactions {
+                let outcome = receive(&state.pool, state.chain.chain_id() as i64, &message, transfer).await
+                    .unwrap_or_else(|(_, e)| Outcome::Rejected(e));
+                record_item(&state.pool, stored.id, None, &transfer.end_to_end_id, &transfer.amount, outcome).await?;
+            }
+            finish(&state.pool, stored, actor).await
+        }
+        other => Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Unsupported message namespace {}; expected {} or {}", other.unwrap_or("(none)"), pain001::NAMESPACE, pacs008::NAMESPACE),
+        )),
+    }
+}
+
+pub async fn find_inbound(pool: &PgPool, message_id: i32) -> Result<InboundMessageDetail, (StatusCode, String)> {
+    let message = sqlx::query_as::<_, InboundMessage>("SELECT * FROM iso20022_inbound_messages WHERE id = $1")
+        .bind(message_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Inbound message {} not found", message_id)))?;
+
+    let items = sqlx::query_as::<_, InboundItem>("SELECT * FROM iso20022_inbound_items WHERE message_id = $1 ORDER BY id")
+        .bind(message.id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(InboundMessageDetail { message, items })
+}
+
+/// Maps a pain.001 transfer to a held payment intent from the debtor's wallet.
+async fn initiate(state: &AppState, payment: &PaymentInstruction, transfer: &TransferInstruction, actor: &str) -> Result<Outcome, (StatusCode, String)> {
+    if payment.method != CREDIT_TRANSFER {
+        return Ok(Outcome::Rejected(format!("Payment method {} is not supported; only {} is", payment.method, CREDIT_TRANSFER)));
+    }
+
+    let wallet = match resolve_wallet(&state.pool, "Debtor", &payment.debtor, Some(&payment.debtor_account)).await? {
+        Ok(wallet) => wallet,
+        Err(reason) => return Ok(Outcome::Rejected(reason)),
+    };
+    let target = match creditor_address(&state.pool, transfer).await? {
+        Ok(address) => address,
+        Err(reason) => return Ok(Outcome::Rejected(reason)),
+    };
+    let token_address = match asset(&state.pool, transfer.amount.currency(), wallet.chain_id).await? {
+        Ok(token) => token.map(|t| t.address),
+        Err(reason) => return Ok(Outcome::Rejected(reason)),
+    };
+
+    let request = CreateIntentRequest {
+        wallet_id: wallet.id,
+        target_address: target,
+        amount: transfer.amount.value().to_string(),
+        token_address,
+        hold_for_review: true,
+    };
+    let intent = intents::create_intent(&state.pool, &request, actor, actor).await?;
+    let intent = intents::process(state, intent, true).await?;
+    Ok(Outcome::Intent(intent.id))
+}
+
+/// Maps a pacs.008 transfer to an incoming-funds record on the creditor's wallet.
+async fn receive(pool: &PgPool, chain_id: i64, message: &Pacs008, transfer: &CreditTransfer) -> Result<Outcome, (StatusCode, String)> {
+    let wallet = match resolve_wallet(pool, "Creditor", &transfer.creditor, transfer.creditor_account.as_ref()).await? {
+        Ok(wallet) => wallet,
+        Err(reason) => return Ok(Outcome::Rejected(reason)),
+    };
+    let token = match asset(pool, transfer.amount.currency(), chain_id).await? {
+        Ok(token) => token,
+        Err(reason) => return Ok(Outcome::Rejected(reason)),
+    };
+    // The debtor is usually outside Aegis; link it when it is one of our entities
+    let debtor_entity = match &transfer.debtor.lei {
+        Some(lei) => entity_by(pool, "lei", lei).await?.map(|e| e.id),
+        None => None,
+    };
+
+    let funds = sqlx::query_as::<_, IncomingFunds>(
+        r#"
+        INSERT INTO incoming_funds (wallet_id, amount, currency, token_address, debtor_name, debtor_entity_id, end_to_end_id, uetr, settlement_date)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING *
+        "#
+    )
+    .bind(wallet.id)
+    .bind(transfer.amount.value())
+    .bind(transfer.amount.currency())
+    .bind(token.map(|t| t.address))
+    .bind(&transfer.debtor.name)
+    .bind(debtor_entity)
+    .bind(&transfer.end_to_end_id)
+    .bind(&transfer.uetr)
+    .bind(message.settlement_date)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Outcome::Funds(funds.id))
+}
+
+/// Finds the registered wallet a party and account refer to. The account decides when it identifies a wallet
+/// (our `AEGIS-W<id>` scheme, an EVM address proxy, or an entity's IBAN); otherwise the party's LEI must name an
+/// entity with exactly one wallet. A party LEI contradicting the account's entity is rejected.
+async fn resolve_wallet(pool: &PgPool, role: &str, party: &Party, account: Option<&Account>) -> Result<Mapping<Wallet>, (StatusCode, String)> {
+    let entity = match &party.lei {
+        Some(lei) => entity_by(pool, "lei", lei).await?,
+        None => None,
+    };
+
+    let from_account = match account {
+        Some(account) => account_wallet(pool, account).await?,
+        None => None,
+    };
+
+    let wallet = match (from_account, &entity) {
+        (Some(Ok(wallet)), Some(entity)) if wallet.entity_id != entity.id => {
+            return Ok(Err(format!("{} account belongs to wallet {}, not to LEI {}", role, wallet.id, entity.lei.as_deref().unwrap_or_default())));
+        }
+        (Some(result), _) => result,
+        (None, Some(entity)) => sole_wallet(pool, entity).await?,
+        (None, None) => Err(format!("{} is not a registered entity or wallet", describe(party, account))),
+    };
+
+    Ok(wallet.map_err(|reason| format!("{}: {}", role, reason)))
+}
+
+/// `None` when the account carries nothing we can map; `Some(Err)` when it names something we do not know.
+async fn account_wallet(pool: &PgPool, account: &Account) -> Result<Option<Mapping<Wallet>>, (StatusCode, String)> {
+    if let AccountId::Other { id, scheme } = &account.id {
+        let wallet_id = id.strip_prefix("AEGIS-W").and_then(|n| n.parse::<i32>().ok());
+        if let Some(wallet_id) = wallet_id.filter(|_| scheme.as_deref().is_none_or(|s| s == WALLET_SCHEME)) {
+            let wallet = sqlx::query_as::<_, Wallet>("SELECT * FROM wallets WHERE id = $1")
+                .bind(wallet_id)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            return Ok(Some(wallet.ok_or(format!("wallet account {} does not exist", id))));
+        }
+    }
+
+    if let Some(address) = proxy_address(account) {
+        let wallet = sqlx::query_as::<_, Wallet>("SELECT * FROM wallets WHERE address = $1")
+            .bind(address.to_lowercase())
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        return Ok(Some(wallet.ok_or(format!("address {} is not a registered wallet", address))));
+    }
+
+    if let AccountId::Iban(iban) = &account.id {
+        return match entity_by(pool, "iban", iban).await? {
+            Some(entity) => sole_wallet(pool, &entity).await.map(Some),
+            None => Ok(Some(Err(format!("IBAN {} does not belong to a registered entity", iban)))),
+        };
+    }
+
+    Ok(None)
+}
+
+/// Target of a pain.001 transfer: a registered wallet, or any on-chain address given as the account proxy.
+async fn creditor_address(pool: &PgPool, transfer: &TransferInstruction) -> Result<Mapping<String>, (StatusCode, String)> {
+    if let Some(address) = transfer.creditor_account.as_ref().and_then(proxy_address) {
+        return Ok(Ok(address.to_lowercase()));
+    }
+
+    let Some(creditor) = &transfer.creditor else {
+        return Ok(Err("Transfer has no creditor".to_string()));
+    };
+    let wallet = resolve_wallet(pool, "Creditor", creditor, transfer.creditor_account.as_ref()).await?;
+    Ok(wallet.map(|w| w.address))
+}
+
+/// Native ETH for "ETH"; otherwise the allowed token whose ISO currency (or, without one, symbol) is `currency`.
+async fn asset(pool: &PgPool, currency: &str, chain_id: i64) -> Result<Mapping<Option<Token>>, (StatusCode, String)> {
+    if currency == NATIVE_SYMBOL {
+        return Ok(Ok(None));
+    }
+
+    let token = sqlx::query_as::<_, Token>(
+        r#"
+        SELECT * FROM tokens
+        WHERE chain_id = $1 AND allowed AND (iso_currency = $2 OR (iso_currency IS NULL AND symbol = $2))
+        ORDER BY id
+        LIMIT 1
+        "#
+    )
+    .bind(chain_id)
+    .bind(currency)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(token.map(Some).ok_or(format!("No allowed token settles {}", currency)))
+}
+
+async fn sole_wallet(pool: &PgPool, entity: &LegalEntity) -> Result<Mapping<Wallet>, (StatusCode, String)> {
+    let mut wallets = sqlx::query_as::<_, Wallet>("SELECT * FROM wallets WHERE entity_id = $1 ORDER BY id LIMIT 2")
+        .bind(entity.id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(match wallets.len() {
+        1 => Ok(wallets.remove(0)),
+        0 => Err(format!("entity {} has no wallet", entity.hash_id)),
+        _ => Err(format!("entity {} has several wallets; identify the account", entity.hash_id)),
+    })
+}
+
+async fn entity_by(pool: &PgPool, column: &str, value: &str) -> Result<Option<LegalEntity>, (StatusCode, String)> {
+    sqlx::query_as::<_, LegalEntity>(&format!("SELECT * FROM legal_entities WHERE {} = $1", column))
+        .bind(value)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+fn proxy_address(account: &Account) -> Option<&str> {
+    account.proxy.as_ref()
+        .filter(|(kind, id)| kind == ADDRESS_PROXY && id.parse::<ethers::types::Address>().is_ok())
+        .map(|(_, id)| id.as_str())
+}
+
+fn describe(party: &Party, account: Option<&Account>) -> String {
+    let account = account.map(|a| match &a.id {
+        AccountId::Iban(iban) => iban.as_str(),
+        AccountId::Other { id, .. } => id.as_str(),
+    });
+    party.name.as_deref().or(account).unwrap_or("(unnamed)").to_string()
+}
+
+fn unprocessable(e: String) -> (StatusCode, String) {
+    (StatusCode::UNPROCESSABLE_ENTITY, e)
+}
+
+async fn store(pool: &PgPool, message_type: &str, msg_id: &str, document: &str, actor: &str) -> Result<InboundMessage, (StatusCode, String)> {
+    sqlx::query_as::<_, InboundMessage>(
+        r#"
+        INSERT INTO iso20022_inbound_messages (message_type, msg_id, document, received_by)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (message_type, msg_id) DO NOTHING
+        RETURNING *
+        "#
+    )
+    .bind(message_type)
+    .bind(msg_id)
+    .bind(document)
+    .bind(actor)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::CONFLICT, format!("{} {} was already received", message_type, msg_id)))
+}
+
+async fn record_item(
+    pool: &PgPool,
+    message_id: i32,
+    payment_info_id: Option<&str>,
+    end_to_end_id: &str,
+    amount: &pacs008::Amount,
+    outcome: Outcome,
+) -> Result<(), (StatusCode, String)> {
+    let (status, intent_id, funds_id, reason) = match outcome {
+        Outcome::Intent(id) => ("intent_created", Some(id), None, None),
+        Outcome::Funds(id) => ("funds_recorded", None, Some(id), None),
+        Outcome::Rejected(reason) => ("rejected", None, None, Some(reason)),
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO iso20022_inbound_items
+            (message_id, payment_info_id, end_to_end_id, amount, currency, status, intent_id, incoming_funds_id, rejection_reason)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#
+    )
+    .bind(message_id)
+    .bind(payment_info_id)
+    .bind(end_to_end_id)
+    .bind(amount.value())
+    .bind(amount.currency())
+    .bind(status)
+    .bind(intent_id)
+    .bind(funds_id)
+    .bind(reason)
+    .execute(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(())
+}
+
+async fn finish(pool: &PgPool, message: InboundMessage, actor: &str) -> Result<InboundMessageDetail, (StatusCode, String)> {
+    let detail = find_inbound(pool, message.id).await?;
+    let rejected = detail.items.iter().filter(|i| i.status == "rejected").count();
+
+    audit::record(pool, actor, "iso20022.ingest", &detail.message.msg_id, serde_json::json!({
+        "message_type": detail.message.message_type,
+        "inbound_message_id": detail.message.id,
+        "items": detail.items.len(),
+        "rejected": rejected,
+    }))
+    .await?;
+
+    Ok(detail)
+}
@@ -0,0 +1,525 @@
+pub mod camt053;
+pub mod camt054;
+pub mod identifiers;
+pub mod inbound;
+pub mod pacs002;
+pub mod pacs008;
+pub mod pain001;
+pub mod statements;
+pub mod xml;
+pub mod xsd;
+
+use axum::http::StatusCode;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::PgPool;
+use crate::state::AppState;
+use crate::agent::intents::{find_intent, IntentStatus};
+use crate::agent::models::PaymentIntent;
+use crate::finance::amounts::NATIVE_SYMBOL;
+use crate::finance::models::{Wallet, WalletFunding};
+use crate::finance::registry::find_wallet;
+use super::audit;
+use super::models::{InboundItem, Iso20022Message, LegalEntity};
+use identifiers::{is_country_code, validate_currency};
+use pacs002::{OriginalGroup, Pacs002, StatusReason, TransactionReport, TransactionStatus};
+use pacs008::{Account, AccountId, Agent, Amount, ChargeBearer, CreditTransfer, Pacs008, Party, SettlementMethod, NOT_PROVIDED};
+use pain001::Pain001;
+
+pub const CAMT_053: &str = "camt.053.001.08";
+pub const CAMT_054: &str = "camt.054.001.08";
+pub const PACS_002: &str = "pacs.002.001.10";
+pub const PACS_008: &str = "pacs.008.001.08";
+pub const PAIN_001: &str = "pain.001.001.09";
+
+/// Proprietary account scheme for Aegis wallets, and the proxy type carrying their on-chain address.
+const WALLET_SCHEME: &str = "AEGIS";
+const ADDRESS_PROXY: &str = "EVM";
+
+/// End-to-end id of an agent payment in every message about it; its pacs.008 is `MSG-<end-to-end id>`.
+fn intent_end_to_end_id(intent_id: i32) -> String {
+    format!("AEGIS-INTENT-{}", intent_id)
+}
+
+/// Returns the stored pacs.008 for a confirmed agent payment, generating it on first request.
+/// The debtor is the paying wallet's entity; the creditor is the target's entity when it is one of our wallets.
+pub async fn pacs008_for_intent(state: &AppState, intent_id: i32, actor: &str) -> Result<Iso20022Message, (StatusCode, String)> {
+    if let Some(message) = stored(&state.pool, PACS_008, Subject::Intent(intent_id), None).await? {
+        return Ok(message);
+    }
+
+    let intent = find_intent(&state.pool, intent_id).await?;
+    if IntentStatus::parse(&intent.status) != Some(IntentStatus::Confirmed) {
+        return Err((StatusCode::CONFLICT, format!("Payment intent {} is {}, not confirmed", intent.id, intent.status)));
+    }
+
+    let wallet = find_wallet(&state.pool, intent.wallet_id).await?;
+    let currency = currency_code(&state.pool, intent.token_address.as_deref(), wallet.chain_id).await?;
+    let amount = Amount::new(&intent.amount, &currency).map_err(unprocessable)?;
+
+    let (debtor, debtor_account, debtor_agent) = wallet_side(&state.pool, &wallet.address).await?;
+    let (creditor, creditor_account, creditor_agent) = wallet_side(&state.pool, &intent.target_address).await?;
+
+    let end_to_end_id = intent_end_to_end_id(intent.id);
+    let message = Pacs008 {
+        msg_id: format!("MSG-{}", end_to_end_id),
+        created_at: Utc::now(),
+        settlement_date: Some(intent.updated_at.date_naive()),
+        settlement_method: SettlementMethod::Clrg,
+        transactions: vec![CreditTransfer {
+            instruction_id: None,
+            tx_id: Some(end_to_end_id.clone()),
+            end_to_end_id,
+            uetr: Some(new_uetr()),
+            amount,
+            charge_bearer: ChargeBearer::Slev,
+            debtor,
+            debtor_account: Some(debtor_account),
+            debtor_agent,
+            creditor_agent,
+            creditor,
+            creditor_account: Some(creditor_account),
+            remittance_info: intent.tx_hash.as_ref().map(|h| format!("On-chain settlement {}", h)),
+        }],
+    };
+
+    let document = message.to_xml().map_err(unprocessable)?;
+    store(&state.pool, NewMessage { message_type: PACS_008, msg_id: &message.msg_id, subject: Subject::Intent(intent.id), original_msg_id: None, status_codes: None, document }, actor).await
+}
+
+/// Returns the stored pacs.008 for a wallet funding, generating it on first request.
+/// The debtor is the backend signer (an entity only if it is a registered wallet); the creditor is the funded wallet's entity.
+pub async fn pacs008_for_funding(state: &AppState, funding_id: i32, actor: &str) -> Result<Iso20022Message, (StatusCode, String)> {
+    if let Some(message) = stored(&state.pool, PACS_008, Subject::Funding(funding_id), None).await? {
+        return Ok(message);
+    }
+
+    let funding = sqlx::query_as::<_, WalletFunding>("SELECT * FROM wallet_fundings WHERE id = $1")
+        .bind(funding_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Funding {} not found", funding_id)))?;
+    let wallet = find_wallet(&state.pool, funding.wallet_id).await?;
+    let amount = Amount::new(&funding.amount, NATIVE_SYMBOL).map_err(unprocessable)?;
+
+    let signer = format!("{:?}", state.chain.signer_address());
+    let (debtor, debtor_account, debtor_agent) = wallet_side(&state.pool, &signer).await?;
+    let (creditor, creditor_account, creditor_agent) = wallet_side(&state.pool, &wallet.address).await?;
+
+    let end_to_end_id = format!("AEGIS-FUND-{}", funding.id);
+    let message = Pacs008 {
+        msg_id: format!("MSG-{}", end_to_end_id),
+        created_at: Utc::now(),
+        settlement_date: Some(funding.created_at.date_naive()),
+        settlement_method: SettlementMethod::Clrg,
+        transactions: vec![CreditTransfer {
+            instruction_id: None,
+            tx_id: Some(end_to_end_id.clone()),
+            end_to_end_id,
+            uetr: Some(new_uetr()),
+            amount,
+            charge_bearer: ChargeBearer::Slev,
+            debtor,
+            debtor_account: Some(debtor_account),
+            debtor_agent,
+            creditor_agent,
+            creditor,
+            creditor_account: Some(creditor_account),
+            remittance_info: Some(format!("On-chain settlement {}", funding.tx_hash)),
+        }],
+    };
+
+    let document = message.to_xml().map_err(unprocessable)?;
+    store(&state.pool, NewMessage { message_type: PACS_008, msg_id: &message.msg_id, subject: Subject::Funding(funding.id), original_msg_id: None, status_codes: None, document }, actor).await
+}
+
+/// Returns a pacs.002 with the agent payment's current status, generating one whenever the status has changed.
+/// The report answers the inbound instruction the payment came from, else the pacs.008 we issued (or, for a payment
+/// that never settled, would issue) for it.
+pub async fn pacs002_for_intent(state: &AppState, intent_id: i32, actor: &str) -> Result<Iso20022Message, (StatusCode, String)> {
+    let intent = find_intent(&state.pool, intent_id).await?;
+
+    let item = sqlx::query_as::<_, InboundItem>("SELECT * FROM iso20022_inbound_items WHERE intent_id = $1")
+        .bind(intent.id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let (original, ids) = match item {
+        Some(item) => {
+            let original = inbound_original(&state.pool, item.message_id).await?;
+            let ids = original.find(item.payment_info_id.as_deref(), &item.end_to_end_id);
+            (original, ids)
+        }
+        None => match stored(&state.pool, PACS_008, Subject::Intent(intent.id), None).await? {
+            Some(issued) => {
+                let original = Original::parse(PACS_008, &issued.document).map_err(unprocessable)?;
+                let ids = original.transactions.first().map(|(_, ids)| ids.clone()).unwrap_or_default();
+                (original, ids)
+            }
+            // Only settled payments get a pacs.008; until then the report refers to it by its deterministic ids
+            None => {
+                let end_to_end_id = intent_end_to_end_id(intent.id);
+                let ids = OriginalIds { tx_id: Some(end_to_end_id.clone()), end_to_end_id, ..OriginalIds::default() };
+                let original = Original {
+                    msg_id: format!("MSG-{}", ids.end_to_end_id),
+                    message_type: PACS_008.to_string(),
+                    created_at: None,
+                    transactions: Vec::new(),
+                };
+                (original, ids)
+            }
+        },
+    };
+
+    let (status, reason, accepted_at) = intent_status(&intent);
+    let report = TransactionReport { status, reason, accepted_at, ..ids.report() };
+    let group = OriginalGroup { msg_id: original.msg_id, message_name: original.message_type, created_at: original.created_at, complete: false };
+    status_report(&state.pool, Subject::Intent(intent.id), group, vec![report], actor).await
+}
+
+/// Returns a pacs.002 covering every transfer of an inbound pain.001 or pacs.008, regenerated when any status changes.
+/// Transfers that became payment intents report the intent's status; recorded incoming funds are settled.
+pub async fn pacs002_for_inbound(state: &AppState, message_id: i32, actor: &str) -> Result<Iso20022Message, (StatusCode, String)> {
+    let detail = inbound::find_inbound(&state.pool, message_id).await?;
+    let original = Original::parse(&detail.message.message_type, &detail.message.document).map_err(unprocessable)?;
+
+    let mut reports = Vec::new();
+    for item in &detail.items {
+        let (status, reason, accepted_at) = match (item.intent_id, &item.rejection_reason) {
+            (Some(intent_id), _) => intent_status(&find_intent(&state.pool, intent_id).await?),
+            (None, Some(reason)) => (TransactionStatus::Rjct, Some(StatusReason::from_failure(reason)), None),
+            (None, None) => (TransactionStatus::Acsc, None, Some(item.created_at)),
+        };
+        let ids = original.find(item.payment_info_id.as_deref(), &item.end_to_end_id);
+        reports.push(TransactionReport { status, reason, accepted_at, ..ids.report() });
+    }
+
+    let group = OriginalGroup { msg_id: original.msg_id, message_name: original.message_type, created_at: original.created_at, complete: true };
+    status_report(&state.pool, Subject::Inbound(detail.message.id), group, reports, actor).await
+}
+
+/// ISO status of an agent payment: settled once confirmed, rejected in any other final state, else pending.
+fn intent_status(intent: &PaymentIntent) -> (TransactionStatus, Option<StatusReason>, Option<DateTime<Utc>>) {
+    let reason = intent.failure_reason.as_deref().unwrap_or_default();
+    match IntentStatus::parse(&intent.status) {
+        Some(IntentStatus::Confirmed) => (TransactionStatus::Acsc, None, Some(intent.updated_at)),
+        Some(IntentStatus::Failed) => (TransactionStatus::Rjct, Some(StatusReason::from_failure(reason)), None),
+        // Declined or left unapproved by the debtor's own approvers
+        Some(IntentStatus::Rejected | IntentStatus::Expired) => (TransactionStatus::Rjct, Some(StatusReason::new("MS02", reason)), None),
+        Some(IntentStatus::Cancelled) => (TransactionStatus::Rjct, Some(StatusReason::new("CUST", reason)), None),
+        _ => (TransactionStatus::Pdng, None, None),
+    }
+}
+
+/// Stores the report unless one with the same statuses is already on file for `subject`.
+async fn status_report(
+    pool: &PgPool,
+    subject: Subject,
+    original: OriginalGroup,
+    transactions: Vec<TransactionReport>,
+    actor: &str,
+) -> Result<Iso20022Message, (StatusCode, String)> {
+    let created_at = Utc::now();
+    let report = Pacs002 {
+        msg_id: format!("STS-{}-{}", subject.tag(), created_at.format("%Y%m%d%H%M%S")),
+        created_at,
+        original,
+        transactions,
+    };
+    let status_codes = report.status_codes();
+
+    if let Some(message) = stored(pool, PACS_002, subject, Some(&status_codes)).await? {
+        return Ok(message);
+    }
+
+    let document = report.to_xml().map_err(unprocessable)?;
+    store(pool, NewMessage {
+        message_type: PACS_002,
+        msg_id: &report.msg_id,
+        subject,
+        original_msg_id: Some(&report.original.msg_id),
+        status_codes: Some(&status_codes),
+        document,
+    }, actor).await
+}
+
+async fn inbound_original(pool: &PgPool, message_id: i32) -> Result<Original, (StatusCode, String)> {
+    let detail = inbound::find_inbound(pool, message_id).await?;
+    Original::parse(&detail.message.message_type, &detail.message.document).map_err(unprocessable)
+}
+
+/// A message a status report answers, re-read from its stored document.
+struct Original {
+    msg_id: String,
+    message_type: String,
+    created_at: Option<DateTime<Utc>>,
+    /// Payment information id (pain.001 only) and identifiers of each transaction.
+    transactions: Vec<(Option<String>, OriginalIds)>,
+}
+
+#[derive(Clone, Default)]
+struct OriginalIds {
+    instruction_id: Option<String>,
+    end_to_end_id: String,
+    tx_id: Option<String>,
+    uetr: Option<String>,
+}
+
+impl Original {
+    fn parse(message_type: &str, document: &str) -> Result<Self, String> {
+        match message_type {
+            PACS_008 => {
+                let message = Pacs008::from_xml(document)?;
+                let transactions = message.transactions.into_iter()
+                    .map(|t| (None, OriginalIds { instruction_id: t.instruction_id, end_to_end_id: t.end_to_end_id, tx_id: t.tx_id, uetr: t.uetr }))
+                    .collect();
+                Ok(Self { msg_id: message.msg_id, message_type: PACS_008.to_string(), created_at: Some(message.created_at), transactions })
+            }
+            PAIN_001 => {
+                let message = Pain001::from_xml(document)?;
+                let transactions = message.payments.into_iter()
+                    .flat_map(|p| {
+                        let payment_info_id = p.payment_info_id;
+                        p.transactions.into_iter().map(move |t| {
+                            (Some(payment_info_id.clone()), OriginalIds { instruction_id: t.instruction_id, end_to_end_id: t.end_to_end_id, tx_id: None, uetr: t.uetr })
+                        })
+                    })
+                    .collect();
+                Ok(Self { msg_id: message.msg_id, message_type: PAIN_001.to_string(), created_at: Some(message.created_at), transactions })
+            }
+            other => Err(format!("Cannot report on {} messages", other)),
+        }
+    }
+
+    /// Identifiers of the transaction with this end-to-end id, or just the end-to-end id when it is not found.
+    fn find(&self, payment_info_id: Option<&str>, end_to_end_id: &str) -> OriginalIds {
+        self.transactions.iter()
+            .find(|(p, ids)| p.as_deref() == payment_info_id && ids.end_to_end_id == end_to_end_id)
+            .map(|(_, ids)| ids.clone())
+            .unwrap_or(OriginalIds { end_to_end_id: end_to_end_id.to_string(), ..OriginalIds::default() })
+    }
+}
+
+impl OriginalIds {
+    /// A pending report for this transaction; callers fill in the actual status.
+    fn report(self) -> TransactionReport {
+        TransactionReport {
+            status_id: None,
+            original_instruction_id: self.instruction_id,
+            original_end_to_end_id: self.end_to_end_id,
+            original_tx_id: self.tx_id,
+            original_uetr: self.uetr,
+            status: TransactionStatus::Pdng,
+            reason: None,
+            accepted_at: None,
+        }
+    }
+}
+
+/// Party, account and servicing agent for an on-chain address. Registered wallets are described by their
+/// entity (name, LEI, country, bank BIC); other addresses only by the address itself.
+async fn wallet_side(pool: &PgPool, address: &str) -> Result<(Party, Account, Agent), (StatusCode, String)> {
+    let wallet = sqlx::query_as::<_, Wallet>("SELECT * FROM wallets WHERE address = $1")
+        .bind(address.to_lowercase())
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let proxy = Some((ADDRESS_PROXY.to_string(), address.to_lowercase()));
+
+    let Some(wallet) = wallet else {
+        let party = Party { name: Some(address.to_lowercase()), ..Party::default() };
+        let account = Account { id: AccountId::Other { id: NOT_PROVIDED.to_string(), scheme: None }, currency: None, name: None, proxy };
+        return Ok((party, account, Agent::not_provided()));
+    };
+
+    let entity = sqlx::query_as::<_, LegalEntity>("SELECT * FROM legal_entities WHERE id = $1")
+        .bind(wallet.entity_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let party = Party {
+        name: Some(entity.legal_name.clone().unwrap_or(entity.hash_id.clone())),
+        bic: None,
+        lei: entity.lei.clone(),
+        other_id: None,
+        country: Some(entity.jurisdiction.clone()).filter(|j| is_country_code(j)),
+    };
+    let account = Account {
+        id: AccountId::Other { id: format!("AEGIS-W{}", wallet.id), scheme: Some(WALLET_SCHEME.to_string()) },
+        currency: None,
+        name: wallet.label.clone(),
+        proxy,
+    };
+    let agent = match &entity.bic {
+        Some(bic) => Agent { bic: Some(bic.clone()), ..Agent::default() },
+        None => Agent::not_provided(),
+    };
+
+    Ok((party, account, agent))
+}
+
+/// ISO 4217 code reported for an asset: the token's configured code, else its symbol when that is a valid code.
+async fn currency_code(pool: &PgPool, token_address: Option<&str>, chain_id: i64) -> Result<String, (StatusCode, String)> {
+    let Some(token_addr) = token_address else {
+        return Ok(NATIVE_SYMBOL.to_string());
+    };
+
+    let (symbol, iso_currency): (String, Option<String>) = sqlx::query_as("SELECT symbol, iso_currency FROM tokens WHERE address = $1 AND chain_id = $2")
+        .bind(token_addr)
+        .bind(chain_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::UNPROCESSABLE_ENTITY, format!("Token {} is not registered", token_addr)))?;
+
+    match iso_currency {
+        Some(code) => Ok(code),
+        None if validate_currency(&symbol).is_ok() => Ok(symbol),
+        None => Err((StatusCode::UNPROCESSABLE_ENTITY, format!("Token {} has no ISO 4217 currency code; set iso_currency on the token", symbol))),
+    }
+}
+
+/// Random UUIDv4, the format SWIFT gpi requires for a UETR.
+fn new_uetr() -> String {
+    let mut bytes: [u8; 16] = rand::random();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = ethers::utils::hex::encode(bytes);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+fn unprocessable(e: String) -> (StatusCode, String) {
+    (StatusCode::UNPROCESSABLE_ENTITY, e)
+}
+
+/// What a generated message is about.
+#[derive(Clone, Copy)]
+enum Subject {
+    Intent(i32),
+    Funding(i32),
+    Inbound(i32),
+    /// A wallet's statement for one day.
+    Statement(i32, NaiveDate),
+    Movement(i32),
+}
+
+impl Subject {
+    fn column(&self) -> &'static str {
+        match self {
+            Subject::Intent(_) => "intent_id",
+            Subject::Funding(_) => "funding_id",
+            Subject::Inbound(_) => "inbound_message_id",
+            Subject::Statement(..) => "wallet_id",
+            Subject::Movement(_) => "movement_id",
+        }
+    }
+
+    fn id(&self) -> i32 {
+        match self {
+            Subject::Intent(id) | Subject::Funding(id) | Subject::Inbound(id) | Subject::Statement(id, _) | Subject::Movement(id) => *id,
+        }
+    }
+
+    fn statement_date(&self) -> Option<NaiveDate> {
+        match self {
+            Subject::Statement(_, date) => Some(*date),
+            _ => None,
+        }
+    }
+
+    /// Short form used in generated message ids.
+    fn tag(&self) -> String {
+        match self {
+            Subject::Intent(id) => format!("I{}", id),
+            Subject::Funding(id) => format!("F{}", id),
+            Subject::Inbound(id) => format!("M{}", id),
+            Subject::Statement(id, date) => format!("W{}-{}", id, date.format("%Y%m%d")),
+            Subject::Movement(id) => format!("MV{}", id),
+        }
+    }
+}
+
+struct NewMessage<'a> {
+    message_type: &'a str,
+    msg_id: &'a str,
+    subject: Subject,
+    original_msg_id: Option<&'a str>,
+    status_codes: Option<&'a str>,
+    document: String,
+}
+
+async fn stored(pool: &PgPool, message_type: &str, subject: Subject, status_codes: Option<&str>) -> Result<Option<Iso20022Message>, (StatusCode, String)> {
+    sqlx::query_as::<_, Iso20022Message>(&format!(
+        "SELECT * FROM iso20022_messages
+         WHERE message_type = $1 AND {} = $2 AND status_codes IS NOT DISTINCT FROM $3 AND statement_date IS NOT DISTINCT FROM $4",
+        subject.column()
+    ))
+    .bind(message_type)
+    .bind(subject.id())
+    .bind(status_codes)
+    .bind(subject.statement_date())
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn store(pool: &PgPool, message: NewMessage<'_>, actor: &str) -> Result<Iso20022Message, (StatusCode, String)> {
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let (intent_id, funding_id, inbound_message_id, wallet_id, movement_id) = match message.subject {
+        Subject::Intent(id) => (Some(id), None, None, None, None),
+        Subject::Funding(id) => (None, Some(id), None, None, None),
+        Subject::Inbound(id) => (None, None, Some(id), None, None),
+        Subject::Statement(id, _) => (None, None, None, Some(id), None),
+        Subject::Movement(id) => (None, None, None, None, Some(id)),
+    };
+
+    // A concurrent request may have stored the message first; keep that one
+    let inserted = sqlx::query_as::<_, Iso20022Message>(
+        r#"
+        INSERT INTO iso20022_messages
+            (message_type, msg_id, intent_id, funding_id, inbound_message_id, wallet_id, statement_date, movement_id,
+             original_msg_id, status_codes, document, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        ON CONFLICT DO NOTHING
+        RETURNING *
+        "#
+    )
+    .bind(message.message_type)
+    .bind(message.msg_id)
+    .bind(intent_id)
+    .bind(funding_id)
+    .bind(inbound_message_id)
+    .bind(wallet_id)
+    .bind(message.subject.statement_date())
+    .bind(movement_id)
+    .bind(message.original_msg_id)
+    .bind(message.status_codes)
+    .bind(&message.document)
+    .bind(actor)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let Some(stored_message) = inserted else {
+        return stored(pool, message.message_type, message.subject, message.status_codes)
+            .await?
+            .ok_or((StatusCode::CONFLICT, format!("Message {} already exists", message.msg_id)));
+    };
+
+    audit::record(&mut *tx, actor, "iso20022.generate", &stored_message.msg_id, serde_json::json!({
+        "message_type": stored_message.message_type,
+        "intent_id": stored_message.intent_id,
+        "funding_id": stored_message.funding_id,
+        "inbound_message_id": stored_message.inbound_message_id,
+        "wallet_id": stored_message.wallet_id,
+        "statement_date": stored_message.statement_date,
+        "movement_id": stored_message.movement_id,
+        "status_codes": stored_message.status_codes,
+    }))
+    .await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(stored_message)
+}
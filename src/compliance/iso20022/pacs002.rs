@@ -0,0 +1,235 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::sync::OnceLock;
+use super::xml::XmlBuilder;
+use super::xsd::Schema;
+
+pub const NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:pacs.002.001.10";
+const XSD: &str = include_str!("../../../schemas/iso20022/pacs.002.001.10.xsd");
+
+/// `Max105Text`, the length of each additional status reason line.
+const INFO_LINE: usize = 105;
+
+/// FIToFIPaymentStatusReportV10.
+pub struct Pacs002 {
+    pub msg_id: String,
+    pub created_at: DateTime<Utc>,
+    pub original: OriginalGroup,
+    pub transactions: Vec<TransactionReport>,
+}
+
+/// The message being reported on. When `complete`, the report covers every transaction in it
+/// and also carries the group status and per-status counts.
+pub struct OriginalGroup {
+    pub msg_id: String,
+    pub message_name: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub complete: bool,
+}
+
+/// PaymentTransaction110, keyed to the original transaction's identifiers.
+pub struct TransactionReport {
+    pub status_id: Option<String>,
+    pub original_instruction_id: Option<String>,
+    pub original_end_to_end_id: String,
+    pub original_tx_id: Option<String>,
+    pub original_uetr: Option<String>,
+    pub status: TransactionStatus,
+    pub reason: Option<StatusReason>,
+    pub accepted_at: Option<DateTime<Utc>>,
+}
+
+/// The `ExternalPaymentTransactionStatus1Code` values Aegis reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// AcceptedSettlementCompleted: the transfer is confirmed on-chain.
+    Acsc,
+    /// Pending: screening, approval or confirmation is outstanding.
+    Pdng,
+    /// Rejected.
+    Rjct,
+}
+
+impl TransactionStatus {
+    pub fn code(&self) -> &'static str {
+        match self {
+            TransactionStatus::Acsc => "ACSC",
+            TransactionStatus::Pdng => "PDNG",
+            TransactionStatus::Rjct => "RJCT",
+        }
+    }
+}
+
+/// An `ExternalStatusReason1Code` and the narrative it was derived from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusReason {
+    pub code: &'static str,
+    pub info: Option<String>,
+}
+
+/// Failure texts mapped to ISO status reason codes, most specific first; matched case-insensitively.
+/// Covers decoded `AegisRules`/`AegisWallet`/ERC-20 reverts, the backend's own screening, agent and policy
+/// rejections, and reasons given when an inbound transfer cannot be mapped.
+const REASON_CODES: &[(&str, &str)] = &[
+    // Contract reverts
+    ("AegisRules: Daily limit exceeded", "AM14"),
+    ("AegisRules: Token limit exceeded", "AM14"),
+    ("AegisRules: Value limit exceeded", "AM14"),
+    ("AegisRules: No price oracle", "MS03"),
+    ("AegisWallet: Caller not verified Agent", "AG01"),
+    ("AegisWallet: Execution failed", "MS03"),
+    ("transfer amount exceeds balance", "AM04"),
+    ("insufficient funds", "AM04"),
+    // Freezes and counterparty lists
+    ("Payments are frozen", "AC06"),
+    ("blocklist", "RR04"),
+    ("is not on the allowlist", "AG01"),
+    // Agents and budgets
+    ("is not a registered agent", "AG01"),
+    ("may not use wallet", "AG01"),
+    ("per-transaction limit", "AM02"),
+    ("daily limit", "AM14"),
+    ("Value limit would be exceeded", "AM14"),
+    // Policies, by rule type
+    ("(max_per_transaction)", "AM02"),
+    ("(velocity)", "AM14"),
+    ("(allowed_tokens)", "AM03"),
+    ("(allowed_hours)", "AG01"),
+    ("(counterparty_jurisdiction)", "RR04"),
+    ("(min_kyc_level)", "RR04"),
+    // Payment details
+    ("Invalid target address", "AC03"),
+    ("Amount must be greater than zero", "AM12"),
+    ("Invalid amount", "AM12"),
+    ("decimal places", "AM12"),
+    ("No allowed token settles", "AM03"),
+    ("is not allowed", "AM03"),
+    ("is not registered", "AM03"),
+    // Inbound transfers that cannot be mapped
+    ("Transfer has no creditor", "RR03"),
+    ("account belongs to wallet", "BE01"),
+    ("not a registered entity or wallet", "AC01"),
+    ("is not a registered wallet", "AC01"),
+    ("does not exist", "AC01"),
+    ("does not belong to a registered entity", "AC01"),
+    ("has no wallet", "AC01"),
+    ("has several wallets", "AC01"),
+    ("Payment method", "AG03"),
+];
+
+/// Code used when a rejection matches nothing above; the narrative is still sent in `AddtlInf`.
+const NARRATIVE: &str = "NARR";
+
+impl StatusReason {
+    /// Maps a recorded failure reason to its ISO code, keeping the text as additional information.
+    pub fn from_failure(reason: &str) -> Self {
+        let lower = reason.to_lowercase();
+        let code = REASON_CODES.iter()
+            .find(|(pattern, _)| lower.contains(&pattern.to_lowercase()))
+            .map_or(NARRATIVE, |(_, code)| code);
+        Self { code, info: Some(reason.to_string()).filter(|r| !r.is_empty()) }
+    }
+
+    pub fn new(code: &'static str, info: &str) -> Self {
+        Self { code, info: Some(info.to_string()) }
+    }
+}
+
+pub fn schema() -> &'static Schema {
+    static SCHEMA: OnceLock<Schema> = OnceLock::new();
+    SCHEMA.get_or_init(|| Schema::parse(XSD).expect("bundled pacs.002 schema is valid"))
+}
+
+impl Pacs002 {
+    /// `ACSC`/`RJCT` when every transaction has that status, `PDNG` while any is pending, else `PART`.
+    pub fn group_status(&self) -> &'static str {
+        let all = |status| self.transactions.iter().all(|t| t.status == status);
+        if all(TransactionStatus::Acsc) {
+            "ACSC"
+        } else if all(TransactionStatus::Rjct) {
+            "RJCT"
+        } else if self.transactions.iter().any(|t| t.status == TransactionStatus::Pdng) {
+            "PDNG"
+        } else {
+            "PART"
+        }
+    }
+
+    /// Transaction status codes in report order, e.g. "ACSC,RJCT". Identifies what a stored report says.
+    pub fn status_codes(&self) -> String {
+        self.transactions.iter().map(|t| t.status.code()).collect::<Vec<_>>().join(",")
+    }
+
+    /// Serializes the report and validates it against the bundled XSD.
+    pub fn to_xml(&self) -> Result<String, String> {
+        if self.transactions.is_empty() {
+            return Err("A pacs.002 needs at least one transaction status".to_string());
+        }
+
+        let mut xml = XmlBuilder::document(NAMESPACE);
+        xml.start("FIToFIPmtStsRpt");
+
+        xml.start("GrpHdr");
+        xml.text("MsgId", &self.msg_id);
+        xml.text("CreDtTm", &self.created_at.to_rfc3339_opts(SecondsFormat::Secs, true));
+        xml.end("GrpHdr");
+
+        let original = &self.original;
+        xml.start("OrgnlGrpInfAndSts");
+        xml.text("OrgnlMsgId", &original.msg_id);
+        xml.text("OrgnlMsgNmId", &original.message_name);
+        xml.optional("OrgnlCreDtTm", original.created_at.map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true)).as_deref());
+        if original.complete {
+            xml.text("OrgnlNbOfTxs", &self.transactions.len().to_string());
+            xml.text("GrpSts", self.group_status());
+            for status in [TransactionStatus::Acsc, TransactionStatus::Pdng, TransactionStatus::Rjct] {
+                let count = self.transactions.iter().filter(|t| t.status == status).count();
+                if count > 0 {
+                    xml.start("NbOfTxsPerSts");
+                    xml.text("DtldNbOfTxs", &count.to_string());
+                    xml.text("DtldSts", status.code());
+                    xml.end("NbOfTxsPerSts");
+                }
+            }
+        }
+        xml.end("OrgnlGrpInfAndSts");
+
+        for tx in &self.transactions {
+            tx.write(&mut xml);
+        }
+
+        xml.end("FIToFIPmtStsRpt");
+        let document = xml.finish();
+
+        schema().validate(&document).map_err(|errors| format!("pacs.002 failed schema validation: {}", errors.join("; ")))?;
+        Ok(document)
+    }
+}
+
+impl TransactionReport {
+    fn write(&self, xml: &mut XmlBuilder) {
+        xml.start("TxInfAndSts");
+        xml.optional("StsId", self.status_id.as_deref());
+        xml.optional("OrgnlInstrId", self.original_instruction_id.as_deref());
+        xml.text("OrgnlEndToEndId", &self.original_end_to_end_id);
+        xml.optional("OrgnlTxId", self.original_tx_id.as_deref());
+        xml.optional("OrgnlUETR", self.original_uetr.as_deref());
+        xml.text("TxSts", self.status.code());
+
+        if let Some(reason) = &self.reason {
+            xml.start("StsRsnInf");
+            xml.start("Rsn");
+            xml.text("Cd", reason.code);
+            xml.end("Rsn");
+            if let Some(info) = &reason.info {
+                let chars: Vec<char> = info.chars().collect();
+                for line in chars.chunks(INFO_LINE) {
+                    xml.text("AddtlInf", &line.iter().collect::<String>());
+                }
+            }
+            xml.end("StsRsnInf");
+        }
+
+        xml.optional("AccptncDtTm", self.accepted_at.map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true)).as_deref());
+        xml.end("TxInfAndSts");
+    }
+}
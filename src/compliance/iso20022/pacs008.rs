@@ -0,0 +1,446 @@
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use roxmltree::{Document, Node};
+use std::sync::OnceLock;
+use super::identifiers::{validate_bic, validate_currency, validate_iban, validate_lei};
+use super::xml::{children, node_at, read_date, read_datetime, required, text_at, truncate, XmlBuilder};
+use super::xsd::Schema;
+
+pub const NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:pacs.008.001.08";
+const XSD: &str = include_str!("../../../schemas/iso20022/pacs.008.001.08.xsd");
+
+/// Placeholder the market uses for an agent or account that cannot be identified.
+pub const NOT_PROVIDED: &str = "NOTPROVIDED";
+
+/// `ActiveCurrencyAndAmount` limits.
+pub(super) const MAX_FRACTION_DIGITS: usize = 5;
+const MAX_TOTAL_DIGITS: usize = 18;
+
+/// FIToFICustomerCreditTransferV08.
+pub struct Pacs008 {
+    pub msg_id: String,
+    pub created_at: DateTime<Utc>,
+    pub settlement_date: Option<NaiveDate>,
+    pub settlement_method: SettlementMethod,
+    pub transactions: Vec<CreditTransfer>,
+}
+
+/// CreditTransferTransaction39.
+pub struct CreditTransfer {
+    pub instruction_id: Option<String>,
+    pub end_to_end_id: String,
+    pub tx_id: Option<String>,
+    pub uetr: Option<String>,
+    pub amount: Amount,
+    pub charge_bearer: ChargeBearer,
+    pub debtor: Party,
+    pub debtor_account: Option<Account>,
+    pub debtor_agent: Agent,
+    pub creditor_agent: Agent,
+    pub creditor: Party,
+    pub creditor_account: Option<Account>,
+    pub remittance_info: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettlementMethod {
+    Inda,
+    Inga,
+    Cove,
+    Clrg,
+}
+
+impl SettlementMethod {
+    pub fn code(&self) -> &'static str {
+        match self {
+            SettlementMethod::Inda => "INDA",
+            SettlementMethod::Inga => "INGA",
+            SettlementMethod::Cove => "COVE",
+            SettlementMethod::Clrg => "CLRG",
+        }
+    }
+
+    pub fn parse(code: &str) -> Option<Self> {
+        match code {
+            "INDA" => Some(SettlementMethod::Inda),
+            "INGA" => Some(SettlementMethod::Inga),
+            "COVE" => Some(SettlementMethod::Cove),
+            "CLRG" => Some(SettlementMethod::Clrg),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChargeBearer {
+    Debt,
+    Cred,
+    Shar,
+    Slev,
+}
+
+impl ChargeBearer {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ChargeBearer::Debt => "DEBT",
+            ChargeBearer::Cred => "CRED",
+            ChargeBearer::Shar => "SHAR",
+            ChargeBearer::Slev => "SLEV",
+        }
+    }
+
+    pub fn parse(code: &str) -> Option<Self> {
+        match code {
+            "DEBT" => Some(ChargeBearer::Debt),
+            "CRED" => Some(ChargeBearer::Cred),
+            "SHAR" => Some(ChargeBearer::Shar),
+            "SLEV" => Some(ChargeBearer::Slev),
+            _ => None,
+        }
+    }
+}
+
+/// A decimal amount normalized to ISO 20022 form ("12.5", never "012.50" or "12.").
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Amount {
+    value: String,
+    currency: String,
+}
+
+impl Amount {
+    pub fn new(value: &str, currency: &str) -> Result<Self, String> {
+        validate_currency(currency)?;
+
+        let trimmed = value.trim();
+        let (whole, frac) = trimmed.split_once('.').unwrap_or((trimmed, ""));
+        if (whole.is_empty() && frac.is_empty()) || !whole.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid amount '{}'", value));
+        }
+
+        let whole = match whole.trim_start_matches('0') {
+            "" => "0",
+            w => w,
+        };
+        let frac = frac.trim_end_matches('0');
+        if frac.len() > MAX_FRACTION_DIGITS {
+            return Err(format!("Amount '{}' has more than {} decimal places allowed in ISO 20022", value, MAX_FRACTION_DIGITS));
+        }
+        if whole.trim_start_matches('0').len() + frac.len() > MAX_TOTAL_DIGITS {
+            return Err(format!("Amount '{}' has more than {} digits allowed in ISO 20022", value, MAX_TOTAL_DIGITS));
+        }
+
+        let value = if frac.is_empty() { whole.to_string() } else { format!("{}.{}", whole, frac) };
+        Ok(Self { value, currency: currency.to_string() })
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+}
+
+/// PartyIdentification135 with an organisation id.
+#[derive(Debug, Clone, Default)]
+pub struct Party {
+    pub name: Option<String>,
+    pub bic: Option<String>,
+    pub lei: Option<String>,
+    /// Proprietary identifier and its scheme name.
+    pub other_id: Option<(String, String)>,
+    pub country: Option<String>,
+}
+
+/// CashAccount38. On-chain wallets have no IBAN; their address goes in the proxy.
+#[derive(Debug, Clone)]
+pub struct Account {
+    pub id: AccountId,
+    pub currency: Option<String>,
+    pub name: Option<String>,
+    /// Proxy type (proprietary) and identifier.
+    pub proxy: Option<(String, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum AccountId {
+    Iban(String),
+    Other { id: String, scheme: Option<String> },
+}
+
+/// FinancialInstitutionIdentification18.
+#[derive(Debug, Clone, Default)]
+pub struct Agent {
+    pub bic: Option<String>,
+    pub lei: Option<String>,
+    pub name: Option<String>,
+    pub other_id: Option<String>,
+}
+
+impl Agent {
+    pub fn not_provided() -> Self {
+        Self { other_id: Some(NOT_PROVIDED.to_string()), ..Self::default() }
+    }
+}
+
+pub fn schema() -> &'static Schema {
+    static SCHEMA: OnceLock<Schema> = OnceLock::new();
+    SCHEMA.get_or_init(|| Schema::parse(XSD).expect("bundled pacs.008 schema is valid"))
+}
+
+impl Pacs008 {
+    /// Serializes the message and validates it against the bundled XSD.
+    pub fn to_xml(&self) -> Result<String, String> {
+        if self.transactions.is_empty() {
+            return Err("A pacs.008 needs at least one transaction".to_string());
+        }
+        for tx in &self.transactions {
+            tx.check_identifiers()?;
+        }
+
+        let mut xml = XmlBuilder::document(NAMESPACE);
+        xml.start("FIToFICstmrCdtTrf");
+
+        xml.start("GrpHdr");
+        xml.text("MsgId", &self.msg_id);
+        xml.text("CreDtTm", &self.created_at.to_rfc3339_opts(SecondsFormat::Secs, true));
+        xml.text("NbOfTxs", &self.transactions.len().to_string());
+        xml.optional("IntrBkSttlmDt", self.settlement_date.map(|d| d.to_string()).as_deref());
+        xml.start("SttlmInf");
+        xml.text("SttlmMtd", self.settlement_method.code());
+        xml.end("SttlmInf");
+        xml.end("GrpHdr");
+
+        for tx in &self.transactions {
+            tx.write(&mut xml);
+        }
+
+        xml.end("FIToFICstmrCdtTrf");
+        let document = xml.finish();
+
+        schema().validate(&document).map_err(|errors| format!("pacs.008 failed schema validation: {}", errors.join("; ")))?;
+        Ok(document)
+    }
+
+    /// Parses a received message after validating the components the bundled XSD declares.
+    /// Optional components outside this model (payment type, ultimate parties, charges, intermediary agents, ...) are accepted but not read;
+    /// those outside the profile schema are not validated either.
+    pub fn from_xml(xml: &str) -> Result<Self, String> {
+        schema().validate_received(xml).map_err(|errors| format!("pacs.008 failed schema validation: {}", errors.join("; ")))?;
+        let doc = Document::parse(xml).map_err(|e| format!("Malformed XML: {}", e))?;
+        let root = node_at(doc.root_element(), &["FIToFICstmrCdtTrf"]).ok_or("Missing FIToFICstmrCdtTrf")?;
+        let header = node_at(root, &["GrpHdr"]).ok_or("Missing GrpHdr")?;
+
+        let method = required(header, &["SttlmInf", "SttlmMtd"])?;
+        Ok(Self {
+            msg_id: required(header, &["MsgId"])?.to_string(),
+            created_at: read_datetime(required(header, &["CreDtTm"])?)?,
+            settlement_date: text_at(header, &["IntrBkSttlmDt"]).map(read_date).transpose()?,
+            settlement_method: SettlementMethod::parse(method).ok_or(format!("Unknown settlement method {}", method))?,
+            transactions: children(root, "CdtTrfTxInf").map(CreditTransfer::read).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl CreditTransfer {
+    fn check_identifiers(&self) -> Result<(), String> {
+        for party in [&self.debtor, &self.creditor] {
+            party.bic.as_deref().map(validate_bic).transpose()?;
+            party.lei.as_deref().map(validate_lei).transpose()?;
+        }
+        for agent in [&self.debtor_agent, &self.creditor_agent] {
+            agent.bic.as_deref().map(validate_bic).transpose()?;
+            agent.lei.as_deref().map(validate_lei).transpose()?;
+        }
+        for account in [&self.debtor_account, &self.creditor_account].into_iter().flatten() {
+            if let AccountId::Iban(iban) = &account.id {
+                validate_iban(iban)?;
+            }
+            account.currency.as_deref().map(validate_currency).transpose()?;
+        }
+        Ok(())
+    }
+
+    fn read(node: Node) -> Result<Self, String> {
+        let amount = node_at(node, &["IntrBkSttlmAmt"]).ok_or("Missing IntrBkSttlmAmt")?;
+        let charge_bearer = required(node, &["ChrgBr"])?;
+        Ok(Self {
+            instruction_id: text_at(node, &["PmtId", "InstrId"]).map(str::to_string),
+            end_to_end_id: required(node, &["PmtId", "EndToEndId"])?.to_string(),
+            tx_id: text_at(node, &["PmtId", "TxId"]).map(str::to_string),
+            uetr: text_at(node, &["PmtId", "UETR"]).map(str::to_string),
+            amount: read_amount(amount)?,
+            charge_bearer: ChargeBearer::parse(charge_bearer).ok_or(format!("Unknown charge bearer {}", charge_bearer))?,
+            debtor: read_party(node_at(node, &["Dbtr"]).ok_or("Missing Dbtr")?),
+            debtor_account: node_at(node, &["DbtrAcct"]).map(read_account).transpose()?,
+            debtor_agent: read_agent(node_at(node, &["DbtrAgt"]).ok_or("Missing DbtrAgt")?),
+            creditor_agent: read_agent(node_at(node, &["CdtrAgt"]).ok_or("Missing CdtrAgt")?),
+            creditor: read_party(node_at(node, &["Cdtr"]).ok_or("Missing Cdtr")?),
+            creditor_account: node_at(node, &["CdtrAcct"]).map(read_account).transpose()?,
+            remittance_info: read_remittance(node),
+        })
+    }
+
+    fn write(&self, xml: &mut XmlBuilder) {
+        xml.start("CdtTrfTxInf");
+
+        xml.start("PmtId");
+        xml.optional("InstrId", self.instruction_id.as_deref());
+        xml.text("EndToEndId", &self.end_to_end_id);
+        xml.optional("TxId", self.tx_id.as_deref());
+        xml.optional("UETR", self.uetr.as_deref());
+        xml.end("PmtId");
+
+        xml.text_with("IntrBkSttlmAmt", &[("Ccy", self.amount.currency())], self.amount.value());
+        xml.text("ChrgBr", self.charge_bearer.code());
+
+        write_party(xml, "Dbtr", &self.debtor);
+        if let Some(account) = &self.debtor_account {
+            write_account(xml, "DbtrAcct", account);
+        }
+        write_agent(xml, "DbtrAgt", &self.debtor_agent);
+        write_agent(xml, "CdtrAgt", &self.creditor_agent);
+        write_party(xml, "Cdtr", &self.creditor);
+        if let Some(account) = &self.creditor_account {
+            write_account(xml, "CdtrAcct", account);
+        }
+
+        if let Some(info) = &self.remittance_info {
+            xml.start("RmtInf");
+            xml.text("Ustrd", &truncate(info, 140));
+            xml.end("RmtInf");
+        }
+
+        xml.end("CdtTrfTxInf");
+    }
+}
+
+pub(super) fn write_party(xml: &mut XmlBuilder, tag: &str, party: &Party) {
+    xml.start(tag);
+    xml.optional("Nm", party.name.as_deref().map(|n| truncate(n, 140)).as_deref());
+    if party.bic.is_some() || party.lei.is_some() || party.other_id.is_some() {
+        xml.start("Id");
+        xml.start("OrgId");
+        xml.optional("AnyBIC", party.bic.as_deref());
+        xml.optional("LEI", party.lei.as_deref());
+        if let Some((id, scheme)) = &party.other_id {
+            xml.start("Othr");
+            xml.text("Id", id);
+            xml.start("SchmeNm");
+            xml.text("Prtry", scheme);
+            xml.end("SchmeNm");
+            xml.end("Othr");
+        }
+        xml.end("OrgId");
+        xml.end("Id");
+    }
+    xml.optional("CtryOfRes", party.country.as_deref());
+    xml.end(tag);
+}
+
+pub(super) fn write_account(xml: &mut XmlBuilder, tag: &str, account: &Account) {
+    xml.start(tag);
+    xml.start("Id");
+    match &account.id {
+        AccountId::Iban(iban) => xml.text("IBAN", iban),
+        AccountId::Other { id, scheme } => {
+            xml.start("Othr");
+            xml.text("Id", id);
+            if let Some(scheme) = scheme {
+                xml.start("SchmeNm");
+                xml.text("Prtry", scheme);
+                xml.end("SchmeNm");
+            }
+            xml.end("Othr");
+        }
+    }
+    xml.end("Id");
+    xml.optional("Ccy", account.currency.as_deref());
+    xml.optional("Nm", account.name.as_deref().map(|n| truncate(n, 70)).as_deref());
+    if let Some((kind, id)) = &account.proxy {
+        xml.start("Prxy");
+        xml.start("Tp");
+        xml.text("Prtry", kind);
+        xml.end("Tp");
+        xml.text("Id", id);
+        xml.end("Prxy");
+    }
+    xml.end(tag);
+}
+
+fn write_agent(xml: &mut XmlBuilder, tag: &str, agent: &Agent) {
+    xml.start(tag);
+    xml.start("FinInstnId");
+    xml.optional("BICFI", agent.bic.as_deref());
+    xml.optional("LEI", agent.lei.as_deref());
+    xml.optional("Nm", agent.name.as_deref().map(|n| truncate(n, 140)).as_deref());
+    if let Some(id) = &agent.other_id {
+        xml.start("Othr");
+        xml.text("Id", id);
+        xml.end("Othr");
+    }
+    xml.end("FinInstnId");
+    xml.end(tag);
+}
+
+/// Reads a `*CurrencyAndAmount` element, normalizing the amount like `Amount::new`.
+pub(super) fn read_amount(node: Node) -> Result<Amount, String> {
+    Amount::new(node.text().unwrap_or_default(), node.attribute("Ccy").unwrap_or_default())
+}
+
+/// Reads a PartyIdentification135. The first proprietary id of either an organisation or a person is kept.
+pub(super) fn read_party(node: Node) -> Party {
+    let org = node_at(node, &["Id", "OrgId"]);
+    let other = org.or_else(|| node_at(node, &["Id", "PrvtId"])).and_then(|id| node_at(id, &["Othr"]));
+    Party {
+        name: text_at(node, &["Nm"]).map(str::to_string),
+        bic: org.and_then(|o| text_at(o, &["AnyBIC"])).map(str::to_string),
+        lei: org.and_then(|o| text_at(o, &["LEI"])).map(str::to_string),
+        other_id: other.and_then(|o| Some((text_at(o, &["Id"])?.to_string(), scheme_name(o).unwrap_or_default().to_string()))),
+        country: text_at(node, &["CtryOfRes"]).or_else(|| text_at(node, &["PstlAdr", "Ctry"])).map(str::to_string),
+    }
+}
+
+pub(super) fn read_account(node: Node) -> Result<Account, String> {
+    let id = match (text_at(node, &["Id", "IBAN"]), node_at(node, &["Id", "Othr"])) {
+        (Some(iban), _) => AccountId::Iban(iban.to_string()),
+        (None, Some(other)) => AccountId::Other {
+            id: required(other, &["Id"])?.to_string(),
+            scheme: scheme_name(other).map(str::to_string),
+        },
+        (None, None) => return Err("Account without an identification".to_string()),
+    };
+    let proxy = node_at(node, &["Prxy"]).and_then(|p| {
+        let kind = text_at(p, &["Tp", "Prtry"]).or_else(|| text_at(p, &["Tp", "Cd"])).unwrap_or_default();
+        Some((kind.to_string(), text_at(p, &["Id"])?.to_string()))
+    });
+    Ok(Account {
+        id,
+        currency: text_at(node, &["Ccy"]).map(str::to_string),
+        name: text_at(node, &["Nm"]).map(str::to_string),
+        proxy,
+    })
+}
+
+pub(super) fn read_agent(node: Node) -> Agent {
+    let institution = node_at(node, &["FinInstnId"]);
+    let text = |path: &[&str]| institution.and_then(|i| text_at(i, path)).map(str::to_string);
+    Agent {
+        bic: text(&["BICFI"]),
+        lei: text(&["LEI"]),
+        name: text(&["Nm"]),
+        other_id: text(&["Othr", "Id"]),
+    }
+}
+
+/// Unstructured remittance lines joined with spaces.
+pub(super) fn read_remittance(node: Node) -> Option<String> {
+    let lines: Vec<&str> = node_at(node, &["RmtInf"])
+        .map(|r| children(r, "Ustrd").filter_map(|u| u.text()).map(str::trim).collect())
+        .unwrap_or_default();
+    Some(lines.join(" ")).filter(|l| !l.is_empty())
+}
+
+fn scheme_name<'a>(node: Node<'a, '_>) -> Option<&'a str> {
+    text_at(node, &["SchmeNm", "Prtry"]).or_else(|| text_at(node, &["SchmeNm", "Cd"]))
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use roxmltree::{Document, Node};
+use std::sync::OnceLock;
+use super::pacs008::{read_account, read_agent, read_amount, read_party, read_remittance, Account, Agent, Amount, Party};
+use super::xml::{children, node_at, read_date, read_datetime, required, text_at};
+use super::xsd::Schema;
+
+pub const NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:pain.001.001.09";
+const XSD: &str = include_str!("../../../schemas/iso20022/pain.001.001.09.xsd");
+
+/// The only `PaymentMethod3Code` Aegis executes; cheques (CHK) and treasury transfers (TRA) are rejected.
+pub const CREDIT_TRANSFER: &str = "TRF";
+
+/// CustomerCreditTransferInitiationV09, as received from a customer or bank.
+pub struct Pain001 {
+    pub msg_id: String,
+    pub created_at: DateTime<Utc>,
+    pub initiating_party: Party,
+    pub payments: Vec<PaymentInstruction>,
+}
+
+/// PaymentInstruction30: one debtor account and the transfers to make from it.
+pub struct PaymentInstruction {
+    pub payment_info_id: String,
+    pub method: String,
+    pub execution_date: NaiveDate,
+    pub debtor: Party,
+    pub debtor_account: Account,
+    pub debtor_agent: Agent,
+    pub transactions: Vec<TransferInstruction>,
+}
+
+/// CreditTransferTransaction34. The creditor is optional in the message; a transfer without one cannot be mapped.
+pub struct TransferInstruction {
+    pub instruction_id: Option<String>,
+    pub end_to_end_id: String,
+    pub uetr: Option<String>,
+    pub amount: Amount,
+    pub creditor_agent: Option<Agent>,
+    pub creditor: Option<Party>,
+    pub creditor_account: Option<Account>,
+    pub remittance_info: Option<String>,
+}
+
+pub fn schema() -> &'static Schema {
+    static SCHEMA: OnceLock<Schema> = OnceLock::new();
+    SCHEMA.get_or_init(|| Schema::parse(XSD).expect("bundled pain.001 schema is valid"))
+}
+
+impl Pain001 {
+    /// Parses a received initiation after validating the components the bundled XSD declares.
+    /// Other components of the full message (cheque instructions, charges, intermediary agents, structured remittance, ...) are skipped.
+    pub fn from_xml(xml: &str) -> Result<Self, String> {
+        schema().validate_received(xml).map_err(|errors| format!("pain.001 failed schema validation: {}", errors.join("; ")))?;
+        let doc = Document::parse(xml).map_err(|e| format!("Malformed XML: {}", e))?;
+        let root = node_at(doc.root_element(), &["CstmrCdtTrfInitn"]).ok_or("Missing CstmrCdtTrfInitn")?;
+        let header = node_at(root, &["GrpHdr"]).ok_or("Missing GrpHdr")?;
+
+        Ok(Self {
+            msg_id: required(header, &["MsgId"])?.to_string(),
+            created_at: read_datetime(required(header, &["CreDtTm"])?)?,
+            initiating_party: read_party(node_at(header, &["InitgPty"]).ok_or("Missing InitgPty")?),
+            payments: children(root, "PmtInf").map(PaymentInstruction::read).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl PaymentInstruction {
+    fn read(node: Node) -> Result<Self, String> {
+        let execution_date = match text_at(node, &["ReqdExctnDt", "Dt"]) {
+            Some(date) => read_date(date)?,
+            None => read_datetime(required(node, &["ReqdExctnDt", "DtTm"])?)?.date_naive(),
+        };
+        Ok(Self {
+            payment_info_id: required(node, &["PmtInfId"])?.to_string(),
+            method: required(node, &["PmtMtd"])?.to_string(),
+            execution_date,
+            debtor: read_party(node_at(node, &["Dbtr"]).ok_or("Missing Dbtr")?),
+            debtor_account: read_account(node_at(node, &["DbtrAcct"]).ok_or("Missing DbtrAcct")?)?,
+            debtor_agent: read_agent(node_at(node, &["DbtrAgt"]).ok_or("Missing DbtrAgt")?),
+            transactions: children(node, "CdtTrfTxInf").map(TransferInstruction::read).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl TransferInstruction {
+    fn read(node: Node) -> Result<Self, String> {
+        Ok(Self {
+            instruction_id: text_at(node, &["PmtId", "InstrId"]).map(str::to_string),
+            end_to_end_id: required(node, &["PmtId", "EndToEndId"])?.to_string(),
+            uetr: text_at(node, &["PmtId", "UETR"]).map(str::to_string),
+            amount: read_amount(node_at(node, &["Amt", "InstdAmt"]).ok_or("Missing Amt/InstdAmt")?)?,
+            creditor_agent: node_at(node, &["CdtrAgt"]).map(read_agent),
+            creditor: node_at(node, &["Cdtr"]).map(read_party),
+            creditor_account: node_at(node, &["CdtrAcct"]).map(read_account).transpose()?,
+            remittance_info: read_remittance(node),
+        })
+    }
+}
@@ -0,0 +1,321 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use ethers::types::{I256, U256};
+use sqlx::PgPool;
+use crate::state::AppState;
+use crate::agent::intents::SYSTEM_ACTOR;
+use crate::finance::amounts::{format_amount, NATIVE_DECIMALS, NATIVE_SYMBOL};
+use crate::finance::indexer;
+use crate::finance::models::{Token, Wallet, WalletMovement};
+use crate::finance::registry::find_wallet;
+use crate::compliance::models::Iso20022Message;
+use super::camt053::{Balance, Camt053, Direction, Entry, Statement};
+use super::camt054::{Camt054, Notification};
+use super::pacs008::{Account, Amount};
+use super::{currency_code, intent_end_to_end_id, store, stored, unprocessable, wallet_side, NewMessage, Subject, CAMT_053, CAMT_054};
+
+/// How often the job looks for statements and notifications to generate.
+const POLL_INTERVAL_SECS: u64 = 300;
+
+/// Notifications generated per run; a backlog is worked off over several runs.
+const NOTIFICATION_BATCH: i64 = 500;
+
+/// An asset a wallet holds, as reported in statements.
+struct Asset {
+    token_address: Option<String>,
+    symbol: String,
+    decimals: u8,
+    currency: String,
+}
+
+impl Asset {
+    async fn find(pool: &PgPool, token_address: Option<&str>, chain_id: i64) -> Result<Self, (StatusCode, String)> {
+        let currency = currency_code(pool, token_address, chain_id).await?;
+        let Some(token_addr) = token_address else {
+            return Ok(Self { token_address: None, symbol: NATIVE_SYMBOL.to_string(), decimals: NATIVE_DECIMALS, currency });
+        };
+
+        let token = sqlx::query_as::<_, Token>("SELECT * FROM tokens WHERE address = $1 AND chain_id = $2")
+            .bind(token_addr)
+            .bind(chain_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        Ok(Self { token_address: Some(token.address), symbol: token.symbol, decimals: token.decimals as u8, currency })
+    }
+
+    fn describe(&self) -> String {
+        match &self.token_address {
+            Some(address) => format!("{} token {}", self.symbol, address),
+            None => format!("{} (native)", self.symbol),
+        }
+    }
+
+    fn amount(&self, base_units: U256) -> Result<Amount, (StatusCode, String)> {
+        Amount::new(&format_amount(base_units, self.decimals), &self.currency).map_err(unprocessable)
+    }
+}
+
+/// Returns the wallet's camt.053 for `date` (UTC), generating it on first request. Each asset the wallet has
+/// held gets its own statement with opening and closing booked balances; the day must be fully indexed.
+pub async fn camt053_for_wallet(state: &AppState, wallet_id: i32, date: NaiveDate, actor: &str) -> Result<Iso20022Message, (StatusCode, String)> {
+    if let Some(message) = stored(&state.pool, CAMT_053, Subject::Statement(wallet_id, date), None).await? {
+        return Ok(message);
+    }
+
+    let wallet = find_wallet(&state.pool, wallet_id).await?;
+    let opened = wallet.created_at.date_naive();
+    if date < opened {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, format!("Wallet {} was created on {}", wallet.id, opened)));
+    }
+
+    let from = date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    let to = from + Duration::days(1);
+    match indexer::synced_at(&state.pool, wallet.chain_id).await? {
+        Some(synced) if synced >= to => {}
+        Some(synced) => return Err((
+            StatusCode::CONFLICT,
+            format!("Wallet movements are indexed up to {}; the statement for {} is not final yet", synced.to_rfc3339(), date),
+        )),
+        None => return Err((StatusCode::CONFLICT, "Wallet movements have not been indexed yet".to_string())),
+    }
+    if indexer::backfill_pending(&state.pool, wallet.id).await? {
+        return Err((StatusCode::CONFLICT, format!("The history of wallet {} or one of its tokens is still being indexed", wallet.id)));
+    }
+
+    // The native asset is always reported; tokens once the wallet has moved them
+    let tokens: Vec<String> = sqlx::query_scalar(
+        "SELECT DISTINCT token_address FROM wallet_movements WHERE wallet_id = $1 AND token_address IS NOT NULL AND block_time < $2 ORDER BY token_address"
+    )
+    .bind(wallet.id)
+    .bind(to)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let (owner, account, _) = wallet_side(&state.pool, &wallet.address).await?;
+    let msg_id = format!("STMT-{}", Subject::Statement(wallet.id, date).tag());
+
+    let mut statements = Vec::new();
+    for (n, token) in std::iter::once(None).chain(tokens.iter().map(|t| Some(t.as_str()))).enumerate() {
+        let asset = Asset::find(&state.pool, token, wallet.chain_id).await?;
+        let movements = movements(&state.pool, &wallet, &asset, from, to).await?;
+
+        let mut entries = Vec::with_capacity(movements.len());
+        for movement in &movements {
+            entries.push(entry(&state.pool, movement, &asset).await?);
+        }
+
+        statements.push(Statement {
+            id: format!("{}-{}", msg_id, n + 1),
+            sequence: Some((date - opened).num_days() as u64 + 1),
+            from,
+            to: to - Duration::seconds(1),
+            account: Account { currency: Some(asset.currency.clone()), ..account.clone() },
+            owner: owner.clone(),
+            opening: balance(&state.pool, &wallet, &asset, from, date).await?,
+            closing: balance(&state.pool, &wallet, &asset, to, date).await?,
+            entries,
+            info: Some(asset.describe()),
+        });
+    }
+
+    let message = Camt053 { msg_id, created_at: Utc::now(), statements };
+    let document = message.to_xml().map_err(unprocessable)?;
+    store(&state.pool, NewMessage {
+        message_type: CAMT_053,
+        msg_id: &message.msg_id,
+        subject: Subject::Statement(wallet.id, date),
+        original_msg_id: None,
+        status_codes: None,
+        document,
+    }, actor).await
+}
+
+/// Returns the camt.054 notifying a single indexed movement, generating it on first request.
+pub async fn camt054_for_movement(state: &AppState, movement_id: i32, actor: &str) -> Result<Iso20022Message, (StatusCode, String)> {
+    if let Some(message) = stored(&state.pool, CAMT_054, Subject::Movement(movement_id), None).await? {
+        return Ok(message);
+    }
+
+    let movement = sqlx::query_as::<_, WalletMovement>("SELECT * FROM wallet_movements WHERE id = $1")
+        .bind(movement_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Wallet movement {} not found", movement_id)))?;
+    let wallet = find_wallet(&state.pool, movement.wallet_id).await?;
+    let asset = Asset::find(&state.pool, movement.token_address.as_deref(), movement.chain_id).await?;
+    let (owner, account, _) = wallet_side(&state.pool, &wallet.address).await?;
+
+    let msg_id = format!("NTF-{}", Subject::Movement(movement.id).tag());
+    let message = Camt054 {
+        msg_id: msg_id.clone(),
+        created_at: Utc::now(),
+        notifications: vec![Notification {
+            id: msg_id,
+            account: Account { currency: Some(asset.currency.clone()), ..account },
+            owner,
+            entries: vec![entry(&state.pool, &movement, &asset).await?],
+            info: Some(asset.describe()),
+        }],
+    };
+
+    let document = message.to_xml().map_err(unprocessable)?;
+    store(&state.pool, NewMessage {
+        message_type: CAMT_054,
+        msg_id: &message.msg_id,
+        subject: Subject::Movement(movement.id),
+        original_msg_id: None,
+        status_codes: None,
+        document,
+    }, actor).await
+}
+
+/// Generates a camt.054 for every new movement and, once a day is fully indexed, each wallet's camt.053 for it.
+pub async fn run(state: AppState) {
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(POLL_INTERVAL_SECS));
+    loop {
+        ticker.tick().await;
+        if let Err((_, e)) = generate_due(&state).await {
+            println!("Statement job error: {}", e);
+        }
+    }
+}
+
+async fn generate_due(state: &AppState) -> Result<(), (StatusCode, String)> {
+    let unnotified: Vec<i32> = sqlx::query_scalar(
+        r#"
+        SELECT m.id FROM wallet_movements m
+        WHERE NOT EXISTS (SELECT 1 FROM iso20022_messages WHERE message_type = $1 AND movement_id = m.id)
+        ORDER BY m.id
+        LIMIT $2
+        "#
+    )
+    .bind(CAMT_054)
+    .bind(NOTIFICATION_BATCH)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for movement_id in unnotified {
+        if let Err((_, e)) = camt054_for_movement(state, movement_id, SYSTEM_ACTOR).await {
+            println!("Statement job error: movement {}: {}", movement_id, e);
+        }
+    }
+
+    let chain_id = state.chain.chain_id() as i64;
+    let Some(synced) = indexer::synced_at(&state.pool, chain_id).await? else { return Ok(()) };
+    let Some(last_day) = synced.date_naive().pred_opt() else { return Ok(()) };
+
+    let wallets = sqlx::query_as::<_, Wallet>("SELECT * FROM wallets WHERE chain_id = $1 ORDER BY id")
+        .bind(chain_id)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for wallet in wallets {
+        // Retried once the indexer has backfilled the wallet's history
+        if indexer::backfill_pending(&state.pool, wallet.id).await? {
+            continue;
+        }
+        let latest: Option<NaiveDate> = sqlx::query_scalar("SELECT MAX(statement_date) FROM iso20022_messages WHERE message_type = $1 AND wallet_id = $2")
+            .bind(CAMT_053)
+            .bind(wallet.id)
+            .fetch_one(&state.pool)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+        // Statements are issued in sequence; a day that fails is retried on the next run before any later day
+        let mut day = latest.and_then(|d| d.succ_opt()).unwrap_or(wallet.created_at.date_naive());
+        while day <= last_day {
+            if let Err((_, e)) = camt053_for_wallet(state, wallet.id, day, SYSTEM_ACTOR).await {
+                println!("Statement job error: wallet {} on {}: {}", wallet.id, day, e);
+                break;
+            }
+            day = day.succ_opt().unwrap_or(NaiveDate::MAX);
+        }
+    }
+
+    Ok(())
+}
+
+async fn movements(pool: &PgPool, wallet: &Wallet, asset: &Asset, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<WalletMovement>, (StatusCode, String)> {
+    sqlx::query_as::<_, WalletMovement>(
+        r#"
+        SELECT * FROM wallet_movements
+        WHERE wallet_id = $1 AND token_address IS NOT DISTINCT FROM $2 AND block_time >= $3 AND block_time < $4
+        ORDER BY block_number, log_index, id
+        "#
+    )
+    .bind(wallet.id)
+    .bind(&asset.token_address)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Booked balance of the asset from every movement before `at`, dated `date`.
+async fn balance(pool: &PgPool, wallet: &Wallet, asset: &Asset, at: DateTime<Utc>, date: NaiveDate) -> Result<Balance, (StatusCode, String)> {
+    let total: String = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(SUM(CASE WHEN direction = 'credit' THEN amount::NUMERIC ELSE -amount::NUMERIC END), 0)::TEXT
+        FROM wallet_movements
+        WHERE wallet_id = $1 AND token_address IS NOT DISTINCT FROM $2 AND block_time < $3
+        "#
+    )
+    .bind(wallet.id)
+    .bind(&asset.token_address)
+    .bind(at)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let total = I256::from_dec_str(&total).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Balance {
+        amount: asset.amount(total.unsigned_abs())?,
+        direction: if total.is_negative() { Direction::Debit } else { Direction::Credit },
+        date,
+    })
+}
+
+async fn entry(pool: &PgPool, movement: &WalletMovement, asset: &Asset) -> Result<Entry, (StatusCode, String)> {
+    let amount = U256::from_dec_str(&movement.amount).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let (counterparty, counterparty_account, _) = wallet_side(pool, &movement.counterparty).await?;
+
+    Ok(Entry {
+        reference: format!("AEGIS-{}", Subject::Movement(movement.id).tag()),
+        amount: asset.amount(amount)?,
+        direction: if movement.direction == "credit" { Direction::Credit } else { Direction::Debit },
+        booked_at: movement.block_time,
+        event: movement.event.clone(),
+        end_to_end_id: end_to_end_id(pool, &movement.tx_hash).await?,
+        counterparty,
+        counterparty_account,
+        details: Some(format!(
+            "{} log {} of transaction {} in block {}",
+            movement.event, movement.log_index, movement.tx_hash, movement.block_number
+        )),
+    })
+}
+
+/// The end-to-end id we gave the agent payment or funding sent in `tx_hash` (see `intent_end_to_end_id`, `pacs008_for_funding`).
+async fn end_to_end_id(pool: &PgPool, tx_hash: &str) -> Result<Option<String>, (StatusCode, String)> {
+    let intent: Option<i32> = sqlx::query_scalar("SELECT id FROM payment_intents WHERE tx_hash = $1 ORDER BY id LIMIT 1")
+        .bind(tx_hash)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if let Some(id) = intent {
+        return Ok(Some(intent_end_to_end_id(id)));
+    }
+
+    let funding: Option<i32> = sqlx::query_scalar("SELECT id FROM wallet_fundings WHERE tx_hash = $1")
+        .bind(tx_hash)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(funding.map(|id| format!("AEGIS-FUND-{}", id)))
+}
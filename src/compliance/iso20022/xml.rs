@@ -0,0 +1,97 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use roxmltree::Node;
+
+/// Thin wrapper over `quick_xml::Writer` for building ISO 20022 documents (and the SAR/STR drafts of
+/// compliance cases); text and attribute values are escaped.
+pub struct XmlBuilder {
+    writer: Writer<Vec<u8>>,
+}
+
+impl XmlBuilder {
+    /// Starts a document with the XML declaration and an opening `<Document xmlns=...>`.
+    pub fn document(namespace: &str) -> Self {
+        let mut builder = Self { writer: Writer::new_with_indent(Vec::new(), b' ', 4) };
+        builder.write(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)));
+        builder.start_with("Document", &[("xmlns", namespace)]);
+        builder
+    }
+
+    pub fn start(&mut self, name: &str) {
+        self.start_with(name, &[]);
+    }
+
+    pub fn start_with(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.write(Event::Start(BytesStart::new(name).with_attributes(attributes.iter().copied())));
+    }
+
+    pub fn end(&mut self, name: &str) {
+        self.write(Event::End(BytesEnd::new(name)));
+    }
+
+    pub fn text(&mut self, name: &str, value: &str) {
+        self.text_with(name, &[], value);
+    }
+
+    pub fn text_with(&mut self, name: &str, attributes: &[(&str, &str)], value: &str) {
+        self.start_with(name, attributes);
+        self.write(Event::Text(BytesText::new(value)));
+        self.end(name);
+    }
+
+    pub fn optional(&mut self, name: &str, value: Option<&str>) {
+        if let Some(value) = value {
+            self.text(name, value);
+        }
+    }
+
+    pub fn finish(mut self) -> String {
+        self.end("Document");
+        String::from_utf8(self.writer.into_inner()).unwrap_or_default()
+    }
+
+    fn write(&mut self, event: Event) {
+        // Writing into a Vec cannot fail
+        let _ = self.writer.write_event(event);
+    }
+}
+
+/// Cuts free text to an ISO 20022 `MaxNText` length on a character boundary.
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    text.chars().take(max_chars).collect()
+}
+
+/// Descends through child elements by local name. Readers only walk schema-validated documents,
+/// so namespaces have already been checked.
+pub fn node_at<'a, 'i>(node: Node<'a, 'i>, path: &[&str]) -> Option<Node<'a, 'i>> {
+    path.iter().try_fold(node, |node, name| node.children().find(|c| c.is_element() && c.tag_name().name() == *name))
+}
+
+pub fn children<'a, 'i>(node: Node<'a, 'i>, name: &'static str) -> impl Iterator<Item = Node<'a, 'i>> {
+    node.children().filter(move |c| c.is_element() && c.tag_name().name() == name)
+}
+
+/// Trimmed text of the element at `path`, if present and non-empty.
+pub fn text_at<'a>(node: Node<'a, '_>, path: &[&str]) -> Option<&'a str> {
+    node_at(node, path).and_then(|n| n.text()).map(str::trim).filter(|t| !t.is_empty())
+}
+
+pub fn required<'a>(node: Node<'a, '_>, path: &[&str]) -> Result<&'a str, String> {
+    text_at(node, path).ok_or_else(|| format!("{}: missing {}", node.tag_name().name(), path.join("/")))
+}
+
+/// An `ISODate`, ignoring any timezone suffix.
+pub fn read_date(value: &str) -> Result<NaiveDate, String> {
+    value.get(..10)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .ok_or(format!("Invalid date '{}'", value))
+}
+
+/// An `ISODateTime`; a local time without offset is taken as UTC.
+pub fn read_datetime(value: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|d| d.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").map(|d| d.and_utc()))
+        .map_err(|_| format!("Invalid date-time '{}'", value))
+}
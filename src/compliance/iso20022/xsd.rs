@@ -0,0 +1,370 @@
+use regex::Regex;
+use roxmltree::{Document, Node};
+use std::collections::HashMap;
+
+const XS_NS: &str = "http://www.w3.org/2001/XMLSchema";
+
+/// The XSD subset ISO 20022 schemas are written in: named complex types built from sequences,
+/// choices and elements, simple-content amounts with attributes, and simple types restricting
+/// built-ins with pattern/length/enumeration/digit facets.
+pub struct Schema {
+    target_ns: String,
+    root: (String, String),
+    complex: HashMap<String, ComplexType>,
+    simple: HashMap<String, SimpleType>,
+}
+
+enum ComplexType {
+    Content(Particle),
+    SimpleContent { base: String, attributes: Vec<Attribute> },
+}
+
+struct Attribute {
+    name: String,
+    type_name: String,
+    required: bool,
+}
+
+enum Particle {
+    Element { name: String, type_name: String, min: usize, max: Option<usize> },
+    Sequence(Vec<Particle>),
+    Choice { options: Vec<Particle>, min: usize },
+}
+
+struct SimpleType {
+    base: String,
+    patterns: Vec<Regex>,
+    enumeration: Vec<String>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    fraction_digits: Option<usize>,
+    total_digits: Option<usize>,
+    min_inclusive: Option<f64>,
+}
+
+impl Schema {
+    pub fn parse(xsd: &str) -> Result<Self, String> {
+        let doc = Document::parse(xsd).map_err(|e| format!("Invalid schema: {}", e))?;
+        let schema = doc.root_element();
+        let target_ns = schema.attribute("targetNamespace").ok_or("Schema has no targetNamespace")?.to_string();
+
+        let mut root = None;
+        let mut complex = HashMap::new();
+        let mut simple = HashMap::new();
+
+        for node in schema.children().filter(Node::is_element) {
+            let name = node.attribute("name").ok_or("Top-level schema component without a name")?.to_string();
+            match node.tag_name().name() {
+                "element" => root = Some((name, attr(&node, "type")?)),
+                "complexType" => { complex.insert(name, parse_complex(&node)?); }
+                "simpleType" => { simple.insert(name, parse_simple(&node)?); }
+                other => return Err(format!("Unsupported schema component xs:{}", other)),
+            }
+        }
+
+        Ok(Self { target_ns, root: root.ok_or("Schema declares no root element")?, complex, simple })
+    }
+
+    /// Validates a document, returning every violation with the path of the offending element.
+    pub fn validate(&self, xml: &str) -> Result<(), Vec<String>> {
+        self.validate_document(xml, false)
+    }
+
+    /// Validates a received document against the components this schema declares. Elements a content model
+    /// does not declare (parts of the full ISO 20022 message outside the profile) are skipped with their content.
+    pub fn validate_received(&self, xml: &str) -> Result<(), Vec<String>> {
+        self.validate_document(xml, true)
+    }
+
+    fn validate_document(&self, xml: &str, skip_undeclared: bool) -> Result<(), Vec<String>> {
+        let doc = Document::parse(xml).map_err(|e| vec![format!("Malformed XML: {}", e)])?;
+        let root = doc.root_element();
+        let mut errors = Vec::new();
+
+        if root.tag_name().name() != self.root.0 || root.tag_name().namespace() != Some(self.target_ns.as_str()) {
+            errors.push(format!("Expected root element {{{}}}{}", self.target_ns, self.root.0));
+        } else {
+            self.validate_element(&root, &self.root.1, &format!("/{}", self.root.0), skip_undeclared, &mut errors);
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    fn validate_element(&self, node: &Node, type_name: &str, path: &str, skip_undeclared: bool, errors: &mut Vec<String>) {
+        match self.complex.get(type_name) {
+            Some(ComplexType::Content(particle)) => {
+                if node.attributes().len() > 0 {
+                    errors.push(format!("{}: unexpected attributes", path));
+                }
+                if node.children().any(|c| c.is_text() && !c.text().unwrap_or_default().trim().is_empty()) {
+                    errors.push(format!("{}: unexpected text content", path));
+                }
+
+                let children: Vec<Node> = node.children()
+                    .filter(|c| c.is_element() && (!skip_undeclared || self.declares(particle, c)))
+                    .collect();
+                let pos = self.match_particle(particle, &children, 0, path, skip_undeclared, errors);
+                if let Some(extra) = children.get(pos) {
+                    errors.push(format!("{}: unexpected element {}", path, extra.tag_name().name()));
+                }
+            }
+            Some(ComplexType::SimpleContent { base, attributes }) => {
+                self.validate_text(node, base, path, errors);
+                for attribute in attributes {
+                    match node.attribute(attribute.name.as_str()) {
+                        Some(value) => {
+                            if let Err(e) = self.check_simple(&attribute.type_name, value) {
+                                errors.push(format!("{}/@{}: {}", path, attribute.name, e));
+                            }
+                        }
+                        None if attribute.required => errors.push(format!("{}: missing attribute {}", path, attribute.name)),
+                        None => {}
+                    }
+                }
+                for extra in node.attributes().filter(|a| !attributes.iter().any(|d| d.name == a.name())) {
+                    errors.push(format!("{}: unexpected attribute {}", path, extra.name()));
+                }
+            }
+            None => {
+                if node.attributes().len() > 0 {
+                    errors.push(format!("{}: unexpected attributes", path));
+                }
+                self.validate_text(node, type_name, path, errors);
+            }
+        }
+    }
+
+    fn validate_text(&self, node: &Node, type_name: &str, path: &str, errors: &mut Vec<String>) {
+        if node.children().any(|c| c.is_element()) {
+            errors.push(format!("{}: unexpected child elements", path));
+            return;
+        }
+        if let Err(e) = self.check_simple(type_name, node.text().unwrap_or_default()) {
+            errors.push(format!("{}: {}", path, e));
+        }
+    }
+
+    /// Matches `particle` against `children` from `pos`, returning the position after it.
+    /// ISO 20022 content models are deterministic, so greedy matching needs no backtracking.
+    fn match_particle(&self, particle: &Particle, children: &[Node], mut pos: usize, path: &str, skip_undeclared: bool, errors: &mut Vec<String>) -> usize {
+        match particle {
+            Particle::Element { name, type_name, min, max } => {
+                let mut count = 0;
+                while max.is_none_or(|m| count < m) {
+                    let Some(child) = children.get(pos).filter(|c| self.is_named(c, name)) else { break };
+                    let child_path = format!("{}/{}", path, name);
+                    self.validate_element(child, type_name, &child_path, skip_undeclared, errors);
+                    pos += 1;
+                    count += 1;
+                }
+                if count < *min {
+                    let found = children.get(pos).map_or("end of element".to_string(), |c| c.tag_name().name().to_string());
+                    errors.push(format!("{}: expected {}, found {}", path, name, found));
+                }
+                pos
+            }
+            Particle::Sequence(particles) => {
+                particles.iter().fold(pos, |pos, p| self.match_particle(p, children, pos, path, skip_undeclared, errors))
+            }
+            Particle::Choice { options, min } => {
+                let chosen = children.get(pos).and_then(|child| options.iter().find(|o| self.starts_with(o, child)));
+                match chosen {
+                    Some(option) => self.match_particle(option, children, pos, path, skip_undeclared, errors),
+                    None => {
+                        if *min > 0 {
+                            let names: Vec<&str> = options.iter().filter_map(first_name).collect();
+                            errors.push(format!("{}: expected one of {}", path, names.join(", ")));
+                        }
+                        pos
+                    }
+                }
+            }
+        }
+    }
+
+    fn starts_with(&self, particle: &Particle, node: &Node) -> bool {
+        match particle {
+            Particle::Element { name, .. } => self.is_named(node, name),
+            Particle::Sequence(particles) => particles.first().is_some_and(|p| self.starts_with(p, node)),
+            Particle::Choice { options, .. } => options.iter().any(|o| self.starts_with(o, node)),
+        }
+    }
+
+    /// Whether `node` is one of the elements `particle` declares, at any depth of its sequences and choices.
+    fn declares(&self, particle: &Particle, node: &Node) -> bool {
+        match particle {
+            Particle::Element { name, .. } => self.is_named(node, name),
+            Particle::Sequence(particles) | Particle::Choice { options: particles, .. } => particles.iter().any(|p| self.declares(p, node)),
+        }
+    }
+
+    fn is_named(&self, node: &Node, name: &str) -> bool {
+        node.tag_name().name() == name && node.tag_name().namespace() == Some(self.target_ns.as_str())
+    }
+
+    fn check_simple(&self, type_name: &str, value: &str) -> Result<(), String> {
+        let Some(simple) = self.simple.get(type_name) else {
+            return check_builtin(type_name, value);
+        };
+        self.check_simple(&simple.base, value)?;
+
+        let length = value.chars().count();
+        if simple.min_length.is_some_and(|min| length < min) || simple.max_length.is_some_and(|max| length > max) {
+            return Err(format!("'{}' has length {}, outside {} bounds", value, length, type_name));
+        }
+        if !simple.patterns.is_empty() && !simple.patterns.iter().any(|p| p.is_match(value)) {
+            return Err(format!("'{}' does not match the {} pattern", value, type_name));
+        }
+        if !simple.enumeration.is_empty() && !simple.enumeration.iter().any(|e| e == value) {
+            return Err(format!("'{}' is not a {} value ({})", value, type_name, simple.enumeration.join(", ")));
+        }
+        if simple.fraction_digits.is_some() || simple.total_digits.is_some() || simple.min_inclusive.is_some() {
+            let (total, fraction) = decimal_digits(value);
+            if simple.fraction_digits.is_some_and(|max| fraction > max) {
+                return Err(format!("'{}' has more than {} fraction digits", value, simple.fraction_digits.unwrap_or_default()));
+            }
+            if simple.total_digits.is_some_and(|max| total > max) {
+                return Err(format!("'{}' has more than {} digits", value, simple.total_digits.unwrap_or_default()));
+            }
+            if simple.min_inclusive.is_some_and(|min| value.trim().parse::<f64>().map_or(true, |v| v < min)) {
+                return Err(format!("'{}' is below the {} minimum", value, type_name));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn first_name(particle: &Particle) -> Option<&str> {
+    match particle {
+        Particle::Element { name, .. } => Some(name),
+        Particle::Sequence(particles) => particles.first().and_then(first_name),
+        Particle::Choice { options, .. } => options.first().and_then(first_name),
+    }
+}
+
+fn attr(node: &Node, name: &str) -> Result<String, String> {
+    node.attribute(name)
+        .map(str::to_string)
+        .ok_or(format!("xs:{} without {}", node.tag_name().name(), name))
+}
+
+fn occurs(node: &Node, name: &str) -> Result<Option<usize>, String> {
+    match node.attribute(name) {
+        None => Ok(Some(1)),
+        Some("unbounded") => Ok(None),
+        Some(n) => n.parse().map(Some).map_err(|_| format!("Invalid {} '{}'", name, n)),
+    }
+}
+
+fn xs_children<'a, 'i>(node: &Node<'a, 'i>) -> impl Iterator<Item = Node<'a, 'i>> {
+    node.children().filter(|c| c.is_element() && c.tag_name().namespace() == Some(XS_NS))
+}
+
+fn parse_complex(node: &Node) -> Result<ComplexType, String> {
+    let body = xs_children(node).next().ok_or("Empty xs:complexType")?;
+    if body.tag_name().name() != "simpleContent" {
+        return parse_particle(&body).map(ComplexType::Content);
+    }
+
+    let extension = xs_children(&body).next().filter(|e| e.tag_name().name() == "extension")
+        .ok_or("xs:simpleContent must contain an xs:extension")?;
+    let attributes = xs_children(&extension)
+        .map(|a| Ok(Attribute {
+            name: attr(&a, "name")?,
+            type_name: attr(&a, "type")?,
+            required: a.attribute("use") == Some("required"),
+        }))
+        .collect::<Result<_, String>>()?;
+    Ok(ComplexType::SimpleContent { base: attr(&extension, "base")?, attributes })
+}
+
+fn parse_particle(node: &Node) -> Result<Particle, String> {
+    match node.tag_name().name() {
+        "element" => Ok(Particle::Element {
+            name: attr(node, "name")?,
+            type_name: attr(node, "type")?,
+            min: occurs(node, "minOccurs")?.unwrap_or_default(),
+            max: occurs(node, "maxOccurs")?,
+        }),
+        "sequence" => xs_children(node).map(|c| parse_particle(&c)).collect::<Result<_, _>>().map(Particle::Sequence),
+        "choice" => Ok(Particle::Choice {
+            options: xs_children(node).map(|c| parse_particle(&c)).collect::<Result<_, _>>()?,
+            min: occurs(node, "minOccurs")?.unwrap_or_default(),
+        }),
+        other => Err(format!("Unsupported content model xs:{}", other)),
+    }
+}
+
+fn parse_simple(node: &Node) -> Result<SimpleType, String> {
+    let restriction = xs_children(node).next().filter(|r| r.tag_name().name() == "restriction")
+        .ok_or("xs:simpleType must contain an xs:restriction")?;
+
+    let mut simple = SimpleType {
+        base: attr(&restriction, "base")?,
+        patterns: Vec::new(),
+        enumeration: Vec::new(),
+        min_length: None,
+        max_length: None,
+        fraction_digits: None,
+        total_digits: None,
+        min_inclusive: None,
+    };
+
+    for facet in xs_children(&restriction) {
+        let value = attr(&facet, "value")?;
+        let number = || value.parse::<usize>().map_err(|_| format!("Invalid {} facet '{}'", facet.tag_name().name(), value));
+        match facet.tag_name().name() {
+            // XSD patterns always match the whole value
+            "pattern" => simple.patterns.push(Regex::new(&format!("^(?:{})$", value)).map_err(|e| e.to_string())?),
+            "enumeration" => simple.enumeration.push(value.clone()),
+            "minLength" => simple.min_length = Some(number()?),
+            "maxLength" => simple.max_length = Some(number()?),
+            "fractionDigits" => simple.fraction_digits = Some(number()?),
+            "totalDigits" => simple.total_digits = Some(number()?),
+            "minInclusive" => simple.min_inclusive = Some(value.parse().map_err(|_| format!("Invalid minInclusive '{}'", value))?),
+            other => return Err(format!("Unsupported facet xs:{}", other)),
+        }
+    }
+
+    Ok(simple)
+}
+
+fn check_builtin(type_name: &str, value: &str) -> Result<(), String> {
+    let valid = match type_name {
+        "xs:string" => true,
+        "xs:boolean" => matches!(value.trim(), "true" | "false" | "1" | "0"),
+        "xs:decimal" => {
+            let v = value.trim().trim_start_matches(['+', '-']);
+            let (whole, frac) = v.split_once('.').unwrap_or((v, ""));
+            !(whole.is_empty() && frac.is_empty()) && whole.chars().chain(frac.chars()).all(|c| c.is_ascii_digit())
+        }
+        "xs:date" => {
+            let v = value.trim();
+            v.len() >= 10 && chrono::NaiveDate::parse_from_str(&v[..10], "%Y-%m-%d").is_ok() && valid_timezone(&v[10..])
+        }
+        "xs:dateTime" => {
+            let v = value.trim();
+            let split = v.find(['Z', '+']).or_else(|| v.rfind('-').filter(|&i| i > 10)).unwrap_or(v.len());
+            chrono::NaiveDateTime::parse_from_str(&v[..split], "%Y-%m-%dT%H:%M:%S%.f").is_ok() && valid_timezone(&v[split..])
+        }
+        other => return Err(format!("Unknown type {}", other)),
+    };
+    if valid { Ok(()) } else { Err(format!("'{}' is not a valid {}", value, type_name)) }
+}
+
+/// Empty, `Z` or `±hh:mm`.
+fn valid_timezone(tz: &str) -> bool {
+    match tz {
+        "" | "Z" => true,
+        _ => tz.len() == 6 && tz.starts_with(['+', '-']) && chrono::NaiveTime::parse_from_str(&tz[1..], "%H:%M").is_ok(),
+    }
+}
+
+/// `(total, fraction)` significant digits of a decimal literal, as XSD counts them.
+fn decimal_digits(value: &str) -> (usize, usize) {
+    let v = value.trim().trim_start_matches(['+', '-']);
+    let (whole, frac) = v.split_once('.').unwrap_or((v, ""));
+    let whole = whole.trim_start_matches('0');
+    let frac = frac.trim_end_matches('0');
+    (whole.len() + frac.len(), frac.len())
+}
@@ -0,0 +1,455 @@
+use axum::http::StatusCode;
+use chrono::Utc;
+use sqlx::{PgPool, Postgres, Transaction};
+use super::documents::DocumentStore;
+use super::metadata;
+use super::models::{KycCase, KycCaseEvent, KycDocument, KycDocumentUpload, KycNote, LegalEntity};
+use super::{audit, reverification, COMPLIANCE_ROLES};
+
+pub const DOCUMENT_TYPES: &[&str] = &[
+    "certificate_of_incorporation",
+    "register_extract",
+    "proof_of_address",
+    "ubo_declaration",
+    "identity_document",
+    "other",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KycStatus {
+    Submitted,
+    InReview,
+    Approved,
+    Rejected,
+    Expired,
+}
+
+impl KycStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KycStatus::Submitted => "submitted",
+            KycStatus::InReview => "in_review",
+            KycStatus::Approved => "approved",
+            KycStatus::Rejected => "rejected",
+            KycStatus::Expired => "expired",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "submitted" => Some(KycStatus::Submitted),
+            "in_review" => Some(KycStatus::InReview),
+            "approved" => Some(KycStatus::Approved),
+            "rejected" => Some(KycStatus::Rejected),
+            "expired" => Some(KycStatus::Expired),
+            _ => None,
+        }
+    }
+
+    /// Open cases still accept documents and can be assigned.
+    pub fn is_open(&self) -> bool {
+        matches!(self, KycStatus::Submitted | KycStatus::InReview)
+    }
+
+    /// Allowed edges of the case workflow. Only a case under review can be decided; open cases go stale
+    /// and approved verifications lapse into `expired`.
+    pub fn can_transition_to(&self, next: KycStatus) -> bool {
+        use KycStatus::*;
+        matches!(
+            (self, next),
+            (Submitted, InReview) | (Submitted, Expired)
+                | (InReview, Approved) | (InReview, Rejected) | (InReview, Expired)
+                | (Approved, Expired)
+        )
+    }
+}
+
+pub async fn find_case(pool: &PgPool, case_id: i32) -> Result<KycCase, (StatusCode, String)> {
+    sqlx::query_as::<_, KycCase>("SELECT * FROM kyc_cases WHERE id = $1")
+        .bind(case_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "KYC case not found".to_string()))
+}
+
+pub async fn case_events(pool: &PgPool, case_id: i32) -> Result<Vec<KycCaseEvent>, (StatusCode, String)> {
+    sqlx::query_as::<_, KycCaseEvent>("SELECT * FROM kyc_case_events WHERE case_id = $1 ORDER BY id")
+        .bind(case_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+pub async fn case_documents(pool: &PgPool, case_id: i32) -> Result<Vec<KycDocument>, (StatusCode, String)> {
+    sqlx::query_as::<_, KycDocument>("SELECT * FROM kyc_documents WHERE case_id = $1 ORDER BY id")
+        .bind(case_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+pub async fn case_notes(pool: &PgPool, case_id: i32) -> Result<Vec<KycNote>, (StatusCode, String)> {
+    sqlx::query_as::<_, KycNote>("SELECT * FROM kyc_case_notes WHERE case_id = $1 ORDER BY id")
+        .bind(case_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Opens a case asking for `requested_level`. An entity has at most one open case.
+pub async fn submit(pool: &PgPool, entity_id: i32, requested_level: i16, actor: &str) -> Result<KycCase, (StatusCode, String)> {
+    if requested_level <= 0 {
+        return Err((StatusCode::BAD_REQUEST, "Invalid KYC Level".to_string()));
+    }
+    let entity_exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM legal_entities WHERE id = $1)")
+        .bind(entity_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !entity_exists {
+        return Err((StatusCode::NOT_FOUND, "Entity not found".to_string()));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let case = sqlx::query_as::<_, KycCase>(
+        "INSERT INTO kyc_cases (entity_id, requested_level, submitted_by) VALUES ($1, $2, $3) RETURNING *"
+    )
+    .bind(entity_id)
+    .bind(requested_level)
+    .bind(actor)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            (StatusCode::CONFLICT, format!("Entity {} already has an open KYC case", entity_id))
+        }
+        e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })?;
+
+    audit::record(&mut *tx, actor, "kyc.submit", &format!("entity:{}", entity_id), serde_json::json!({
+        "case_id": case.id,
+        "requested_level": case.requested_level,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(case)
+}
+
+/// Assigns an open case to a compliance reviewer, moving a new case into review.
+/// The submitter cannot review their own case.
+pub async fn assign(pool: &PgPool, case: KycCase, reviewer: &str, actor: &str) -> Result<KycCase, (StatusCode, String)> {
+    let status = KycStatus::parse(&case.status).ok_or((StatusCode::INTERNAL_SERVER_ERROR, format!("Unknown KYC case status {}", case.status)))?;
+    if !status.is_open() {
+        return Err((StatusCode::CONFLICT, format!("KYC case {} is {}", case.id, case.status)));
+    }
+    if reviewer == case.submitted_by {
+        return Err((StatusCode::BAD_REQUEST, "The submitter cannot review their own KYC case".to_string()));
+    }
+    let role: Option<String> = sqlx::query_scalar("SELECT role FROM users WHERE username = $1")
+        .bind(reviewer)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !role.is_some_and(|r| COMPLIANCE_ROLES.contains(&r.as_str())) {
+        return Err((StatusCode::BAD_REQUEST, format!("{} is not a compliance reviewer", reviewer)));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    sqlx::query("UPDATE kyc_cases SET assigned_to = $1, updated_at = NOW() WHERE id = $2 AND status IN ('submitted', 'in_review')")
+        .bind(reviewer)
+        .bind(case.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let note = format!("Assigned to {}", reviewer);
+    let case = match status {
+        KycStatus::Submitted => transition(&mut tx, case.id, status, KycStatus::InReview, actor, Some(&note)).await?,
+        _ => find_case_for_update(&mut tx, case.id).await?,
+    };
+
+    audit::record(&mut *tx, actor, "kyc.assign", &format!("entity:{}", case.entity_id), serde_json::json!({
+        "case_id": case.id,
+        "reviewer": reviewer,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(case)
+}
+
+/// Approves a case under review and grants its level to the entity, restarting its review cycle.
+/// Only the assigned reviewer decides, and only on a case with at least one document.
+/// Freezes from an earlier lapse stay in place until lifted.
+pub async fn approve(pool: &PgPool, case: KycCase, actor: &str, reason: Option<&str>) -> Result<KycCase, (StatusCode, String)> {
+    check_reviewer(&case, actor)?;
+    let documents: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM kyc_documents WHERE case_id = $1")
+        .bind(case.id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if documents == 0 {
+        return Err((StatusCode::CONFLICT, format!("KYC case {} has no documents", case.id)));
+    }
+
+    let jurisdiction: String = sqlx::query_scalar("SELECT jurisdiction FROM legal_entities WHERE id = $1")
+        .bind(case.entity_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let verified_at = Utc::now();
+    let expires_at = reverification::deadline(pool, &jurisdiction, case.requested_level, verified_at).await?;
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    transition(&mut tx, case.id, KycStatus::InReview, KycStatus::Approved, actor, reason).await?;
+    let case = sqlx::query_as::<_, KycCase>("UPDATE kyc_cases SET expires_at = $1 WHERE id = $2 RETURNING *")
+        .bind(expires_at)
+        .bind(case.id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let entity = sqlx::query_as::<_, LegalEntity>(
+        r#"
+        UPDATE legal_entities SET kyc_level = $1, kyc_case_id = $2, kyc_verified_at = $3, kyc_status = 'verified'
+        WHERE id = $4
+        RETURNING *
+        "#
+    )
+    .bind(case.requested_level)
+    .bind(case.id)
+    .bind(verified_at)
+    .bind(case.entity_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit::record(&mut *tx, actor, "kyc.approve", &format!("entity:{}", entity.id), serde_json::json!({
+        "case_id": case.id,
+        "kyc_level": entity.kyc_level,
+        "expires_at": case.expires_at,
+        "reason": reason,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(case)
+}
+
+/// Rejects a case under review; the entity keeps its current level.
+pub async fn reject(pool: &PgPool, case: KycCase, actor: &str, reason: &str) -> Result<KycCase, (StatusCode, String)> {
+    check_reviewer(&case, actor)?;
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let case = transition(&mut tx, case.id, KycStatus::InReview, KycStatus::Rejected, actor, Some(reason)).await?;
+
+    audit::record(&mut *tx, actor, "kyc.reject", &format!("entity:{}", case.entity_id), serde_json::json!({
+        "case_id": case.id,
+        "reason": reason,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(case)
+}
+
+/// Closes an open case that went stale, or marks an approved verification whose validity has run out.
+/// The entity's level is left as is.
+pub async fn expire(pool: &PgPool, case: KycCase, actor: &str, reason: &str) -> Result<KycCase, (StatusCode, String)> {
+    let status = KycStatus::parse(&case.status).ok_or((StatusCode::INTERNAL_SERVER_ERROR, format!("Unknown KYC case status {}", case.status)))?;
+    if status == KycStatus::Approved && case.expires_at.is_some_and(|at| at > Utc::now()) {
+        return Err((StatusCode::CONFLICT, format!("KYC case {} is valid until {}", case.id, case.expires_at.unwrap_or_default())));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let case = transition(&mut tx, case.id, status, KycStatus::Expired, actor, Some(reason)).await?;
+
+    audit::record(&mut *tx, actor, "kyc.expire", &format!("entity:{}", case.entity_id), serde_json::json!({
+        "case_id": case.id,
+        "from": status.as_str(),
+        "reason": reason,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(case)
+}
+
+/// Encrypts an uploaded document to disk and records it on an open case.
+pub async fn add_document(
+    pool: &PgPool,
+    store: &DocumentStore,
+    case: &KycCase,
+    upload: &KycDocumentUpload,
+    content_type: &str,
+    content: &[u8],
+    actor: &str,
+) -> Result<KycDocument, (StatusCode, String)> {
+    if !KycStatus::parse(&case.status).is_some_and(|s| s.is_open()) {
+        return Err((StatusCode::CONFLICT, format!("KYC case {} is {}", case.id, case.status)));
+    }
+    if !DOCUMENT_TYPES.contains(&upload.document_type.as_str()) {
+        return Err((StatusCode::BAD_REQUEST, format!("document_type must be one of: {}", DOCUMENT_TYPES.join(", "))));
+    }
+    if content.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Document is empty".to_string()));
+    }
+    let file_name = clean_file_name(&upload.file_name).ok_or((StatusCode::BAD_REQUEST, "Invalid file_name".to_string()))?;
+
+    let storage_key = store.put(content).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let document = sqlx::query_as::<_, KycDocument>(
+        r#"
+        INSERT INTO kyc_documents (case_id, document_type, file_name, content_type, size_bytes, content_hash, storage_key, uploaded_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING *
+        "#
+    )
+    .bind(case.id)
+    .bind(&upload.document_type)
+    .bind(&file_name)
+    .bind(content_type)
+    .bind(content.len() as i64)
+    .bind(metadata::content_hash_bytes(content))
+    .bind(&storage_key)
+    .bind(actor)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit::record(&mut *tx, actor, "kyc.document.upload", &format!("entity:{}", case.entity_id), serde_json::json!({
+        "case_id": case.id,
+        "document_id": document.id,
+        "document_type": document.document_type,
+        "content_hash": document.content_hash,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(document)
+}
+
+/// Decrypts a document for download, refusing one that no longer matches the hash taken at upload.
+pub async fn read_document(pool: &PgPool, store: &DocumentStore, document_id: i32, actor: &str) -> Result<(KycDocument, Vec<u8>), (StatusCode, String)> {
+    let document = sqlx::query_as::<_, KycDocument>("SELECT * FROM kyc_documents WHERE id = $1")
+        .bind(document_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Document not found".to_string()))?;
+
+    let content = store.get(&document.storage_key).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    if metadata::content_hash_bytes(&content) != document.content_hash {
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, "Document integrity check failed".to_string()));
+    }
+
+    // Evidence holds personal data, so every read is on the record
+    audit::record(pool, actor, "kyc.document.download", &format!("kyc_case:{}", document.case_id), serde_json::json!({
+        "document_id": document.id,
+    })).await?;
+
+    Ok((document, content))
+}
+
+pub async fn add_note(pool: &PgPool, case: &KycCase, body: &str, author: &str) -> Result<KycNote, (StatusCode, String)> {
+    if body.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Note is empty".to_string()));
+    }
+
+    sqlx::query_as::<_, KycNote>("INSERT INTO kyc_case_notes (case_id, author, body) VALUES ($1, $2, $3) RETURNING *")
+        .bind(case.id)
+        .bind(author)
+        .bind(body.trim())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Last path component of an uploaded file name, without quotes or control characters
+/// (it ends up in a `Content-Disposition` header).
+pub(super) fn clean_file_name(name: &str) -> Option<String> {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = base.chars().filter(|c| !c.is_control() && *c != '"').take(255).collect();
+    let cleaned = cleaned.trim();
+    (!cleaned.is_empty() && cleaned != "." && cleaned != "..").then(|| cleaned.to_string())
+}
+
+fn check_reviewer(case: &KycCase, actor: &str) -> Result<(), (StatusCode, String)> {
+    if case.submitted_by == actor {
+        return Err((StatusCode::FORBIDDEN, "The submitter cannot decide on their own KYC case".to_string()));
+    }
+    if case.assigned_to.as_deref() != Some(actor) {
+        return Err((StatusCode::FORBIDDEN, format!("KYC case {} is not assigned to {}", case.id, actor)));
+    }
+    Ok(())
+}
+
+async fn find_case_for_update(tx: &mut Transaction<'_, Postgres>, case_id: i32) -> Result<KycCase, (StatusCode, String)> {
+    sqlx::query_as::<_, KycCase>("SELECT * FROM kyc_cases WHERE id = $1 FOR UPDATE")
+        .bind(case_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Expires the approved case behind a lapsed entity, if it is still the approved one.
+pub(super) async fn lapse_case(tx: &mut Transaction<'_, Postgres>, case_id: i32, actor: &str, reason: &str) -> Result<(), (StatusCode, String)> {
+    let case = find_case_for_update(tx, case_id).await?;
+    if KycStatus::parse(&case.status) == Some(KycStatus::Approved) {
+        transition(tx, case_id, KycStatus::Approved, KycStatus::Expired, actor, Some(reason)).await?;
+    }
+    Ok(())
+}
+
+/// Moves a case along the workflow and records the event. Decisions stamp `decided_*`.
+async fn transition(
+    tx: &mut Transaction<'_, Postgres>,
+    case_id: i32,
+    from: KycStatus,
+    to: KycStatus,
+    actor: &str,
+    note: Option<&str>,
+) -> Result<KycCase, (StatusCode, String)> {
+    if !from.can_transition_to(to) {
+        return Err((StatusCode::CONFLICT, format!("Cannot move a KYC case from {} to {}", from.as_str(), to.as_str())));
+    }
+
+    let decided = matches!(to, KycStatus::Approved | KycStatus::Rejected);
+
+    let case = sqlx::query_as::<_, KycCase>(
+        r#"
+        UPDATE kyc_cases
+        SET status = $1, updated_at = NOW(),
+            decided_by = CASE WHEN $2 THEN $3 ELSE decided_by END,
+            decided_at = CASE WHEN $2 THEN NOW() ELSE decided_at END,
+            decision_reason = CASE WHEN $2 THEN $4 ELSE decision_reason END
+        WHERE id = $5 AND status = $6
+        RETURNING *
+        "#
+    )
+    .bind(to.as_str())
+    .bind(decided)
+    .bind(actor)
+    .bind(note)
+    .bind(case_id)
+    .bind(from.as_str())
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::CONFLICT, format!("KYC case {} is no longer {}", case_id, from.as_str())))?;
+
+    sqlx::query("INSERT INTO kyc_case_events (case_id, from_status, to_status, actor, note) VALUES ($1, $2, $3, $4, $5)")
+        .bind(case_id)
+        .bind(from.as_str())
+        .bind(to.as_str())
+        .bind(actor)
+        .bind(note)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(case)
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use chrono::{DateTime, Utc};
+use super::models::LegalEntity;
+
+/// Validity of a KYC verification where no review period is configured (see `reverification`).
+pub const KYC_VALIDITY_DAYS: i64 = 365;
+
+/// ERC-721 metadata document for an AegisID token.
+/// Only non-sensitive attributes are included; the entity's `hash_id` and any PII stay off-chain.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub description: String,
+    pub attributes: Vec<MetadataAttribute>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MetadataAttribute {
+    pub trait_type: String,
+    pub value: Value,
+}
+
+impl TokenMetadata {
+    /// `expires_at` is the entity's next KYC review deadline.
+    pub fn for_entity(entity: &LegalEntity, issued_at: DateTime<Utc>, expires_at: DateTime<Utc>) -> Self {
+        Self {
+            name: format!("AegisID KYC Level {}", entity.kyc_level),
+            description: "Soulbound proof of off-chain identity verification issued by Aegis.".to_string(),
+            attributes: vec![
+                MetadataAttribute { trait_type: "KYC Level".to_string(), value: Value::from(entity.kyc_level) },
+                MetadataAttribute { trait_type: "Jurisdiction".to_string(), value: Value::from(entity.jurisdiction.clone()) },
+                MetadataAttribute { trait_type: "Issued".to_string(), value: Value::from(issued_at.format("%Y-%m-%d").to_string()) },
+                MetadataAttribute { trait_type: "Expires".to_string(), value: Value::from(expires_at.format("%Y-%m-%d").to_string()) },
+            ],
+        }
+    }
+
+    /// Serializes the document and returns `(document, content_hash)`.
+    /// The hash is keccak256 over the exact serialized bytes, so the stored document can be re-verified.
+    pub fn seal(&self) -> Result<(String, String), serde_json::Error> {
+        let document = serde_json::to_string(self)?;
+        let hash = content_hash(&document);
+        Ok((document, hash))
+    }
+}
+
+pub fn content_hash(document: &str) -> String {
+    content_hash_bytes(document.as_bytes())
+}
+
+/// keccak256 of arbitrary content, `0x`-prefixed hex.
+pub fn content_hash_bytes(content: &[u8]) -> String {
+    format!("0x{}", ethers::utils::hex::encode(ethers::utils::keccak256(content)))
+}
+
+pub fn token_uri(base_url: &str, content_hash: &str) -> String {
+    format!("{}/metadata/{}", base_url.trim_end_matches('/'), content_hash)
+}
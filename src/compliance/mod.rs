@@ -1,16 +1,104 @@
 pub mod models;
 pub mod handlers;
+pub mod metadata;
+pub mod audit;
+pub mod counterparties;
+pub mod iso20022;
+pub mod kyc;
+pub mod documents;
+pub mod reverification;
+pub mod notifications;
+pub mod sanctions;
+pub mod monitoring;
+pub mod cases;
 
 use axum::{
-    routing::post,
+    extract::DefaultBodyLimit,
+    routing::{delete, get, post, put},
     Router,
 };
 use crate::state::AppState;
 
+/// Roles allowed to manage compliance settings such as counterparty lists.
+pub const COMPLIANCE_ROLES: &[&str] = &["compliance_officer", "admin"];
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/register", post(handlers::register_entity))
         .route("/mint", post(handlers::mint_token))
         .route("/entities", axum::routing::get(handlers::list_entities))
+        .route("/identities/bulk", post(handlers::get_identities_bulk))
+        .route("/counterparties", post(handlers::add_counterparty).get(handlers::list_counterparties))
+        .route("/counterparties/:entry_id", delete(handlers::remove_counterparty))
+        .route("/wallets/:wallet_id/counterparty-mode", put(handlers::set_counterparty_mode))
+        .route("/audit-log", get(handlers::list_audit_log))
+        .route("/iso20022/messages", get(handlers::list_iso20022_messages))
+        .route("/iso20022/messages/:message_id", get(handlers::get_iso20022_message))
+        .route("/iso20022/pacs008/intents/:intent_id", get(handlers::get_intent_pacs008))
+        .route("/iso20022/pacs008/fundings/:funding_id", get(handlers::get_funding_pacs008))
+        .route("/iso20022/inbound", post(handlers::receive_iso20022_message).get(handlers::list_inbound_messages))
+        .route("/iso20022/inbound/:message_id", get(handlers::get_inbound_message))
+        .route("/iso20022/pacs002/intents/:intent_id", get(handlers::get_intent_pacs002))
+        .route("/iso20022/pacs002/inbound/:message_id", get(handlers::get_inbound_pacs002))
+        .route("/iso20022/incoming-funds", get(handlers::list_incoming_funds))
+        .route("/iso20022/camt053/wallets/:wallet_id", get(handlers::get_wallet_camt053))
+        .route("/iso20022/camt054/movements/:movement_id", get(handlers::get_movement_camt054))
+        .route("/kyc/cases", post(handlers::submit_kyc_case).get(handlers::list_kyc_cases))
+        .route("/kyc/cases/:case_id", get(handlers::get_kyc_case))
+        .route("/kyc/cases/:case_id/assignee", put(handlers::assign_kyc_case))
+        .route("/kyc/cases/:case_id/approve", post(handlers::approve_kyc_case))
+        .route("/kyc/cases/:case_id/reject", post(handlers::reject_kyc_case))
+        .route("/kyc/cases/:case_id/expire", post(handlers::expire_kyc_case))
+        .route(
+            "/kyc/cases/:case_id/documents",
+            post(handlers::upload_kyc_document).layer(DefaultBodyLimit::max(documents::MAX_DOCUMENT_BYTES)),
+        )
+        .route("/kyc/cases/:case_id/notes", post(handlers::add_kyc_note))
+        .route("/kyc/documents/:document_id", get(handlers::download_kyc_document))
+        .route("/kyc/review-periods", post(handlers::create_review_period).get(handlers::list_review_periods))
+        .route("/kyc/review-periods/:period_id", delete(handlers::remove_review_period))
+        .route("/kyc/reviews", get(handlers::list_entity_reviews))
+        .route("/kyc/reviews/:entity_id/actions", get(handlers::list_entity_review_actions))
+        .route(
+            "/sanctions/lists",
+            post(handlers::import_sanctions_list).get(handlers::list_sanctions_lists).layer(DefaultBodyLimit::max(sanctions::MAX_LIST_BYTES)),
+        )
+        .route("/sanctions/hits", get(handlers::list_sanctions_hits))
+        .route("/sanctions/hits/:hit_id", get(handlers::get_sanctions_hit))
+        .route("/sanctions/hits/:hit_id/confirm", post(handlers::confirm_sanctions_hit))
+        .route("/sanctions/hits/:hit_id/dismiss", post(handlers::dismiss_sanctions_hit))
+        .route("/monitoring/scenarios", post(handlers::create_monitoring_scenario).get(handlers::list_monitoring_scenarios))
+        .route("/monitoring/scenarios/:scenario_id", put(handlers::update_monitoring_scenario))
+        .route("/monitoring/run", post(handlers::run_monitoring))
+        .route("/monitoring/alerts", get(handlers::list_monitoring_alerts))
+        .route("/monitoring/alerts/:alert_id", get(handlers::get_monitoring_alert))
+        .route("/monitoring/alerts/:alert_id/assignee", put(handlers::assign_monitoring_alert))
+        .route("/monitoring/alerts/:alert_id/escalate", post(handlers::escalate_monitoring_alert))
+        .route("/monitoring/alerts/:alert_id/dismiss", post(handlers::dismiss_monitoring_alert))
+        .route("/monitoring/alerts/:alert_id/resolve", post(handlers::resolve_monitoring_alert))
+        .route("/monitoring/alerts/:alert_id/notes", post(handlers::add_monitoring_alert_note))
+        .route("/cases", post(handlers::open_case).get(handlers::list_cases))
+        .route("/cases/:case_id", get(handlers::get_case).put(handlers::update_case))
+        .route("/cases/:case_id/assignee", put(handlers::assign_case))
+        .route("/cases/:case_id/escalate", post(handlers::escalate_case))
+        .route("/cases/:case_id/return", post(handlers::return_case))
+        .route("/cases/:case_id/file", post(handlers::file_case_report))
+        .route("/cases/:case_id/close", post(handlers::close_case))
+        .route("/cases/:case_id/links", post(handlers::add_case_link))
+        .route("/cases/:case_id/links/:link_id", delete(handlers::remove_case_link))
+        .route("/cases/:case_id/comments", post(handlers::add_case_comment))
+        .route(
+            "/cases/:case_id/attachments",
+            post(handlers::upload_case_attachment).layer(DefaultBodyLimit::max(documents::MAX_DOCUMENT_BYTES)),
+        )
+        .route("/cases/attachments/:attachment_id", get(handlers::download_case_attachment))
+        .route("/cases/:case_id/report", get(handlers::export_case_report))
+        .route("/notifications", get(handlers::list_notifications))
+        .route("/notifications/:notification_id/acknowledge", post(handlers::acknowledge_notification))
 }
 
+/// Public routes serving AegisID token metadata (token URIs must be resolvable without a JWT).
+pub fn metadata_router() -> Router<AppState> {
+    Router::new()
+        .route("/metadata/:content_hash", get(handlers::get_metadata))
+}
@@ -1,11 +1,15 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+/// New entities start unverified (level 0); a level is only granted by approving a KYC case.
 #[derive(Debug, Deserialize)]
 pub struct RegisterEntityRequest {
     pub hash_id: String,
     pub jurisdiction: String,
-    pub kyc_level: i16, // using i16 for SMALLINT
+    pub legal_name: Option<String>,
+    pub lei: Option<String>,
+    pub bic: Option<String>,
+    pub iban: Option<String>,
 }
 
 #[derive(Debug, Serialize, FromRow)]
@@ -16,4 +20,811 @@ pub struct LegalEntity {
     pub kyc_level: i16,
     pub on_chain_id: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    pub legal_name: Option<String>,
+    pub lei: Option<String>,
+    pub bic: Option<String>,
+    pub iban: Option<String>,
+    /// Approved KYC case behind `kyc_level`.
+    pub kyc_case_id: Option<i32>,
+    pub kyc_verified_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// "unverified", "verified", "expiring", "downgraded" or "suspended".
+    pub kyc_status: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MintRequest {
+    pub wallet_address: String,
+    pub entity_id: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MintResponse {
+    pub tx_hash: String,
+    pub token_id: String,
+    pub token_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkIdentityRequest {
+    pub addresses: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IdentityStatus {
+    pub address: String,
+    pub verified: bool,
+    pub token_count: Option<String>, // None if the read failed
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct CounterpartyEntry {
+    pub id: i32,
+    pub wallet_id: Option<i32>,
+    pub address: String,
+    pub list_type: String,
+    pub label: Option<String>,
+    pub created_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// `list_type` is "allow" or "block"; omit `wallet_id` for a global entry.
+#[derive(Debug, Deserialize)]
+pub struct AddCounterpartyRequest {
+    pub wallet_id: Option<i32>,
+    pub address: String,
+    pub list_type: String,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CounterpartyFilter {
+    pub wallet_id: Option<i32>,
+    pub list_type: Option<String>,
+    pub address: Option<String>,
+}
+
+/// `status` is "submitted", "in_review", "approved", "rejected" or "expired".
+#[derive(Debug, Serialize, FromRow)]
+pub struct KycCase {
+    pub id: i32,
+    pub entity_id: i32,
+    pub requested_level: i16,
+    pub status: String,
+    pub submitted_by: String,
+    pub assigned_to: Option<String>,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub decision_reason: Option<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct KycCaseEvent {
+    pub id: i32,
+    pub case_id: i32,
+    pub from_status: String,
+    pub to_status: String,
+    pub actor: String,
+    pub note: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Metadata of an uploaded document; the content is only served decrypted through its download route.
+#[derive(Debug, Serialize, FromRow)]
+pub struct KycDocument {
+    pub id: i32,
+    pub case_id: i32,
+    pub document_type: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub content_hash: String,
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    pub uploaded_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct KycNote {
+    pub id: i32,
+    pub case_id: i32,
+    pub author: String,
+    pub body: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KycCaseDetail {
+    pub case: KycCase,
+    pub events: Vec<KycCaseEvent>,
+    pub documents: Vec<KycDocument>,
+    pub notes: Vec<KycNote>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitKycCaseRequest {
+    pub entity_id: i32,
+    pub requested_level: i16,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KycCaseFilter {
+    pub entity_id: Option<i32>,
+    pub status: Option<String>,
+    pub assigned_to: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignKycCaseRequest {
+    pub reviewer: String,
+}
+
+/// `reason` is required to reject or expire a case.
+#[derive(Debug, Default, Deserialize)]
+pub struct KycDecisionRequest {
+    pub reason: Option<String>,
+}
+
+/// Query of a document upload; the request body is the file itself.
+#[derive(Debug, Deserialize)]
+pub struct KycDocumentUpload {
+    pub document_type: String,
+    pub file_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddKycNoteRequest {
+    pub body: String,
+}
+
+/// NULL `jurisdiction`/`kyc_level` match any entity; `on_lapse` is "downgrade" (to `downgrade_to`) or "suspend".
+#[derive(Debug, Serialize, FromRow)]
+pub struct KycReviewPeriod {
+    pub id: i32,
+    pub jurisdiction: Option<String>,
+    pub kyc_level: Option<i16>,
+    pub review_days: i32,
+    pub warning_days: i32,
+    pub on_lapse: String,
+    pub downgrade_to: Option<i16>,
+    pub created_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReviewPeriodRequest {
+    pub jurisdiction: Option<String>,
+    pub kyc_level: Option<i16>,
+    pub review_days: i32,
+    pub warning_days: i32,
+    pub on_lapse: String,
+    pub downgrade_to: Option<i16>,
+}
+
+/// Review deadline of a verified entity under the period that applies to it.
+#[derive(Debug, Serialize)]
+pub struct EntityReview {
+    pub entity_id: i32,
+    pub jurisdiction: String,
+    pub kyc_level: i16,
+    pub kyc_status: String,
+    pub verified_at: chrono::DateTime<chrono::Utc>,
+    pub warn_at: chrono::DateTime<chrono::Utc>,
+    pub due_at: chrono::DateTime<chrono::Utc>,
+    /// None when the built-in default applies.
+    pub review_period_id: Option<i32>,
+    pub on_lapse: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EntityReviewFilter {
+    /// Only entities due within this many days (overdue ones included).
+    pub within_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct KycReviewAction {
+    pub id: i32,
+    pub entity_id: i32,
+    pub action: String,
+    pub due_at: chrono::DateTime<chrono::Utc>,
+    pub kyc_level_before: Option<i16>,
+    pub kyc_level_after: Option<i16>,
+    pub token_id: Option<String>,
+    pub freeze_id: Option<i32>,
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ComplianceNotification {
+    pub id: i32,
+    pub kind: String,
+    pub subject: String,
+    pub message: String,
+    pub details: serde_json::Value,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub acknowledged_by: Option<String>,
+    pub acknowledged_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationFilter {
+    pub kind: Option<String>,
+    /// Include acknowledged notifications (default: open ones only).
+    #[serde(default)]
+    pub all: bool,
+}
+
+/// `mode` is "block" (pay anyone not blocklisted) or "allow_only".
+#[derive(Debug, Deserialize)]
+pub struct CounterpartyModeRequest {
+    pub mode: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct AuditEntry {
+    pub id: i32,
+    pub actor: String,
+    pub action: String,
+    pub subject: String,
+    pub details: serde_json::Value,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditFilter {
+    pub subject: Option<String>,
+    pub action: Option<String>,
+}
+
+/// A generated ISO 20022 message, tied to an agent payment, a wallet funding or (for status reports) an inbound message.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Iso20022Message {
+    pub id: i32,
+    pub message_type: String,
+    pub msg_id: String,
+    pub intent_id: Option<i32>,
+    pub funding_id: Option<i32>,
+    pub document: String,
+    pub created_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub inbound_message_id: Option<i32>,
+    pub original_msg_id: Option<String>,
+    pub status_codes: Option<String>,
+    pub wallet_id: Option<i32>,
+    pub statement_date: Option<chrono::NaiveDate>,
+    pub movement_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Iso20022Filter {
+    pub intent_id: Option<i32>,
+    pub funding_id: Option<i32>,
+    pub inbound_message_id: Option<i32>,
+    pub message_type: Option<String>,
+    /// Statements of the wallet and notifications of its movements.
+    pub wallet_id: Option<i32>,
+    pub movement_id: Option<i32>,
+}
+
+/// Day a camt.053 covers (UTC).
+#[derive(Debug, Deserialize)]
+pub struct StatementQuery {
+    pub date: chrono::NaiveDate,
+}
+
+/// A received ISO 20022 message, stored as sent.
+#[derive(Debug, Serialize, FromRow)]
+pub struct InboundMessage {
+    pub id: i32,
+    pub message_type: String,
+    pub msg_id: String,
+    pub document: String,
+    pub received_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// One credit transfer of an inbound message. `status` is "intent_created", "funds_recorded" or "rejected".
+#[derive(Debug, Serialize, FromRow)]
+pub struct InboundItem {
+    pub id: i32,
+    pub message_id: i32,
+    pub payment_info_id: Option<String>,
+    pub end_to_end_id: String,
+    pub amount: String,
+    pub currency: String,
+    pub status: String,
+    pub intent_id: Option<i32>,
+    pub incoming_funds_id: Option<i32>,
+    pub rejection_reason: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InboundMessageDetail {
+    pub message: InboundMessage,
+    pub items: Vec<InboundItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InboundFilter {
+    pub message_type: Option<String>,
+}
+
+/// Funds a bank reported as credited to a wallet through a pacs.008.
+#[derive(Debug, Serialize, FromRow)]
+pub struct IncomingFunds {
+    pub id: i32,
+    pub wallet_id: i32,
+    pub amount: String,
+    pub currency: String,
+    pub token_address: Option<String>,
+    pub debtor_name: Option<String>,
+    pub debtor_entity_id: Option<i32>,
+    pub end_to_end_id: String,
+    pub uetr: Option<String>,
+    pub settlement_date: Option<chrono::NaiveDate>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IncomingFundsFilter {
+    pub wallet_id: Option<i32>,
+}
+
+/// One imported version of a sanctions list.
+#[derive(Debug, Serialize, FromRow)]
+pub struct SanctionsList {
+    pub id: i32,
+    pub source: String,
+    pub format: String,
+    pub version: String,
+    pub content_hash: String,
+    pub entry_count: i32,
+    pub address_count: i32,
+    pub active: bool,
+    pub imported_by: String,
+    pub imported_at: chrono::DateTime<chrono::Utc>,
+    pub screened_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Query of a list import; the request body is the list file itself.
+/// `version` defaults to the publication date in the file (the OFAC CSV has none, so it must be given).
+#[derive(Debug, Deserialize)]
+pub struct SanctionsImportQuery {
+    pub source: String,
+    pub format: String,
+    pub version: Option<String>,
+}
+
+/// A listed name or alias, with the entry it belongs to, as fuzzy name matching sees it.
+#[derive(Debug, Clone, FromRow)]
+pub struct SanctionsCandidate {
+    pub entry_id: i32,
+    pub list_id: i32,
+    pub source: String,
+    pub source_uid: String,
+    pub listed_name: String,
+    pub name: String,
+    pub normalized: String,
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct SanctionsHit {
+    pub id: i32,
+    pub subject_type: String,
+    pub subject: String,
+    pub entity_id: Option<i32>,
+    pub source: String,
+    pub source_uid: String,
+    pub list_id: i32,
+    pub entry_id: i32,
+    pub listed_name: String,
+    pub match_type: String,
+    pub matched_value: String,
+    pub score: f64,
+    pub context: String,
+    pub status: String,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub review_note: Option<String>,
+    pub freeze_id: Option<i32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SanctionsHitFilter {
+    pub status: Option<String>,
+    pub subject_type: Option<String>,
+    pub entity_id: Option<i32>,
+}
+
+/// A note is required to dismiss a hit and optional when confirming one.
+#[derive(Debug, Default, Deserialize)]
+pub struct SanctionsHitReviewRequest {
+    pub note: Option<String>,
+}
+
+/// A detection scenario, tagged by `type`. Omitted parameters take the defaults in `monitoring::scenarios`.
+/// Amounts are in the decimal units of `token_address` (omitted means native ETH), like policy rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScenarioRule {
+    /// Several outgoing payments within `window_seconds`, each less than `margin_percent` below `limit`.
+    /// Without a `limit`, the lowest approval threshold of the wallet and asset is used.
+    Structuring {
+        token_address: Option<String>,
+        limit: Option<String>,
+        margin_percent: Option<u32>,
+        window_seconds: Option<i64>,
+        min_count: Option<i64>,
+    },
+    /// Funds received and paid out again within `window_seconds`, at least `min_ratio_percent` of the credit.
+    RapidInOut {
+        token_address: Option<String>,
+        min_amount: Option<String>,
+        window_seconds: Option<i64>,
+        min_ratio_percent: Option<u32>,
+    },
+    /// Payments to `min_counterparties` or more counterparties within `window_seconds`, none of which
+    /// the wallet paid in the `lookback_days` before.
+    FanOut {
+        window_seconds: Option<i64>,
+        min_counterparties: Option<i64>,
+        lookback_days: Option<i64>,
+    },
+    /// Any movement after `dormant_days` or more without one (counted from the wallet's creation).
+    DormantReactivation {
+        dormant_days: Option<i64>,
+    },
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct MonitoringScenario {
+    pub id: i32,
+    pub name: String,
+    pub kind: String,
+    pub rule: serde_json::Value,
+    pub wallet_id: Option<i32>,
+    pub severity: String,
+    pub enabled: bool,
+    pub created_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// `severity` is "low", "medium" or "high" and is carried over to the scenario's alerts.
+#[derive(Debug, Deserialize)]
+pub struct CreateScenarioRequest {
+    pub name: String,
+    pub wallet_id: Option<i32>,
+    pub severity: String,
+    pub rule: ScenarioRule,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateScenarioRequest {
+    pub rule: Option<ScenarioRule>,
+    pub severity: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct MonitoringAlert {
+    pub id: i32,
+    pub scenario_id: i32,
+    pub kind: String,
+    pub wallet_id: i32,
+    pub entity_id: i32,
+    pub severity: String,
+    pub status: String,
+    pub summary: String,
+    pub evidence: serde_json::Value,
+    pub assigned_to: Option<String>,
+    pub closed_by: Option<String>,
+    pub closed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub close_reason: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct MonitoringAlertEvent {
+    pub id: i32,
+    pub alert_id: i32,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub actor: String,
+    pub note: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct MonitoringAlertNote {
+    pub id: i32,
+    pub alert_id: i32,
+    pub author: String,
+    pub body: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MonitoringAlertDetail {
+    pub alert: MonitoringAlert,
+    pub events: Vec<MonitoringAlertEvent>,
+    pub notes: Vec<MonitoringAlertNote>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MonitoringAlertFilter {
+    pub status: Option<String>,
+    pub scenario_id: Option<i32>,
+    pub wallet_id: Option<i32>,
+    pub severity: Option<String>,
+    pub assigned_to: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignAlertRequest {
+    pub assignee: String,
+}
+
+/// `reason` is required to escalate, dismiss or resolve an alert.
+#[derive(Debug, Deserialize)]
+pub struct AlertDecisionRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddAlertNoteRequest {
+    pub body: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ComplianceCase {
+    pub id: i32,
+    pub title: String,
+    pub priority: String,
+    pub status: String,
+    pub narrative: Option<String>,
+    pub opened_by: String,
+    pub assigned_to: Option<String>,
+    pub due_at: chrono::DateTime<chrono::Utc>,
+    pub sla_warned_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub sla_breached_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub filing_reference: Option<String>,
+    pub filed_by: Option<String>,
+    pub filed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub closed_by: Option<String>,
+    pub closed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub close_reason: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ComplianceCaseLink {
+    pub id: i32,
+    pub case_id: i32,
+    pub link_type: String,
+    pub link_id: i32,
+    pub note: Option<String>,
+    pub added_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ComplianceCaseEvent {
+    pub id: i32,
+    pub case_id: i32,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub actor: String,
+    pub note: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ComplianceCaseComment {
+    pub id: i32,
+    pub case_id: i32,
+    pub author: String,
+    pub body: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ComplianceCaseAttachment {
+    pub id: i32,
+    pub case_id: i32,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub content_hash: String,
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    pub uploaded_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComplianceCaseDetail {
+    pub case: ComplianceCase,
+    pub links: Vec<ComplianceCaseLink>,
+    pub events: Vec<ComplianceCaseEvent>,
+    pub comments: Vec<ComplianceCaseComment>,
+    pub attachments: Vec<ComplianceCaseAttachment>,
+}
+
+/// `link_type` is "entity", "wallet", "intent", "movement", "monitoring_alert", "sanctions_hit" or "kyc_case";
+/// `link_id` is the id of that record.
+#[derive(Debug, Deserialize)]
+pub struct CaseLinkRequest {
+    pub link_type: String,
+    pub link_id: i32,
+    pub note: Option<String>,
+}
+
+/// `priority` is "low", "medium", "high" or "critical". Without `due_at`, the SLA deadline follows from the priority.
+#[derive(Debug, Deserialize)]
+pub struct CreateCaseRequest {
+    pub title: String,
+    pub priority: String,
+    pub narrative: Option<String>,
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub links: Vec<CaseLinkRequest>,
+}
+
+/// Changing `priority` without a `due_at` moves the deadline to the one of the new priority.
+#[derive(Debug, Deserialize)]
+pub struct UpdateCaseRequest {
+    pub title: Option<String>,
+    pub priority: Option<String>,
+    pub narrative: Option<String>,
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CaseFilter {
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    pub assigned_to: Option<String>,
+    /// Only cases past their SLA deadline that are still undecided
+    pub overdue: Option<bool>,
+    /// Cases linked to this record (together with `link_id`)
+    pub link_type: Option<String>,
+    pub link_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignCaseRequest {
+    pub assignee: String,
+}
+
+/// `reason` is required to close a case and recorded with any other transition.
+#[derive(Debug, Default, Deserialize)]
+pub struct CaseDecisionRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FileCaseReportRequest {
+    pub filing_reference: String,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddCaseCommentRequest {
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CaseAttachmentUpload {
+    pub file_name: String,
+}
+
+/// `report_type` is "sar" (default) or "str"; `format` is "json" (default) or "xml".
+#[derive(Debug, Deserialize)]
+pub struct CaseReportQuery {
+    pub report_type: Option<String>,
+    pub format: Option<String>,
+}
+
+/// A suspicious activity (SAR) or suspicious transaction (STR) report drafted from a case, for review
+/// and filing with the regulator or FIU. `missing` lists what must be completed before filing.
+#[derive(Debug, Serialize)]
+pub struct SarDraft {
+    pub report_type: String,
+    pub reference: String,
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+    pub generated_by: String,
+    pub case: SarCase,
+    pub subjects: Vec<SarSubject>,
+    pub activity: SarActivity,
+    pub transactions: Vec<SarTransaction>,
+    pub indicators: Vec<SarIndicator>,
+    pub narrative: Option<String>,
+    pub attachments: Vec<SarAttachment>,
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SarCase {
+    pub id: i32,
+    pub title: String,
+    pub priority: String,
+    pub status: String,
+    pub opened_by: String,
+    pub assigned_to: Option<String>,
+    pub opened_at: chrono::DateTime<chrono::Utc>,
+    pub due_at: chrono::DateTime<chrono::Utc>,
+    pub filing_reference: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SarSubject {
+    pub entity_id: i32,
+    pub legal_name: Option<String>,
+    pub lei: Option<String>,
+    pub bic: Option<String>,
+    pub iban: Option<String>,
+    pub jurisdiction: String,
+    pub kyc_level: i16,
+    pub kyc_status: String,
+    pub wallets: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SarActivity {
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub totals: Vec<SarTotal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SarTotal {
+    pub direction: String,
+    pub asset: String,
+    pub count: usize,
+    pub amount: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SarTransaction {
+    pub flow_type: String,
+    pub id: i32,
+    pub at: chrono::DateTime<chrono::Utc>,
+    pub direction: String,
+    pub wallet: String,
+    pub counterparty: String,
+    pub asset: String,
+    pub amount: String,
+    pub tx_hash: Option<String>,
+}
+
+/// What raised the suspicion: a monitoring alert, a sanctions screening hit or a KYC case.
+#[derive(Debug, Serialize)]
+pub struct SarIndicator {
+    pub source: String,
+    pub id: i32,
+    pub kind: String,
+    pub status: String,
+    pub description: String,
+    pub raised_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SarAttachment {
+    pub id: i32,
+    pub file_name: String,
+    pub content_type: String,
+    pub content_hash: String,
 }
@@ -0,0 +1,294 @@
+use axum::http::StatusCode;
+use sqlx::{PgPool, Postgres, Transaction};
+use crate::agent::intents::SYSTEM_ACTOR;
+use crate::compliance::models::{MonitoringAlert, MonitoringAlertDetail, MonitoringAlertEvent, MonitoringAlertNote, MonitoringScenario};
+use crate::compliance::{audit, notifications, COMPLIANCE_ROLES};
+use super::scenarios::Finding;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertStatus {
+    Open,
+    Investigating,
+    Escalated,
+    Dismissed,
+    Resolved,
+}
+
+impl AlertStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertStatus::Open => "open",
+            AlertStatus::Investigating => "investigating",
+            AlertStatus::Escalated => "escalated",
+            AlertStatus::Dismissed => "dismissed",
+            AlertStatus::Resolved => "resolved",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "open" => Some(AlertStatus::Open),
+            "investigating" => Some(AlertStatus::Investigating),
+            "escalated" => Some(AlertStatus::Escalated),
+            "dismissed" => Some(AlertStatus::Dismissed),
+            "resolved" => Some(AlertStatus::Resolved),
+            _ => None,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        matches!(self, AlertStatus::Dismissed | AlertStatus::Resolved)
+    }
+
+    /// Allowed edges of the triage workflow. Obvious false positives can be dismissed straight away;
+    /// everything else is investigated by an assignee first.
+    pub fn can_transition_to(&self, next: AlertStatus) -> bool {
+        use AlertStatus::*;
+        matches!(
+            (self, next),
+            (Open, Investigating) | (Open, Dismissed)
+                | (Investigating, Escalated) | (Investigating, Dismissed) | (Investigating, Resolved)
+                | (Escalated, Resolved)
+        )
+    }
+}
+
+pub async fn find_alert(pool: &PgPool, alert_id: i32) -> Result<MonitoringAlert, (StatusCode, String)> {
+    sqlx::query_as::<_, MonitoringAlert>("SELECT * FROM monitoring_alerts WHERE id = $1")
+        .bind(alert_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Alert {} not found", alert_id)))
+}
+
+pub async fn alert_detail(pool: &PgPool, alert_id: i32) -> Result<MonitoringAlertDetail, (StatusCode, String)> {
+    let alert = find_alert(pool, alert_id).await?;
+
+    let events = sqlx::query_as::<_, MonitoringAlertEvent>("SELECT * FROM monitoring_alert_events WHERE alert_id = $1 ORDER BY id")
+        .bind(alert.id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let notes = sqlx::query_as::<_, MonitoringAlertNote>("SELECT * FROM monitoring_alert_notes WHERE alert_id = $1 ORDER BY id")
+        .bind(alert.id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(MonitoringAlertDetail { alert, events, notes })
+}
+
+/// Stores a finding as an open alert and notifies compliance. Returns None if another run already raised
+/// an alert for one of its flows.
+pub(super) async fn raise(
+    pool: &PgPool,
+    scenario: &MonitoringScenario,
+    wallet_id: i32,
+    entity_id: i32,
+    finding: &Finding,
+    evidence: serde_json::Value,
+) -> Result<Option<MonitoringAlert>, (StatusCode, String)> {
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let alert = sqlx::query_as::<_, MonitoringAlert>(
+        r#"
+        INSERT INTO monitoring_alerts (scenario_id, kind, wallet_id, entity_id, severity, summary, evidence)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#
+    )
+    .bind(scenario.id)
+    .bind(&scenario.kind)
+    .bind(wallet_id)
+    .bind(entity_id)
+    .bind(&scenario.severity)
+    .bind(&finding.summary)
+    .bind(evidence)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let linked = sqlx::query(
+        r#"
+        INSERT INTO monitoring_alert_flows (alert_id, scenario_id, flow_type, flow_id)
+        SELECT $1, $2, flow_type, flow_id FROM UNNEST($3::TEXT[], $4::INTEGER[]) AS f(flow_type, flow_id)
+        ON CONFLICT DO NOTHING
+        "#
+    )
+    .bind(alert.id)
+    .bind(scenario.id)
+    .bind(finding.flows.iter().map(|f| f.flow_type.to_string()).collect::<Vec<_>>())
+    .bind(finding.flows.iter().map(|f| f.id).collect::<Vec<_>>())
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if linked.rows_affected() as usize != finding.flows.len() {
+        // Dropping the transaction rolls the alert back
+        return Ok(None);
+    }
+
+    record_event(&mut tx, alert.id, None, AlertStatus::Open, SYSTEM_ACTOR, None).await?;
+    notifications::notify(
+        &mut *tx,
+        "monitoring.alert",
+        &format!("monitoring_alert:{}", alert.id),
+        &format!("{} ({}) on wallet {}: {}", scenario.name, alert.severity, wallet_id, alert.summary),
+        serde_json::json!({
+            "alert_id": alert.id,
+            "scenario_id": scenario.id,
+            "kind": alert.kind,
+            "wallet_id": wallet_id,
+            "entity_id": entity_id,
+        }),
+    ).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Some(alert))
+}
+
+/// Assigns an alert that is not closed to a compliance user; an open alert moves to `investigating`.
+pub async fn assign(pool: &PgPool, alert: MonitoringAlert, assignee: &str, actor: &str) -> Result<MonitoringAlert, (StatusCode, String)> {
+    let status = parse_status(&alert)?;
+    if status.is_closed() {
+        return Err((StatusCode::CONFLICT, format!("Alert {} is {}", alert.id, alert.status)));
+    }
+    let role: Option<String> = sqlx::query_scalar("SELECT role FROM users WHERE username = $1")
+        .bind(assignee)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !role.is_some_and(|r| COMPLIANCE_ROLES.contains(&r.as_str())) {
+        return Err((StatusCode::BAD_REQUEST, format!("{} is not a compliance user", assignee)));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let updated = sqlx::query_as::<_, MonitoringAlert>(
+        "UPDATE monitoring_alerts SET assigned_to = $1, updated_at = NOW() WHERE id = $2 AND status = $3 RETURNING *"
+    )
+    .bind(assignee)
+    .bind(alert.id)
+    .bind(status.as_str())
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::CONFLICT, format!("Alert {} is no longer {}", alert.id, status.as_str())))?;
+
+    let note = format!("Assigned to {}", assignee);
+    let alert = match status {
+        AlertStatus::Open => transition(&mut tx, alert.id, status, AlertStatus::Investigating, actor, Some(&note)).await?,
+        _ => {
+            record_event(&mut tx, alert.id, Some(status), status, actor, Some(&note)).await?;
+            updated
+        }
+    };
+
+    audit::record(&mut *tx, actor, "monitoring.alert.assign", &format!("monitoring_alert:{}", alert.id), serde_json::json!({
+        "assignee": assignee,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(alert)
+}
+
+/// Escalates, dismisses or resolves an alert. Only the assignee decides on an alert under investigation;
+/// an open alert can be dismissed by any compliance user.
+pub async fn decide(pool: &PgPool, alert: MonitoringAlert, to: AlertStatus, actor: &str, reason: &str) -> Result<MonitoringAlert, (StatusCode, String)> {
+    let status = parse_status(&alert)?;
+    if !status.can_transition_to(to) {
+        return Err((StatusCode::CONFLICT, format!("Cannot move alert {} from {} to {}", alert.id, status.as_str(), to.as_str())));
+    }
+    if status != AlertStatus::Open && alert.assigned_to.as_deref() != Some(actor) {
+        return Err((StatusCode::FORBIDDEN, format!("Alert {} is not assigned to {}", alert.id, actor)));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let alert = transition(&mut tx, alert.id, status, to, actor, Some(reason)).await?;
+    audit::record(&mut *tx, actor, &format!("monitoring.alert.{}", to.as_str()), &format!("monitoring_alert:{}", alert.id), serde_json::json!({
+        "from": status.as_str(),
+        "reason": reason,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(alert)
+}
+
+pub async fn add_note(pool: &PgPool, alert: &MonitoringAlert, body: &str, author: &str) -> Result<MonitoringAlertNote, (StatusCode, String)> {
+    if body.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Note is empty".to_string()));
+    }
+
+    sqlx::query_as::<_, MonitoringAlertNote>("INSERT INTO monitoring_alert_notes (alert_id, author, body) VALUES ($1, $2, $3) RETURNING *")
+        .bind(alert.id)
+        .bind(author)
+        .bind(body.trim())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+fn parse_status(alert: &MonitoringAlert) -> Result<AlertStatus, (StatusCode, String)> {
+    AlertStatus::parse(&alert.status).ok_or((StatusCode::INTERNAL_SERVER_ERROR, format!("Unknown alert status {}", alert.status)))
+}
+
+async fn transition(
+    tx: &mut Transaction<'_, Postgres>,
+    alert_id: i32,
+    from: AlertStatus,
+    to: AlertStatus,
+    actor: &str,
+    note: Option<&str>,
+) -> Result<MonitoringAlert, (StatusCode, String)> {
+    if !from.can_transition_to(to) {
+        return Err((StatusCode::CONFLICT, format!("Cannot move an alert from {} to {}", from.as_str(), to.as_str())));
+    }
+
+    let closed = to.is_closed();
+    let alert = sqlx::query_as::<_, MonitoringAlert>(
+        r#"
+        UPDATE monitoring_alerts
+        SET status = $1, updated_at = NOW(),
+            closed_by = CASE WHEN $2 THEN $3 ELSE closed_by END,
+            closed_at = CASE WHEN $2 THEN NOW() ELSE closed_at END,
+            close_reason = CASE WHEN $2 THEN $4 ELSE close_reason END
+        WHERE id = $5 AND status = $6
+        RETURNING *
+        "#
+    )
+    .bind(to.as_str())
+    .bind(closed)
+    .bind(actor)
+    .bind(note)
+    .bind(alert_id)
+    .bind(from.as_str())
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::CONFLICT, format!("Alert {} is no longer {}", alert_id, from.as_str())))?;
+
+    record_event(tx, alert_id, Some(from), to, actor, note).await?;
+    Ok(alert)
+}
+
+async fn record_event(
+    tx: &mut Transaction<'_, Postgres>,
+    alert_id: i32,
+    from: Option<AlertStatus>,
+    to: AlertStatus,
+    actor: &str,
+    note: Option<&str>,
+) -> Result<(), (StatusCode, String)> {
+    sqlx::query("INSERT INTO monitoring_alert_events (alert_id, from_status, to_status, actor, note) VALUES ($1, $2, $3, $4, $5)")
+        .bind(alert_id)
+        .bind(from.map(|s| s.as_str()))
+        .bind(to.as_str())
+        .bind(actor)
+        .bind(note)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(())
+}
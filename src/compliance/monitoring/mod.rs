@@ -0,0 +1,495 @@
+pub mod alerts;
+pub mod scenarios;
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::U256;
+use sqlx::{FromRow, PgPool};
+use std::collections::{HashMap, HashSet};
+use crate::finance::amounts::{format_amount, parse_amount, NATIVE_DECIMALS, NATIVE_SYMBOL};
+use crate::finance::models::Wallet;
+use crate::finance::registry::{find_allowed_token, find_wallet};
+use crate::state::AppState;
+use super::audit;
+use super::models::{CreateScenarioRequest, MonitoringAlert, MonitoringScenario, ScenarioRule, UpdateScenarioRequest};
+use scenarios::{Evaluation, Finding, Flow, CREDIT, DEBIT, FLOW_INTENT, FLOW_MOVEMENT};
+
+/// How often enabled scenarios are evaluated.
+const POLL_INTERVAL_SECS: u64 = 3600;
+
+/// Patterns completed within this many days are reported; older flows only serve as history.
+const EVALUATION_DAYS: i64 = 7;
+
+pub const SEVERITIES: &[&str] = &["low", "medium", "high"];
+
+/// Evaluates all enabled scenarios and raises alerts for new findings.
+pub async fn run(pool: PgPool) {
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(POLL_INTERVAL_SECS));
+    loop {
+        ticker.tick().await;
+        if let Err((_, e)) = evaluate(&pool).await {
+            println!("Transaction monitoring error: {}", e);
+        }
+    }
+}
+
+/// One pass over all enabled scenarios. Returns the alerts raised; a failing scenario does not stop the others.
+pub async fn evaluate(pool: &PgPool) -> Result<Vec<MonitoringAlert>, (StatusCode, String)> {
+    let scenarios = sqlx::query_as::<_, MonitoringScenario>("SELECT * FROM monitoring_scenarios WHERE enabled ORDER BY id")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let tokens = token_index(pool).await?;
+    let from = Utc::now() - Duration::days(EVALUATION_DAYS);
+
+    let mut raised = Vec::new();
+    for scenario in &scenarios {
+        match evaluate_scenario(pool, scenario, &tokens, from).await {
+            Ok(alerts) => raised.extend(alerts),
+            Err((_, e)) => println!("Transaction monitoring error for scenario {}: {}", scenario.id, e),
+        }
+    }
+    Ok(raised)
+}
+
+pub async fn find_scenario(pool: &PgPool, scenario_id: i32) -> Result<MonitoringScenario, (StatusCode, String)> {
+    sqlx::query_as::<_, MonitoringScenario>("SELECT * FROM monitoring_scenarios WHERE id = $1")
+        .bind(scenario_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Scenario {} not found", scenario_id)))
+}
+
+pub async fn create_scenario(state: &AppState, payload: CreateScenarioRequest, actor: &str) -> Result<MonitoringScenario, (StatusCode, String)> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "name is required".to_string()));
+    }
+    validate_severity(&payload.severity)?;
+    if let Some(wallet_id) = payload.wallet_id {
+        find_wallet(&state.pool, wallet_id).await?;
+    }
+    validate_rule(state, &payload.rule).await?;
+    let rule = serde_json::to_value(&payload.rule).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let scenario = sqlx::query_as::<_, MonitoringScenario>(
+        r#"
+        INSERT INTO monitoring_scenarios (name, kind, rule, wallet_id, severity, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#
+    )
+    .bind(name)
+    .bind(kind(&payload.rule))
+    .bind(&rule)
+    .bind(payload.wallet_id)
+    .bind(&payload.severity)
+    .bind(actor)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit::record(&mut *tx, actor, "monitoring.scenario.create", &format!("monitoring_scenario:{}", scenario.id), serde_json::json!({
+        "name": scenario.name,
+        "wallet_id": scenario.wallet_id,
+        "severity": scenario.severity,
+        "rule": rule,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(scenario)
+}
+
+/// Changes a scenario's rule (of the same kind), severity or whether it is evaluated. Existing alerts keep
+/// the rule they were raised under in their evidence.
+pub async fn update_scenario(
+    state: &AppState,
+    scenario: MonitoringScenario,
+    payload: UpdateScenarioRequest,
+    actor: &str,
+) -> Result<MonitoringScenario, (StatusCode, String)> {
+    if let Some(severity) = &payload.severity {
+        validate_severity(severity)?;
+    }
+    let rule = match &payload.rule {
+        Some(rule) => {
+            if kind(rule) != scenario.kind {
+                return Err((StatusCode::BAD_REQUEST, format!("Scenario {} is a {} scenario", scenario.id, scenario.kind)));
+            }
+            validate_rule(state, rule).await?;
+            Some(serde_json::to_value(rule).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?)
+        }
+        None => None,
+    };
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let updated = sqlx::query_as::<_, MonitoringScenario>(
+        r#"
+        UPDATE monitoring_scenarios
+        SET rule = COALESCE($1, rule), severity = COALESCE($2, severity), enabled = COALESCE($3, enabled), updated_at = NOW()
+        WHERE id = $4
+        RETURNING *
+        "#
+    )
+    .bind(&rule)
+    .bind(&payload.severity)
+    .bind(payload.enabled)
+    .bind(scenario.id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit::record(&mut *tx, actor, "monitoring.scenario.update", &format!("monitoring_scenario:{}", scenario.id), serde_json::json!({
+        "previous": {
+            "rule": scenario.rule,
+            "severity": scenario.severity,
+            "enabled": scenario.enabled,
+        },
+        "rule": updated.rule,
+        "severity": updated.severity,
+        "enabled": updated.enabled,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(updated)
+}
+
+fn kind(rule: &ScenarioRule) -> &'static str {
+    match rule {
+        ScenarioRule::Structuring { .. } => scenarios::STRUCTURING,
+        ScenarioRule::RapidInOut { .. } => scenarios::RAPID_IN_OUT,
+        ScenarioRule::FanOut { .. } => scenarios::FAN_OUT,
+        ScenarioRule::DormantReactivation { .. } => scenarios::DORMANT_REACTIVATION,
+    }
+}
+
+fn validate_severity(severity: &str) -> Result<(), (StatusCode, String)> {
+    if !SEVERITIES.contains(&severity) {
+        return Err((StatusCode::BAD_REQUEST, format!("severity must be one of {}", SEVERITIES.join(", "))));
+    }
+    Ok(())
+}
+
+async fn validate_rule(state: &AppState, rule: &ScenarioRule) -> Result<(), (StatusCode, String)> {
+    let bad_request = |e: String| (StatusCode::BAD_REQUEST, e);
+    let window = |seconds: Option<i64>| match seconds {
+        Some(s) if s < 60 => Err(bad_request("window_seconds must be at least 60".to_string())),
+        _ => Ok(()),
+    };
+
+    match rule {
+        ScenarioRule::Structuring { token_address, limit, margin_percent, window_seconds, min_count } => {
+            if let Some(limit) = limit {
+                let decimals = rule_decimals(state, token_address.as_deref()).await?;
+                parse_amount(limit, decimals).map_err(bad_request)?;
+            }
+            if margin_percent.is_some_and(|m| !(1..=50).contains(&m)) {
+                return Err(bad_request("margin_percent must be between 1 and 50".to_string()));
+            }
+            if min_count.is_some_and(|c| c < 2) {
+                return Err(bad_request("min_count must be at least 2".to_string()));
+            }
+            window(*window_seconds)
+        }
+        ScenarioRule::RapidInOut { token_address, min_amount, window_seconds, min_ratio_percent } => {
+            let decimals = rule_decimals(state, token_address.as_deref()).await?;
+            if let Some(min_amount) = min_amount {
+                parse_amount(min_amount, decimals).map_err(bad_request)?;
+            }
+            if min_ratio_percent.is_some_and(|r| !(1..=100).contains(&r)) {
+                return Err(bad_request("min_ratio_percent must be between 1 and 100".to_string()));
+            }
+            window(*window_seconds)
+        }
+        ScenarioRule::FanOut { window_seconds, min_counterparties, lookback_days } => {
+            if min_counterparties.is_some_and(|c| c < 2) {
+                return Err(bad_request("min_counterparties must be at least 2".to_string()));
+            }
+            if lookback_days.is_some_and(|d| d < 1) {
+                return Err(bad_request("lookback_days must be at least 1".to_string()));
+            }
+            window(*window_seconds)
+        }
+        ScenarioRule::DormantReactivation { dormant_days } => {
+            if dormant_days.is_some_and(|d| d < 1) {
+                return Err(bad_request("dormant_days must be at least 1".to_string()));
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn rule_decimals(state: &AppState, token_address: Option<&str>) -> Result<u8, (StatusCode, String)> {
+    match token_address {
+        Some(token_addr) => Ok(find_allowed_token(&state.pool, token_addr, state.chain.chain_id()).await?.decimals as u8),
+        None => Ok(NATIVE_DECIMALS),
+    }
+}
+
+/// Symbol and decimals of registered tokens by chain and lowercase address.
+pub(super) type TokenIndex = HashMap<(i64, String), (String, u8)>;
+
+pub(super) async fn token_index(pool: &PgPool) -> Result<TokenIndex, (StatusCode, String)> {
+    let rows: Vec<(i64, String, String, i16)> = sqlx::query_as("SELECT chain_id, LOWER(address), symbol, decimals FROM tokens")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(rows.into_iter().map(|(chain_id, address, symbol, decimals)| ((chain_id, address), (symbol, decimals as u8))).collect())
+}
+
+/// Symbol and decimals of an asset on a chain; None for tokens that are not registered.
+pub(super) fn asset(tokens: &TokenIndex, chain_id: i64, token_address: Option<&str>) -> Option<(String, u8)> {
+    match token_address {
+        Some(address) => tokens.get(&(chain_id, address.to_lowercase())).cloned(),
+        None => Some((NATIVE_SYMBOL.to_string(), NATIVE_DECIMALS)),
+    }
+}
+
+async fn evaluate_scenario(
+    pool: &PgPool,
+    scenario: &MonitoringScenario,
+    tokens: &TokenIndex,
+    from: DateTime<Utc>,
+) -> Result<Vec<MonitoringAlert>, (StatusCode, String)> {
+    let rule: ScenarioRule = serde_json::from_value(scenario.rule.clone())
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Invalid rule: {}", e)))?;
+    let history = history(&rule);
+
+    let wallets = sqlx::query_as::<_, Wallet>("SELECT * FROM wallets WHERE ($1::INTEGER IS NULL OR id = $1) ORDER BY id")
+        .bind(scenario.wallet_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let alerted: HashSet<(&'static str, i32)> = sqlx::query_as::<_, (String, i32)>(
+        "SELECT flow_type, flow_id FROM monitoring_alert_flows WHERE scenario_id = $1"
+    )
+    .bind(scenario.id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .into_iter()
+    .map(|(flow_type, id)| (if flow_type == FLOW_INTENT { FLOW_INTENT } else { FLOW_MOVEMENT }, id))
+    .collect();
+    let eval = Evaluation { from, alerted: &alerted };
+
+    let mut raised = Vec::new();
+    for wallet in &wallets {
+        let since = from - history;
+        let flows = load_flows(pool, wallet, since, tokens).await?;
+        if flows.is_empty() {
+            continue;
+        }
+
+        let findings = match &rule {
+            ScenarioRule::Structuring { token_address, limit, margin_percent, window_seconds, min_count } => {
+                let Some((_, decimals)) = asset(tokens, wallet.chain_id, token_address.as_deref()) else { continue };
+                let limit = match limit {
+                    Some(limit) => Some(parse_amount(limit, decimals).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?),
+                    None => lowest_threshold(pool, wallet.id, token_address.as_deref(), decimals).await?,
+                };
+                // Without a limit or approval threshold there is nothing to stay below
+                let Some(limit) = limit else { continue };
+                scenarios::structuring(
+                    &flows,
+                    &eval,
+                    token_address.as_deref(),
+                    limit,
+                    margin_percent.unwrap_or(scenarios::DEFAULT_STRUCTURING_MARGIN_PERCENT),
+                    Duration::seconds(window_seconds.unwrap_or(scenarios::DEFAULT_STRUCTURING_WINDOW_SECS)),
+                    min_count.unwrap_or(scenarios::DEFAULT_STRUCTURING_MIN_COUNT) as usize,
+                )
+            }
+            ScenarioRule::RapidInOut { token_address, min_amount, window_seconds, min_ratio_percent } => {
+                let Some((_, decimals)) = asset(tokens, wallet.chain_id, token_address.as_deref()) else { continue };
+                let min_amount = match min_amount {
+                    Some(amount) => parse_amount(amount, decimals).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?,
+                    None => U256::zero(),
+                };
+                scenarios::rapid_in_out(
+                    &flows,
+                    &eval,
+                    token_address.as_deref(),
+                    min_amount,
+                    Duration::seconds(window_seconds.unwrap_or(scenarios::DEFAULT_RAPID_WINDOW_SECS)),
+                    min_ratio_percent.unwrap_or(scenarios::DEFAULT_RAPID_MIN_RATIO_PERCENT),
+                )
+            }
+            ScenarioRule::FanOut { window_seconds, min_counterparties, .. } => scenarios::fan_out(
+                &flows,
+                &eval,
+                Duration::seconds(window_seconds.unwrap_or(scenarios::DEFAULT_FAN_OUT_WINDOW_SECS)),
+                min_counterparties.unwrap_or(scenarios::DEFAULT_FAN_OUT_MIN_COUNTERPARTIES) as usize,
+                fan_out_lookback(&rule),
+            ),
+            ScenarioRule::DormantReactivation { .. } => {
+                let active_since = last_active_before(pool, wallet, since).await?;
+                scenarios::dormant_reactivation(&flows, &eval, active_since, history)
+            }
+        };
+
+        for finding in &findings {
+            let evidence = evidence(scenario, finding);
+            if let Some(alert) = alerts::raise(pool, scenario, wallet.id, wallet.entity_id, finding, evidence).await? {
+                raised.push(alert);
+            }
+        }
+    }
+    Ok(raised)
+}
+
+/// How far before the evaluation period flows are needed to complete a pattern.
+fn history(rule: &ScenarioRule) -> Duration {
+    match rule {
+        ScenarioRule::Structuring { window_seconds, .. } => {
+            Duration::seconds(window_seconds.unwrap_or(scenarios::DEFAULT_STRUCTURING_WINDOW_SECS))
+        }
+        ScenarioRule::RapidInOut { window_seconds, .. } => Duration::seconds(window_seconds.unwrap_or(scenarios::DEFAULT_RAPID_WINDOW_SECS)),
+        ScenarioRule::FanOut { window_seconds, .. } => {
+            Duration::seconds(window_seconds.unwrap_or(scenarios::DEFAULT_FAN_OUT_WINDOW_SECS)) + fan_out_lookback(rule)
+        }
+        ScenarioRule::DormantReactivation { dormant_days } => Duration::days(dormant_days.unwrap_or(scenarios::DEFAULT_DORMANT_DAYS)),
+    }
+}
+
+fn fan_out_lookback(rule: &ScenarioRule) -> Duration {
+    match rule {
+        ScenarioRule::FanOut { lookback_days, .. } => Duration::days(lookback_days.unwrap_or(scenarios::DEFAULT_FAN_OUT_LOOKBACK_DAYS)),
+        _ => Duration::zero(),
+    }
+}
+
+/// The lowest approval threshold configured for the wallet (or all wallets) in this asset.
+async fn lowest_threshold(pool: &PgPool, wallet_id: i32, token_address: Option<&str>, decimals: u8) -> Result<Option<U256>, (StatusCode, String)> {
+    let amounts: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT threshold_amount FROM approval_thresholds
+        WHERE (wallet_id IS NULL OR wallet_id = $1) AND token_address IS NOT DISTINCT FROM $2
+        "#
+    )
+    .bind(wallet_id)
+    .bind(token_address.map(str::to_lowercase))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(amounts.iter().filter_map(|a| parse_amount(a, decimals).ok()).min())
+}
+
+/// When the wallet last moved funds before `since`, or its creation if it never did.
+async fn last_active_before(pool: &PgPool, wallet: &Wallet, since: DateTime<Utc>) -> Result<DateTime<Utc>, (StatusCode, String)> {
+    let last: Option<DateTime<Utc>> = sqlx::query_scalar(
+        r#"
+        SELECT GREATEST(
+            (SELECT MAX(updated_at) FROM payment_intents WHERE wallet_id = $1 AND status = 'confirmed' AND updated_at < $2),
+            (SELECT MAX(block_time) FROM wallet_movements WHERE wallet_id = $1 AND block_time < $2)
+        )
+        "#
+    )
+    .bind(wallet.id)
+    .bind(since)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(last.unwrap_or(wallet.created_at))
+}
+
+#[derive(FromRow)]
+struct FlowRow {
+    flow_type: String,
+    id: i32,
+    at: DateTime<Utc>,
+    direction: String,
+    token_address: Option<String>,
+    amount: String,
+    counterparty: String,
+    tx_hash: Option<String>,
+}
+
+/// A wallet's flows since `since`, oldest first: confirmed agent payments (amounts in decimal units) and
+/// indexed movements (base units), leaving out the debits of transactions a confirmed payment accounts for.
+async fn load_flows(pool: &PgPool, wallet: &Wallet, since: DateTime<Utc>, tokens: &TokenIndex) -> Result<Vec<Flow>, (StatusCode, String)> {
+    let rows = sqlx::query_as::<_, FlowRow>(
+        r#"
+        SELECT 'intent' AS flow_type, i.id, i.updated_at AS at, 'debit' AS direction, LOWER(i.token_address) AS token_address,
+               i.amount, LOWER(i.target_address) AS counterparty, i.tx_hash
+        FROM payment_intents i
+        WHERE i.wallet_id = $1 AND i.status = 'confirmed' AND i.updated_at >= $2
+        UNION ALL
+        SELECT 'movement', m.id, m.block_time, m.direction, LOWER(m.token_address), m.amount, LOWER(m.counterparty), m.tx_hash
+        FROM wallet_movements m
+        WHERE m.wallet_id = $1 AND m.block_time >= $2
+          AND NOT (m.direction = 'debit' AND EXISTS (
+              SELECT 1 FROM payment_intents i
+              WHERE i.wallet_id = m.wallet_id AND i.status = 'confirmed' AND LOWER(i.tx_hash) = LOWER(m.tx_hash)
+          ))
+        ORDER BY at, flow_type, id
+        "#
+    )
+    .bind(wallet.id)
+    .bind(since)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut flows = Vec::with_capacity(rows.len());
+    for row in rows {
+        let intent = row.flow_type == FLOW_INTENT;
+        let (symbol, decimals) = match asset(tokens, wallet.chain_id, row.token_address.as_deref()) {
+            Some(asset) => asset,
+            // Movements of unregistered tokens are kept in base units
+            None if !intent => (row.token_address.clone().unwrap_or_default(), 0),
+            None => continue,
+        };
+        let amount = match intent {
+            true => parse_amount(&row.amount, decimals),
+            false => U256::from_dec_str(&row.amount).map_err(|e| e.to_string()),
+        };
+        let Ok(amount) = amount else {
+            println!("Transaction monitoring skipped {} {}: invalid amount {}", row.flow_type, row.id, row.amount);
+            continue;
+        };
+
+        flows.push(Flow {
+            flow_type: if intent { FLOW_INTENT } else { FLOW_MOVEMENT },
+            id: row.id,
+            wallet_id: wallet.id,
+            at: row.at,
+            direction: if row.direction == CREDIT { CREDIT } else { DEBIT },
+            token_address: row.token_address,
+            symbol,
+            amount,
+            decimals,
+            counterparty: row.counterparty,
+            tx_hash: row.tx_hash,
+        });
+    }
+    Ok(flows)
+}
+
+fn evidence(scenario: &MonitoringScenario, finding: &Finding) -> serde_json::Value {
+    serde_json::json!({
+        "scenario": {
+            "id": scenario.id,
+            "name": scenario.name,
+            "rule": scenario.rule,
+        },
+        "metrics": finding.metrics,
+        "flows": finding.flows.iter().map(|f| serde_json::json!({
+            "type": f.flow_type,
+            "id": f.id,
+            "at": f.at,
+            "direction": f.direction,
+            "asset": f.symbol,
+            "token_address": f.token_address,
+            "amount": format_amount(f.amount, f.decimals),
+            "counterparty": f.counterparty,
+            "tx_hash": f.tx_hash,
+        })).collect::<Vec<_>>(),
+    })
+}
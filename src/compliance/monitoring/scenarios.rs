@@ -0,0 +1,273 @@
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::U256;
+use std::collections::{HashMap, HashSet};
+use crate::finance::amounts::format_amount;
+
+pub const STRUCTURING: &str = "structuring";
+pub const RAPID_IN_OUT: &str = "rapid_in_out";
+pub const FAN_OUT: &str = "fan_out";
+pub const DORMANT_REACTIVATION: &str = "dormant_reactivation";
+
+pub const DEFAULT_STRUCTURING_MARGIN_PERCENT: u32 = 10;
+pub const DEFAULT_STRUCTURING_WINDOW_SECS: i64 = 86400;
+pub const DEFAULT_STRUCTURING_MIN_COUNT: i64 = 3;
+pub const DEFAULT_RAPID_WINDOW_SECS: i64 = 86400;
+pub const DEFAULT_RAPID_MIN_RATIO_PERCENT: u32 = 80;
+pub const DEFAULT_FAN_OUT_WINDOW_SECS: i64 = 86400;
+pub const DEFAULT_FAN_OUT_MIN_COUNTERPARTIES: i64 = 5;
+pub const DEFAULT_FAN_OUT_LOOKBACK_DAYS: i64 = 90;
+pub const DEFAULT_DORMANT_DAYS: i64 = 180;
+
+pub const FLOW_INTENT: &str = "intent";
+pub const FLOW_MOVEMENT: &str = "movement";
+
+pub const CREDIT: &str = "credit";
+pub const DEBIT: &str = "debit";
+
+/// One movement of funds in or out of a wallet: a confirmed agent payment, or an indexed wallet event
+/// that no confirmed payment accounts for.
+#[derive(Debug, Clone)]
+pub struct Flow {
+    pub flow_type: &'static str,
+    pub id: i32,
+    pub wallet_id: i32,
+    pub at: DateTime<Utc>,
+    pub direction: &'static str,
+    /// Lowercase token address; None for native ETH
+    pub token_address: Option<String>,
+    pub symbol: String,
+    pub amount: U256,
+    pub decimals: u8,
+    /// Lowercase address of the other side
+    pub counterparty: String,
+    pub tx_hash: Option<String>,
+}
+
+impl Flow {
+    pub fn key(&self) -> (&'static str, i32) {
+        (self.flow_type, self.id)
+    }
+
+    fn is_asset(&self, token_address: Option<&str>) -> bool {
+        self.token_address.as_deref() == token_address.map(str::to_lowercase).as_deref()
+    }
+
+    fn display_amount(&self, amount: U256) -> String {
+        format!("{} {}", format_amount(amount, self.decimals), self.symbol)
+    }
+}
+
+/// What a detector is run against besides the flows.
+pub struct Evaluation<'a> {
+    /// Patterns completed before this are not reported (earlier flows only serve as history)
+    pub from: DateTime<Utc>,
+    /// Flows that already triggered this scenario
+    pub alerted: &'a HashSet<(&'static str, i32)>,
+}
+
+impl Evaluation<'_> {
+    fn fresh(&self, flow: &Flow) -> bool {
+        !self.alerted.contains(&flow.key())
+    }
+}
+
+/// A detected pattern, before it is raised as an alert.
+#[derive(Debug)]
+pub struct Finding {
+    pub flows: Vec<Flow>,
+    pub summary: String,
+    pub metrics: serde_json::Value,
+}
+
+/// Outgoing payments in one asset just below `limit`: at least `min_count` within `window`, each no more than
+/// `margin_percent` under the limit. `flows` are one wallet's, oldest first.
+pub fn structuring(
+    flows: &[Flow],
+    eval: &Evaluation,
+    token_address: Option<&str>,
+    limit: U256,
+    margin_percent: u32,
+    window: Duration,
+    min_count: usize,
+) -> Vec<Finding> {
+    let floor = limit - limit * U256::from(margin_percent) / U256::from(100);
+    let near: Vec<&Flow> = flows.iter()
+        .filter(|f| f.direction == DEBIT && f.is_asset(token_address) && f.amount >= floor && f.amount < limit && eval.fresh(f))
+        .collect();
+
+    let mut findings = Vec::new();
+    let mut start = 0;
+    while start < near.len() {
+        let end = start + near[start..].iter().take_while(|f| f.at - near[start].at <= window).count();
+        let group = &near[start..end];
+        if group.len() < min_count || group[group.len() - 1].at < eval.from {
+            start += 1;
+            continue;
+        }
+
+        let first = group[0];
+        let total = group.iter().fold(U256::zero(), |sum, f| sum + f.amount);
+        let smallest = group.iter().map(|f| f.amount).min().unwrap_or_default();
+        let largest = group.iter().map(|f| f.amount).max().unwrap_or_default();
+        findings.push(Finding {
+            summary: format!(
+                "{} payments of {} to {} within {}, just below the limit of {}",
+                group.len(), format_amount(smallest, first.decimals), first.display_amount(largest), span(group), first.display_amount(limit),
+            ),
+            metrics: serde_json::json!({
+                "count": group.len(),
+                "limit": format_amount(limit, first.decimals),
+                "floor": format_amount(floor, first.decimals),
+                "total": format_amount(total, first.decimals),
+                "asset": first.symbol,
+                "window_seconds": window.num_seconds(),
+            }),
+            flows: group.iter().map(|f| (*f).clone()).collect(),
+        });
+        start = end;
+    }
+    findings
+}
+
+/// A credit of at least `min_amount` followed within `window` by debits in the same asset adding up to
+/// `min_ratio_percent` of it. Each flow counts towards one pattern.
+pub fn rapid_in_out(
+    flows: &[Flow],
+    eval: &Evaluation,
+    token_address: Option<&str>,
+    min_amount: U256,
+    window: Duration,
+    min_ratio_percent: u32,
+) -> Vec<Finding> {
+    let relevant: Vec<&Flow> = flows.iter().filter(|f| f.is_asset(token_address) && eval.fresh(f)).collect();
+    let mut used: HashSet<usize> = HashSet::new();
+    let mut findings = Vec::new();
+
+    for (i, credit) in relevant.iter().enumerate() {
+        if credit.direction != CREDIT || credit.amount < min_amount || credit.amount.is_zero() || used.contains(&i) {
+            continue;
+        }
+        let target = credit.amount * U256::from(min_ratio_percent) / U256::from(100);
+
+        let mut paid_out = U256::zero();
+        let mut debits = Vec::new();
+        for (j, debit) in relevant.iter().enumerate().skip(i + 1) {
+            if debit.at - credit.at > window || paid_out >= target {
+                break;
+            }
+            if debit.direction == DEBIT && !used.contains(&j) {
+                paid_out += debit.amount;
+                debits.push(j);
+            }
+        }
+        let Some(&last) = debits.last() else { continue };
+        if paid_out < target || relevant[last].at < eval.from {
+            continue;
+        }
+
+        used.insert(i);
+        used.extend(&debits);
+        let group: Vec<&Flow> = std::iter::once(i).chain(debits).map(|k| relevant[k]).collect();
+        findings.push(Finding {
+            summary: format!(
+                "{} received and {} paid out to {} within {}",
+                credit.display_amount(credit.amount), credit.display_amount(paid_out), counterparties(&group[1..]), span(&group),
+            ),
+            metrics: serde_json::json!({
+                "credited": format_amount(credit.amount, credit.decimals),
+                "paid_out": format_amount(paid_out, credit.decimals),
+                "ratio_percent": (paid_out * U256::from(100) / credit.amount).as_u64(),
+                "asset": credit.symbol,
+                "elapsed_seconds": (group[group.len() - 1].at - credit.at).num_seconds(),
+            }),
+            flows: group.into_iter().cloned().collect(),
+        });
+    }
+    findings
+}
+
+/// Payments to at least `min_counterparties` new counterparties within `window`, in any asset. A counterparty
+/// is new if the wallet did not pay it in the `lookback` before; `flows` must reach back that far.
+pub fn fan_out(
+    flows: &[Flow],
+    eval: &Evaluation,
+    window: Duration,
+    min_counterparties: usize,
+    lookback: Duration,
+) -> Vec<Finding> {
+    let mut last_paid: HashMap<&str, DateTime<Utc>> = HashMap::new();
+    let mut firsts: Vec<&Flow> = Vec::new();
+    for flow in flows.iter().filter(|f| f.direction == DEBIT) {
+        let new = last_paid.get(flow.counterparty.as_str()).is_none_or(|at| flow.at - *at > lookback);
+        if new && flow.at >= eval.from - window && eval.fresh(flow) {
+            firsts.push(flow);
+        }
+        last_paid.insert(&flow.counterparty, flow.at);
+    }
+
+    let mut findings = Vec::new();
+    let mut start = 0;
+    while start < firsts.len() {
+        let end = start + firsts[start..].iter().take_while(|f| f.at - firsts[start].at <= window).count();
+        let group = &firsts[start..end];
+        if group.len() < min_counterparties || group[group.len() - 1].at < eval.from {
+            start += 1;
+            continue;
+        }
+
+        findings.push(Finding {
+            summary: format!("Paid {} within {}", counterparties(group), span(group)),
+            metrics: serde_json::json!({
+                "new_counterparties": group.len(),
+                "window_seconds": window.num_seconds(),
+                "lookback_days": lookback.num_days(),
+            }),
+            flows: group.iter().map(|f| (*f).clone()).collect(),
+        });
+        start = end;
+    }
+    findings
+}
+
+/// The first movement after `dormant` or more without one. `active_since` is when the wallet was last active
+/// before the first of `flows` (its last earlier movement, or its creation).
+pub fn dormant_reactivation(flows: &[Flow], eval: &Evaluation, active_since: DateTime<Utc>, dormant: Duration) -> Vec<Finding> {
+    let mut previous = active_since;
+    let mut findings = Vec::new();
+    for flow in flows {
+        if flow.at >= eval.from && flow.at - previous >= dormant && eval.fresh(flow) {
+            findings.push(Finding {
+                summary: format!(
+                    "{} {} after {} days without movements",
+                    if flow.direction == CREDIT { "Received" } else { "Paid" }, flow.display_amount(flow.amount), (flow.at - previous).num_days(),
+                ),
+                metrics: serde_json::json!({
+                    "inactive_since": previous,
+                    "inactive_days": (flow.at - previous).num_days(),
+                    "dormant_days": dormant.num_days(),
+                }),
+                flows: vec![flow.clone()],
+            });
+        }
+        previous = previous.max(flow.at);
+    }
+    findings
+}
+
+fn counterparties(flows: &[&Flow]) -> String {
+    let distinct: HashSet<&str> = flows.iter().map(|f| f.counterparty.as_str()).collect();
+    match distinct.len() {
+        1 => "1 counterparty".to_string(),
+        n => format!("{} counterparties", n),
+    }
+}
+
+/// Time between the first and last flow, for summaries.
+fn span(flows: &[&Flow]) -> String {
+    let elapsed = flows[flows.len() - 1].at - flows[0].at;
+    match elapsed.num_minutes() {
+        m if m < 120 => format!("{} minutes", m),
+        m if m < 48 * 60 => format!("{} hours", m / 60),
+        m => format!("{} days", m / (24 * 60)),
+    }
+}
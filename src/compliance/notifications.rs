@@ -0,0 +1,26 @@
+use axum::http::StatusCode;
+use sqlx::{Executor, Postgres};
+
+/// Raises an item for the compliance team (listed by `GET /api/compliance/notifications` until acknowledged).
+/// Pass a transaction to raise it atomically with the change it reports.
+pub async fn notify<'e, E>(
+    executor: E,
+    kind: &str,
+    subject: &str,
+    message: &str,
+    details: serde_json::Value,
+) -> Result<(), (StatusCode, String)>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("INSERT INTO compliance_notifications (kind, subject, message, details) VALUES ($1, $2, $3, $4)")
+        .bind(kind)
+        .bind(subject)
+        .bind(message)
+        .bind(details)
+        .execute(executor)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(())
+}
@@ -0,0 +1,444 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{Executor, PgPool, Postgres};
+use crate::agent::intents::SYSTEM_ACTOR;
+use crate::governance::freezes::{self, SCOPE_ENTITY};
+use crate::governance::models::CreateFreezeRequest;
+use crate::state::AppState;
+use super::kyc;
+use super::metadata::KYC_VALIDITY_DAYS;
+use super::models::{CreateReviewPeriodRequest, EntityReview, KycReviewPeriod, LegalEntity};
+use super::{audit, notifications};
+
+/// How often verified entities are checked against their review deadlines.
+const POLL_INTERVAL_SECS: u64 = 3600;
+
+/// Warning window of entities no configured period applies to (they use `KYC_VALIDITY_DAYS`).
+const DEFAULT_WARNING_DAYS: i64 = 30;
+
+pub const LAPSE_DOWNGRADE: &str = "downgrade";
+pub const LAPSE_SUSPEND: &str = "suspend";
+
+/// Flags entities entering their warning window and lapses those past their deadline.
+pub async fn run(state: AppState) {
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(POLL_INTERVAL_SECS));
+    loop {
+        ticker.tick().await;
+        if let Err((_, e)) = sweep(&state).await {
+            println!("KYC review error: {}", e);
+        }
+    }
+}
+
+pub async fn periods(pool: &PgPool) -> Result<Vec<KycReviewPeriod>, (StatusCode, String)> {
+    sqlx::query_as::<_, KycReviewPeriod>("SELECT * FROM kyc_review_periods ORDER BY id")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// The most specific period for an entity: jurisdiction and level, then jurisdiction, then level, then the catch-all.
+/// Jurisdictions match case-insensitively on the full string.
+pub fn applicable<'a>(periods: &'a [KycReviewPeriod], jurisdiction: &str, kyc_level: i16) -> Option<&'a KycReviewPeriod> {
+    periods.iter()
+        .filter(|p| p.jurisdiction.as_deref().is_none_or(|j| j.eq_ignore_ascii_case(jurisdiction)))
+        .filter(|p| p.kyc_level.is_none_or(|l| l == kyc_level))
+        .max_by_key(|p| (p.jurisdiction.is_some(), p.kyc_level.is_some()))
+}
+
+/// Deadlines of a verified entity; None for unverified ones.
+pub fn review(entity: &LegalEntity, periods: &[KycReviewPeriod]) -> Option<EntityReview> {
+    let verified_at = entity.kyc_verified_at.filter(|_| entity.kyc_level > 0)?;
+    let period = applicable(periods, &entity.jurisdiction, entity.kyc_level);
+    let (review_days, warning_days) = period.map_or((KYC_VALIDITY_DAYS, DEFAULT_WARNING_DAYS), |p| (p.review_days as i64, p.warning_days as i64));
+    let due_at = verified_at + Duration::days(review_days);
+
+    Some(EntityReview {
+        entity_id: entity.id,
+        jurisdiction: entity.jurisdiction.clone(),
+        kyc_level: entity.kyc_level,
+        kyc_status: entity.kyc_status.clone(),
+        verified_at,
+        warn_at: due_at - Duration::days(warning_days),
+        due_at,
+        review_period_id: period.map(|p| p.id),
+        on_lapse: period.map_or(LAPSE_SUSPEND, |p| p.on_lapse.as_str()).to_string(),
+    })
+}
+
+/// When a verification of `kyc_level` in `jurisdiction` made at `verified_at` must be renewed.
+pub async fn deadline(pool: &PgPool, jurisdiction: &str, kyc_level: i16, verified_at: DateTime<Utc>) -> Result<DateTime<Utc>, (StatusCode, String)> {
+    let periods = periods(pool).await?;
+    let review_days = applicable(&periods, jurisdiction, kyc_level).map_or(KYC_VALIDITY_DAYS, |p| p.review_days as i64);
+    Ok(verified_at + Duration::days(review_days))
+}
+
+pub async fn create_period(pool: &PgPool, req: &CreateReviewPeriodRequest, actor: &str) -> Result<KycReviewPeriod, (StatusCode, String)> {
+    let jurisdiction = req.jurisdiction.as_deref().map(str::trim);
+    if jurisdiction == Some("") {
+        return Err((StatusCode::BAD_REQUEST, "jurisdiction must not be empty (omit it to match any)".to_string()));
+    }
+    if req.kyc_level.is_some_and(|l| l <= 0) {
+        return Err((StatusCode::BAD_REQUEST, "Invalid KYC Level".to_string()));
+    }
+    if req.review_days <= 0 || req.warning_days < 0 || req.warning_days >= req.review_days {
+        return Err((StatusCode::BAD_REQUEST, "review_days must be positive and warning_days between 0 and review_days".to_string()));
+    }
+    match (req.on_lapse.as_str(), req.downgrade_to) {
+        (LAPSE_SUSPEND, None) => {}
+        (LAPSE_DOWNGRADE, Some(level)) if level >= 0 && req.kyc_level.is_none_or(|l| level < l) => {}
+        (LAPSE_DOWNGRADE, _) => return Err((StatusCode::BAD_REQUEST, "downgrade_to must be a lower level than kyc_level".to_string())),
+        (LAPSE_SUSPEND, Some(_)) => return Err((StatusCode::BAD_REQUEST, "downgrade_to only applies to on_lapse 'downgrade'".to_string())),
+        _ => return Err((StatusCode::BAD_REQUEST, "on_lapse must be 'downgrade' or 'suspend'".to_string())),
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let period = sqlx::query_as::<_, KycReviewPeriod>(
+        r#"
+        INSERT INTO kyc_review_periods (jurisdiction, kyc_level, review_days, warning_days, on_lapse, downgrade_to, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#
+    )
+    .bind(jurisdiction)
+    .bind(req.kyc_level)
+    .bind(req.review_days)
+    .bind(req.warning_days)
+    .bind(&req.on_lapse)
+    .bind(req.downgrade_to)
+    .bind(actor)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            (StatusCode::CONFLICT, "A review period for this jurisdiction and level already exists".to_string())
+        }
+        e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })?;
+
+    audit::record(&mut *tx, actor, "kyc.review_period.create", &format!("review_period:{}", period.id), serde_json::json!({
+        "jurisdiction": period.jurisdiction,
+        "kyc_level": period.kyc_level,
+        "review_days": period.review_days,
+        "warning_days": period.warning_days,
+        "on_lapse": period.on_lapse,
+        "downgrade_to": period.downgrade_to,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(period)
+}
+
+pub async fn remove_period(pool: &PgPool, period_id: i32, actor: &str) -> Result<(), (StatusCode, String)> {
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let period = sqlx::query_as::<_, KycReviewPeriod>("DELETE FROM kyc_review_periods WHERE id = $1 RETURNING *")
+        .bind(period_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Review period not found".to_string()))?;
+
+    audit::record(&mut *tx, actor, "kyc.review_period.remove", &format!("review_period:{}", period.id), serde_json::json!({
+        "jurisdiction": period.jurisdiction,
+        "kyc_level": period.kyc_level,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Verified entities still in the review cycle (downgraded and suspended ones wait for a new KYC case).
+pub async fn under_review(pool: &PgPool) -> Result<Vec<LegalEntity>, (StatusCode, String)> {
+    sqlx::query_as::<_, LegalEntity>(
+        "SELECT * FROM legal_entities WHERE kyc_status IN ('verified', 'expiring') AND kyc_level > 0 ORDER BY id"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn sweep(state: &AppState) -> Result<(), (StatusCode, String)> {
+    let periods = periods(&state.pool).await?;
+    let now = Utc::now();
+
+    for entity in under_review(&state.pool).await? {
+        let Some(review) = review(&entity, &periods) else { continue };
+
+        // One entity failing must not hold up the others
+        let result = if review.due_at <= now {
+            let period = review.review_period_id.and_then(|id| periods.iter().find(|p| p.id == id));
+            lapse(state, &entity, &review, period).await
+        } else if review.warn_at <= now && entity.kyc_status == "verified" {
+            flag(&state.pool, &entity, &review).await
+        } else if review.warn_at > now && entity.kyc_status == "expiring" {
+            // A longer review period moved the deadline out of the warning window again
+            unflag(&state.pool, entity.id).await
+        } else {
+            Ok(())
+        };
+        if let Err((_, e)) = result {
+            println!("KYC review error for entity {}: {}", entity.id, e);
+        }
+    }
+
+    retry_revocations(state).await
+}
+
+/// Marks an entity whose deadline is near as `expiring` and tells compliance.
+async fn flag(pool: &PgPool, entity: &LegalEntity, review: &EntityReview) -> Result<(), (StatusCode, String)> {
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let flagged = sqlx::query("UPDATE legal_entities SET kyc_status = 'expiring' WHERE id = $1 AND kyc_status = 'verified'")
+        .bind(entity.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if flagged.rows_affected() == 0 {
+        return Ok(());
+    }
+
+    record_action(&mut *tx, entity.id, "flagged", review.due_at, ReviewAction::default()).await?;
+
+    notifications::notify(
+        &mut *tx,
+        "kyc.expiring",
+        &format!("entity:{}", entity.id),
+        &format!(
+            "KYC review of entity {} ({}, level {}) is due on {}; it will be {} unless a new KYC case is approved",
+            entity.id, entity.jurisdiction, entity.kyc_level, review.due_at.format("%Y-%m-%d"),
+            if review.on_lapse == LAPSE_DOWNGRADE { "downgraded" } else { "suspended" },
+        ),
+        serde_json::json!({ "entity_id": entity.id, "due_at": review.due_at, "on_lapse": review.on_lapse }),
+    ).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn unflag(pool: &PgPool, entity_id: i32) -> Result<(), (StatusCode, String)> {
+    sqlx::query("UPDATE legal_entities SET kyc_status = 'verified' WHERE id = $1 AND kyc_status = 'expiring'")
+        .bind(entity_id)
+        .execute(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(())
+}
+
+/// Downgrades or suspends an overdue entity and expires its KYC case, then revokes its AegisID and,
+/// when suspending, freezes its wallets and agents. On-chain failures are recorded and reported; failed
+/// revocations are retried by later sweeps.
+async fn lapse(state: &AppState, entity: &LegalEntity, review: &EntityReview, period: Option<&KycReviewPeriod>) -> Result<(), (StatusCode, String)> {
+    let (status, level_after) = match period {
+        // A catch-all downgrade level can be at or above the entity's own; it still has to go down
+        Some(p) if p.on_lapse == LAPSE_DOWNGRADE => ("downgraded", p.downgrade_to.unwrap_or(0).min(entity.kyc_level - 1)),
+        _ => ("suspended", 0),
+    };
+    let reason = lapse_reason(review.due_at);
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let lapsed = sqlx::query("UPDATE legal_entities SET kyc_level = $1, kyc_status = $2 WHERE id = $3 AND kyc_status IN ('verified', 'expiring')")
+        .bind(level_after)
+        .bind(status)
+        .bind(entity.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if lapsed.rows_affected() == 0 {
+        return Ok(());
+    }
+
+    if let Some(case_id) = entity.kyc_case_id {
+        kyc::lapse_case(&mut tx, case_id, SYSTEM_ACTOR, &reason).await?;
+    }
+
+    record_action(&mut *tx, entity.id, status, review.due_at, ReviewAction {
+        kyc_level_before: Some(entity.kyc_level),
+        kyc_level_after: Some(level_after),
+        ..ReviewAction::default()
+    }).await?;
+
+    audit::record(&mut *tx, SYSTEM_ACTOR, "kyc.lapse", &format!("entity:{}", entity.id), serde_json::json!({
+        "status": status,
+        "kyc_level_before": entity.kyc_level,
+        "kyc_level_after": level_after,
+        "due_at": review.due_at,
+        "kyc_case_id": entity.kyc_case_id,
+    })).await?;
+
+    notifications::notify(
+        &mut *tx,
+        "kyc.lapsed",
+        &format!("entity:{}", entity.id),
+        &format!("Entity {} missed its KYC review due on {} and was {} (level {} to {})", entity.id, review.due_at.format("%Y-%m-%d"), status, entity.kyc_level, level_after),
+        serde_json::json!({ "entity_id": entity.id, "status": status, "kyc_level_before": entity.kyc_level, "kyc_level_after": level_after }),
+    ).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut failures = Vec::new();
+
+    // Every identity minted for the entity still carries the lapsed level in its metadata
+    for token_id in identities(&state.pool, entity.id).await? {
+        if let Err(e) = revoke(state, entity.id, &token_id, review.due_at).await? {
+            failures.push(format!("revoking AegisID {}: {}", token_id, e));
+            record_action(&state.pool, entity.id, "identity_revoked", review.due_at, ReviewAction {
+                token_id: Some(token_id),
+                error: Some(e),
+                ..ReviewAction::default()
+            }).await?;
+        }
+    }
+
+    if status == "suspended" {
+        let request = CreateFreezeRequest { scope: SCOPE_ENTITY.to_string(), target_id: Some(entity.id), reason: reason.clone() };
+        match freezes::freeze(state, &request, SYSTEM_ACTOR).await {
+            Ok(detail) => {
+                audit::record(&state.pool, SYSTEM_ACTOR, "freeze.create", &format!("{}:{}", SCOPE_ENTITY, entity.id), serde_json::json!({
+                    "freeze_id": detail.freeze.id,
+                    "reason": detail.freeze.reason,
+                })).await?;
+                record_action(&state.pool, entity.id, "frozen", review.due_at, ReviewAction {
+                    freeze_id: Some(detail.freeze.id),
+                    ..ReviewAction::default()
+                }).await?;
+            }
+            Err((_, e)) => {
+                failures.push(format!("freezing: {}", e));
+                record_action(&state.pool, entity.id, "frozen", review.due_at, ReviewAction {
+                    error: Some(e),
+                    ..ReviewAction::default()
+                }).await?;
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        notifications::notify(
+            &state.pool,
+            "kyc.lapse_failed",
+            &format!("entity:{}", entity.id),
+            &format!("Entity {} was {} but needs manual follow-up: {}", entity.id, status, failures.join("; ")),
+            serde_json::json!({ "entity_id": entity.id, "failures": failures }),
+        ).await?;
+    }
+
+    Ok(())
+}
+
+fn lapse_reason(due_at: DateTime<Utc>) -> String {
+    format!("KYC review overdue since {}", due_at.format("%Y-%m-%d"))
+}
+
+/// AegisIDs minted for the entity and not revoked yet, including one minted before metadata was stored.
+async fn identities(pool: &PgPool, entity_id: i32) -> Result<Vec<String>, (StatusCode, String)> {
+    sqlx::query_scalar(
+        r#"
+        SELECT token_id FROM identity_metadata WHERE entity_id = $1 AND token_id IS NOT NULL AND revoked_at IS NULL
+        UNION
+        SELECT e.on_chain_id FROM legal_entities e
+        WHERE e.id = $1 AND e.on_chain_id IS NOT NULL
+            AND NOT EXISTS (SELECT 1 FROM identity_metadata m WHERE m.token_id = e.on_chain_id AND m.revoked_at IS NOT NULL)
+        "#
+    )
+    .bind(entity_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Burns one AegisID of a lapsed entity and records the revocation. `Ok(Err(_))` is an on-chain failure,
+/// which the caller records or reports.
+async fn revoke(state: &AppState, entity_id: i32, token_id: &str, due_at: DateTime<Utc>) -> Result<Result<(), String>, (StatusCode, String)> {
+    let tx_hash = match state.chain.revoke_identity(token_id, &lapse_reason(due_at)).await {
+        Ok(tx_hash) => tx_hash,
+        Err(e) => return Ok(Err(e.to_string())),
+    };
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    sqlx::query("UPDATE identity_metadata SET revoked_at = NOW() WHERE token_id = $1 AND revoked_at IS NULL")
+        .bind(token_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    sqlx::query("UPDATE legal_entities SET on_chain_id = NULL WHERE id = $1 AND on_chain_id = $2")
+        .bind(entity_id)
+        .bind(token_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    record_action(&mut *tx, entity_id, "identity_revoked", due_at, ReviewAction {
+        token_id: Some(token_id.to_string()),
+        tx_hash: Some(tx_hash),
+        ..ReviewAction::default()
+    }).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Ok(()))
+}
+
+/// Retries the revocations that failed when entities lapsed. Lapsed entities leave [`under_review`], so
+/// nothing else picks them up; repeated failures are only logged.
+async fn retry_revocations(state: &AppState) -> Result<(), (StatusCode, String)> {
+    let failed = sqlx::query_as::<_, (i32, String, DateTime<Utc>)>(
+        r#"
+        SELECT DISTINCT ON (a.token_id) a.entity_id, a.token_id, a.due_at
+        FROM kyc_review_actions a
+        WHERE a.action = 'identity_revoked' AND a.error IS NOT NULL
+            AND NOT EXISTS (
+                SELECT 1 FROM kyc_review_actions r
+                WHERE r.action = 'identity_revoked' AND r.token_id = a.token_id AND r.tx_hash IS NOT NULL
+            )
+        ORDER BY a.token_id, a.id DESC
+        "#
+    )
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for (entity_id, token_id, due_at) in failed {
+        match revoke(state, entity_id, &token_id, due_at).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) | Err((_, e)) => println!("KYC review error for entity {}: revoking AegisID {}: {}", entity_id, token_id, e),
+        }
+    }
+    Ok(())
+}
+
+/// Optional columns of a `kyc_review_actions` row.
+#[derive(Default)]
+struct ReviewAction {
+    kyc_level_before: Option<i16>,
+    kyc_level_after: Option<i16>,
+    token_id: Option<String>,
+    freeze_id: Option<i32>,
+    tx_hash: Option<String>,
+    error: Option<String>,
+}
+
+async fn record_action<'e, E>(executor: E, entity_id: i32, action: &str, due_at: DateTime<Utc>, details: ReviewAction) -> Result<(), (StatusCode, String)>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO kyc_review_actions (entity_id, action, due_at, kyc_level_before, kyc_level_after, token_id, freeze_id, tx_hash, error)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#
+    )
+    .bind(entity_id)
+    .bind(action)
+    .bind(due_at)
+    .bind(details.kyc_level_before)
+    .bind(details.kyc_level_after)
+    .bind(details.token_id)
+    .bind(details.freeze_id)
+    .bind(details.tx_hash)
+    .bind(details.error)
+    .execute(executor)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(())
+}
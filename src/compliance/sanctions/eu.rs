@@ -0,0 +1,53 @@
+use roxmltree::{Document, Node};
+use crate::compliance::iso20022::xml::children;
+use super::{ListEntry, ParsedList, ENTRY_ENTITY, ENTRY_INDIVIDUAL};
+
+/// Parses the EU consolidated financial sanctions list (FSF XML export, `export` root element).
+/// The EU list carries no crypto addresses, so entries are matched by name only.
+pub fn parse_xml(xml: &str) -> Result<ParsedList, String> {
+    let doc = Document::parse(xml).map_err(|e| format!("Malformed XML: {}", e))?;
+    let root = doc.root_element();
+    if root.tag_name().name() != "export" {
+        return Err(format!("Expected an EU export document, found {}", root.tag_name().name()));
+    }
+
+    Ok(ParsedList {
+        // generationDate is a timestamp; its date is the list version
+        version: root.attribute("generationDate").map(|d| d.split('T').next().unwrap_or(d).to_string()),
+        entries: children(root, "sanctionEntity").map(read_entity).collect::<Result<_, _>>()?,
+    })
+}
+
+fn read_entity(node: Node) -> Result<ListEntry, String> {
+    let uid = node.attribute("logicalId").ok_or("sanctionEntity: missing logicalId")?;
+
+    let mut names: Vec<String> = Vec::new();
+    for alias in children(node, "nameAlias") {
+        let name = alias.attribute("wholeName").map(str::trim).unwrap_or_default();
+        if !name.is_empty() && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    if names.is_empty() {
+        return Err(format!("sanctionEntity {}: no nameAlias with a wholeName", uid));
+    }
+
+    let mut programs: Vec<String> = Vec::new();
+    for programme in children(node, "regulation").filter_map(|r| r.attribute("programme")).map(str::trim) {
+        if !programme.is_empty() && !programs.iter().any(|p| p == programme) {
+            programs.push(programme.to_string());
+        }
+    }
+
+    let subject_type = children(node, "subjectType").next().and_then(|s| s.attribute("code")).unwrap_or_default();
+    let name = names.remove(0);
+
+    Ok(ListEntry {
+        source_uid: uid.to_string(),
+        entry_type: if subject_type == "person" { ENTRY_INDIVIDUAL } else { ENTRY_ENTITY },
+        name,
+        aliases: names,
+        programs,
+        addresses: Vec::new(),
+    })
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use crate::compliance::models::SanctionsCandidate;
+
+/// Lowest similarity (0..=1) at which a name is raised as a hit.
+pub const MATCH_THRESHOLD: f64 = 0.88;
+
+/// Legal forms and filler words that say nothing about who a party is.
+const STOP_WORDS: &[&str] = &[
+    "THE", "OF", "AND", "FOR", "LTD", "LIMITED", "LLC", "INC", "INCORPORATED", "CORP", "CORPORATION", "CO", "COMPANY",
+    "GMBH", "AG", "KG", "UG", "SA", "SAS", "SARL", "SRL", "SPA", "BV", "NV", "PLC", "LLP", "LP", "AB", "AS", "OY",
+    "JSC", "PJSC", "OJSC", "CJSC", "OAO", "ZAO", "OOO", "FZE", "FZCO", "FZC", "PTE", "PTY",
+];
+
+/// Transliterates to ASCII, uppercases, drops punctuation and legal forms.
+/// "Bank Melli Iran, S.A." and "BANK MELLI IRAN" normalize alike.
+pub fn normalize(name: &str) -> String {
+    let ascii = deunicode::deunicode(name).to_uppercase();
+    let cleaned: String = ascii.chars()
+        .filter(|c| !matches!(c, '\'' | '.'))
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { ' ' })
+        .collect();
+    let tokens: Vec<&str> = cleaned.split_whitespace().collect();
+    let kept: Vec<&str> = tokens.iter().copied().filter(|t| !STOP_WORDS.contains(t)).collect();
+
+    // A name made only of stop words is kept as it is
+    if kept.is_empty() { tokens.join(" ") } else { kept.join(" ") }
+}
+
+/// Blocking keys of a normalized name: the consonant skeleton of each significant token.
+/// Two names are only scored against each other if they share a key, so a match needs one token
+/// spelled with the same consonants (vowel variants such as MOHAMMED / MUHAMMAD still share one).
+pub fn keys(normalized: &str) -> Vec<String> {
+    let tokens: Vec<&str> = normalized.split_whitespace().collect();
+    let significant: Vec<&str> = tokens.iter().copied().filter(|t| t.len() >= 3).collect();
+    let tokens = if significant.is_empty() { tokens } else { significant };
+
+    let mut keys: Vec<String> = tokens.iter().map(|t| skeleton(t)).collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+fn skeleton(token: &str) -> String {
+    let mut chars = token.chars();
+    let mut key: String = chars.next().into_iter().collect();
+    for c in chars.filter(|c| !matches!(c, 'A' | 'E' | 'I' | 'O' | 'U' | 'Y' | 'H' | 'W')) {
+        if !key.ends_with(c) {
+            key.push(c);
+        }
+    }
+    key
+}
+
+/// Similarity of two normalized names, independent of token order: each token is paired with its closest
+/// Jaro-Winkler match in the other name, weighted by length, and both directions are averaged.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<&str> = a.split_whitespace().collect();
+    let b: Vec<&str> = b.split_whitespace().collect();
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    (coverage(&a, &b) + coverage(&b, &a)) / 2.0
+}
+
+fn coverage(from: &[&str], to: &[&str]) -> f64 {
+    let total: usize = from.iter().map(|t| t.len()).sum();
+    let matched: f64 = from.iter()
+        .map(|t| t.len() as f64 * to.iter().map(|u| strsim::jaro_winkler(t, u)).fold(0.0, f64::max))
+        .sum();
+    matched / total as f64
+}
+
+/// A listed name that scored at or above `MATCH_THRESHOLD`.
+#[derive(Debug)]
+pub struct NameMatch<'a> {
+    pub candidate: &'a SanctionsCandidate,
+    pub score: f64,
+}
+
+/// Listed names looked up by blocking key.
+pub struct NameIndex<'a> {
+    candidates: &'a [SanctionsCandidate],
+    by_key: HashMap<&'a str, Vec<usize>>,
+}
+
+impl<'a> NameIndex<'a> {
+    pub fn new(candidates: &'a [SanctionsCandidate]) -> Self {
+        let mut by_key: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (i, candidate) in candidates.iter().enumerate() {
+            for key in &candidate.keys {
+                by_key.entry(key.as_str()).or_default().push(i);
+            }
+        }
+        Self { candidates, by_key }
+    }
+
+    /// The best-scoring name of each listed entry matching `name`, best first.
+    pub fn search(&self, name: &str) -> Vec<NameMatch<'a>> {
+        let normalized = normalize(name);
+        let mut indices: Vec<usize> = keys(&normalized).iter()
+            .filter_map(|k| self.by_key.get(k.as_str()))
+            .flatten()
+            .copied()
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+
+        let mut best: HashMap<i32, NameMatch<'a>> = HashMap::new();
+        for candidate in indices.into_iter().map(|i| &self.candidates[i]) {
+            let score = similarity(&normalized, &candidate.normalized);
+            if score < MATCH_THRESHOLD {
+                continue;
+            }
+            match best.get(&candidate.entry_id) {
+                Some(existing) if existing.score >= score => {}
+                _ => { best.insert(candidate.entry_id, NameMatch { candidate, score }); }
+            }
+        }
+
+        let mut matches: Vec<NameMatch<'a>> = best.into_values().collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.candidate.entry_id.cmp(&b.candidate.entry_id)));
+        matches
+    }
+}
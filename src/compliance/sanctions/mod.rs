@@ -0,0 +1,248 @@
+pub mod eu;
+pub mod matching;
+pub mod ofac;
+pub mod screening;
+
+use axum::http::StatusCode;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use crate::compliance::audit;
+use crate::compliance::metadata::content_hash;
+use crate::compliance::models::{SanctionsImportQuery, SanctionsList};
+
+pub const SOURCE_OFAC_SDN: &str = "ofac_sdn";
+pub const SOURCE_EU: &str = "eu_consolidated";
+
+pub const FORMAT_XML: &str = "xml";
+pub const FORMAT_CSV: &str = "csv";
+
+pub const ENTRY_INDIVIDUAL: &str = "individual";
+pub const ENTRY_ENTITY: &str = "entity";
+pub const ENTRY_VESSEL: &str = "vessel";
+pub const ENTRY_AIRCRAFT: &str = "aircraft";
+
+/// Upper bound on an uploaded list file (the full SDN XML is well under this).
+pub const MAX_LIST_BYTES: usize = 128 * 1024 * 1024;
+
+/// A list file as parsed, before it is stored.
+pub struct ParsedList {
+    /// Publication date found in the file, if the format has one
+    pub version: Option<String>,
+    pub entries: Vec<ListEntry>,
+}
+
+/// One listed party, whatever the source format.
+pub struct ListEntry {
+    pub source_uid: String,
+    pub entry_type: &'static str,
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub programs: Vec<String>,
+    pub addresses: Vec<ListedAddress>,
+}
+
+pub struct ListedAddress {
+    /// Currency code as listed (XBT, ETH, USDT, ...)
+    pub currency: String,
+    pub address: String,
+}
+
+/// Parses a list file. `Err` describes why the file is not a list of that source and format.
+pub fn parse(source: &str, format: &str, content: &str) -> Result<ParsedList, String> {
+    match (source, format) {
+        (SOURCE_OFAC_SDN, FORMAT_XML) => ofac::parse_xml(content),
+        (SOURCE_OFAC_SDN, FORMAT_CSV) => ofac::parse_csv(content),
+        (SOURCE_EU, FORMAT_XML) => eu::parse_xml(content),
+        (SOURCE_EU, _) => Err("The EU consolidated list is imported as XML".to_string()),
+        _ => Err(format!("source must be '{}' or '{}' and format '{}' or '{}'", SOURCE_OFAC_SDN, SOURCE_EU, FORMAT_XML, FORMAT_CSV)),
+    }
+}
+
+/// Hex (EVM) and bech32 addresses compare case-insensitively and are lowercased; base58 ones are kept as listed.
+pub fn normalize_address(address: &str) -> String {
+    let address = address.trim();
+    let lower = address.to_ascii_lowercase();
+    if lower.starts_with("0x") || ["bc1", "tb1", "ltc1"].iter().any(|prefix| lower.starts_with(prefix)) {
+        lower
+    } else {
+        address.to_string()
+    }
+}
+
+/// How a source is named in hit reasons and notifications.
+pub fn source_label(source: &str) -> &'static str {
+    match source {
+        SOURCE_OFAC_SDN => "OFAC SDN list",
+        SOURCE_EU => "EU consolidated list",
+        _ => "sanctions list",
+    }
+}
+
+pub async fn find_list(pool: &PgPool, list_id: i32) -> Result<SanctionsList, (StatusCode, String)> {
+    sqlx::query_as::<_, SanctionsList>("SELECT * FROM sanctions_lists WHERE id = $1")
+        .bind(list_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Sanctions list {} not found", list_id)))
+}
+
+/// Stores a new version of a list and makes it the active one for its source.
+/// The caller re-screens the population against it (`screening::run_rescreen`).
+pub async fn import(pool: &PgPool, query: &SanctionsImportQuery, content: String, actor: &str) -> Result<SanctionsList, (StatusCode, String)> {
+    match (query.source.as_str(), query.format.as_str()) {
+        (SOURCE_OFAC_SDN, FORMAT_XML | FORMAT_CSV) | (SOURCE_EU, FORMAT_XML) => {}
+        (source, format) => {
+            return Err((StatusCode::BAD_REQUEST, format!(
+                "Unsupported list '{}' in format '{}'; supported are {} (xml, csv) and {} (xml)", source, format, SOURCE_OFAC_SDN, SOURCE_EU,
+            )));
+        }
+    }
+    let source = query.source.clone();
+    let format = query.format.clone();
+    let hash = content_hash(&content);
+
+    // Full lists take a while to parse; keep that off the async workers
+    let parsed = tokio::task::spawn_blocking(move || parse(&source, &format, &content))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e))?;
+    if parsed.entries.is_empty() {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, "The list has no entries".to_string()));
+    }
+    let version = query.version.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
+        .or(parsed.version)
+        .ok_or((StatusCode::BAD_REQUEST, "version is required; the file does not carry a publication date".to_string()))?;
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let existing: Option<i32> = sqlx::query_scalar("SELECT id FROM sanctions_lists WHERE source = $1 AND content_hash = $2")
+        .bind(&query.source)
+        .bind(&hash)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if let Some(list_id) = existing {
+        return Err((StatusCode::CONFLICT, format!("This file was already imported as sanctions list {}", list_id)));
+    }
+
+    sqlx::query("UPDATE sanctions_lists SET active = FALSE WHERE source = $1 AND active")
+        .bind(&query.source)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let address_count = parsed.entries.iter().map(|e| e.addresses.len()).sum::<usize>();
+    let list = sqlx::query_as::<_, SanctionsList>(
+        r#"
+        INSERT INTO sanctions_lists (source, format, version, content_hash, entry_count, address_count, imported_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#
+    )
+    .bind(&query.source)
+    .bind(&query.format)
+    .bind(&version)
+    .bind(&hash)
+    .bind(parsed.entries.len() as i32)
+    .bind(address_count as i32)
+    .bind(actor)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let entry_ids: HashMap<String, i32> = sqlx::query_as::<_, (String, i32)>(
+        r#"
+        INSERT INTO sanctions_entries (list_id, source_uid, entry_type, name, programs)
+        SELECT $1, uid, entry_type, name, string_to_array(programs, '|')
+        FROM UNNEST($2::TEXT[], $3::TEXT[], $4::TEXT[], $5::TEXT[]) AS e(uid, entry_type, name, programs)
+        RETURNING source_uid, id
+        "#
+    )
+    .bind(list.id)
+    .bind(parsed.entries.iter().map(|e| e.source_uid.clone()).collect::<Vec<_>>())
+    .bind(parsed.entries.iter().map(|e| e.entry_type.to_string()).collect::<Vec<_>>())
+    .bind(parsed.entries.iter().map(|e| e.name.clone()).collect::<Vec<_>>())
+    .bind(parsed.entries.iter().map(|e| e.programs.join("|")).collect::<Vec<_>>())
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            (StatusCode::UNPROCESSABLE_ENTITY, "The list contains the same entry twice".to_string())
+        }
+        e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })?
+    .into_iter()
+    .collect();
+
+    let (mut name_entries, mut names, mut normalized, mut keys) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    let (mut address_entries, mut currencies, mut addresses) = (Vec::new(), Vec::new(), Vec::new());
+    for entry in &parsed.entries {
+        let entry_id = entry_ids[&entry.source_uid];
+
+        let mut seen: Vec<String> = Vec::new();
+        for name in std::iter::once(&entry.name).chain(&entry.aliases) {
+            let norm = matching::normalize(name);
+            if norm.is_empty() || seen.contains(&norm) {
+                continue;
+            }
+            name_entries.push(entry_id);
+            names.push(name.clone());
+            keys.push(matching::keys(&norm).join(" "));
+            normalized.push(norm.clone());
+            seen.push(norm);
+        }
+
+        let mut seen: Vec<String> = Vec::new();
+        for listed in &entry.addresses {
+            let address = normalize_address(&listed.address);
+            if seen.contains(&address) {
+                continue;
+            }
+            address_entries.push(entry_id);
+            currencies.push(listed.currency.clone());
+            addresses.push(address.clone());
+            seen.push(address);
+        }
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO sanctions_names (entry_id, name, normalized, keys)
+        SELECT entry_id, name, normalized, string_to_array(keys, ' ')
+        FROM UNNEST($1::INTEGER[], $2::TEXT[], $3::TEXT[], $4::TEXT[]) AS n(entry_id, name, normalized, keys)
+        "#
+    )
+    .bind(name_entries)
+    .bind(names)
+    .bind(normalized)
+    .bind(keys)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO sanctions_addresses (entry_id, currency, address)
+        SELECT * FROM UNNEST($1::INTEGER[], $2::TEXT[], $3::TEXT[])
+        "#
+    )
+    .bind(address_entries)
+    .bind(currencies)
+    .bind(addresses)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit::record(&mut *tx, actor, "sanctions.list.import", &format!("sanctions_list:{}", list.id), serde_json::json!({
+        "source": list.source,
+        "format": list.format,
+        "version": list.version,
+        "content_hash": list.content_hash,
+        "entry_count": list.entry_count,
+        "address_count": list.address_count,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(list)
+}
@@ -0,0 +1,135 @@
+use chrono::NaiveDate;
+use regex::Regex;
+use roxmltree::{Document, Node};
+use std::sync::OnceLock;
+use crate::compliance::iso20022::xml::{children, node_at, text_at};
+use super::{ListEntry, ListedAddress, ParsedList, ENTRY_AIRCRAFT, ENTRY_ENTITY, ENTRY_INDIVIDUAL, ENTRY_VESSEL};
+
+/// How the SDN list labels crypto addresses, followed by the currency code (XBT, ETH, USDT, ...).
+const DIGITAL_CURRENCY_ADDRESS: &str = "Digital Currency Address - ";
+
+/// Empty fields in the SDN CSV files.
+const CSV_NULL: &str = "-0-";
+
+/// Parses the SDN list in its XML form (`sdn.xml`). Element names are matched without their namespace,
+/// which OFAC has changed between publication services.
+pub fn parse_xml(xml: &str) -> Result<ParsedList, String> {
+    let doc = Document::parse(xml).map_err(|e| format!("Malformed XML: {}", e))?;
+    let root = doc.root_element();
+    if root.tag_name().name() != "sdnList" {
+        return Err(format!("Expected an sdnList document, found {}", root.tag_name().name()));
+    }
+
+    Ok(ParsedList {
+        version: text_at(root, &["publshInformation", "Publish_Date"]).map(publish_date),
+        entries: children(root, "sdnEntry").map(read_entry).collect::<Result<_, _>>()?,
+    })
+}
+
+fn read_entry(node: Node) -> Result<ListEntry, String> {
+    let uid = text_at(node, &["uid"]).ok_or("sdnEntry: missing uid")?;
+    let name = full_name(node).ok_or_else(|| format!("sdnEntry {}: missing lastName", uid))?;
+
+    let addresses = node_at(node, &["idList"]).into_iter()
+        .flat_map(|list| children(list, "id"))
+        .filter_map(|id| {
+            let currency = text_at(id, &["idType"])?.strip_prefix(DIGITAL_CURRENCY_ADDRESS)?;
+            Some(ListedAddress { currency: currency.trim().to_string(), address: text_at(id, &["idNumber"])?.to_string() })
+        })
+        .collect();
+
+    Ok(ListEntry {
+        source_uid: uid.to_string(),
+        entry_type: entry_type(text_at(node, &["sdnType"]).unwrap_or_default()),
+        name,
+        aliases: node_at(node, &["akaList"]).into_iter().flat_map(|list| children(list, "aka")).filter_map(full_name).collect(),
+        programs: node_at(node, &["programList"]).into_iter()
+            .flat_map(|list| children(list, "program"))
+            .filter_map(|p| p.text().map(str::trim).filter(|p| !p.is_empty()).map(str::to_string))
+            .collect(),
+        addresses,
+    })
+}
+
+/// "firstName lastName"; entities and vessels only have a lastName.
+fn full_name(node: Node) -> Option<String> {
+    let last = text_at(node, &["lastName"])?;
+    Some(match text_at(node, &["firstName"]) {
+        Some(first) => format!("{} {}", first, last),
+        None => last.to_string(),
+    })
+}
+
+/// Parses `sdn.csv`: headerless rows of ent_num, SDN_Name, SDN_Type, Program, Title, Call_Sign, Vess_type,
+/// Tonnage, GRT, Vess_flag, Vess_owner, Remarks. Crypto addresses and aliases are read from the remarks;
+/// the separate `alt.csv` is not needed.
+pub fn parse_csv(csv: &str) -> Result<ParsedList, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(csv.as_bytes());
+
+    let mut entries = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("Line {}: {}", i + 1, e))?;
+        // The file ends with a DOS end-of-file marker on a line of its own
+        if record.iter().all(|field| field.trim_matches('\u{1a}').is_empty()) {
+            continue;
+        }
+        if record.len() < 12 {
+            return Err(format!("Line {}: expected 12 fields, found {}", i + 1, record.len()));
+        }
+
+        let field = |n: usize| Some(record[n].trim()).filter(|f| !f.is_empty() && *f != CSV_NULL);
+        let uid = field(0).ok_or_else(|| format!("Line {}: missing ent_num", i + 1))?;
+        let remarks = field(11).unwrap_or_default();
+
+        entries.push(ListEntry {
+            source_uid: uid.to_string(),
+            entry_type: entry_type(field(2).unwrap_or_default()),
+            name: field(1).ok_or_else(|| format!("Line {}: missing SDN_Name", i + 1))?.to_string(),
+            aliases: alias_pattern().captures_iter(remarks).map(|c| c[1].trim().to_string()).collect(),
+            programs: field(3).map(csv_programs).unwrap_or_default(),
+            addresses: address_pattern().captures_iter(remarks)
+                .map(|c| ListedAddress { currency: c[1].to_string(), address: c[2].to_string() })
+                .collect(),
+        });
+    }
+
+    Ok(ParsedList { version: None, entries })
+}
+
+/// "SDGT] [IRGC" lists two programs.
+fn csv_programs(field: &str) -> Vec<String> {
+    field.trim_matches(|c| c == '[' || c == ']')
+        .split("] [")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn address_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"Digital Currency Address - ([A-Z0-9]+) ([A-Za-z0-9]+)").expect("valid pattern"))
+}
+
+fn alias_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"a\.k\.a\. '([^']+)'").expect("valid pattern"))
+}
+
+fn entry_type(sdn_type: &str) -> &'static str {
+    match sdn_type.to_ascii_lowercase().as_str() {
+        "individual" => ENTRY_INDIVIDUAL,
+        "vessel" => ENTRY_VESSEL,
+        "aircraft" => ENTRY_AIRCRAFT,
+        _ => ENTRY_ENTITY,
+    }
+}
+
+/// Publish_Date is MM/DD/YYYY; versions are kept as ISO dates.
+fn publish_date(date: &str) -> String {
+    NaiveDate::parse_from_str(date, "%m/%d/%Y").map(|d| d.to_string()).unwrap_or_else(|_| date.to_string())
+}
@@ -0,0 +1,487 @@
+use axum::http::StatusCode;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use crate::agent::intents::SYSTEM_ACTOR;
+use crate::compliance::models::{LegalEntity, SanctionsCandidate, SanctionsHit, SanctionsHitReviewRequest};
+use crate::compliance::{audit, notifications};
+use crate::finance::models::Wallet;
+use crate::governance::freezes::{self, SCOPE_ENTITY};
+use crate::governance::models::CreateFreezeRequest;
+use crate::state::AppState;
+use super::matching::{self, NameIndex, NameMatch};
+use super::{find_list, normalize_address, source_label};
+
+pub const SUBJECT_ENTITY: &str = "entity";
+pub const SUBJECT_ADDRESS: &str = "address";
+
+pub const MATCH_NAME: &str = "name";
+pub const MATCH_ADDRESS: &str = "address";
+
+pub const CONTEXT_REGISTRATION: &str = "registration";
+pub const CONTEXT_PAYMENT: &str = "payment";
+pub const CONTEXT_RESCREEN: &str = "rescreen";
+
+pub const HIT_OPEN: &str = "open";
+pub const HIT_CONFIRMED: &str = "confirmed";
+pub const HIT_DISMISSED: &str = "dismissed";
+pub const HIT_WITHDRAWN: &str = "withdrawn";
+
+/// Listed names with their entry, list and source; callers add the filter after `WHERE l.active`.
+const CANDIDATES: &str = r#"
+    SELECT n.entry_id, e.list_id, l.source, e.source_uid, e.name AS listed_name, n.name, n.normalized, n.keys
+    FROM sanctions_names n
+    JOIN sanctions_entries e ON e.id = n.entry_id
+    JOIN sanctions_lists l ON l.id = e.list_id
+    WHERE l.active
+"#;
+
+/// A listed party a subject matched, before it is stored as a hit.
+struct Match {
+    entry_id: i32,
+    list_id: i32,
+    source: String,
+    source_uid: String,
+    listed_name: String,
+    match_type: &'static str,
+    matched_value: String,
+    score: f64,
+}
+
+impl From<&NameMatch<'_>> for Match {
+    fn from(m: &NameMatch) -> Self {
+        Self {
+            entry_id: m.candidate.entry_id,
+            list_id: m.candidate.list_id,
+            source: m.candidate.source.clone(),
+            source_uid: m.candidate.source_uid.clone(),
+            listed_name: m.candidate.listed_name.clone(),
+            match_type: MATCH_NAME,
+            matched_value: m.candidate.name.clone(),
+            score: m.score,
+        }
+    }
+}
+
+pub async fn find_hit(pool: &PgPool, hit_id: i32) -> Result<SanctionsHit, (StatusCode, String)> {
+    sqlx::query_as::<_, SanctionsHit>("SELECT * FROM sanctions_hits WHERE id = $1")
+        .bind(hit_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Sanctions hit {} not found", hit_id)))
+}
+
+/// Screens an entity's legal name against all active lists and raises a hit per matching entry.
+/// Entities without a legal name cannot be name-screened.
+pub async fn screen_entity(pool: &PgPool, entity: &LegalEntity, context: &str) -> Result<Vec<SanctionsHit>, (StatusCode, String)> {
+    let Some(name) = entity.legal_name.as_deref() else {
+        return Ok(Vec::new());
+    };
+
+    let candidates = sqlx::query_as::<_, SanctionsCandidate>(&format!("{} AND n.keys && $1", CANDIDATES))
+        .bind(matching::keys(&matching::normalize(name)))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let matches: Vec<Match> = NameIndex::new(&candidates).search(name).iter().map(Match::from).collect();
+
+    let mut hits = Vec::new();
+    for m in &matches {
+        hits.push(raise(pool, SUBJECT_ENTITY, &entity.id.to_string(), Some(entity.id), m, context).await?.0);
+    }
+    Ok(hits)
+}
+
+/// Exact match of an address against the listed ones of all active lists.
+pub async fn screen_address(pool: &PgPool, address: &str, context: &str) -> Result<Vec<SanctionsHit>, (StatusCode, String)> {
+    let address = normalize_address(address);
+    let matches = address_matches(pool, std::slice::from_ref(&address), None).await?;
+    if matches.is_empty() {
+        return Ok(Vec::new());
+    }
+    let owners = wallet_owners(pool, std::slice::from_ref(&address)).await?;
+
+    let mut hits = Vec::new();
+    for (_, m) in &matches {
+        hits.push(raise(pool, SUBJECT_ADDRESS, &address, owners.get(&address).copied(), m, context).await?.0);
+    }
+    Ok(hits)
+}
+
+/// Pre-payment check. The target address is screened, and neither the paying entity nor the entity owning
+/// the target wallet (if it is one of ours) may have an open or confirmed hit: payments wait for review.
+/// `Err` carries the rejection reason recorded on the payment.
+pub async fn check_payment(pool: &PgPool, wallet: &Wallet, target_address: &str) -> Result<(), String> {
+    let hits = screen_address(pool, target_address, CONTEXT_PAYMENT).await.map_err(|(_, e)| e)?;
+    if let Some(hit) = hits.iter().find(|h| h.status == HIT_OPEN || h.status == HIT_CONFIRMED) {
+        return Err(format!(
+            "Counterparty {} is listed on the {} as {} (sanctions hit {})",
+            target_address, source_label(&hit.source), hit.listed_name, hit.id,
+        ));
+    }
+
+    let target_entity = wallet_owners(pool, &[normalize_address(target_address)]).await.map_err(|(_, e)| e)?.into_values().next();
+    for entity_id in std::iter::once(wallet.entity_id).chain(target_entity) {
+        let unresolved: Vec<i32> = sqlx::query_scalar("SELECT id FROM sanctions_hits WHERE entity_id = $1 AND status IN ('open', 'confirmed') ORDER BY id")
+            .bind(entity_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !unresolved.is_empty() {
+            let ids: Vec<String> = unresolved.iter().map(i32::to_string).collect();
+            return Err(format!("Entity {} has unresolved sanctions hits ({})", entity_id, ids.join(", ")));
+        }
+    }
+
+    Ok(())
+}
+
+/// Re-screen after an import, in the background. A failed run is retried by `resume` at the next start.
+pub async fn run_rescreen(pool: PgPool, list_id: i32) {
+    if let Err((_, e)) = rescreen(&pool, list_id).await {
+        println!("Sanctions re-screen error: {}", e);
+    }
+}
+
+/// Finishes re-screens that never completed, e.g. because the service stopped during one.
+pub async fn resume(pool: PgPool) {
+    let pending: Result<Vec<i32>, _> = sqlx::query_scalar("SELECT id FROM sanctions_lists WHERE active AND screened_at IS NULL ORDER BY id")
+        .fetch_all(&pool)
+        .await;
+    match pending {
+        Ok(list_ids) => {
+            for list_id in list_ids {
+                run_rescreen(pool.clone(), list_id).await;
+            }
+        }
+        Err(e) => println!("Sanctions re-screen error: {}", e),
+    }
+}
+
+/// Screens the whole population against one list version: every named entity, and every address we hold or
+/// have dealt with (our wallets, payment and schedule targets, indexed counterparties, counterparty list entries).
+/// Open hits from earlier versions of the same source that were not matched again are withdrawn.
+pub async fn rescreen(pool: &PgPool, list_id: i32) -> Result<(), (StatusCode, String)> {
+    let list = find_list(pool, list_id).await?;
+    if !list.active {
+        // Superseded before it was screened; the newer version's re-screen covers it
+        return Ok(());
+    }
+
+    let candidates = sqlx::query_as::<_, SanctionsCandidate>(&format!("{} AND e.list_id = $1", CANDIDATES))
+        .bind(list.id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let entities = sqlx::query_as::<_, LegalEntity>("SELECT * FROM legal_entities WHERE legal_name IS NOT NULL ORDER BY id")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let index = NameIndex::new(&candidates);
+    let name_matches: Vec<(i32, Match)> = entities.iter()
+        .flat_map(|entity| {
+            let name = entity.legal_name.as_deref().unwrap_or_default();
+            index.search(name).iter().map(|m| (entity.id, Match::from(m))).collect::<Vec<_>>()
+        })
+        .collect();
+
+    let population: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT LOWER(address) FROM (
+            SELECT address FROM wallets
+            UNION ALL SELECT target_address FROM payment_intents
+            UNION ALL SELECT target_address FROM payment_schedules
+            UNION ALL SELECT counterparty FROM wallet_movements
+            UNION ALL SELECT address FROM counterparty_entries
+        ) AS population(address)
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let address_matches = address_matches(pool, &population, Some(list.id)).await?;
+    let matched: Vec<String> = address_matches.iter().map(|(address, _)| address.clone()).collect();
+    let owners = wallet_owners(pool, &matched).await?;
+
+    let mut hits = 0;
+    let mut raised = 0;
+    for (entity_id, m) in &name_matches {
+        let (_, new) = raise(pool, SUBJECT_ENTITY, &entity_id.to_string(), Some(*entity_id), m, CONTEXT_RESCREEN).await?;
+        hits += 1;
+        raised += new as usize;
+    }
+    for (address, m) in &address_matches {
+        let (_, new) = raise(pool, SUBJECT_ADDRESS, address, owners.get(address).copied(), m, CONTEXT_RESCREEN).await?;
+        hits += 1;
+        raised += new as usize;
+    }
+
+    let withdrawn: Vec<i32> = sqlx::query_scalar(
+        r#"
+        UPDATE sanctions_hits SET status = 'withdrawn', updated_at = NOW()
+        WHERE source = $1 AND list_id <> $2 AND status = 'open'
+        RETURNING id
+        "#
+    )
+    .bind(&list.source)
+    .bind(list.id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    sqlx::query("UPDATE sanctions_lists SET screened_at = NOW() WHERE id = $1")
+        .bind(list.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let subject = format!("sanctions_list:{}", list.id);
+    let details = serde_json::json!({
+        "source": list.source,
+        "version": list.version,
+        "entities": entities.len(),
+        "addresses": population.len(),
+        "hits": hits,
+        "new_hits": raised,
+        "withdrawn_hits": withdrawn,
+    });
+    audit::record(&mut *tx, SYSTEM_ACTOR, "sanctions.rescreen", &subject, details.clone()).await?;
+    notifications::notify(
+        &mut *tx,
+        "sanctions.rescreened",
+        &subject,
+        &format!(
+            "Re-screened {} entities and {} addresses against the {} of {}: {} new hits, {} withdrawn",
+            entities.len(), population.len(), source_label(&list.source), list.version, raised, withdrawn.len(),
+        ),
+        details,
+    ).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Confirms or dismisses an open hit. A note is required to dismiss.
+/// Confirming a hit that belongs to an entity freezes the entity, which also stops its agents on-chain.
+pub async fn review(
+    state: &AppState,
+    hit_id: i32,
+    status: &str,
+    req: &SanctionsHitReviewRequest,
+    actor: &str,
+) -> Result<SanctionsHit, (StatusCode, String)> {
+    let note = req.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    if status == HIT_DISMISSED && note.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "A note is required to dismiss a hit".to_string()));
+    }
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let hit = sqlx::query_as::<_, SanctionsHit>(
+        r#"
+        UPDATE sanctions_hits
+        SET status = $1, reviewed_by = $2, reviewed_at = NOW(), review_note = $3, updated_at = NOW()
+        WHERE id = $4 AND status = 'open'
+        RETURNING *
+        "#
+    )
+    .bind(status)
+    .bind(actor)
+    .bind(note)
+    .bind(hit_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let hit = match hit {
+        Some(hit) => hit,
+        None => {
+            let current = find_hit(&state.pool, hit_id).await?;
+            return Err((StatusCode::CONFLICT, format!("Sanctions hit {} is {}, not open", hit_id, current.status)));
+        }
+    };
+
+    let action = if status == HIT_CONFIRMED { "sanctions.hit.confirm" } else { "sanctions.hit.dismiss" };
+    audit::record(&mut *tx, actor, action, &format!("sanctions_hit:{}", hit.id), serde_json::json!({
+        "subject_type": hit.subject_type,
+        "subject": hit.subject,
+        "source": hit.source,
+        "source_uid": hit.source_uid,
+        "note": hit.review_note,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    match hit.entity_id.filter(|_| status == HIT_CONFIRMED) {
+        Some(entity_id) => freeze_entity(state, hit, entity_id, actor).await,
+        None => Ok(hit),
+    }
+}
+
+async fn freeze_entity(state: &AppState, hit: SanctionsHit, entity_id: i32, actor: &str) -> Result<SanctionsHit, (StatusCode, String)> {
+    let request = CreateFreezeRequest {
+        scope: SCOPE_ENTITY.to_string(),
+        target_id: Some(entity_id),
+        reason: format!("Confirmed match on the {} ({}, sanctions hit {})", source_label(&hit.source), hit.listed_name, hit.id),
+    };
+
+    match freezes::freeze(state, &request, actor).await {
+        Ok(detail) => {
+            audit::record(&state.pool, actor, "freeze.create", &format!("{}:{}", SCOPE_ENTITY, entity_id), serde_json::json!({
+                "freeze_id": detail.freeze.id,
+                "reason": detail.freeze.reason,
+            })).await?;
+            sqlx::query_as::<_, SanctionsHit>("UPDATE sanctions_hits SET freeze_id = $1 WHERE id = $2 RETURNING *")
+                .bind(detail.freeze.id)
+                .bind(hit.id)
+                .fetch_one(&state.pool)
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        }
+        // An earlier freeze already covers the entity
+        Err((StatusCode::CONFLICT, _)) => Ok(hit),
+        Err((_, e)) => {
+            notifications::notify(
+                &state.pool,
+                "sanctions.freeze_failed",
+                &format!("sanctions_hit:{}", hit.id),
+                &format!("Entity {} could not be frozen after sanctions hit {} was confirmed: {}", entity_id, hit.id, e),
+                serde_json::json!({ "entity_id": entity_id, "error": e }),
+            ).await?;
+            Ok(hit)
+        }
+    }
+}
+
+/// Listed addresses equal to one of `addresses`: of one list version, or of all active lists.
+async fn address_matches(pool: &PgPool, addresses: &[String], list_id: Option<i32>) -> Result<Vec<(String, Match)>, (StatusCode, String)> {
+    let rows = sqlx::query_as::<_, (String, i32, i32, String, String, String, String)>(
+        r#"
+        SELECT a.address, a.entry_id, e.list_id, l.source, e.source_uid, e.name, a.currency
+        FROM sanctions_addresses a
+        JOIN sanctions_entries e ON e.id = a.entry_id
+        JOIN sanctions_lists l ON l.id = e.list_id
+        WHERE l.active AND a.address = ANY($1) AND ($2::INTEGER IS NULL OR l.id = $2)
+        ORDER BY a.id
+        "#
+    )
+    .bind(addresses)
+    .bind(list_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(rows.into_iter()
+        .map(|(address, entry_id, list_id, source, source_uid, listed_name, currency)| {
+            let m = Match {
+                entry_id,
+                list_id,
+                source,
+                source_uid,
+                listed_name,
+                match_type: MATCH_ADDRESS,
+                matched_value: format!("{} {}", currency, address),
+                score: 1.0,
+            };
+            (address, m)
+        })
+        .collect())
+}
+
+/// Owning entity of each of `addresses` that is one of our wallets, keyed by lowercase address.
+async fn wallet_owners(pool: &PgPool, addresses: &[String]) -> Result<HashMap<String, i32>, (StatusCode, String)> {
+    let rows = sqlx::query_as::<_, (String, i32)>("SELECT LOWER(address), entity_id FROM wallets WHERE LOWER(address) = ANY($1)")
+        .bind(addresses)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Stores a match as a hit, or refreshes the existing hit of the same subject and listed party.
+/// Reviewed hits keep their decision; withdrawn ones reopen. New and reopened hits notify compliance.
+/// Returns the hit and whether it was raised anew.
+async fn raise(
+    pool: &PgPool,
+    subject_type: &str,
+    subject: &str,
+    entity_id: Option<i32>,
+    m: &Match,
+    context: &str,
+) -> Result<(SanctionsHit, bool), (StatusCode, String)> {
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let previous: Option<String> = sqlx::query_scalar(
+        "SELECT status FROM sanctions_hits WHERE subject_type = $1 AND subject = $2 AND source = $3 AND source_uid = $4 FOR UPDATE"
+    )
+    .bind(subject_type)
+    .bind(subject)
+    .bind(&m.source)
+    .bind(&m.source_uid)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let hit = sqlx::query_as::<_, SanctionsHit>(
+        r#"
+        INSERT INTO sanctions_hits
+            (subject_type, subject, entity_id, source, source_uid, list_id, entry_id, listed_name, match_type, matched_value, score, context)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        ON CONFLICT (subject_type, subject, source, source_uid) DO UPDATE SET
+            entity_id = COALESCE(EXCLUDED.entity_id, sanctions_hits.entity_id),
+            list_id = EXCLUDED.list_id,
+            entry_id = EXCLUDED.entry_id,
+            listed_name = EXCLUDED.listed_name,
+            matched_value = EXCLUDED.matched_value,
+            score = EXCLUDED.score,
+            status = CASE WHEN sanctions_hits.status = 'withdrawn' THEN 'open' ELSE sanctions_hits.status END,
+            updated_at = NOW()
+        RETURNING *
+        "#
+    )
+    .bind(subject_type)
+    .bind(subject)
+    .bind(entity_id)
+    .bind(&m.source)
+    .bind(&m.source_uid)
+    .bind(m.list_id)
+    .bind(m.entry_id)
+    .bind(&m.listed_name)
+    .bind(m.match_type)
+    .bind(&m.matched_value)
+    .bind(m.score)
+    .bind(context)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let new = previous.as_deref().is_none_or(|status| status == HIT_WITHDRAWN);
+    if new {
+        let subject_label = match entity_id {
+            Some(id) if subject_type == SUBJECT_ENTITY => format!("Entity {}", id),
+            _ => format!("Address {}", subject),
+        };
+        notifications::notify(
+            &mut *tx,
+            "sanctions.hit",
+            &format!("sanctions_hit:{}", hit.id),
+            &format!(
+                "{} matches {} on the {} ({} match, score {:.2})",
+                subject_label, hit.listed_name, source_label(&hit.source), hit.match_type, hit.score,
+            ),
+            serde_json::json!({
+                "hit_id": hit.id,
+                "subject_type": hit.subject_type,
+                "subject": hit.subject,
+                "entity_id": hit.entity_id,
+                "source_uid": hit.source_uid,
+                "matched_value": hit.matched_value,
+                "context": hit.context,
+            }),
+        ).await?;
+    }
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok((hit, new))
+}
@@ -0,0 +1,40 @@
+use ethers::types::U256;
+
+/// Decimals of the chain's native asset (ETH).
+pub const NATIVE_DECIMALS: u8 = 18;
+pub const NATIVE_SYMBOL: &str = "ETH";
+
+/// Converts a human-readable decimal string ("12.5") into base units.
+/// Rejects signs, exponents and any significant fractional digits beyond `decimals` instead of rounding;
+/// trailing zeros carry no precision and are accepted ("1.50" for a 1-decimal token).
+pub fn parse_amount(amount: &str, decimals: u8) -> Result<U256, String> {
+    let amount = amount.trim();
+    let (whole, frac) = amount.split_once('.').unwrap_or((amount, ""));
+
+    if whole.is_empty() && frac.is_empty() {
+        return Err(format!("Invalid amount '{}'", amount));
+    }
+    if !whole.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid amount '{}'", amount));
+    }
+    let frac = frac.trim_end_matches('0');
+    if frac.len() > decimals as usize {
+        return Err(format!("Amount '{}' exceeds {} decimal places", amount, decimals));
+    }
+
+    let digits = format!("{}{:0<width$}", whole, frac, width = decimals as usize);
+    U256::from_dec_str(&digits).map_err(|_| format!("Amount '{}' is out of range", amount))
+}
+
+/// Formats base units as a decimal string with trailing zeros removed ("12.5", "0.000001", "3").
+pub fn format_amount(value: U256, decimals: u8) -> String {
+    let digits = format!("{:0>width$}", value.to_string(), width = decimals as usize + 1);
+    let (whole, frac) = digits.split_at(digits.len() - decimals as usize);
+    let frac = frac.trim_end_matches('0');
+
+    if frac.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, frac)
+    }
+}
@@ -1,35 +1,402 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
+    response::Response,
 };
+use crate::auth::Claims;
+use crate::idempotency::{idempotent, resource_id, ClaimedKey};
 use crate::state::AppState;
-use super::models::{BalanceResponse, FundRequest, FundResponse};
+use super::models::{
+    BalanceResponse, BulkBalanceRequest, TokenBalance, FundRequest, FundResponse, Wallet, CreateWalletRequest, UpdateRulesRequest,
+    Token, RegisterTokenRequest, UpdateTokenRequest, WalletFunding, FundingFilter, WalletMovement, MovementFilter,
+};
+use super::amounts::{parse_amount, format_amount, NATIVE_DECIMALS, NATIVE_SYMBOL};
+use super::registry::find_wallet;
+use crate::chain::MAX_BATCH_READ;
+use crate::compliance::iso20022::identifiers::validate_currency;
 
 pub async fn get_balance(
     State(state): State<AppState>,
-    Path(address): Path<String>,
+    Path(wallet_id): Path<i32>,
 ) -> Result<Json<BalanceResponse>, (StatusCode, String)> {
-    let balance = state.chain.get_wallet_balance(&address)
+    let wallet = find_wallet(&state.pool, wallet_id).await?;
+
+    let native = state.chain.get_wallet_balance(&wallet.address)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    let mut balances = vec![TokenBalance {
+        symbol: NATIVE_SYMBOL.to_string(),
+        token_address: None,
+        decimals: NATIVE_DECIMALS,
+        balance: Some(format_amount(native, NATIVE_DECIMALS)),
+        error: None,
+    }];
+
+    let tokens = sqlx::query_as::<_, Token>("SELECT * FROM tokens WHERE chain_id = $1 AND allowed ORDER BY symbol")
+        .bind(state.chain.chain_id() as i64)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for token in tokens {
+        let raw = state.chain.get_token_balance(&token.address, &wallet.address)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+        balances.push(TokenBalance {
+            symbol: token.symbol,
+            token_address: Some(token.address),
+            decimals: token.decimals as u8,
+            balance: Some(format_amount(raw, token.decimals as u8)),
+            error: None,
+        });
+    }
+
     Ok(Json(BalanceResponse {
-        address,
-        balance_wei: balance,
+        wallet_id,
+        address: wallet.address,
+        balances,
     }))
 }
 
+/// Reported for a balance whose call failed inside the Multicall3 batch.
+const BALANCE_READ_FAILED: &str = "Balance read failed";
+
+/// Balances for many wallets using Multicall3: one round-trip per asset instead of one per wallet and asset.
+/// A read that fails inside the batch yields a null `balance` with an `error` rather than a zero balance.
+pub async fn get_balances_bulk(
+    State(state): State<AppState>,
+    Json(payload): Json<BulkBalanceRequest>,
+) -> Result<Json<Vec<BalanceResponse>>, (StatusCode, String)> {
+    if payload.wallet_ids.len() > MAX_BATCH_READ {
+        return Err((StatusCode::BAD_REQUEST, format!("At most {} wallets per request", MAX_BATCH_READ)));
+    }
+
+    let wallets = sqlx::query_as::<_, Wallet>("SELECT * FROM wallets WHERE id = ANY($1) ORDER BY id")
+        .bind(&payload.wallet_ids)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if let Some(missing) = payload.wallet_ids.iter().find(|id| !wallets.iter().any(|w| w.id == **id)) {
+        return Err((StatusCode::NOT_FOUND, format!("Wallet {} not found", missing)));
+    }
+
+    let holders = wallets.iter()
+        .map(|w| w.address.parse::<ethers::types::Address>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let native = state.chain.batch_native_balances(&holders)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut responses: Vec<BalanceResponse> = wallets.iter()
+        .zip(native)
+        .map(|(wallet, balance)| BalanceResponse {
+            wallet_id: wallet.id,
+            address: wallet.address.clone(),
+            balances: vec![TokenBalance {
+                symbol: NATIVE_SYMBOL.to_string(),
+                token_address: None,
+                decimals: NATIVE_DECIMALS,
+                balance: balance.map(|b| format_amount(b, NATIVE_DECIMALS)),
+                error: balance.is_none().then(|| BALANCE_READ_FAILED.to_string()),
+            }],
+        })
+        .collect();
+
+    let tokens = sqlx::query_as::<_, Token>("SELECT * FROM tokens WHERE chain_id = $1 AND allowed ORDER BY symbol")
+        .bind(state.chain.chain_id() as i64)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for token in tokens {
+        let token_address = token.address.parse()
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, format!("Bad token address {}", token.address)))?;
+        let balances = state.chain.batch_token_balances(token_address, &holders)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+        for (response, balance) in responses.iter_mut().zip(balances) {
+            response.balances.push(TokenBalance {
+                symbol: token.symbol.clone(),
+                token_address: Some(token.address.clone()),
+                decimals: token.decimals as u8,
+                balance: balance.map(|b| format_amount(b, token.decimals as u8)),
+                error: balance.is_none().then(|| BALANCE_READ_FAILED.to_string()),
+            });
+        }
+    }
+
+    Ok(Json(responses))
+}
+
+/// Honours `Idempotency-Key` so a retried funding request is never sent twice.
 pub async fn fund_wallet(
     State(state): State<AppState>,
+    Claims(claims): Claims,
+    headers: HeaderMap,
     Json(payload): Json<FundRequest>,
+) -> Response {
+    idempotent(
+        &state.pool, &headers, &claims.sub, "finance.fund", &payload,
+        |key| send_funds(&state, &payload, &claims.sub, key),
+        |resource| interrupted_funding(&state, resource),
+    ).await
+}
+
+async fn send_funds(
+    state: &AppState,
+    payload: &FundRequest,
+    actor: &str,
+    key: ClaimedKey,
 ) -> Result<Json<FundResponse>, (StatusCode, String)> {
-    let tx_hash = state.chain.fund_wallet(&payload.wallet_address, &payload.amount_eth)
+    let wallet = find_wallet(&state.pool, payload.wallet_id).await?;
+    let value = parse_amount(&payload.amount_eth, NATIVE_DECIMALS)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let tx_hash = state.chain.fund_wallet(&wallet.address, value)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    let funding_id: i32 = sqlx::query_scalar(
+        "INSERT INTO wallet_fundings (wallet_id, amount, tx_hash, funded_by) VALUES ($1, $2, $3, $4) RETURNING id"
+    )
+    .bind(wallet.id)
+    .bind(format_amount(value, NATIVE_DECIMALS))
+    .bind(&tx_hash)
+    .bind(actor)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    key.record(&format!("wallet_funding:{}", funding_id)).await?;
+
     Ok(Json(FundResponse {
         tx_hash,
         status: "Funded".to_string(),
+        funding_id,
     }))
 }
+
+/// Reports the funding an interrupted request recorded.
+async fn interrupted_funding(state: &AppState, resource: String) -> Result<Json<FundResponse>, (StatusCode, String)> {
+    let funding = sqlx::query_as::<_, WalletFunding>("SELECT * FROM wallet_fundings WHERE id = $1")
+        .bind(resource_id::<i32>(&resource, "wallet_funding")?)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(FundResponse {
+        tx_hash: funding.tx_hash,
+        status: "Funded".to_string(),
+        funding_id: funding.id,
+    }))
+}
+
+pub async fn list_fundings(
+    State(state): State<AppState>,
+    Query(filter): Query<FundingFilter>,
+) -> Result<Json<Vec<WalletFunding>>, (StatusCode, String)> {
+    let fundings = sqlx::query_as::<_, WalletFunding>(
+        "SELECT * FROM wallet_fundings WHERE ($1::INTEGER IS NULL OR wallet_id = $1) ORDER BY id DESC"
+    )
+    .bind(filter.wallet_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(fundings))
+}
+
+pub async fn create_wallet(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateWalletRequest>,
+) -> Result<(StatusCode, Json<Wallet>), (StatusCode, String)> {
+    let entity_exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM legal_entities WHERE id = $1)")
+        .bind(payload.entity_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if !entity_exists {
+        return Err((StatusCode::NOT_FOUND, "Entity not found".to_string()));
+    }
+
+    payload.rules_contract.parse::<ethers::types::Address>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid rules contract address".to_string()))?;
+
+    let (address, deploy_tx_hash) = state.chain.deploy_wallet()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Wallet deployment failed: {}", e)))?;
+
+    // Recorded before the rules are linked, so a failed link leaves a wallet that can be retried
+    let wallet = sqlx::query_as::<_, Wallet>(
+        r#"
+        INSERT INTO wallets (address, entity_id, owner_address, rules_contract, chain_id, deploy_tx_hash, label, status)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, 'incomplete')
+        RETURNING *
+        "#
+    )
+    .bind(address)
+    .bind(payload.entity_id)
+    .bind(format!("{:?}", state.chain.signer_address()))
+    .bind(payload.rules_contract.to_lowercase())
+    .bind(state.chain.chain_id() as i64)
+    .bind(deploy_tx_hash)
+    .bind(payload.label)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    state.chain.set_wallet_rules(&wallet.address, &wallet.rules_contract)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!(
+            "Wallet {} deployed at {} but linking its rules contract failed: {}; retry with PUT /wallets/{}/rules",
+            wallet.id, wallet.address, e, wallet.id
+        )))?;
+
+    let wallet = sqlx::query_as::<_, Wallet>("UPDATE wallets SET status = 'active' WHERE id = $1 RETURNING *")
+        .bind(wallet.id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(wallet)))
+}
+
+pub async fn list_wallets(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Wallet>>, (StatusCode, String)> {
+    let wallets = sqlx::query_as::<_, Wallet>("SELECT * FROM wallets ORDER BY created_at DESC")
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(wallets))
+}
+
+pub async fn get_wallet(
+    State(state): State<AppState>,
+    Path(wallet_id): Path<i32>,
+) -> Result<Json<Wallet>, (StatusCode, String)> {
+    Ok(Json(find_wallet(&state.pool, wallet_id).await?))
+}
+
+/// Indexed balance changes of a wallet in chain order, optionally limited to `[from, to)`.
+pub async fn list_wallet_movements(
+    State(state): State<AppState>,
+    Path(wallet_id): Path<i32>,
+    Query(filter): Query<MovementFilter>,
+) -> Result<Json<Vec<WalletMovement>>, (StatusCode, String)> {
+    let wallet = find_wallet(&state.pool, wallet_id).await?;
+
+    let movements = sqlx::query_as::<_, WalletMovement>(
+        r#"
+        SELECT * FROM wallet_movements
+        WHERE wallet_id = $1 AND ($2::TIMESTAMPTZ IS NULL OR block_time >= $2) AND ($3::TIMESTAMPTZ IS NULL OR block_time < $3)
+        ORDER BY block_number, log_index
+        "#
+    )
+    .bind(wallet.id)
+    .bind(filter.from)
+    .bind(filter.to)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(movements))
+}
+
+pub async fn update_wallet_rules(
+    State(state): State<AppState>,
+    Path(wallet_id): Path<i32>,
+    Json(payload): Json<UpdateRulesRequest>,
+) -> Result<Json<Wallet>, (StatusCode, String)> {
+    let wallet = find_wallet(&state.pool, wallet_id).await?;
+
+    state.chain.set_wallet_rules(&wallet.address, &payload.rules_contract)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let wallet = sqlx::query_as::<_, Wallet>("UPDATE wallets SET rules_contract = $1, status = 'active' WHERE id = $2 RETURNING *")
+        .bind(payload.rules_contract.to_lowercase())
+        .bind(wallet_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(wallet))
+}
+
+pub async fn register_token(
+    State(state): State<AppState>,
+    Json(payload): Json<RegisterTokenRequest>,
+) -> Result<(StatusCode, Json<Token>), (StatusCode, String)> {
+    if let Some(code) = &payload.iso_currency {
+        validate_currency(code).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
+
+    // Symbol and decimals always come from the contract, never from the caller
+    let (symbol, decimals) = state.chain.get_token_info(&payload.address)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Not a readable ERC-20 contract: {}", e)))?;
+
+    let token = sqlx::query_as::<_, Token>(
+        r#"
+        INSERT INTO tokens (address, symbol, decimals, chain_id, allowed, iso_currency)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (address, chain_id) DO UPDATE
+        SET symbol = EXCLUDED.symbol, decimals = EXCLUDED.decimals, allowed = EXCLUDED.allowed, iso_currency = EXCLUDED.iso_currency
+        RETURNING *
+        "#
+    )
+    .bind(payload.address.to_lowercase())
+    .bind(symbol)
+    .bind(decimals as i16)
+    .bind(state.chain.chain_id() as i64)
+    .bind(payload.allowed.unwrap_or(true))
+    .bind(payload.iso_currency)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(token)))
+}
+
+pub async fn list_tokens(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Token>>, (StatusCode, String)> {
+    let tokens = sqlx::query_as::<_, Token>("SELECT * FROM tokens ORDER BY chain_id, symbol")
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(tokens))
+}
+
+pub async fn update_token(
+    State(state): State<AppState>,
+    Path(token_id): Path<i32>,
+    Json(payload): Json<UpdateTokenRequest>,
+) -> Result<Json<Token>, (StatusCode, String)> {
+    if let Some(code) = &payload.iso_currency {
+        validate_currency(code).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
+
+    let token = sqlx::query_as::<_, Token>(
+        "UPDATE tokens SET allowed = COALESCE($1, allowed), iso_currency = COALESCE($2, iso_currency) WHERE id = $3 RETURNING *"
+    )
+    .bind(payload.allowed)
+    .bind(payload.iso_currency)
+    .bind(token_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::NOT_FOUND, "Token not found".to_string()))?;
+
+    Ok(Json(token))
+}
@@ -0,0 +1,260 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use ethers::types::Address;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::HashMap;
+use crate::state::AppState;
+use super::models::{Token, Wallet};
+
+/// How often the indexer polls for new blocks.
+const POLL_INTERVAL_SECS: u64 = 15;
+
+/// Blocks per `eth_getLogs` request; public RPC providers commonly cap ranges around this size.
+const BLOCK_RANGE: u64 = 2_000;
+
+/// Indexes wallet movements into `wallet_movements`. Starts at `start_block` on a fresh database and
+/// stays `confirmations` blocks behind the head so reorganized blocks are not recorded.
+pub async fn run(state: AppState, start_block: u64, confirmations: u64) {
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(POLL_INTERVAL_SECS));
+    loop {
+        ticker.tick().await;
+        if let Err((_, e)) = catch_up(&state, start_block, confirmations).await {
+            println!("Indexer error: {}", e);
+        }
+    }
+}
+
+/// Time up to which the indexer has recorded every movement, if it has caught up with the chain at all.
+pub async fn synced_at(pool: &PgPool, chain_id: i64) -> Result<Option<DateTime<Utc>>, (StatusCode, String)> {
+    let synced: Option<Option<DateTime<Utc>>> = sqlx::query_scalar("SELECT synced_at FROM chain_cursors WHERE chain_id = $1")
+        .bind(chain_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(synced.flatten())
+}
+
+/// Whether the wallet's own history, or that of a token registered on its chain, is still to be backfilled,
+/// so movements up to [`synced_at`] may be missing.
+pub async fn backfill_pending(pool: &PgPool, wallet_id: i32) -> Result<bool, (StatusCode, String)> {
+    sqlx::query_scalar(
+        r#"
+        SELECT NOT w.backfilled OR EXISTS (SELECT 1 FROM tokens t WHERE t.chain_id = w.chain_id AND NOT t.backfilled)
+        FROM wallets w WHERE w.id = $1
+        "#
+    )
+    .bind(wallet_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn catch_up(state: &AppState, start_block: u64, confirmations: u64) -> Result<(), (StatusCode, String)> {
+    let chain_id = state.chain.chain_id() as i64;
+    let head = state.chain.block_number().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?.saturating_sub(confirmations);
+
+    let last_block: Option<i64> = sqlx::query_scalar("SELECT last_block FROM chain_cursors WHERE chain_id = $1")
+        .bind(chain_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let mut from = match last_block {
+        Some(last_block) => {
+            backfill(state, chain_id, last_block as u64).await?;
+            last_block as u64 + 1
+        }
+        // A fresh database indexes every registered wallet and token from `start_block`
+        None => {
+            for table in ["wallets", "tokens"] {
+                sqlx::query(&format!("UPDATE {} SET backfilled = TRUE WHERE chain_id = $1", table))
+                    .bind(chain_id)
+                    .execute(&state.pool)
+                    .await
+                    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            }
+            start_block
+        }
+    };
+
+    while from <= head {
+        let to = head.min(from + BLOCK_RANGE - 1);
+        index_range(state, chain_id, from, to).await?;
+        from = to + 1;
+    }
+
+    // Without confirmations the indexer is at the head, so nothing mined up to now is missing. Otherwise the
+    // unconfirmed blocks are still outstanding and only the last indexed block's time is covered.
+    let synced_at = match confirmations {
+        0 => Utc::now(),
+        _ => {
+            let timestamp = state.chain.block_timestamp(head).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            DateTime::from_timestamp(timestamp as i64, 0).unwrap_or_default()
+        }
+    };
+    sqlx::query("UPDATE chain_cursors SET synced_at = $2 WHERE chain_id = $1")
+        .bind(chain_id)
+        .bind(synced_at)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(())
+}
+
+/// Indexes the history the cursor passed before a wallet or token was registered: a new wallet from the
+/// block it was deployed in, a new token for the already indexed wallets from the earliest of their
+/// deployments, both up to `last_block`. Movements recorded twice are skipped.
+async fn backfill(state: &AppState, chain_id: i64, last_block: u64) -> Result<(), (StatusCode, String)> {
+    let new_wallets = wallets(&state.pool, chain_id, Some(false)).await?;
+    let new_tokens = tokens(&state.pool, chain_id, Some(false)).await?;
+    if new_wallets.is_empty() && new_tokens.is_empty() {
+        return Ok(());
+    }
+
+    let all_tokens = tokens(&state.pool, chain_id, None).await?;
+    for wallet in &new_wallets {
+        let deployed = deployment_block(state, wallet).await?;
+        scan(state, chain_id, std::slice::from_ref(wallet), &all_tokens, deployed, last_block).await?;
+    }
+
+    if !new_tokens.is_empty() {
+        let indexed = wallets(&state.pool, chain_id, Some(true)).await?;
+        let mut from = None;
+        for wallet in &indexed {
+            let deployed = deployment_block(state, wallet).await?;
+            from = Some(from.map_or(deployed, |from: u64| from.min(deployed)));
+        }
+        if let Some(from) = from {
+            scan(state, chain_id, &indexed, &new_tokens, from, last_block).await?;
+        }
+    }
+
+    let wallet_ids: Vec<i32> = new_wallets.iter().map(|w| w.id).collect();
+    let token_ids: Vec<i32> = new_tokens.iter().map(|t| t.id).collect();
+    for (table, ids) in [("wallets", wallet_ids), ("tokens", token_ids)] {
+        sqlx::query(&format!("UPDATE {} SET backfilled = TRUE WHERE id = ANY($1)", table))
+            .bind(ids)
+            .execute(&state.pool)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+    Ok(())
+}
+
+async fn deployment_block(state: &AppState, wallet: &Wallet) -> Result<u64, (StatusCode, String)> {
+    state.chain.transaction_block(&wallet.deploy_tx_hash).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Deployment of wallet {}: {}", wallet.id, e)))
+}
+
+/// Records the movements of `wallets` in `from..=to` without moving the cursor.
+async fn scan(state: &AppState, chain_id: i64, wallets: &[Wallet], tokens: &[Token], mut from: u64, to: u64) -> Result<(), (StatusCode, String)> {
+    while from <= to {
+        let end = to.min(from + BLOCK_RANGE - 1);
+        let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        record(state, &mut tx, chain_id, wallets, tokens, from, end).await?;
+        tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        from = end + 1;
+    }
+    Ok(())
+}
+
+/// Wallets of the chain, optionally only those whose history has (or has not) been backfilled.
+async fn wallets(pool: &PgPool, chain_id: i64, backfilled: Option<bool>) -> Result<Vec<Wallet>, (StatusCode, String)> {
+    sqlx::query_as::<_, Wallet>("SELECT * FROM wallets WHERE chain_id = $1 AND ($2::BOOLEAN IS NULL OR backfilled = $2) ORDER BY id")
+        .bind(chain_id)
+        .bind(backfilled)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Tokens of the chain, filtered like [`wallets`]. Disallowed tokens are included: a wallet can hold them
+/// even if agents may not pay with them.
+async fn tokens(pool: &PgPool, chain_id: i64, backfilled: Option<bool>) -> Result<Vec<Token>, (StatusCode, String)> {
+    sqlx::query_as::<_, Token>("SELECT * FROM tokens WHERE chain_id = $1 AND ($2::BOOLEAN IS NULL OR backfilled = $2) ORDER BY id")
+        .bind(chain_id)
+        .bind(backfilled)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Records the movements in `from..=to` and advances the cursor in one transaction.
+async fn index_range(state: &AppState, chain_id: i64, from: u64, to: u64) -> Result<(), (StatusCode, String)> {
+    let wallets = wallets(&state.pool, chain_id, None).await?;
+    let tokens = tokens(&state.pool, chain_id, None).await?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    record(state, &mut tx, chain_id, &wallets, &tokens, from, to).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO chain_cursors (chain_id, last_block) VALUES ($1, $2)
+        ON CONFLICT (chain_id) DO UPDATE SET last_block = EXCLUDED.last_block, updated_at = NOW()
+        "#
+    )
+    .bind(chain_id)
+    .bind(to as i64)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Records the movements of `wallets` in the native asset and `tokens` in blocks `from..=to`.
+async fn record(
+    state: &AppState,
+    tx: &mut Transaction<'_, Postgres>,
+    chain_id: i64,
+    wallets: &[Wallet],
+    tokens: &[Token],
+    from: u64,
+    to: u64,
+) -> Result<(), (StatusCode, String)> {
+    let wallet_ids: HashMap<Address, i32> = wallets.iter()
+        .filter_map(|w| Some((w.address.parse().ok()?, w.id)))
+        .collect();
+    let wallet_addresses: Vec<Address> = wallet_ids.keys().copied().collect();
+    let token_addresses: Vec<Address> = tokens.iter().filter_map(|t| t.address.parse().ok()).collect();
+
+    let logs = state.chain.wallet_logs(&wallet_addresses, &token_addresses, from, to).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut block_times = HashMap::new();
+    for log in logs {
+        let Some(wallet_id) = wallet_ids.get(&log.wallet) else { continue };
+        let block_time = match block_times.get(&log.block_number) {
+            Some(time) => *time,
+            None => {
+                let timestamp = state.chain.block_timestamp(log.block_number).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+                let time = DateTime::from_timestamp(timestamp as i64, 0).unwrap_or_default();
+                block_times.insert(log.block_number, time);
+                time
+            }
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO wallet_movements
+                (wallet_id, chain_id, block_number, block_time, tx_hash, log_index, event, token_address, direction, amount, counterparty)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT DO NOTHING
+            "#
+        )
+        .bind(wallet_id)
+        .bind(chain_id)
+        .bind(log.block_number as i64)
+        .bind(block_time)
+        .bind(format!("{:?}", log.tx_hash))
+        .bind(log.log_index as i32)
+        .bind(log.event)
+        .bind(log.token.map(|t| format!("{:?}", t)))
+        .bind(if log.credit { "credit" } else { "debit" })
+        .bind(log.amount.to_string())
+        .bind(format!("{:?}", log.counterparty))
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+    Ok(())
+}
@@ -1,14 +1,26 @@
 use axum::{
-    routing::{get, post},
+    routing::{get, post, put},
     Router,
 };
 use crate::state::AppState;
 
+pub mod amounts;
 pub mod handlers;
+pub mod indexer;
 pub mod models;
+pub mod pricing;
+pub mod registry;
 
 pub fn router() -> Router<AppState> {
     Router::new()
-        .route("/balance/:address", get(handlers::get_balance))
+        .route("/balance/:wallet_id", get(handlers::get_balance))
+        .route("/balances/bulk", post(handlers::get_balances_bulk))
         .route("/fund", post(handlers::fund_wallet))
+        .route("/fundings", get(handlers::list_fundings))
+        .route("/wallets", post(handlers::create_wallet).get(handlers::list_wallets))
+        .route("/wallets/:wallet_id", get(handlers::get_wallet))
+        .route("/wallets/:wallet_id/rules", put(handlers::update_wallet_rules))
+        .route("/wallets/:wallet_id/movements", get(handlers::list_wallet_movements))
+        .route("/tokens", post(handlers::register_token).get(handlers::list_tokens))
+        .route("/tokens/:token_id", put(handlers::update_token))
 }
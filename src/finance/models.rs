@@ -1,14 +1,31 @@
 use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
 
 #[derive(Serialize)]
 pub struct BalanceResponse {
+    pub wallet_id: i32,
     pub address: String,
-    pub balance_wei: String,
+    pub balances: Vec<TokenBalance>,
+}
+
+/// A single asset balance. `token_address` is `None` for the native asset.
+#[derive(Serialize)]
+pub struct TokenBalance {
+    pub symbol: String,
+    pub token_address: Option<String>,
+    pub decimals: u8,
+    pub balance: Option<String>, // None if the read failed
+    pub error: Option<String>,
 }
 
 #[derive(Deserialize)]
+pub struct BulkBalanceRequest {
+    pub wallet_ids: Vec<i32>,
+}
+
+#[derive(Deserialize, Serialize)]
 pub struct FundRequest {
-    pub wallet_address: String,
+    pub wallet_id: i32,
     pub amount_eth: String,
 }
 
@@ -16,4 +33,100 @@ pub struct FundRequest {
 pub struct FundResponse {
     pub tx_hash: String,
     pub status: String,
+    pub funding_id: i32,
+}
+
+/// A confirmed transfer from the backend signer into a wallet; `amount` is in ETH.
+#[derive(Debug, Serialize, FromRow)]
+pub struct WalletFunding {
+    pub id: i32,
+    pub wallet_id: i32,
+    pub amount: String,
+    pub tx_hash: String,
+    pub funded_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct FundingFilter {
+    pub wallet_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Wallet {
+    pub id: i32,
+    pub address: String,
+    pub entity_id: i32,
+    pub owner_address: String,
+    pub rules_contract: String,
+    pub chain_id: i64,
+    pub deploy_tx_hash: String,
+    pub label: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub counterparty_mode: String,
+    /// "incomplete" until the rules contract is linked on-chain, then "active".
+    pub status: String,
+}
+
+#[derive(Deserialize)]
+pub struct CreateWalletRequest {
+    pub entity_id: i32,
+    pub rules_contract: String,
+    pub label: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateRulesRequest {
+    pub rules_contract: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct Token {
+    pub id: i32,
+    pub address: String,
+    pub symbol: String,
+    pub decimals: i16,
+    pub chain_id: i64,
+    pub allowed: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub iso_currency: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RegisterTokenRequest {
+    pub address: String,
+    pub allowed: Option<bool>,
+    pub iso_currency: Option<String>,
+}
+
+/// Omitted fields are left unchanged.
+#[derive(Deserialize)]
+pub struct UpdateTokenRequest {
+    pub allowed: Option<bool>,
+    pub iso_currency: Option<String>,
+}
+
+/// A balance change of a wallet, indexed from chain logs. `amount` is in base units of the asset;
+/// `token_address` is `None` for the native asset.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct WalletMovement {
+    pub id: i32,
+    pub wallet_id: i32,
+    pub chain_id: i64,
+    pub block_number: i64,
+    pub block_time: chrono::DateTime<chrono::Utc>,
+    pub tx_hash: String,
+    pub log_index: i32,
+    pub event: String,
+    pub token_address: Option<String>,
+    pub direction: String,
+    pub amount: String,
+    pub counterparty: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct MovementFilter {
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
 }
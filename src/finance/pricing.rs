@@ -0,0 +1,77 @@
+use axum::async_trait;
+use ethers::types::{Address, U256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::chain::ChainClient;
+use super::amounts::{parse_amount, NATIVE_SYMBOL};
+
+/// Prices are quoted per whole unit of an asset in the reference currency, scaled like `IPriceOracle`.
+pub const PRICE_DECIMALS: u8 = 18;
+
+/// Where the backend gets asset prices for value-denominated limits.
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    /// Price of one whole unit of `asset` (`None` for native ETH), `PRICE_DECIMALS` scaled.
+    async fn price(&self, asset: Option<Address>) -> Result<U256, String>;
+}
+
+/// Fixed prices, e.g. from `PRICE_FIXTURES="ETH=3000,0xa0b8...=1"`. Meant for tests and local nodes.
+#[derive(Default)]
+pub struct StaticPrices {
+    prices: HashMap<Option<Address>, U256>,
+}
+
+impl StaticPrices {
+    /// Parses comma-separated `ASSET=PRICE` pairs, where ASSET is `ETH` or a token address.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut prices = HashMap::new();
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (asset, price) = pair.split_once('=')
+                .ok_or(format!("Invalid price fixture '{}', expected ASSET=PRICE", pair))?;
+            let asset = match asset.trim() {
+                a if a.eq_ignore_ascii_case(NATIVE_SYMBOL) => None,
+                a => Some(a.parse::<Address>().map_err(|_| format!("Invalid asset '{}' in price fixtures", a))?),
+            };
+            prices.insert(asset, parse_amount(price, PRICE_DECIMALS)?);
+        }
+        Ok(Self { prices })
+    }
+}
+
+#[async_trait]
+impl PriceSource for StaticPrices {
+    async fn price(&self, asset: Option<Address>) -> Result<U256, String> {
+        self.prices.get(&asset).copied().ok_or_else(|| format!("No price for {}", asset_label(asset)))
+    }
+}
+
+/// Reads an on-chain `IPriceOracle`, the same kind of source `AegisRules` prices value limits with.
+pub struct OnChainOracle {
+    chain: Arc<ChainClient>,
+    oracle: Address,
+}
+
+impl OnChainOracle {
+    pub fn new(chain: Arc<ChainClient>, oracle_addr: &str) -> Result<Self, String> {
+        let oracle = oracle_addr.parse().map_err(|_| format!("Invalid price oracle address '{}'", oracle_addr))?;
+        Ok(Self { chain, oracle })
+    }
+}
+
+#[async_trait]
+impl PriceSource for OnChainOracle {
+    async fn price(&self, asset: Option<Address>) -> Result<U256, String> {
+        self.chain.get_oracle_price(self.oracle, asset.unwrap_or_default())
+            .await
+            .map_err(|e| format!("No price for {}: {}", asset_label(asset), e))
+    }
+}
+
+/// Reference-currency value of `amount` base units, mirroring `AegisRules.valueOf`.
+pub fn value_of(price: U256, amount: U256, decimals: u8) -> U256 {
+    amount.saturating_mul(price) / U256::exp10(decimals as usize)
+}
+
+fn asset_label(asset: Option<Address>) -> String {
+    asset.map_or(NATIVE_SYMBOL.to_string(), |a| format!("{:?}", a))
+}
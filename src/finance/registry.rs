@@ -0,0 +1,30 @@
+use axum::http::StatusCode;
+use sqlx::PgPool;
+use super::models::{Token, Wallet};
+
+/// Resolves a registered wallet by id. Other modules use this instead of accepting raw addresses.
+pub async fn find_wallet(pool: &PgPool, wallet_id: i32) -> Result<Wallet, (StatusCode, String)> {
+    sqlx::query_as::<_, Wallet>("SELECT * FROM wallets WHERE id = $1")
+        .bind(wallet_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Wallet {} not found", wallet_id)))
+}
+
+/// Resolves a token on the given chain and ensures it is allowed for payments.
+pub async fn find_allowed_token(pool: &PgPool, address: &str, chain_id: u64) -> Result<Token, (StatusCode, String)> {
+    let token = sqlx::query_as::<_, Token>("SELECT * FROM tokens WHERE address = $1 AND chain_id = $2")
+        .bind(address.to_lowercase())
+        .bind(chain_id as i64)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::BAD_REQUEST, format!("Token {} is not registered", address)))?;
+
+    if !token.allowed {
+        return Err((StatusCode::FORBIDDEN, format!("Token {} is not allowed", token.symbol)));
+    }
+
+    Ok(token)
+}
@@ -0,0 +1,178 @@
+use axum::http::StatusCode;
+use chrono::{Timelike, Utc};
+use ethers::types::{Address, U256};
+use sqlx::PgPool;
+use crate::state::AppState;
+use crate::finance::registry::find_allowed_token;
+use crate::finance::amounts::{parse_amount, NATIVE_DECIMALS};
+use crate::finance::pricing::value_of;
+use crate::agent::models::PaymentIntent;
+use super::limits::Spend;
+use super::models::{Agent, AgentDetail};
+
+/// Users with this role can only pay as a registered, active agent.
+pub const AGENT_ROLE: &str = "agent";
+
+pub const STATUS_ACTIVE: &str = "active";
+pub const STATUS_SUSPENDED: &str = "suspended";
+
+/// Intent states that count towards an agent's daily limit.
+const SPENDING_STATUSES: &[&str] = &["screened", "pending_approval", "approved", "submitted", "confirmed"];
+
+pub async fn find_agent(pool: &PgPool, agent_id: i32) -> Result<AgentDetail, (StatusCode, String)> {
+    let agent = sqlx::query_as::<_, Agent>("SELECT * FROM agents WHERE id = $1")
+        .bind(agent_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Agent {} not found", agent_id)))?;
+
+    let wallet_ids = sqlx::query_scalar("SELECT wallet_id FROM agent_wallets WHERE agent_id = $1 ORDER BY wallet_id")
+        .bind(agent_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(AgentDetail { agent, wallet_ids })
+}
+
+/// Checks that `actor` may make `spends` from `wallet_id`.
+/// Actors registered as agents must be active, hold a grant for the wallet and stay within their limits;
+/// API users with the agent role but no registry entry are refused. Other users are not agents and pass.
+/// Agent limits are in ETH: each spend must fit the per-transaction limit and all of them the daily one,
+/// with tokens valued in ETH at the backend's prices.
+pub async fn authorize(state: &AppState, actor: &str, wallet_id: i32, spends: &[Spend]) -> Result<(), String> {
+    check_agent(state, actor, wallet_id, spends, None).await
+}
+
+/// `authorize` for an intent about to be submitted, so a suspension, revoked grant or lowered limit since it
+/// was screened still stops it. The intent already counts towards the day's spending and is left out of it.
+pub async fn reauthorize(state: &AppState, intent: &PaymentIntent, amount: U256) -> Result<(), String> {
+    let spend = Spend { token_address: intent.token_address.clone(), amount };
+    check_agent(state, &intent.initiated_by, intent.wallet_id, &[spend], Some(intent.id)).await
+}
+
+async fn check_agent(state: &AppState, actor: &str, wallet_id: i32, spends: &[Spend], counted_intent: Option<i32>) -> Result<(), String> {
+    let agent = sqlx::query_as::<_, Agent>("SELECT * FROM agents WHERE username = $1")
+        .bind(actor)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let Some(agent) = agent else {
+        let role: Option<String> = sqlx::query_scalar("SELECT role FROM users WHERE username = $1")
+            .bind(actor)
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        return match role.as_deref() {
+            Some(AGENT_ROLE) => Err(format!("{} is not a registered agent", actor)),
+            _ => Ok(()),
+        };
+    };
+
+    if agent.status != STATUS_ACTIVE {
+        return Err(format!(
+            "Agent {} is {}{}",
+            agent.name,
+            agent.status,
+            agent.status_reason.as_ref().map(|r| format!(": {}", r)).unwrap_or_default()
+        ));
+    }
+
+    let granted: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM agent_wallets WHERE agent_id = $1 AND wallet_id = $2)")
+        .bind(agent.id)
+        .bind(wallet_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !granted {
+        return Err(format!("Agent {} may not use wallet {}", agent.name, wallet_id));
+    }
+
+    if agent.per_tx_limit_eth.is_none() && agent.daily_limit_eth.is_none() {
+        return Ok(());
+    }
+
+    let mut total = U256::zero();
+    for spend in spends {
+        let decimals = token_decimals(state, spend.token_address.as_deref()).await?;
+        let value = eth_value(state, spend.token_address.as_deref(), spend.amount, decimals).await?;
+        if let Some(limit) = &agent.per_tx_limit_eth {
+            if value > parse_amount(limit, NATIVE_DECIMALS)? {
+                return Err(format!("Amount exceeds agent {}'s per-transaction limit of {} ETH", agent.name, limit));
+            }
+        }
+        total = total.saturating_add(value);
+    }
+
+    if let Some(limit) = &agent.daily_limit_eth {
+        let spent = spent_today(state, &agent.username, counted_intent).await?;
+        if spent.saturating_add(total) > parse_amount(limit, NATIVE_DECIMALS)? {
+            return Err(format!("Amount exceeds agent {}'s daily limit of {} ETH", agent.name, limit));
+        }
+    }
+
+    Ok(())
+}
+
+async fn token_decimals(state: &AppState, token_address: Option<&str>) -> Result<u8, String> {
+    match token_address {
+        Some(token_addr) => Ok(find_allowed_token(&state.pool, token_addr, state.chain.chain_id())
+            .await
+            .map_err(|(_, e)| e)?
+            .decimals as u8),
+        None => Ok(NATIVE_DECIMALS),
+    }
+}
+
+/// Wei-equivalent of `amount` base units of `token_address` (ETH itself when `None`).
+async fn eth_value(state: &AppState, token_address: Option<&str>, amount: U256, decimals: u8) -> Result<U256, String> {
+    let Some(token_addr) = token_address else {
+        return Ok(amount);
+    };
+    let token = token_addr.parse::<Address>().map_err(|_| format!("Invalid token address '{}'", token_addr))?;
+
+    let value = value_of(state.prices.price(Some(token)).await?, amount, decimals);
+    let eth_price = state.prices.price(None).await?;
+    if eth_price.is_zero() {
+        return Err("ETH price is zero".to_string());
+    }
+    Ok(value.saturating_mul(U256::exp10(NATIVE_DECIMALS as usize)) / eth_price)
+}
+
+/// Wei-equivalent total of the agent's payments since midnight UTC (excluding intents still being created).
+async fn spent_today(state: &AppState, username: &str, excluded_intent: Option<i32>) -> Result<U256, String> {
+    let now = Utc::now();
+    let midnight = now - chrono::Duration::seconds(now.num_seconds_from_midnight() as i64);
+
+    let rows = sqlx::query_as::<_, (String, Option<String>, Option<i16>)>(
+        r#"
+        SELECT i.amount, i.token_address, t.decimals
+        FROM payment_intents i
+        LEFT JOIN tokens t ON t.address = i.token_address AND t.chain_id = $1
+        WHERE i.initiated_by = $2 AND i.created_at >= $3 AND i.status = ANY($4) AND ($5::INTEGER IS NULL OR i.id <> $5)
+        "#
+    )
+    .bind(state.chain.chain_id() as i64)
+    .bind(username)
+    .bind(midnight)
+    .bind(SPENDING_STATUSES)
+    .bind(excluded_intent)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut spent = U256::zero();
+    for (amount, token_address, decimals) in rows {
+        let decimals = match (&token_address, decimals) {
+            (None, _) => NATIVE_DECIMALS,
+            (Some(_), Some(d)) => d as u8,
+            (Some(t), None) => return Err(format!("Token {} is not registered", t)),
+        };
+        let Ok(amount) = parse_amount(&amount, decimals) else { continue };
+        spent = spent.saturating_add(eth_value(state, token_address.as_deref(), amount, decimals).await?);
+    }
+    Ok(spent)
+}
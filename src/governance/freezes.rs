@@ -0,0 +1,279 @@
+use axum::http::StatusCode;
+use ethers::types::U256;
+use sqlx::PgPool;
+use crate::state::AppState;
+use crate::finance::models::Wallet;
+use crate::finance::registry::find_wallet;
+use super::agents;
+use super::models::{Agent, CreateFreezeRequest, Freeze, FreezeAction, FreezeDetail};
+
+pub const SCOPE_AGENT: &str = "agent";
+pub const SCOPE_WALLET: &str = "wallet";
+pub const SCOPE_ENTITY: &str = "entity";
+pub const SCOPE_GLOBAL: &str = "global";
+
+/// Blocks a payment from `wallet` (initiated by `actor`, when known) if any active freeze covers it,
+/// or if the wallet's rules contract was never linked.
+pub async fn check(pool: &PgPool, wallet: &Wallet, actor: Option<&str>) -> Result<(), String> {
+    if wallet.status != "active" {
+        return Err(format!("Wallet {} is {}: link its rules contract first", wallet.id, wallet.status));
+    }
+
+    let freeze = sqlx::query_as::<_, Freeze>(
+        r#"
+        SELECT * FROM freezes
+        WHERE lifted_at IS NULL AND (
+            scope = 'global'
+            OR (scope = 'wallet' AND target_id = $1)
+            OR (scope = 'entity' AND target_id = $2)
+            OR (scope = 'agent' AND target_id = (SELECT id FROM agents WHERE username = $3))
+        )
+        ORDER BY id
+        LIMIT 1
+        "#
+    )
+    .bind(wallet.id)
+    .bind(wallet.entity_id)
+    .bind(actor)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    match freeze {
+        Some(f) => Err(format!("Payments are frozen ({} freeze {}): {}", f.scope, f.id, f.reason)),
+        None => Ok(()),
+    }
+}
+
+pub async fn find_freeze(pool: &PgPool, freeze_id: i32) -> Result<FreezeDetail, (StatusCode, String)> {
+    let freeze = sqlx::query_as::<_, Freeze>("SELECT * FROM freezes WHERE id = $1")
+        .bind(freeze_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Freeze {} not found", freeze_id)))?;
+
+    let actions = sqlx::query_as::<_, FreezeAction>("SELECT * FROM freeze_actions WHERE freeze_id = $1 ORDER BY id")
+        .bind(freeze_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(FreezeDetail { freeze, actions })
+}
+
+/// Wallets to pause and agents to block on a wallet's rules contract for a scope.
+struct Targets {
+    pause: Vec<Wallet>,
+    block: Vec<(Wallet, Agent)>,
+}
+
+async fn targets(pool: &PgPool, scope: &str, target_id: Option<i32>) -> Result<Targets, (StatusCode, String)> {
+    let wallets_query = match scope {
+        SCOPE_AGENT => "SELECT w.* FROM wallets w JOIN agent_wallets g ON g.wallet_id = w.id WHERE g.agent_id = $1 ORDER BY w.id",
+        SCOPE_WALLET => "SELECT * FROM wallets WHERE id = $1",
+        SCOPE_ENTITY => "SELECT * FROM wallets WHERE entity_id = $1 ORDER BY id",
+        _ => "SELECT * FROM wallets WHERE $1::INTEGER IS NULL ORDER BY id",
+    };
+    let wallets = sqlx::query_as::<_, Wallet>(wallets_query)
+        .bind(target_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    // Agents are blocked on every wallet in scope; a global freeze relies on pausing alone
+    let grants = sqlx::query_as::<_, (i32, i32)>(
+        r#"
+        SELECT g.wallet_id, g.agent_id FROM agent_wallets g
+        JOIN agents a ON a.id = g.agent_id
+        WHERE g.wallet_id = ANY($1) AND ($2 <> 'agent' OR a.id = $3)
+        "#
+    )
+    .bind(wallets.iter().map(|w| w.id).collect::<Vec<_>>())
+    .bind(scope)
+    .bind(target_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut block = Vec::new();
+    if scope != SCOPE_GLOBAL {
+        for (wallet_id, agent_id) in grants {
+            let wallet = wallets.iter().find(|w| w.id == wallet_id).cloned()
+                .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "Grant for unknown wallet".to_string()))?;
+            block.push((wallet, agents::find_agent(pool, agent_id).await?.agent));
+        }
+    }
+
+    let pause = if scope == SCOPE_AGENT { Vec::new() } else { wallets };
+    Ok(Targets { pause, block })
+}
+
+/// Records the freeze first (which blocks the off-chain path at once), then blocks agents
+/// and pauses wallets on-chain. On-chain failures are recorded per action and do not undo the freeze.
+pub async fn freeze(state: &AppState, req: &CreateFreezeRequest, actor: &str) -> Result<FreezeDetail, (StatusCode, String)> {
+    match (req.scope.as_str(), req.target_id) {
+        (SCOPE_GLOBAL, None) => {}
+        (SCOPE_GLOBAL, Some(_)) => return Err((StatusCode::BAD_REQUEST, "A global freeze has no target_id".to_string())),
+        (_, None) => return Err((StatusCode::BAD_REQUEST, "target_id is required".to_string())),
+        (SCOPE_AGENT, Some(id)) => { agents::find_agent(&state.pool, id).await?; }
+        (SCOPE_WALLET, Some(id)) => { find_wallet(&state.pool, id).await?; }
+        (SCOPE_ENTITY, Some(id)) => {
+            let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM legal_entities WHERE id = $1)")
+                .bind(id)
+                .fetch_one(&state.pool)
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            if !exists {
+                return Err((StatusCode::NOT_FOUND, format!("Entity {} not found", id)));
+            }
+        }
+        _ => return Err((StatusCode::BAD_REQUEST, "scope must be 'agent', 'wallet', 'entity' or 'global'".to_string())),
+    }
+    if req.reason.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "A reason is required".to_string()));
+    }
+
+    let freeze_id: i32 = sqlx::query_scalar(
+        "INSERT INTO freezes (scope, target_id, reason, frozen_by) VALUES ($1, $2, $3, $4) RETURNING id"
+    )
+    .bind(&req.scope)
+    .bind(req.target_id)
+    .bind(req.reason.trim())
+    .bind(actor)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| match e.as_database_error().and_then(|d| d.code()) {
+        Some(code) if code == "23505" => (StatusCode::CONFLICT, "An active freeze already covers this target".to_string()),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })?;
+
+    let targets = targets(&state.pool, &req.scope, req.target_id).await?;
+
+    for (wallet, agent) in &targets.block {
+        // Rules contracts deployed before `setBlocked` existed record an error here
+        let result = state.chain.set_agent_blocked(&wallet.rules_contract, &agent.address, true).await.map_err(|e| e.to_string());
+        record_action(&state.pool, freeze_id, "block", wallet.id, Some(&agent.address), None, result.as_deref().map_err(String::as_str)).await?;
+    }
+
+    for wallet in &targets.pause {
+        // Wallets deployed before the pause function existed (or already paused) record an error here
+        let result = state.chain.pause_wallet(&wallet.address).await.map_err(|e| e.to_string());
+        record_action(&state.pool, freeze_id, "pause", wallet.id, None, None, result.as_deref().map_err(String::as_str)).await?;
+    }
+
+    find_freeze(&state.pool, freeze_id).await
+}
+
+/// Lifts a freeze and reverts its on-chain actions, except where another active freeze still applies.
+pub async fn lift(state: &AppState, freeze_id: i32, reason: &str, actor: &str) -> Result<FreezeDetail, (StatusCode, String)> {
+    if reason.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "A reason is required".to_string()));
+    }
+
+    let lifted = sqlx::query(
+        "UPDATE freezes SET lifted_by = $1, lifted_at = NOW(), lift_reason = $2 WHERE id = $3 AND lifted_at IS NULL"
+    )
+    .bind(actor)
+    .bind(reason.trim())
+    .bind(freeze_id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if lifted.rows_affected() == 0 {
+        find_freeze(&state.pool, freeze_id).await?;
+        return Err((StatusCode::CONFLICT, format!("Freeze {} is already lifted", freeze_id)));
+    }
+
+    let detail = find_freeze(&state.pool, freeze_id).await?;
+    for action in detail.actions.iter().filter(|a| a.error.is_none()) {
+        let wallet = find_wallet(&state.pool, action.wallet_id).await?;
+
+        match (action.action.as_str(), &action.agent_address, &action.previous_limit) {
+            ("block", Some(agent_address), _) => {
+                if still_frozen(&state.pool, &wallet, Some(agent_address)).await? {
+                    continue;
+                }
+                let result = state.chain.set_agent_blocked(&wallet.rules_contract, agent_address, false).await.map_err(|e| e.to_string());
+                record_action(&state.pool, freeze_id, "unblock", wallet.id, Some(agent_address), None, result.as_deref().map_err(String::as_str)).await?;
+            }
+            // Freezes from before agents could be blocked zeroed the legacy limit instead
+            ("zero_limit", Some(agent_address), Some(previous)) => {
+                if still_frozen(&state.pool, &wallet, Some(agent_address)).await? {
+                    continue;
+                }
+                let limit = U256::from_dec_str(previous).unwrap_or_default();
+                let result = state.chain.set_agent_limit(&wallet.rules_contract, agent_address, limit).await.map_err(|e| e.to_string());
+                record_action(&state.pool, freeze_id, "restore_limit", wallet.id, Some(agent_address), Some(previous), result.as_deref().map_err(String::as_str)).await?;
+            }
+            ("pause", _, _) => {
+                if still_frozen(&state.pool, &wallet, None).await? {
+                    continue;
+                }
+                let result = state.chain.unpause_wallet(&wallet.address).await.map_err(|e| e.to_string());
+                record_action(&state.pool, freeze_id, "unpause", wallet.id, None, None, result.as_deref().map_err(String::as_str)).await?;
+            }
+            _ => {}
+        }
+    }
+
+    find_freeze(&state.pool, freeze_id).await
+}
+
+/// Whether another active freeze still requires the wallet paused (or, with `agent_address`, the agent blocked).
+async fn still_frozen(pool: &PgPool, wallet: &Wallet, agent_address: Option<&str>) -> Result<bool, (StatusCode, String)> {
+    sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM freezes
+            WHERE lifted_at IS NULL AND (
+                (scope = 'wallet' AND target_id = $1)
+                OR (scope = 'entity' AND target_id = $2)
+                OR ($3::VARCHAR IS NULL AND scope = 'global')
+                OR (scope = 'agent' AND target_id = (SELECT id FROM agents WHERE address = $3))
+            )
+        )
+        "#
+    )
+    .bind(wallet.id)
+    .bind(wallet.entity_id)
+    .bind(agent_address)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn record_action(
+    pool: &PgPool,
+    freeze_id: i32,
+    action: &str,
+    wallet_id: i32,
+    agent_address: Option<&str>,
+    previous_limit: Option<&str>,
+    result: Result<&str, &str>,
+) -> Result<(), (StatusCode, String)> {
+    let (tx_hash, error) = match result {
+        Ok(tx_hash) => (Some(tx_hash), None),
+        Err(error) => (None, Some(error)),
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO freeze_actions (freeze_id, action, wallet_id, agent_address, previous_limit, tx_hash, error)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#
+    )
+    .bind(freeze_id)
+    .bind(action)
+    .bind(wallet_id)
+    .bind(agent_address)
+    .bind(previous_limit)
+    .bind(tx_hash)
+    .bind(error)
+    .execute(pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(())
+}
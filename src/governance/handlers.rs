@@ -1,19 +1,44 @@
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     Json,
     http::StatusCode,
 };
+use crate::auth::{require_role, Claims};
 use crate::state::AppState;
-use super::models::{SetLimitRequest, GovernanceResponse};
+use crate::finance::registry::{find_wallet, find_allowed_token};
+use crate::finance::amounts::{parse_amount, format_amount, NATIVE_DECIMALS, NATIVE_SYMBOL};
+use crate::finance::models::Token;
+use crate::finance::pricing::PRICE_DECIMALS;
+use crate::chain::AgentLimit;
+use crate::chain::parse_addresses;
+use crate::agent::calldata::parse_signature;
+use super::models::{
+    SetLimitRequest, GovernanceResponse, CallAllowlistEntry, AllowCallRequest, BulkLimitRequest, AgentLimitStatus,
+    SetTokenLimitRequest, RemoveTokenLimitRequest, SetValueLimitRequest, RemoveValueLimitRequest, SetPriceOracleRequest,
+    LimitStatus, TokenLimitStatus, AgentLimitsDetail, AssetPrice,
+    ApprovalThreshold, CreateThresholdRequest,
+    Policy, PolicyVersion, PolicyDetail, CreatePolicyRequest, UpdatePolicyRequest,
+    Agent, AgentDetail, CreateAgentRequest, AgentStatusRequest, GrantWalletRequest,
+    Freeze, FreezeDetail, CreateFreezeRequest, LiftFreezeRequest, FreezeFilter,
+};
+use crate::compliance::{audit, COMPLIANCE_ROLES};
+use super::{agents, freezes, policy, ADMIN_ROLES};
 
 pub async fn set_limit(
     State(state): State<AppState>,
+    Claims(claims): Claims,
     Json(payload): Json<SetLimitRequest>,
 ) -> Result<Json<GovernanceResponse>, (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+    let wallet = find_wallet(&state.pool, payload.wallet_id).await?;
+
+    let limit = parse_amount(&payload.limit_eth, NATIVE_DECIMALS)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
     let tx_hash = state.chain.set_agent_limit(
-            &payload.rules_contract, 
+            &wallet.rules_contract, 
             &payload.agent_address, 
-            &payload.limit_eth
+            limit
         )
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -23,3 +48,767 @@ pub async fn set_limit(
         status: "Limit Updated".to_string(),
     }))
 }
+
+/// Reads `AegisRules.agentRules` for many agents of a wallet's rules contract in one Multicall3 round-trip.
+pub async fn get_limits_bulk(
+    State(state): State<AppState>,
+    Json(payload): Json<BulkLimitRequest>,
+) -> Result<Json<Vec<AgentLimitStatus>>, (StatusCode, String)> {
+    let wallet = find_wallet(&state.pool, payload.wallet_id).await?;
+    let agents = parse_addresses(&payload.agent_addresses).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let rules = wallet.rules_contract.parse()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Bad rules contract address".to_string()))?;
+
+    let limits = state.chain.batch_agent_limits(rules, &agents)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let now = chrono::Utc::now().timestamp() as u64;
+
+    let statuses = agents.iter()
+        .zip(limits)
+        .map(|(agent, limit)| AgentLimitStatus {
+            agent_address: format!("{:?}", agent),
+            daily_limit_eth: limit.as_ref().map(|l| format_amount(l.daily_limit, NATIVE_DECIMALS)),
+            spent_today_eth: limit.as_ref().map(|l| format_amount(l.spent_at(now), NATIVE_DECIMALS)),
+            remaining_eth: limit.as_ref().map(|l| format_amount(l.remaining_at(now), NATIVE_DECIMALS)),
+        })
+        .collect();
+
+    Ok(Json(statuses))
+}
+
+/// Resolves an optional token address to its on-chain address (zero for ETH), symbol and decimals.
+async fn resolve_asset(state: &AppState, token_address: Option<&str>) -> Result<(ethers::types::Address, String, u8), (StatusCode, String)> {
+    match token_address {
+        Some(token_addr) => {
+            let token = find_allowed_token(&state.pool, token_addr, state.chain.chain_id()).await?;
+            let address = token.address.parse()
+                .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Bad token address".to_string()))?;
+            Ok((address, token.symbol, token.decimals as u8))
+        }
+        None => Ok((ethers::types::Address::zero(), NATIVE_SYMBOL.to_string(), NATIVE_DECIMALS)),
+    }
+}
+
+fn limit_status(limit: &AgentLimit, decimals: u8, now: u64) -> LimitStatus {
+    LimitStatus {
+        daily_limit: format_amount(limit.daily_limit, decimals),
+        spent_today: format_amount(limit.spent_at(now), decimals),
+        remaining: format_amount(limit.remaining_at(now), decimals),
+    }
+}
+
+pub async fn set_token_limit(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<SetTokenLimitRequest>,
+) -> Result<Json<GovernanceResponse>, (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+    let wallet = find_wallet(&state.pool, payload.wallet_id).await?;
+    let (token, symbol, decimals) = resolve_asset(&state, payload.token_address.as_deref()).await?;
+    let limit = parse_amount(&payload.limit, decimals).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let tx_hash = state.chain.set_token_limit(&wallet.rules_contract, &payload.agent_address, token, limit)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(GovernanceResponse {
+        tx_hash,
+        status: format!("{} Limit Updated", symbol),
+    }))
+}
+
+pub async fn remove_token_limit(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<RemoveTokenLimitRequest>,
+) -> Result<Json<GovernanceResponse>, (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+    let wallet = find_wallet(&state.pool, payload.wallet_id).await?;
+    let (token, symbol, _) = resolve_asset(&state, payload.token_address.as_deref()).await?;
+
+    let tx_hash = state.chain.remove_token_limit(&wallet.rules_contract, &payload.agent_address, token)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(GovernanceResponse {
+        tx_hash,
+        status: format!("{} Limit Removed", symbol),
+    }))
+}
+
+pub async fn set_value_limit(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<SetValueLimitRequest>,
+) -> Result<Json<GovernanceResponse>, (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+    let wallet = find_wallet(&state.pool, payload.wallet_id).await?;
+    let limit = parse_amount(&payload.limit, PRICE_DECIMALS).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    // Value limits revert every transfer until the rules contract can price assets
+    let oracle = state.chain.get_price_oracle(&wallet.rules_contract)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if oracle.is_zero() {
+        return Err((StatusCode::CONFLICT, format!("Wallet {} has no price oracle configured", wallet.id)));
+    }
+
+    let tx_hash = state.chain.set_value_limit(&wallet.rules_contract, &payload.agent_address, limit)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(GovernanceResponse {
+        tx_hash,
+        status: "Value Limit Updated".to_string(),
+    }))
+}
+
+pub async fn remove_value_limit(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<RemoveValueLimitRequest>,
+) -> Result<Json<GovernanceResponse>, (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+    let wallet = find_wallet(&state.pool, payload.wallet_id).await?;
+
+    let tx_hash = state.chain.remove_value_limit(&wallet.rules_contract, &payload.agent_address)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(GovernanceResponse {
+        tx_hash,
+        status: "Value Limit Removed".to_string(),
+    }))
+}
+
+pub async fn set_price_oracle(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<SetPriceOracleRequest>,
+) -> Result<Json<GovernanceResponse>, (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+    let wallet = find_wallet(&state.pool, payload.wallet_id).await?;
+    payload.oracle_address.parse::<ethers::types::Address>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid oracle address".to_string()))?;
+
+    let tx_hash = state.chain.set_price_oracle(&wallet.rules_contract, &payload.oracle_address)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(GovernanceResponse {
+        tx_hash,
+        status: "Price Oracle Updated".to_string(),
+    }))
+}
+
+/// All budgets of one agent on a wallet's rules contract: legacy, per token (ETH and allowed tokens) and value.
+pub async fn get_agent_limits(
+    State(state): State<AppState>,
+    Path((wallet_id, agent_address)): Path<(i32, String)>,
+) -> Result<Json<AgentLimitsDetail>, (StatusCode, String)> {
+    let wallet = find_wallet(&state.pool, wallet_id).await?;
+    let agent: ethers::types::Address = agent_address.parse()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid agent address".to_string()))?;
+    let rules = &wallet.rules_contract;
+    let now = chrono::Utc::now().timestamp() as u64;
+    let chain_err = |e: Box<dyn std::error::Error>| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+
+    let legacy = state.chain.get_agent_limit(rules, agent).await.map_err(chain_err)?;
+    let value_limit = state.chain.get_value_limit(rules, agent).await.map_err(chain_err)?;
+    let oracle = state.chain.get_price_oracle(rules).await.map_err(chain_err)?;
+
+    let tokens = allowed_tokens(&state).await?;
+    let mut token_limits = Vec::new();
+    for (token_address, symbol, decimals) in std::iter::once((None, NATIVE_SYMBOL.to_string(), NATIVE_DECIMALS))
+        .chain(tokens.into_iter().map(|t| (Some(t.address), t.symbol, t.decimals as u8)))
+    {
+        let token = match &token_address {
+            Some(t) => t.parse().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Bad token address".to_string()))?,
+            None => ethers::types::Address::zero(),
+        };
+        if let Some(limit) = state.chain.get_token_limit(rules, agent, token).await.map_err(chain_err)? {
+            token_limits.push(TokenLimitStatus { token_address, symbol, limit: limit_status(&limit, decimals, now) });
+        }
+    }
+
+    Ok(Json(AgentLimitsDetail {
+        agent_address: format!("{:?}", agent),
+        price_oracle: (!oracle.is_zero()).then(|| format!("{:?}", oracle)),
+        legacy: limit_status(&legacy, NATIVE_DECIMALS, now),
+        token_limits,
+        value_limit: value_limit.as_ref().map(|l| limit_status(l, PRICE_DECIMALS, now)),
+    }))
+}
+
+/// Prices the backend uses for value-limit pre-checks, for ETH and every allowed token.
+pub async fn list_prices(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<AssetPrice>>, (StatusCode, String)> {
+    let tokens = allowed_tokens(&state).await?;
+    let mut prices = Vec::with_capacity(tokens.len() + 1);
+
+    for (token_address, symbol) in std::iter::once((None, NATIVE_SYMBOL.to_string()))
+        .chain(tokens.into_iter().map(|t| (Some(t.address), t.symbol)))
+    {
+        let asset = match &token_address {
+            Some(t) => Some(t.parse().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Bad token address".to_string()))?),
+            None => None,
+        };
+        let (price, error) = match state.prices.price(asset).await {
+            Ok(price) => (Some(format_amount(price, PRICE_DECIMALS)), None),
+            Err(e) => (None, Some(e)),
+        };
+        prices.push(AssetPrice { token_address, symbol, price, error });
+    }
+
+    Ok(Json(prices))
+}
+
+async fn allowed_tokens(state: &AppState) -> Result<Vec<Token>, (StatusCode, String)> {
+    sqlx::query_as::<_, Token>("SELECT * FROM tokens WHERE chain_id = $1 AND allowed ORDER BY symbol")
+        .bind(state.chain.chain_id() as i64)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+pub async fn add_call_allowlist_entry(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<AllowCallRequest>,
+) -> Result<(StatusCode, Json<CallAllowlistEntry>), (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+
+    let target: ethers::types::Address = payload.target_address.parse()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid target address".to_string()))?;
+
+    let selector = match (payload.selector, payload.function_signature) {
+        (Some(selector), _) => {
+            let selector = selector.to_lowercase();
+            if selector.len() != 10 || !selector.starts_with("0x") || !selector[2..].chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err((StatusCode::BAD_REQUEST, "Selector must be 4 bytes of hex, e.g. 0xa9059cbb".to_string()));
+            }
+            Some(selector)
+        }
+        (None, Some(signature)) => {
+            let function = parse_signature(&signature).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+            Some(format!("0x{}", ethers::utils::hex::encode(function.short_signature())))
+        }
+        (None, None) => None,
+    };
+
+    let entry = sqlx::query_as::<_, CallAllowlistEntry>(
+        "INSERT INTO call_allowlist (target_address, selector, label) VALUES ($1, $2, $3) RETURNING *"
+    )
+    .bind(format!("{:?}", target))
+    .bind(selector)
+    .bind(payload.label)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| match e.as_database_error().and_then(|d| d.code()) {
+        Some(code) if code == "23505" => (StatusCode::CONFLICT, "Call is already allowlisted".to_string()),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })?;
+
+    audit::record(&state.pool, &claims.sub, "call_allowlist.add", &entry.target_address, serde_json::json!({
+        "entry_id": entry.id,
+        "selector": entry.selector,
+    })).await?;
+
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+pub async fn list_call_allowlist(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<CallAllowlistEntry>>, (StatusCode, String)> {
+    let entries = sqlx::query_as::<_, CallAllowlistEntry>("SELECT * FROM call_allowlist ORDER BY created_at DESC")
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(entries))
+}
+
+pub async fn remove_call_allowlist_entry(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(entry_id): Path<i32>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+
+    let entry = sqlx::query_as::<_, CallAllowlistEntry>("DELETE FROM call_allowlist WHERE id = $1 RETURNING *")
+        .bind(entry_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Allowlist entry not found".to_string()))?;
+
+    audit::record(&state.pool, &claims.sub, "call_allowlist.remove", &entry.target_address, serde_json::json!({
+        "entry_id": entry.id,
+        "selector": entry.selector,
+    })).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Default window approvers have to act on a payment before it expires.
+const DEFAULT_APPROVAL_EXPIRY_SECS: i64 = 86_400;
+
+pub async fn add_approval_threshold(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<CreateThresholdRequest>,
+) -> Result<(StatusCode, Json<ApprovalThreshold>), (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+
+    if let Some(wallet_id) = payload.wallet_id {
+        find_wallet(&state.pool, wallet_id).await?;
+    }
+
+    let decimals = match &payload.token_address {
+        Some(token_addr) => find_allowed_token(&state.pool, token_addr, state.chain.chain_id()).await?.decimals as u8,
+        None => NATIVE_DECIMALS,
+    };
+    parse_amount(&payload.threshold_amount, decimals).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    if payload.required_approvals < 1 {
+        return Err((StatusCode::BAD_REQUEST, "required_approvals must be at least 1".to_string()));
+    }
+    let expiry_seconds = payload.expiry_seconds.unwrap_or(DEFAULT_APPROVAL_EXPIRY_SECS);
+    if expiry_seconds < 60 {
+        return Err((StatusCode::BAD_REQUEST, "expiry_seconds must be at least 60".to_string()));
+    }
+
+    let threshold = sqlx::query_as::<_, ApprovalThreshold>(
+        r#"
+        INSERT INTO approval_thresholds (wallet_id, agent, token_address, threshold_amount, required_approvals, expiry_seconds, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#
+    )
+    .bind(payload.wallet_id)
+    .bind(payload.agent)
+    .bind(payload.token_address.map(|t| t.to_lowercase()))
+    .bind(payload.threshold_amount.trim())
+    .bind(payload.required_approvals)
+    .bind(expiry_seconds)
+    .bind(&claims.sub)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit::record(&state.pool, &claims.sub, "approval_threshold.add", &threshold.id.to_string(), serde_json::json!({
+        "wallet_id": threshold.wallet_id,
+        "agent": threshold.agent,
+        "token_address": threshold.token_address,
+        "threshold_amount": threshold.threshold_amount,
+        "required_approvals": threshold.required_approvals,
+        "expiry_seconds": threshold.expiry_seconds,
+    })).await?;
+
+    Ok((StatusCode::CREATED, Json(threshold)))
+}
+
+pub async fn list_approval_thresholds(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ApprovalThreshold>>, (StatusCode, String)> {
+    let thresholds = sqlx::query_as::<_, ApprovalThreshold>("SELECT * FROM approval_thresholds ORDER BY created_at DESC")
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(thresholds))
+}
+
+pub async fn remove_approval_threshold(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(threshold_id): Path<i32>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+
+    let threshold = sqlx::query_as::<_, ApprovalThreshold>("DELETE FROM approval_thresholds WHERE id = $1 RETURNING *")
+        .bind(threshold_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Approval threshold not found".to_string()))?;
+
+    audit::record(&state.pool, &claims.sub, "approval_threshold.remove", &threshold.id.to_string(), serde_json::json!({
+        "wallet_id": threshold.wallet_id,
+        "agent": threshold.agent,
+        "token_address": threshold.token_address,
+        "threshold_amount": threshold.threshold_amount,
+        "required_approvals": threshold.required_approvals,
+    })).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn create_policy(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<CreatePolicyRequest>,
+) -> Result<(StatusCode, Json<PolicyDetail>), (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+
+    if let Some(wallet_id) = payload.wallet_id {
+        find_wallet(&state.pool, wallet_id).await?;
+    }
+    policy::validate_rules(&state, &payload.rules).await.map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let rules = serde_json::to_value(&payload.rules).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let policy_id: i32 = sqlx::query_scalar("INSERT INTO policies (name, wallet_id, created_by) VALUES ($1, $2, $3) RETURNING id")
+        .bind(&payload.name)
+        .bind(payload.wallet_id)
+        .bind(&claims.sub)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    sqlx::query("INSERT INTO policy_versions (policy_id, version, rules, created_by) VALUES ($1, 1, $2, $3)")
+        .bind(policy_id)
+        .bind(rules)
+        .bind(&claims.sub)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(find_policy(&state, policy_id).await?)))
+}
+
+pub async fn list_policies(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Policy>>, (StatusCode, String)> {
+    let policies = sqlx::query_as::<_, Policy>("SELECT * FROM policies ORDER BY id")
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(policies))
+}
+
+pub async fn get_policy(
+    State(state): State<AppState>,
+    Path(policy_id): Path<i32>,
+) -> Result<Json<PolicyDetail>, (StatusCode, String)> {
+    Ok(Json(find_policy(&state, policy_id).await?))
+}
+
+/// Replacing the rules appends a version and makes it current; earlier versions stay for the decision log.
+pub async fn update_policy(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(policy_id): Path<i32>,
+    Json(payload): Json<UpdatePolicyRequest>,
+) -> Result<Json<PolicyDetail>, (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let current: i32 = sqlx::query_scalar("SELECT current_version FROM policies WHERE id = $1 FOR UPDATE")
+        .bind(policy_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Policy {} not found", policy_id)))?;
+
+    let mut version = current;
+    if let Some(rules) = &payload.rules {
+        policy::validate_rules(&state, rules).await.map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        let rules = serde_json::to_value(rules).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        version += 1;
+
+        sqlx::query("INSERT INTO policy_versions (policy_id, version, rules, created_by) VALUES ($1, $2, $3, $4)")
+            .bind(policy_id)
+            .bind(version)
+            .bind(rules)
+            .bind(&claims.sub)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+
+    sqlx::query("UPDATE policies SET current_version = $1, enabled = COALESCE($2, enabled), updated_at = NOW() WHERE id = $3")
+        .bind(version)
+        .bind(payload.enabled)
+        .bind(policy_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(find_policy(&state, policy_id).await?))
+}
+
+async fn find_policy(state: &AppState, policy_id: i32) -> Result<PolicyDetail, (StatusCode, String)> {
+    let policy = sqlx::query_as::<_, Policy>("SELECT * FROM policies WHERE id = $1")
+        .bind(policy_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Policy {} not found", policy_id)))?;
+
+    let versions = sqlx::query_as::<_, PolicyVersion>("SELECT * FROM policy_versions WHERE policy_id = $1 ORDER BY version DESC")
+        .bind(policy_id)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(PolicyDetail { policy, versions })
+}
+
+pub async fn create_agent(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<CreateAgentRequest>,
+) -> Result<(StatusCode, Json<AgentDetail>), (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+
+    let address: ethers::types::Address = payload.address.parse()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid agent address".to_string()))?;
+    for limit in [&payload.per_tx_limit_eth, &payload.daily_limit_eth].into_iter().flatten() {
+        parse_amount(limit, NATIVE_DECIMALS).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
+
+    let entity_exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM legal_entities WHERE id = $1)")
+        .bind(payload.entity_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !entity_exists {
+        return Err((StatusCode::NOT_FOUND, format!("Entity {} not found", payload.entity_id)));
+    }
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let agent = sqlx::query_as::<_, Agent>(
+        r#"
+        INSERT INTO agents (name, address, username, entity_id, per_tx_limit_eth, daily_limit_eth, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#
+    )
+    .bind(&payload.name)
+    .bind(format!("{:?}", address))
+    .bind(&payload.username)
+    .bind(payload.entity_id)
+    .bind(payload.per_tx_limit_eth.as_deref().map(str::trim))
+    .bind(payload.daily_limit_eth.as_deref().map(str::trim))
+    .bind(&claims.sub)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match e.as_database_error().and_then(|d| d.code()) {
+        Some(code) if code == "23505" => (StatusCode::CONFLICT, "Agent address or username already registered".to_string()),
+        Some(code) if code == "23503" => (StatusCode::BAD_REQUEST, format!("User {} does not exist", payload.username)),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })?;
+
+    audit::record(&mut *tx, &claims.sub, "agent.create", &format!("agent:{}", agent.id), serde_json::json!({
+        "name": agent.name,
+        "address": agent.address,
+        "username": agent.username,
+        "entity_id": agent.entity_id,
+        "per_tx_limit_eth": agent.per_tx_limit_eth,
+        "daily_limit_eth": agent.daily_limit_eth,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok((StatusCode::CREATED, Json(AgentDetail { agent, wallet_ids: Vec::new() })))
+}
+
+pub async fn list_agents(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Agent>>, (StatusCode, String)> {
+    let agents = sqlx::query_as::<_, Agent>("SELECT * FROM agents ORDER BY id")
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(agents))
+}
+
+pub async fn get_agent(
+    State(state): State<AppState>,
+    Path(agent_id): Path<i32>,
+) -> Result<Json<AgentDetail>, (StatusCode, String)> {
+    Ok(Json(agents::find_agent(&state.pool, agent_id).await?))
+}
+
+pub async fn set_agent_status(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(agent_id): Path<i32>,
+    Json(payload): Json<AgentStatusRequest>,
+) -> Result<Json<AgentDetail>, (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+
+    if payload.status != agents::STATUS_ACTIVE && payload.status != agents::STATUS_SUSPENDED {
+        return Err((StatusCode::BAD_REQUEST, "status must be 'active' or 'suspended'".to_string()));
+    }
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let result = sqlx::query("UPDATE agents SET status = $1, status_reason = $2, updated_at = NOW() WHERE id = $3")
+        .bind(&payload.status)
+        .bind(&payload.reason)
+        .bind(agent_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, format!("Agent {} not found", agent_id)));
+    }
+
+    audit::record(&mut *tx, &claims.sub, "agent.status", &format!("agent:{}", agent_id), serde_json::json!({
+        "status": payload.status,
+        "reason": payload.reason,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(agents::find_agent(&state.pool, agent_id).await?))
+}
+
+/// Lets the agent drive a wallet of its own entity. With a daily limit set, the same limit is
+/// mirrored to the wallet's `AegisRules` for the agent's address.
+pub async fn grant_agent_wallet(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(agent_id): Path<i32>,
+    Json(payload): Json<GrantWalletRequest>,
+) -> Result<Json<AgentDetail>, (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+
+    let detail = agents::find_agent(&state.pool, agent_id).await?;
+    let wallet = find_wallet(&state.pool, payload.wallet_id).await?;
+    if wallet.entity_id != detail.agent.entity_id {
+        return Err((StatusCode::FORBIDDEN, format!("Wallet {} belongs to another entity than agent {}", wallet.id, agent_id)));
+    }
+
+    if let Some(limit) = &detail.agent.daily_limit_eth {
+        let limit = parse_amount(limit, NATIVE_DECIMALS).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        state.chain.set_agent_limit(&wallet.rules_contract, &detail.agent.address, limit)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    sqlx::query("INSERT INTO agent_wallets (agent_id, wallet_id, granted_by) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING")
+        .bind(agent_id)
+        .bind(wallet.id)
+        .bind(&claims.sub)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit::record(&mut *tx, &claims.sub, "agent.grant", &format!("agent:{}", agent_id), serde_json::json!({
+        "wallet_id": wallet.id,
+        "daily_limit_eth": detail.agent.daily_limit_eth,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(agents::find_agent(&state.pool, agent_id).await?))
+}
+
+/// Withdraws the grant and zeroes the limit mirrored to the wallet's `AegisRules` for the agent's address,
+/// so the key cannot keep spending on-chain. The grant stays if the chain call fails.
+pub async fn revoke_agent_wallet(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path((agent_id, wallet_id)): Path<(i32, i32)>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+
+    let detail = agents::find_agent(&state.pool, agent_id).await?;
+    let wallet = find_wallet(&state.pool, wallet_id).await?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let result = sqlx::query("DELETE FROM agent_wallets WHERE agent_id = $1 AND wallet_id = $2")
+        .bind(agent_id)
+        .bind(wallet_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Agent has no grant for that wallet".to_string()));
+    }
+
+    let tx_hash = state.chain.set_agent_limit(&wallet.rules_contract, &detail.agent.address, ethers::types::U256::zero())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit::record(&mut *tx, &claims.sub, "agent.revoke", &format!("agent:{}", agent_id), serde_json::json!({
+        "wallet_id": wallet.id,
+        "tx_hash": tx_hash,
+    })).await?;
+
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Kill switch: compliance officers and admins can freeze; lifting a freeze is reserved to admins.
+pub async fn create_freeze(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Json(payload): Json<CreateFreezeRequest>,
+) -> Result<(StatusCode, Json<FreezeDetail>), (StatusCode, String)> {
+    require_role(&claims, COMPLIANCE_ROLES)?;
+
+    let detail = freezes::freeze(&state, &payload, &claims.sub).await?;
+
+    audit::record(&state.pool, &claims.sub, "freeze.create", &format!("{}:{}", detail.freeze.scope, detail.freeze.target_id.unwrap_or_default()), serde_json::json!({
+        "freeze_id": detail.freeze.id,
+        "reason": detail.freeze.reason,
+    })).await?;
+
+    Ok((StatusCode::CREATED, Json(detail)))
+}
+
+pub async fn list_freezes(
+    State(state): State<AppState>,
+    Query(filter): Query<FreezeFilter>,
+) -> Result<Json<Vec<Freeze>>, (StatusCode, String)> {
+    let freezes = sqlx::query_as::<_, Freeze>("SELECT * FROM freezes WHERE NOT $1 OR lifted_at IS NULL ORDER BY id DESC")
+        .bind(filter.active)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(freezes))
+}
+
+pub async fn get_freeze(
+    State(state): State<AppState>,
+    Path(freeze_id): Path<i32>,
+) -> Result<Json<FreezeDetail>, (StatusCode, String)> {
+    Ok(Json(freezes::find_freeze(&state.pool, freeze_id).await?))
+}
+
+pub async fn lift_freeze(
+    State(state): State<AppState>,
+    Claims(claims): Claims,
+    Path(freeze_id): Path<i32>,
+    Json(payload): Json<LiftFreezeRequest>,
+) -> Result<Json<FreezeDetail>, (StatusCode, String)> {
+    require_role(&claims, ADMIN_ROLES)?;
+
+    let detail = freezes::lift(&state, freeze_id, &payload.reason, &claims.sub).await?;
+
+    audit::record(&state.pool, &claims.sub, "freeze.lift", &format!("{}:{}", detail.freeze.scope, detail.freeze.target_id.unwrap_or_default()), serde_json::json!({
+        "freeze_id": detail.freeze.id,
+        "reason": payload.reason,
+    })).await?;
+
+    Ok(Json(detail))
+}
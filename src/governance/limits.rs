@@ -0,0 +1,90 @@
+use ethers::types::{Address, U256};
+use std::collections::BTreeMap;
+use crate::state::AppState;
+use crate::finance::registry::find_allowed_token;
+use crate::finance::amounts::{format_amount, NATIVE_DECIMALS, NATIVE_SYMBOL};
+use crate::finance::pricing::{value_of, PRICE_DECIMALS};
+
+/// A payment about to leave a wallet; `amount` is in the asset's base units.
+pub struct Spend {
+    pub token_address: Option<String>,
+    pub amount: U256,
+}
+
+/// Checks that `spends` fit the backend signer's budgets on `rules_addr`, mirroring `AegisRules.checkTransfer`:
+/// per-token limits count base units, the value limit counts reference-currency value at the backend's prices,
+/// and assets governed by neither fall back to the legacy daily limit.
+pub async fn check_budget(state: &AppState, rules_addr: &str, spends: &[Spend]) -> Result<(), String> {
+    let agent = state.chain.signer_address();
+    let now = chrono::Utc::now().timestamp() as u64;
+
+    let mut per_asset: BTreeMap<Option<Address>, U256> = BTreeMap::new();
+    for spend in spends {
+        let token = match &spend.token_address {
+            Some(t) => Some(t.parse::<Address>().map_err(|_| format!("Invalid token address '{}'", t))?),
+            None => None,
+        };
+        let total = per_asset.entry(token).or_default();
+        *total = total.saturating_add(spend.amount);
+    }
+
+    let value_limit = state.chain.get_value_limit(rules_addr, agent).await.map_err(|e| e.to_string())?;
+    let mut value = U256::zero();
+    let mut legacy = U256::zero();
+
+    for (token, amount) in per_asset {
+        let (symbol, decimals) = match token {
+            Some(t) => {
+                let token = find_allowed_token(&state.pool, &format!("{:?}", t), state.chain.chain_id()).await.map_err(|(_, e)| e)?;
+                (token.symbol, token.decimals as u8)
+            }
+            None => (NATIVE_SYMBOL.to_string(), NATIVE_DECIMALS),
+        };
+
+        let token_limit = state.chain.get_token_limit(rules_addr, agent, token.unwrap_or_default())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if let Some(limit) = &token_limit {
+            let remaining = limit.remaining_at(now);
+            if amount > remaining {
+                return Err(format!(
+                    "{} daily limit would be exceeded (remaining {} {})",
+                    symbol, format_amount(remaining, decimals), symbol
+                ));
+            }
+        }
+
+        if value_limit.is_some() {
+            let price = state.prices.price(token).await?;
+            value = value.saturating_add(value_of(price, amount, decimals));
+        } else if token_limit.is_none() {
+            // Without a price, the legacy limit counts base units of every asset alike
+            legacy = legacy.saturating_add(amount);
+        }
+    }
+
+    if let Some(limit) = value_limit {
+        let remaining = limit.remaining_at(now);
+        if value > remaining {
+            return Err(format!(
+                "Value limit would be exceeded ({} needed, {} remaining)",
+                format_amount(value, PRICE_DECIMALS), format_amount(remaining, PRICE_DECIMALS)
+            ));
+        }
+    }
+
+    if !legacy.is_zero() {
+        let limit = state.chain.get_agent_limit(rules_addr, agent).await.map_err(|e| e.to_string())?;
+        let remaining = limit.remaining_at(now);
+        if legacy > remaining {
+            return Err(format!(
+                "Daily limit would be exceeded (remaining {} base units, {} ETH)",
+                remaining,
+                format_amount(remaining, NATIVE_DECIMALS)
+            ));
+        }
+    }
+
+    Ok(())
+}
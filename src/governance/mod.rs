@@ -1,13 +1,40 @@
 use axum::{
-    routing::post,
+    routing::{delete, get, post, put},
     Router,
 };
 use crate::state::AppState;
 
 pub mod handlers;
+pub mod agents;
+pub mod freezes;
+pub mod limits;
 pub mod models;
+pub mod policy;
+
+/// Roles allowed to change payment policies and the agent registry.
+pub const ADMIN_ROLES: &[&str] = &["admin"];
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/limit", post(handlers::set_limit))
+        .route("/limits/bulk", post(handlers::get_limits_bulk))
+        .route("/limits/:wallet_id/:agent_address", get(handlers::get_agent_limits))
+        .route("/token-limits", post(handlers::set_token_limit).delete(handlers::remove_token_limit))
+        .route("/value-limits", post(handlers::set_value_limit).delete(handlers::remove_value_limit))
+        .route("/price-oracle", put(handlers::set_price_oracle))
+        .route("/prices", get(handlers::list_prices))
+        .route("/call-allowlist", post(handlers::add_call_allowlist_entry).get(handlers::list_call_allowlist))
+        .route("/call-allowlist/:entry_id", delete(handlers::remove_call_allowlist_entry))
+        .route("/approval-thresholds", post(handlers::add_approval_threshold).get(handlers::list_approval_thresholds))
+        .route("/approval-thresholds/:threshold_id", delete(handlers::remove_approval_threshold))
+        .route("/policies", post(handlers::create_policy).get(handlers::list_policies))
+        .route("/policies/:policy_id", get(handlers::get_policy).put(handlers::update_policy))
+        .route("/agents", post(handlers::create_agent).get(handlers::list_agents))
+        .route("/agents/:agent_id", get(handlers::get_agent))
+        .route("/agents/:agent_id/status", put(handlers::set_agent_status))
+        .route("/agents/:agent_id/wallets", post(handlers::grant_agent_wallet))
+        .route("/agents/:agent_id/wallets/:wallet_id", delete(handlers::revoke_agent_wallet))
+        .route("/freezes", post(handlers::create_freeze).get(handlers::list_freezes))
+        .route("/freezes/:freeze_id", get(handlers::get_freeze))
+        .route("/freezes/:freeze_id/lift", post(handlers::lift_freeze))
 }
@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
 
 #[derive(Deserialize)]
 pub struct SetLimitRequest {
-    pub rules_contract: String,
+    pub wallet_id: i32,
     pub agent_address: String,
     pub limit_eth: String,
 }
@@ -12,3 +13,328 @@ pub struct GovernanceResponse {
     pub tx_hash: String,
     pub status: String,
 }
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct CallAllowlistEntry {
+    pub id: i32,
+    pub target_address: String,
+    pub selector: Option<String>,
+    pub label: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Either `selector` (e.g. "0xa9059cbb") or `function_signature` may be given; neither allows any function.
+#[derive(Deserialize)]
+pub struct AllowCallRequest {
+    pub target_address: String,
+    pub selector: Option<String>,
+    pub function_signature: Option<String>,
+    pub label: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct BulkLimitRequest {
+    pub wallet_id: i32,
+    pub agent_addresses: Vec<String>,
+}
+
+/// Current `AegisRules` budget for an agent, in ETH.
+#[derive(Serialize)]
+pub struct AgentLimitStatus {
+    pub agent_address: String,
+    pub daily_limit_eth: Option<String>,
+    pub spent_today_eth: Option<String>,
+    pub remaining_eth: Option<String>,
+}
+
+/// Per-token budget; omitted `token_address` means native ETH. `limit` is in the asset's decimal units.
+#[derive(Deserialize)]
+pub struct SetTokenLimitRequest {
+    pub wallet_id: i32,
+    pub agent_address: String,
+    pub token_address: Option<String>,
+    pub limit: String,
+}
+
+#[derive(Deserialize)]
+pub struct RemoveTokenLimitRequest {
+    pub wallet_id: i32,
+    pub agent_address: String,
+    pub token_address: Option<String>,
+}
+
+/// Budget across all assets; `limit` is in the price oracle's reference currency.
+#[derive(Deserialize)]
+pub struct SetValueLimitRequest {
+    pub wallet_id: i32,
+    pub agent_address: String,
+    pub limit: String,
+}
+
+#[derive(Deserialize)]
+pub struct RemoveValueLimitRequest {
+    pub wallet_id: i32,
+    pub agent_address: String,
+}
+
+#[derive(Deserialize)]
+pub struct SetPriceOracleRequest {
+    pub wallet_id: i32,
+    pub oracle_address: String,
+}
+
+/// One `AegisRules` budget in decimal units of its asset (or the reference currency).
+#[derive(Serialize)]
+pub struct LimitStatus {
+    pub daily_limit: String,
+    pub spent_today: String,
+    pub remaining: String,
+}
+
+#[derive(Serialize)]
+pub struct TokenLimitStatus {
+    pub token_address: Option<String>,
+    pub symbol: String,
+    #[serde(flatten)]
+    pub limit: LimitStatus,
+}
+
+/// Every budget an agent has on a wallet's rules contract. Transfers of assets without a token limit
+/// count against `value_limit` when set, otherwise against the legacy `daily_limit_eth`.
+#[derive(Serialize)]
+pub struct AgentLimitsDetail {
+    pub agent_address: String,
+    pub price_oracle: Option<String>,
+    pub legacy: LimitStatus,
+    pub token_limits: Vec<TokenLimitStatus>,
+    pub value_limit: Option<LimitStatus>,
+}
+
+/// Backend price quote for an asset in the reference currency.
+#[derive(Serialize)]
+pub struct AssetPrice {
+    pub token_address: Option<String>,
+    pub symbol: String,
+    pub price: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Maker-checker rule: payments of at least `threshold_amount` need `required_approvals` approvals.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ApprovalThreshold {
+    pub id: i32,
+    pub wallet_id: Option<i32>,
+    pub agent: Option<String>,
+    pub token_address: Option<String>,
+    pub threshold_amount: String,
+    pub required_approvals: i32,
+    pub expiry_seconds: i64,
+    pub created_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Omitted `wallet_id` / `agent` apply the threshold to every wallet / initiator;
+/// omitted `token_address` means native ETH. `threshold_amount` is in the asset's decimal units.
+#[derive(Deserialize)]
+pub struct CreateThresholdRequest {
+    pub wallet_id: Option<i32>,
+    pub agent: Option<String>,
+    pub token_address: Option<String>,
+    pub threshold_amount: String,
+    pub required_approvals: i32,
+    pub expiry_seconds: Option<i64>,
+}
+
+/// A declarative policy rule, tagged by `type`. Amount rules only apply to payments in the same asset
+/// (`token_address` omitted means native ETH) and are expressed in that asset's decimal units.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Rule {
+    MaxPerTransaction {
+        amount: String,
+        token_address: Option<String>,
+    },
+    /// Limits the wallet's payments within a sliding window (default one hour).
+    Velocity {
+        window_seconds: Option<i64>,
+        max_count: Option<i64>,
+        max_amount: Option<String>,
+        token_address: Option<String>,
+    },
+    AllowedTokens {
+        tokens: Vec<String>,
+        #[serde(default)]
+        native: bool,
+    },
+    /// Payments are only allowed on `days` between `from_hour` (inclusive) and `to_hour` (exclusive), UTC.
+    AllowedHours {
+        days: Vec<chrono::Weekday>,
+        from_hour: u32,
+        to_hour: u32,
+    },
+    /// Jurisdiction of the legal entity behind the counterparty wallet; unknown counterparties are denied.
+    CounterpartyJurisdiction {
+        #[serde(default)]
+        allowed: Vec<String>,
+        #[serde(default)]
+        denied: Vec<String>,
+    },
+    /// Minimum KYC level of the legal entity behind the counterparty wallet; unknown counterparties are denied.
+    MinKycLevel {
+        level: i16,
+    },
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct Policy {
+    pub id: i32,
+    pub name: String,
+    pub wallet_id: Option<i32>,
+    pub enabled: bool,
+    pub current_version: i32,
+    pub created_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PolicyVersion {
+    pub id: i32,
+    pub policy_id: i32,
+    pub version: i32,
+    pub rules: serde_json::Value,
+    pub created_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Serialize)]
+pub struct PolicyDetail {
+    #[serde(flatten)]
+    pub policy: Policy,
+    pub versions: Vec<PolicyVersion>,
+}
+
+#[derive(Deserialize)]
+pub struct CreatePolicyRequest {
+    pub name: String,
+    pub wallet_id: Option<i32>,
+    pub rules: Vec<Rule>,
+}
+
+/// New `rules` create a new version; `enabled` toggles the policy without versioning.
+#[derive(Deserialize)]
+pub struct UpdatePolicyRequest {
+    pub rules: Option<Vec<Rule>>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PolicyDecision {
+    pub id: i32,
+    pub intent_id: i32,
+    pub policy_id: i32,
+    pub policy_version: i32,
+    pub rule_index: i32,
+    pub rule_type: String,
+    pub outcome: String,
+    pub reason: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Limits are in ETH units and, like `AegisRules`, count raw amounts of every asset.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Agent {
+    pub id: i32,
+    pub name: String,
+    pub address: String,
+    pub username: String,
+    pub entity_id: i32,
+    pub status: String,
+    pub status_reason: Option<String>,
+    pub per_tx_limit_eth: Option<String>,
+    pub daily_limit_eth: Option<String>,
+    pub created_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Serialize)]
+pub struct AgentDetail {
+    #[serde(flatten)]
+    pub agent: Agent,
+    pub wallet_ids: Vec<i32>,
+}
+
+/// `username` is the API user the agent authenticates as. Limits are in ETH; token payments count at their ETH value.
+#[derive(Deserialize)]
+pub struct CreateAgentRequest {
+    pub name: String,
+    pub address: String,
+    pub username: String,
+    pub entity_id: i32,
+    pub per_tx_limit_eth: Option<String>,
+    pub daily_limit_eth: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AgentStatusRequest {
+    pub status: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct GrantWalletRequest {
+    pub wallet_id: i32,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct Freeze {
+    pub id: i32,
+    pub scope: String,
+    pub target_id: Option<i32>,
+    pub reason: String,
+    pub frozen_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub lifted_by: Option<String>,
+    pub lifted_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub lift_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct FreezeAction {
+    pub id: i32,
+    pub freeze_id: i32,
+    pub action: String,
+    pub wallet_id: i32,
+    pub agent_address: Option<String>,
+    pub previous_limit: Option<String>,
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Serialize)]
+pub struct FreezeDetail {
+    #[serde(flatten)]
+    pub freeze: Freeze,
+    pub actions: Vec<FreezeAction>,
+}
+
+/// `scope` is "agent", "wallet", "entity" or "global" (without `target_id`).
+#[derive(Deserialize)]
+pub struct CreateFreezeRequest {
+    pub scope: String,
+    pub target_id: Option<i32>,
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+pub struct LiftFreezeRequest {
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+pub struct FreezeFilter {
+    #[serde(default)]
+    pub active: bool,
+}
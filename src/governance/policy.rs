@@ -0,0 +1,333 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+use ethers::types::U256;
+use sqlx::PgPool;
+use crate::state::AppState;
+use crate::agent::models::PaymentIntent;
+use crate::compliance::models::LegalEntity;
+use crate::finance::registry::find_allowed_token;
+use crate::finance::amounts::{parse_amount, NATIVE_DECIMALS};
+use super::models::{PolicyDecision, Rule};
+
+/// Default velocity window.
+const DEFAULT_VELOCITY_WINDOW_SECS: i64 = 3600;
+
+/// Intent states that count towards velocity (everything that may still move or has moved funds).
+const VELOCITY_STATUSES: &[&str] = &["screened", "pending_approval", "approved", "submitted", "confirmed"];
+
+impl Rule {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Rule::MaxPerTransaction { .. } => "max_per_transaction",
+            Rule::Velocity { .. } => "velocity",
+            Rule::AllowedTokens { .. } => "allowed_tokens",
+            Rule::AllowedHours { .. } => "allowed_hours",
+            Rule::CounterpartyJurisdiction { .. } => "counterparty_jurisdiction",
+            Rule::MinKycLevel { .. } => "min_kyc_level",
+        }
+    }
+
+    fn token(&self) -> Option<&String> {
+        match self {
+            Rule::MaxPerTransaction { token_address, .. } | Rule::Velocity { token_address, .. } => token_address.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// Checks rules before they are stored, so evaluation never meets a malformed rule.
+pub async fn validate_rules(state: &AppState, rules: &[Rule]) -> Result<(), String> {
+    if rules.is_empty() {
+        return Err("A policy needs at least one rule".to_string());
+    }
+
+    for (i, rule) in rules.iter().enumerate() {
+        let decimals = asset_decimals(state, rule.token().map(String::as_str)).await
+            .map_err(|e| format!("Rule #{}: {}", i, e))?;
+
+        check_rule(rule, decimals).map_err(|e| format!("Rule #{} ({}): {}", i, rule.kind(), e))?;
+    }
+
+    Ok(())
+}
+
+/// Checks one rule's parameters; amounts are in units of an asset with `decimals` decimals.
+pub fn check_rule(rule: &Rule, decimals: u8) -> Result<(), String> {
+    match rule {
+        Rule::MaxPerTransaction { amount, .. } => parse_amount(amount, decimals).map(|_| ()),
+        Rule::Velocity { window_seconds, max_count, max_amount, .. } => {
+            if max_count.is_none() && max_amount.is_none() {
+                Err("Set max_count and/or max_amount".to_string())
+            } else if window_seconds.is_some_and(|w| w < 60) {
+                Err("window_seconds must be at least 60".to_string())
+            } else if max_count.is_some_and(|c| c < 0) {
+                Err("max_count cannot be negative".to_string())
+            } else {
+                max_amount.as_ref().map_or(Ok(()), |a| parse_amount(a, decimals).map(|_| ()))
+            }
+        }
+        Rule::AllowedTokens { tokens, .. } => tokens.iter()
+            .try_for_each(|t| t.parse::<ethers::types::Address>().map(|_| ()).map_err(|_| format!("Invalid token address '{}'", t))),
+        Rule::AllowedHours { days, from_hour, to_hour } => {
+            if days.is_empty() {
+                Err("days cannot be empty".to_string())
+            } else if from_hour >= to_hour || *to_hour > 24 {
+                Err("Hours must satisfy from_hour < to_hour <= 24".to_string())
+            } else {
+                Ok(())
+            }
+        }
+        Rule::CounterpartyJurisdiction { allowed, denied } if allowed.is_empty() && denied.is_empty() => {
+            Err("Set allowed and/or denied jurisdictions".to_string())
+        }
+        Rule::CounterpartyJurisdiction { .. } => Ok(()),
+        Rule::MinKycLevel { level } if *level < 0 => Err("level cannot be negative".to_string()),
+        Rule::MinKycLevel { .. } => Ok(()),
+    }
+}
+
+async fn asset_decimals(state: &AppState, token_address: Option<&str>) -> Result<u8, String> {
+    match token_address {
+        Some(token_addr) => find_allowed_token(&state.pool, token_addr, state.chain.chain_id())
+            .await
+            .map(|t| t.decimals as u8)
+            .map_err(|(_, e)| e),
+        None => Ok(NATIVE_DECIMALS),
+    }
+}
+
+fn same_asset(rule_token: Option<&String>, payment_token: Option<&String>) -> bool {
+    match (rule_token, payment_token) {
+        (None, None) => true,
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+        _ => false,
+    }
+}
+
+/// What a rule sees of the payment being decided.
+pub struct Payment<'a> {
+    pub intent: &'a PaymentIntent,
+    pub amount: U256,
+    pub decimals: u8,
+    pub now: DateTime<Utc>,
+    pub counterparty: Option<LegalEntity>,
+}
+
+pub struct Outcome {
+    pub policy_id: i32,
+    pub policy_version: i32,
+    pub rule_index: usize,
+    pub rule_type: &'static str,
+    pub allowed: bool,
+    pub reason: String,
+}
+
+/// Evaluates every enabled policy that applies to the intent's wallet and logs each rule's outcome.
+/// `Err` carries the reason of the first denying rule (or an evaluation error), recorded on the intent.
+pub async fn enforce(state: &AppState, intent: &PaymentIntent, amount: U256) -> Result<(), String> {
+    let policies = current_policies(&state.pool, intent.wallet_id).await?;
+    if policies.is_empty() {
+        return Ok(());
+    }
+
+    let payment = Payment {
+        intent,
+        amount,
+        decimals: asset_decimals(state, intent.token_address.as_deref()).await?,
+        now: Utc::now(),
+        counterparty: counterparty_entity(&state.pool, &intent.target_address).await?,
+    };
+
+    let mut outcomes = Vec::new();
+    for (policy_id, policy_version, rules) in policies {
+        for (rule_index, rule) in rules.iter().enumerate() {
+            let (allowed, reason) = evaluate(&state.pool, rule, &payment).await?;
+            outcomes.push(Outcome { policy_id, policy_version, rule_index, rule_type: rule.kind(), allowed, reason });
+        }
+    }
+
+    record(&state.pool, intent.id, &outcomes).await?;
+    verdict(&outcomes)
+}
+
+/// The rules of the current version of every enabled policy for `wallet_id`, including global ones, by policy id.
+pub async fn current_policies(pool: &PgPool, wallet_id: i32) -> Result<Vec<(i32, i32, Vec<Rule>)>, String> {
+    let policies = sqlx::query_as::<_, (i32, i32, serde_json::Value)>(
+        r#"
+        SELECT p.id, p.current_version, v.rules
+        FROM policies p
+        JOIN policy_versions v ON v.policy_id = p.id AND v.version = p.current_version
+        WHERE p.enabled AND (p.wallet_id IS NULL OR p.wallet_id = $1)
+        ORDER BY p.id
+        "#
+    )
+    .bind(wallet_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    policies.into_iter()
+        .map(|(policy_id, policy_version, rules)| {
+            serde_json::from_value(rules)
+                .map(|rules| (policy_id, policy_version, rules))
+                .map_err(|e| format!("Policy {} v{} is malformed: {}", policy_id, policy_version, e))
+        })
+        .collect()
+}
+
+/// A single denying rule denies the payment; the first one, in policy and rule order, is reported.
+pub fn verdict(outcomes: &[Outcome]) -> Result<(), String> {
+    match outcomes.iter().find(|o| !o.allowed) {
+        Some(denied) => Err(format!(
+            "Denied by policy {} v{} rule #{} ({}): {}",
+            denied.policy_id, denied.policy_version, denied.rule_index, denied.rule_type, denied.reason
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The legal entity behind a counterparty, when the target is one of our registered wallets.
+pub async fn counterparty_entity(pool: &PgPool, address: &str) -> Result<Option<LegalEntity>, String> {
+    sqlx::query_as::<_, LegalEntity>(
+        "SELECT e.* FROM legal_entities e JOIN wallets w ON w.entity_id = e.id WHERE w.address = $1"
+    )
+    .bind(address.to_lowercase())
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Returns `(allowed, explanation)`; `Err` only for evaluation failures.
+async fn evaluate(pool: &PgPool, rule: &Rule, payment: &Payment<'_>) -> Result<(bool, String), String> {
+    let recent = match rule {
+        Rule::Velocity { window_seconds, .. } if same_asset(rule.token(), payment.intent.token_address.as_ref()) => {
+            recent_amounts(pool, payment, window_seconds.unwrap_or(DEFAULT_VELOCITY_WINDOW_SECS)).await?
+        }
+        _ => Vec::new(),
+    };
+    decide(rule, payment, &recent)
+}
+
+/// Amounts of the wallet's other payments in the same asset within the last `window` seconds.
+async fn recent_amounts(pool: &PgPool, payment: &Payment<'_>, window: i64) -> Result<Vec<String>, String> {
+    sqlx::query_scalar(
+        r#"
+        SELECT amount FROM payment_intents
+        WHERE wallet_id = $1 AND id <> $2 AND created_at > $3
+          AND token_address IS NOT DISTINCT FROM $4 AND status = ANY($5)
+        "#
+    )
+    .bind(payment.intent.wallet_id)
+    .bind(payment.intent.id)
+    .bind(payment.now - Duration::seconds(window))
+    .bind(&payment.intent.token_address)
+    .bind(VELOCITY_STATUSES)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Applies a rule to the payment; `recent` holds the amounts `recent_amounts` found for a velocity rule.
+pub fn decide(rule: &Rule, payment: &Payment<'_>, recent: &[String]) -> Result<(bool, String), String> {
+    let intent = payment.intent;
+
+    if let Rule::MaxPerTransaction { .. } | Rule::Velocity { .. } = rule {
+        if !same_asset(rule.token(), intent.token_address.as_ref()) {
+            return Ok((true, "Applies to a different asset".to_string()));
+        }
+    }
+
+    Ok(match rule {
+        Rule::MaxPerTransaction { amount, .. } => {
+            let max = parse_amount(amount, payment.decimals)?;
+            if payment.amount > max {
+                (false, format!("Amount {} exceeds the per-transaction maximum of {}", intent.amount, amount))
+            } else {
+                (true, format!("Amount {} is within the per-transaction maximum of {}", intent.amount, amount))
+            }
+        }
+        Rule::Velocity { window_seconds, max_count, max_amount, .. } => {
+            let window = window_seconds.unwrap_or(DEFAULT_VELOCITY_WINDOW_SECS);
+            let count = recent.len() as i64 + 1;
+            let total = recent.iter()
+                .filter_map(|a| parse_amount(a, payment.decimals).ok())
+                .fold(payment.amount, |acc, a| acc.saturating_add(a));
+
+            if let Some(max) = max_count.filter(|max| count > *max) {
+                (false, format!("{} payments in the last {}s exceed the maximum of {}", count, window, max))
+            } else if let Some(max) = max_amount {
+                if total > parse_amount(max, payment.decimals)? {
+                    (false, format!("Volume in the last {}s would exceed the maximum of {}", window, max))
+                } else {
+                    (true, format!("{} payments in the last {}s, volume within {}", count, window, max))
+                }
+            } else {
+                (true, format!("{} payments in the last {}s", count, window))
+            }
+        }
+        Rule::AllowedTokens { tokens, native } => match &intent.token_address {
+            Some(token) if tokens.iter().any(|t| t.eq_ignore_ascii_case(token)) => (true, format!("Token {} is allowed", token)),
+            Some(token) => (false, format!("Token {} is not in the allowed set", token)),
+            None if *native => (true, "Native ETH is allowed".to_string()),
+            None => (false, "Native ETH is not allowed".to_string()),
+        },
+        Rule::AllowedHours { days, from_hour, to_hour } => {
+            let (day, hour) = (payment.now.weekday(), payment.now.hour());
+            if days.contains(&day) && (*from_hour..*to_hour).contains(&hour) {
+                (true, format!("{} {:02}:00 UTC is inside the allowed window", day, hour))
+            } else {
+                (false, format!("{} {:02}:00 UTC is outside the allowed window ({:02}:00-{:02}:00 on {:?})", day, hour, from_hour, to_hour, days))
+            }
+        }
+        Rule::CounterpartyJurisdiction { allowed, denied } => match &payment.counterparty {
+            None => (false, format!("Counterparty {} has no known legal entity", intent.target_address)),
+            Some(entity) if denied.iter().any(|j| j.eq_ignore_ascii_case(&entity.jurisdiction)) => {
+                (false, format!("Counterparty jurisdiction {} is denied", entity.jurisdiction))
+            }
+            Some(entity) if !allowed.is_empty() && !allowed.iter().any(|j| j.eq_ignore_ascii_case(&entity.jurisdiction)) => {
+                (false, format!("Counterparty jurisdiction {} is not allowed", entity.jurisdiction))
+            }
+            Some(entity) => (true, format!("Counterparty jurisdiction {} is permitted", entity.jurisdiction)),
+        },
+        Rule::MinKycLevel { level } => match &payment.counterparty {
+            None => (false, format!("Counterparty {} has no known legal entity", intent.target_address)),
+            Some(entity) if entity.kyc_level < *level => {
+                (false, format!("Counterparty KYC level {} is below the minimum of {}", entity.kyc_level, level))
+            }
+            Some(entity) => (true, format!("Counterparty KYC level {} meets the minimum of {}", entity.kyc_level, level)),
+        },
+    })
+}
+
+async fn record(pool: &PgPool, intent_id: i32, outcomes: &[Outcome]) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    for outcome in outcomes {
+        sqlx::query(
+            r#"
+            INSERT INTO policy_decisions (intent_id, policy_id, policy_version, rule_index, rule_type, outcome, reason)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(intent_id)
+        .bind(outcome.policy_id)
+        .bind(outcome.policy_version)
+        .bind(outcome.rule_index as i32)
+        .bind(outcome.rule_type)
+        .bind(if outcome.allowed { "allow" } else { "deny" })
+        .bind(&outcome.reason)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+pub async fn intent_decisions(pool: &PgPool, intent_id: i32) -> Result<Vec<PolicyDecision>, (StatusCode, String)> {
+    sqlx::query_as::<_, PolicyDecision>("SELECT * FROM policy_decisions WHERE intent_id = $1 ORDER BY id")
+        .bind(intent_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
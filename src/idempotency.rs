@@ -0,0 +1,249 @@
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use std::future::Future;
+use std::str::FromStr;
+
+pub const IDEMPOTENCY_HEADER: &str = "idempotency-key";
+const REPLAYED_HEADER: &str = "idempotent-replayed";
+const MAX_KEY_LEN: usize = 255;
+/// Keys older than this are forgotten and may be reused.
+const RETENTION_HOURS: i32 = 24;
+/// An in-progress claim older than this is treated as interrupted (the process died mid-request, or the
+/// request is still waiting on the chain). A retry with the same body then reports the resource the
+/// original recorded instead of running again.
+pub const IN_PROGRESS_LEASE_SECS: i64 = 300;
+
+/// Runs `handler` at most once per `(user, scope, Idempotency-Key)`.
+///
+/// Without the header the handler simply runs. With it, the first request's response is stored and
+/// replayed for retries carrying the same body; reusing the key with a different body yields 409,
+/// as does a retry that arrives while the original is still in flight (within `IN_PROGRESS_LEASE_SECS`).
+/// The handler records what it creates on the [`ClaimedKey`]; a retry after the lease passes that resource
+/// to `resume`, and gets 409 for manual resolution if nothing was recorded. The handler never runs twice.
+pub async fn idempotent<T, F, Fut, R, RFut>(
+    pool: &PgPool,
+    headers: &HeaderMap,
+    username: &str,
+    scope: &str,
+    request: &impl Serialize,
+    handler: F,
+    resume: R,
+) -> Response
+where
+    T: Serialize,
+    F: FnOnce(ClaimedKey) -> Fut,
+    Fut: Future<Output = Result<Json<T>, (StatusCode, String)>>,
+    R: FnOnce(String) -> RFut,
+    RFut: Future<Output = Result<Json<T>, (StatusCode, String)>>,
+{
+    let key = match headers.get(IDEMPOTENCY_HEADER).map(|v| v.to_str()) {
+        None => return handler(ClaimedKey { pool: pool.clone(), key: None }).await.into_response(),
+        Some(Ok(key)) if !key.is_empty() && key.len() <= MAX_KEY_LEN => key.to_string(),
+        Some(_) => return (StatusCode::BAD_REQUEST, "Invalid Idempotency-Key header".to_string()).into_response(),
+    };
+
+    let request_hash = match serde_json::to_vec(request) {
+        Ok(bytes) => format!("0x{}", ethers::utils::hex::encode(ethers::utils::keccak256(bytes))),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+
+    let outcome = match claim_key(pool, username, scope, &key, &request_hash).await {
+        Ok(Claim::Fresh) => {
+            let claimed = ClaimedKey { pool: pool.clone(), key: Some((username.to_string(), scope.to_string(), key.clone())) };
+            handler(claimed).await
+        }
+        Ok(Claim::Resume(resource)) => resume(resource).await,
+        Ok(Claim::Replay(status, body)) => {
+            let mut response = stored_response(status, body);
+            response.headers_mut().insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+            return response;
+        }
+        Ok(Claim::Conflict(reason)) => return (StatusCode::CONFLICT, reason).into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+
+    let (status, body) = match outcome {
+        Ok(Json(value)) => match serde_json::to_string(&value) {
+            Ok(body) => (StatusCode::OK, body),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        },
+        Err((status, message)) => (status, message),
+    };
+
+    // Every outcome is stored, including failures: a 500 after broadcasting must not be retried blindly
+    if let Err(e) = sqlx::query(
+        r#"
+        UPDATE idempotency_keys SET response_status = $1, response_body = $2, completed_at = NOW()
+        WHERE username = $3 AND scope = $4 AND idempotency_key = $5
+        "#
+    )
+    .bind(status.as_u16() as i16)
+    .bind(&body)
+    .bind(username)
+    .bind(scope)
+    .bind(&key)
+    .execute(pool)
+    .await
+    {
+        println!("Failed to store idempotent response for key {}: {}", key, e);
+    }
+
+    stored_response(status.as_u16() as i16, body)
+}
+
+/// The key a handler runs under.
+#[derive(Clone)]
+pub struct ClaimedKey {
+    pool: PgPool,
+    /// `(username, scope, key)`; None without an Idempotency-Key header
+    key: Option<(String, String, String)>,
+}
+
+impl ClaimedKey {
+    /// Records the resource the request created (`kind:id`, e.g. `payment_intent:42`). Call it as soon as the
+    /// resource exists, before anything that can outlast the lease, such as broadcasting.
+    pub async fn record(&self, resource: &str) -> Result<(), (StatusCode, String)> {
+        let Some((username, scope, key)) = &self.key else { return Ok(()) };
+        sqlx::query("UPDATE idempotency_keys SET resource = $1 WHERE username = $2 AND scope = $3 AND idempotency_key = $4")
+            .bind(resource)
+            .bind(username)
+            .bind(scope)
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// The id of a `kind:id` resource recorded with [`ClaimedKey::record`].
+pub fn resource_id<T: FromStr>(resource: &str, kind: &str) -> Result<T, (StatusCode, String)> {
+    resource.strip_prefix(kind)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .and_then(|id| id.parse().ok())
+        .ok_or((StatusCode::INTERNAL_SERVER_ERROR, format!("Idempotency-Key recorded {}, not a {}", resource, kind)))
+}
+
+enum Claim {
+    Fresh,
+    Resume(String),
+    Replay(i16, String),
+    Conflict(String),
+}
+
+/// A previously claimed key as stored in `idempotency_keys`.
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredKey {
+    pub request_hash: String,
+    pub response_status: Option<i16>,
+    pub response_body: Option<String>,
+    /// What the original request created, once it did
+    pub resource: Option<String>,
+    /// When the key was claimed; a takeover re-claims it
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum KeyState {
+    /// The original request completed; its response is replayed.
+    Completed(i16, String),
+    /// The key was used with a different request body.
+    Mismatch,
+    /// The original request is still being processed.
+    InProgress,
+    /// The original request did not complete within the lease. It may have created the resource it
+    /// recorded, and may have done so without recording anything.
+    Interrupted(Option<String>),
+}
+
+/// What a retry carrying `request_hash` at `now` finds under an existing key.
+pub fn key_state(stored: &StoredKey, request_hash: &str, now: DateTime<Utc>) -> KeyState {
+    if stored.request_hash != request_hash {
+        return KeyState::Mismatch;
+    }
+    match stored.response_status {
+        Some(status) => KeyState::Completed(status, stored.response_body.clone().unwrap_or_default()),
+        None if now - stored.created_at >= Duration::seconds(IN_PROGRESS_LEASE_SECS) => KeyState::Interrupted(stored.resource.clone()),
+        None => KeyState::InProgress,
+    }
+}
+
+async fn claim_key(pool: &PgPool, username: &str, scope: &str, key: &str, request_hash: &str) -> Result<Claim, sqlx::Error> {
+    sqlx::query("DELETE FROM idempotency_keys WHERE created_at < NOW() - make_interval(hours => $1)")
+        .bind(RETENTION_HOURS)
+        .execute(pool)
+        .await?;
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO idempotency_keys (username, scope, idempotency_key, request_hash)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT DO NOTHING
+        "#
+    )
+    .bind(username)
+    .bind(scope)
+    .bind(key)
+    .bind(request_hash)
+    .execute(pool)
+    .await?;
+
+    if inserted.rows_affected() == 1 {
+        return Ok(Claim::Fresh);
+    }
+
+    let stored = sqlx::query_as::<_, StoredKey>(
+        "SELECT request_hash, response_status, response_body, resource, created_at FROM idempotency_keys WHERE username = $1 AND scope = $2 AND idempotency_key = $3"
+    )
+    .bind(username)
+    .bind(scope)
+    .bind(key)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(match key_state(&stored, request_hash, Utc::now()) {
+        KeyState::Completed(status, body) => Claim::Replay(status, body),
+        KeyState::Mismatch => Claim::Conflict("Idempotency-Key was already used with a different request".to_string()),
+        KeyState::InProgress => Claim::Conflict("A request with this Idempotency-Key is still in progress".to_string()),
+        // The handler may have paid before it was interrupted; only someone checking the chain can tell
+        KeyState::Interrupted(None) => Claim::Conflict(
+            "The request with this Idempotency-Key was interrupted before recording what it created; check its effects before retrying with a new key".to_string(),
+        ),
+        KeyState::Interrupted(Some(resource)) => {
+            // Conditional on the claim we read, so only one of several concurrent retries resumes it
+            let taken = sqlx::query(
+                r#"
+                UPDATE idempotency_keys SET created_at = NOW()
+                WHERE username = $1 AND scope = $2 AND idempotency_key = $3 AND response_status IS NULL AND created_at = $4
+                "#
+            )
+            .bind(username)
+            .bind(scope)
+            .bind(key)
+            .bind(stored.created_at)
+            .execute(pool)
+            .await?;
+
+            if taken.rows_affected() == 1 {
+                Claim::Resume(resource)
+            } else {
+                Claim::Conflict("A request with this Idempotency-Key is still in progress".to_string())
+            }
+        }
+    })
+}
+
+fn stored_response(status: i16, body: String) -> Response {
+    let status = StatusCode::from_u16(status as u16).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    if status.is_success() {
+        (status, [(header::CONTENT_TYPE, "application/json")], body).into_response()
+    } else {
+        (status, body).into_response()
+    }
+}
@@ -5,3 +5,4 @@ pub mod auth;
 pub mod finance;
 pub mod governance;
 pub mod agent;
+pub mod idempotency;
@@ -9,13 +9,16 @@ use dotenvy::dotenv;
 use std::env;
 use aegis_fintech_v1::state::AppState;
 use aegis_fintech_v1::compliance;
+use aegis_fintech_v1::compliance::documents::DocumentStore;
 use aegis_fintech_v1::finance;
+use aegis_fintech_v1::finance::pricing::{PriceSource, StaticPrices, OnChainOracle};
 use aegis_fintech_v1::governance;
 use aegis_fintech_v1::agent;
 
 use tower_http::cors::CorsLayer;
 
-use tower::{ServiceBuilder, limit::RateLimitLayer};
+use tower::{ServiceBuilder, BoxError, buffer::BufferLayer, limit::RateLimitLayer};
+use axum::{error_handling::HandleErrorLayer, http::StatusCode};
 use std::time::Duration;
 
 #[tokio::main]
@@ -40,15 +43,65 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let private_key = env::var("PRIVATE_KEY").unwrap_or("".to_string()); // FORCE USER TO SET ENV VAR
     let contract_addr = env::var("CONTRACT_ADDRESS").unwrap_or("0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string());
 
-    let chain_client = aegis_fintech_v1::chain::ChainClient::new(&rpc_url, &private_key, &contract_addr)
+    let mut chain_client = aegis_fintech_v1::chain::ChainClient::new(&rpc_url, &private_key, &contract_addr)
         .await
         .expect("Chain Client init failed. Is the Hardhat node running? (Check RPC_URL)");
 
+    // Local nodes need their own Multicall3 deployment (see scripts/deploy.js)
+    if let Ok(multicall_addr) = env::var("MULTICALL_ADDRESS") {
+        chain_client = chain_client.with_multicall(&multicall_addr)?;
+    }
+
+    // Public base URL under which AegisID token metadata is served (used as the token URI prefix)
+    let metadata_base_url = env::var("METADATA_BASE_URL").unwrap_or("http://localhost:8080".to_string());
+
+    let chain = std::sync::Arc::new(chain_client);
+
+    // Prices for value-denominated limits: an on-chain IPriceOracle, or fixed fixtures for tests and local nodes
+    let prices: std::sync::Arc<dyn PriceSource> = match env::var("PRICE_ORACLE_ADDRESS") {
+        Ok(oracle_addr) => std::sync::Arc::new(OnChainOracle::new(chain.clone(), &oracle_addr)?),
+        Err(_) => std::sync::Arc::new(StaticPrices::parse(&env::var("PRICE_FIXTURES").unwrap_or_default())?),
+    };
+
+    // KYC evidence is encrypted at rest on local disk; losing the key makes stored documents unreadable
+    let document_key = env::var("KYC_DOCUMENT_KEY").expect("KYC_DOCUMENT_KEY must be set (32 bytes as hex)");
+    let document_dir = env::var("KYC_DOCUMENT_DIR").unwrap_or("./data/kyc-documents".to_string());
+    let documents = std::sync::Arc::new(DocumentStore::new(document_dir, &document_key)?);
+
     let state = AppState { 
         pool,
-        chain: std::sync::Arc::new(chain_client),
+        chain,
+        metadata_base_url,
+        prices,
+        documents,
     };
 
+    // Recurring payments run in the background against the same state as the API
+    tokio::spawn(agent::scheduler::run(state.clone()));
+    tokio::spawn(agent::approvals::run_expiry(state.pool.clone()));
+
+    // Payments whose receipt was missed are settled from the chain; batches interrupted by a restart carry on
+    tokio::spawn(agent::intents::run_reconciliation(state.clone()));
+    tokio::spawn(agent::payouts::resume(state.clone()));
+
+    // Wallet movements are indexed from chain logs and reported as camt.053/camt.054
+    let indexer_start_block = env::var("INDEXER_START_BLOCK").ok().and_then(|b| b.parse().ok()).unwrap_or(0);
+    let indexer_confirmations = env::var("INDEXER_CONFIRMATIONS").ok().and_then(|c| c.parse().ok()).unwrap_or(0);
+    tokio::spawn(finance::indexer::run(state.clone(), indexer_start_block, indexer_confirmations));
+    tokio::spawn(compliance::iso20022::statements::run(state.clone()));
+
+    // Verified entities are flagged before their KYC review is due and lapsed once it is overdue
+    tokio::spawn(compliance::reverification::run(state.clone()));
+
+    // Finish any sanctions re-screen interrupted by a restart (imports start their own)
+    tokio::spawn(compliance::sanctions::screening::resume(state.pool.clone()));
+
+    // Transaction monitoring scenarios are evaluated hourly and raise alerts for compliance triage
+    tokio::spawn(compliance::monitoring::run(state.pool.clone()));
+
+    // Compliance cases nearing or past their SLA deadline are flagged to the team
+    tokio::spawn(compliance::cases::sla::run(state.pool.clone()));
+
     // Middleware: Rate Limit (100 req/sec) & Strict CORS
     let cors = CorsLayer::new()
         .allow_origin(["http://localhost:3000".parse().unwrap(), "http://127.0.0.1:3000".parse().unwrap()])
@@ -59,6 +112,7 @@ use axum::middleware;
 
     let app = Router::new()
         .route("/health", get(health_check))
+        .merge(compliance::metadata_router())
         .nest("/api/compliance", compliance::router().route_layer(middleware::from_extractor::<aegis_fintech_v1::auth::Claims>()))
         .nest("/api/finance", finance::router().route_layer(middleware::from_extractor::<aegis_fintech_v1::auth::Claims>()))
         .nest("/api/governance", governance::router().route_layer(middleware::from_extractor::<aegis_fintech_v1::auth::Claims>()))
@@ -67,6 +121,8 @@ use axum::middleware;
         .with_state(state)
         .layer(
             ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(|_: BoxError| async { StatusCode::TOO_MANY_REQUESTS }))
+                .layer(BufferLayer::new(1024))
                 .layer(RateLimitLayer::new(100, Duration::from_secs(1)))
                 .layer(cors)
         );
@@ -1,10 +1,15 @@
 use sqlx::postgres::PgPool;
 use std::sync::Arc;
 use crate::chain::ChainClient;
+use crate::compliance::documents::DocumentStore;
+use crate::finance::pricing::PriceSource;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
     pub chain: Arc<ChainClient>,
+    pub metadata_base_url: String,
+    pub prices: Arc<dyn PriceSource>,
+    pub documents: Arc<DocumentStore>,
 }
 
@@ -23,4 +23,20 @@ describe("AegisID", function () {
             aegisId.transferFrom(owner.address, otherAccount.address, 0)
         ).to.be.revertedWith("AegisID: Soulbound token cannot be transferred");
     });
+
+    it("Should let the issuer revoke an identity", async function () {
+        const [owner, otherAccount] = await ethers.getSigners();
+        const AegisID = await ethers.getContractFactory("AegisID");
+        const aegisId = await AegisID.deploy();
+
+        await aegisId.mint(otherAccount.address, "http://example.com/token/1");
+
+        await expect(aegisId.connect(otherAccount).revoke(0, "KYC review overdue")).to.be.reverted;
+
+        await expect(aegisId.revoke(0, "KYC review overdue"))
+            .to.emit(aegisId, "Revoked")
+            .withArgs(0, otherAccount.address, "KYC review overdue");
+        expect(await aegisId.balanceOf(otherAccount.address)).to.equal(0);
+        await expect(aegisId.ownerOf(0)).to.be.reverted;
+    });
 });
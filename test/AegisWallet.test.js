@@ -173,4 +173,144 @@ describe("AegisWallet Part 1: Financial Core", function () {
             ).to.be.revertedWith("AegisRules: Daily limit exceeded");
         });
     });
+
+    describe("Token and Value Limits", function () {
+        let oracle;
+
+        beforeEach(async function () {
+            await owner.sendTransaction({
+                to: await wallet.getAddress(),
+                value: ethers.parseEther("5.0")
+            });
+            await usdc.mint(await wallet.getAddress(), ethers.parseEther("10000.0"));
+
+            // Reference currency prices: 1 ETH = 3000, 1 USDC = 1
+            const OracleFactory = await ethers.getContractFactory("StaticPriceOracle");
+            oracle = await OracleFactory.deploy();
+            await oracle.waitForDeployment();
+            await oracle.setPrice(ethers.ZeroAddress, ethers.parseEther("3000"));
+            await oracle.setPrice(await usdc.getAddress(), ethers.parseEther("1"));
+            await rules.setPriceOracle(await oracle.getAddress());
+        });
+
+        it("Should count token amounts against the token's own limit, not the ETH limit", async function () {
+            // 10 ETH legacy limit would block 500 USDC if amounts were counted raw
+            await rules.setTokenLimit(agent.address, await usdc.getAddress(), ethers.parseEther("1000.0"));
+
+            await wallet.connect(agent).executeERC20(await usdc.getAddress(), recipient.address, ethers.parseEther("500.0"));
+            await wallet.connect(agent).executeERC20(await usdc.getAddress(), recipient.address, ethers.parseEther("500.0"));
+            await expect(
+                wallet.connect(agent).executeERC20(await usdc.getAddress(), recipient.address, ethers.parseEther("1.0"))
+            ).to.be.revertedWith("AegisRules: Token limit exceeded");
+
+            // ETH still uses the legacy daily limit
+            await wallet.connect(agent).execute(recipient.address, ethers.parseEther("1.0"), "0x");
+            const [, spent] = await rules.agentRules(agent.address);
+            expect(spent).to.equal(ethers.parseEther("1.0"));
+        });
+
+        it("Should enforce a unified value limit across assets", async function () {
+            // 5000 in the reference currency per day
+            await rules.setValueLimit(agent.address, ethers.parseEther("5000"));
+
+            // 1 ETH = 3000
+            await wallet.connect(agent).execute(recipient.address, ethers.parseEther("1.0"), "0x");
+            // 1500 USDC = 1500 (total 4500)
+            await wallet.connect(agent).executeERC20(await usdc.getAddress(), recipient.address, ethers.parseEther("1500.0"));
+
+            const [, spent] = await rules.valueRules(agent.address);
+            expect(spent).to.equal(ethers.parseEther("4500"));
+
+            // 600 USDC would make 5100
+            await expect(
+                wallet.connect(agent).executeERC20(await usdc.getAddress(), recipient.address, ethers.parseEther("600.0"))
+            ).to.be.revertedWith("AegisRules: Value limit exceeded");
+        });
+
+        it("Should apply both token and value limits when both are set", async function () {
+            await rules.setValueLimit(agent.address, ethers.parseEther("5000"));
+            await rules.setTokenLimit(agent.address, await usdc.getAddress(), ethers.parseEther("100.0"));
+
+            await expect(
+                wallet.connect(agent).executeERC20(await usdc.getAddress(), recipient.address, ethers.parseEther("200.0"))
+            ).to.be.revertedWith("AegisRules: Token limit exceeded");
+        });
+
+        it("Should fall back to the legacy limit once token limits are removed", async function () {
+            await rules.setTokenLimit(agent.address, await usdc.getAddress(), ethers.parseEther("1000.0"));
+            await rules.removeTokenLimit(agent.address, await usdc.getAddress());
+
+            await expect(
+                wallet.connect(agent).executeERC20(await usdc.getAddress(), recipient.address, ethers.parseEther("500.0"))
+            ).to.be.revertedWith("AegisRules: Daily limit exceeded");
+        });
+
+        it("Should refuse value limits without a price for the asset", async function () {
+            await rules.setValueLimit(agent.address, ethers.parseEther("5000"));
+            await oracle.setPrice(await usdc.getAddress(), 0);
+
+            await expect(
+                wallet.connect(agent).executeERC20(await usdc.getAddress(), recipient.address, ethers.parseEther("1.0"))
+            ).to.be.revertedWith("StaticPriceOracle: Unknown asset");
+        });
+
+        it("Should BLOCK a blocked agent whatever limits apply", async function () {
+            await rules.setValueLimit(agent.address, ethers.parseEther("5000"));
+            await rules.setTokenLimit(agent.address, await usdc.getAddress(), ethers.parseEther("1000.0"));
+            await rules.setBlocked(agent.address, true);
+
+            await expect(
+                wallet.connect(agent).executeERC20(await usdc.getAddress(), recipient.address, ethers.parseEther("1.0"))
+            ).to.be.revertedWith("AegisRules: Agent blocked");
+            await expect(
+                wallet.connect(agent).execute(recipient.address, ethers.parseEther("1.0"), "0x")
+            ).to.be.revertedWith("AegisRules: Agent blocked");
+
+            await rules.setBlocked(agent.address, false);
+            await wallet.connect(agent).executeERC20(await usdc.getAddress(), recipient.address, ethers.parseEther("1.0"));
+        });
+
+        it("Should only let the Owner configure limits and the oracle", async function () {
+            await expect(rules.connect(agent).setValueLimit(agent.address, 1))
+                .to.be.revertedWithCustomError(rules, "OwnableUnauthorizedAccount");
+            await expect(rules.connect(agent).setPriceOracle(agent.address))
+                .to.be.revertedWithCustomError(rules, "OwnableUnauthorizedAccount");
+            await expect(rules.connect(agent).setBlocked(agent.address, false))
+                .to.be.revertedWithCustomError(rules, "OwnableUnauthorizedAccount");
+        });
+    });
+
+    describe("Emergency Pause", function () {
+        beforeEach(async function () {
+            await owner.sendTransaction({
+                to: await wallet.getAddress(),
+                value: ethers.parseEther("5.0")
+            });
+            await usdc.mint(await wallet.getAddress(), ethers.parseEther("100.0"));
+        });
+
+        it("Should BLOCK execute and executeERC20 while paused", async function () {
+            await wallet.pause();
+
+            await expect(
+                wallet.connect(agent).execute(recipient.address, ethers.parseEther("1.0"), "0x")
+            ).to.be.revertedWithCustomError(wallet, "EnforcedPause");
+            await expect(
+                wallet.connect(agent).executeERC20(await usdc.getAddress(), recipient.address, ethers.parseEther("1.0"))
+            ).to.be.revertedWithCustomError(wallet, "EnforcedPause");
+        });
+
+        it("Should resume after unpause", async function () {
+            await wallet.pause();
+            await wallet.unpause();
+
+            await wallet.connect(agent).execute(recipient.address, ethers.parseEther("1.0"), "0x");
+            expect(await wallet.paused()).to.equal(false);
+        });
+
+        it("Should only let the Owner pause", async function () {
+            await expect(wallet.connect(agent).pause())
+                .to.be.revertedWithCustomError(wallet, "OwnableUnauthorizedAccount");
+        });
+    });
 });
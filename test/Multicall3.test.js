@@ -0,0 +1,51 @@
+const { expect } = require("chai");
+const { ethers } = require("hardhat");
+
+describe("Multicall3 (Batched Reads)", function () {
+    let multicall, aegisID;
+    let owner, agent, stranger;
+
+    beforeEach(async function () {
+        [owner, agent, stranger] = await ethers.getSigners();
+
+        const MulticallFactory = await ethers.getContractFactory("Multicall3");
+        multicall = await MulticallFactory.deploy();
+        await multicall.waitForDeployment();
+
+        const AegisIDFactory = await ethers.getContractFactory("AegisID");
+        aegisID = await AegisIDFactory.deploy();
+        await aegisID.waitForDeployment();
+
+        await aegisID.mint(agent.address, "ipfs://identity-metadata");
+    });
+
+    it("Should batch AegisID balanceOf for many holders", async function () {
+        const target = await aegisID.getAddress();
+        const calls = [agent, stranger].map((signer) => ({
+            target,
+            allowFailure: true,
+            callData: aegisID.interface.encodeFunctionData("balanceOf", [signer.address]),
+        }));
+
+        const results = await multicall.aggregate3.staticCall(calls);
+        const balances = results.map((r) => aegisID.interface.decodeFunctionResult("balanceOf", r.returnData)[0]);
+
+        expect(balances).to.deep.equal([1n, 0n]);
+    });
+
+    it("Should report failed calls without reverting when failure is allowed", async function () {
+        const calls = [{
+            target: await aegisID.getAddress(),
+            allowFailure: true,
+            callData: aegisID.interface.encodeFunctionData("ownerOf", [42]),
+        }];
+
+        const results = await multicall.aggregate3.staticCall(calls);
+        expect(results[0].success).to.equal(false);
+    });
+
+    it("Should return native balances", async function () {
+        const expected = await ethers.provider.getBalance(stranger.address);
+        expect(await multicall.getEthBalance(stranger.address)).to.equal(expected);
+    });
+});
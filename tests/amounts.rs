@@ -0,0 +1,63 @@
+use aegis_fintech_v1::finance::amounts::{format_amount, parse_amount, NATIVE_DECIMALS};
+use ethers::types::U256;
+
+#[test]
+fn parses_decimal_strings_into_base_units() {
+    assert_eq!(parse_amount("12.5", 6), Ok(U256::from(12_500_000u64)));
+    assert_eq!(parse_amount("1", NATIVE_DECIMALS), Ok(U256::exp10(18)));
+    assert_eq!(parse_amount("0.000001", 6), Ok(U256::one()));
+    assert_eq!(parse_amount(".5", 1), Ok(U256::from(5)));
+    assert_eq!(parse_amount("7.", 2), Ok(U256::from(700)));
+    assert_eq!(parse_amount(" 3 ", 0), Ok(U256::from(3)));
+}
+
+#[test]
+fn rejects_excess_precision_instead_of_rounding() {
+    // 0.0000005 would round to 0 or 1 base units; neither is what was asked for
+    assert!(parse_amount("0.0000005", 6).unwrap_err().contains("exceeds 6 decimal places"));
+    assert!(parse_amount("1.999", 2).is_err());
+    assert!(parse_amount("1.5", 0).is_err());
+}
+
+#[test]
+fn leading_and_trailing_zeros_do_not_change_the_value() {
+    assert_eq!(parse_amount("007.50", 2), Ok(U256::from(750)));
+    assert_eq!(parse_amount("0000", 6), Ok(U256::zero()));
+    // Trailing zeros past the token's decimals add no precision
+    assert_eq!(parse_amount("1.5000000", 6), Ok(U256::from(1_500_000u64)));
+    assert_eq!(parse_amount("2.0", 0), Ok(U256::from(2)));
+}
+
+#[test]
+fn rejects_malformed_amounts() {
+    for amount in ["", ".", "-1", "+1", "1e18", "1,5", "1.2.3", "0x10", "one"] {
+        assert!(parse_amount(amount, 18).is_err(), "{:?} was accepted", amount);
+    }
+}
+
+#[test]
+fn rejects_amounts_beyond_uint256() {
+    let max = U256::MAX.to_string();
+    assert_eq!(parse_amount(&max, 0), Ok(U256::MAX));
+    assert!(parse_amount(&format!("{}0", max), 0).unwrap_err().contains("out of range"));
+    // Fits as a whole number but not once scaled to 18 decimals
+    assert!(parse_amount(&max[..61], NATIVE_DECIMALS).is_err());
+}
+
+#[test]
+fn formats_base_units_without_trailing_zeros() {
+    assert_eq!(format_amount(U256::from(12_500_000u64), 6), "12.5");
+    assert_eq!(format_amount(U256::one(), 6), "0.000001");
+    assert_eq!(format_amount(U256::from(3_000_000u64), 6), "3");
+    assert_eq!(format_amount(U256::zero(), 18), "0");
+    assert_eq!(format_amount(U256::from(42), 0), "42");
+    assert_eq!(format_amount(U256::MAX, 0), U256::MAX.to_string());
+}
+
+#[test]
+fn formatting_round_trips_through_parsing() {
+    for (amount, decimals) in [("12.5", 6), ("0.000000000000000001", 18), ("1000000", 6), ("9.87654321", 8)] {
+        let value = parse_amount(amount, decimals).unwrap();
+        assert_eq!(format_amount(value, decimals), amount);
+    }
+}
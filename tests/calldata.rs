@@ -0,0 +1,21 @@
+use aegis_fintech_v1::agent::calldata::{encode_call, fund_moving_function, parse_signature, selector_of};
+
+#[test]
+fn recognises_token_transfers_and_approvals() {
+    let transfer = parse_signature("transfer(address to, uint256 amount) returns (bool)").unwrap();
+    let data = encode_call(&transfer, &["0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(), "1000000".to_string()]).unwrap();
+    assert_eq!(selector_of(&data).as_deref(), Some("0xa9059cbb"));
+    assert_eq!(fund_moving_function(&data), Some("transfer(address,uint256)"));
+
+    let approve = parse_signature("approve(address spender, uint256 amount)").unwrap();
+    let data = encode_call(&approve, &["0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(), "1".to_string()]).unwrap();
+    assert_eq!(fund_moving_function(&data), Some("approve(address,uint256)"));
+}
+
+#[test]
+fn other_calls_do_not_move_funds() {
+    let vote = parse_signature("castVote(uint256 proposalId, uint8 support)").unwrap();
+    let data = encode_call(&vote, &["7".to_string(), "1".to_string()]).unwrap();
+    assert_eq!(fund_moving_function(&data), None);
+    assert_eq!(fund_moving_function(&Default::default()), None);
+}
@@ -0,0 +1,177 @@
+use aegis_fintech_v1::compliance::cases::report::{missing_information, to_xml, XML_NAMESPACE};
+use aegis_fintech_v1::compliance::cases::sla::{due_at, warn_at};
+use aegis_fintech_v1::compliance::cases::CaseStatus;
+use aegis_fintech_v1::compliance::models::{
+    SarActivity, SarAttachment, SarCase, SarDraft, SarIndicator, SarSubject, SarTotal, SarTransaction,
+};
+use chrono::{Duration, TimeZone, Utc};
+
+fn draft() -> SarDraft {
+    let opened_at = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+    let at = Utc.with_ymd_and_hms(2026, 3, 1, 14, 30, 0).unwrap();
+    SarDraft {
+        report_type: "SAR".to_string(),
+        reference: "CASE-000042-SAR".to_string(),
+        generated_at: opened_at + Duration::days(3),
+        generated_by: "officer".to_string(),
+        case: SarCase {
+            id: 42,
+            title: "Structured payouts to new counterparties".to_string(),
+            priority: "high".to_string(),
+            status: "escalated".to_string(),
+            opened_by: "analyst".to_string(),
+            assigned_to: Some("analyst".to_string()),
+            opened_at,
+            due_at: due_at("high", opened_at),
+            filing_reference: None,
+        },
+        subjects: vec![SarSubject {
+            entity_id: 7,
+            legal_name: Some("Northwind Trading & Co".to_string()),
+            lei: Some("5299000J2N45DDNE4Y28".to_string()),
+            bic: None,
+            iban: None,
+            jurisdiction: "DE".to_string(),
+            kyc_level: 2,
+            kyc_status: "verified".to_string(),
+            wallets: vec!["0x5fbdb2315678afecb367f032d93f642f64180aa3".to_string()],
+        }],
+        activity: SarActivity {
+            from: Some(at),
+            to: Some(at + Duration::hours(3)),
+            totals: vec![SarTotal { direction: "debit".to_string(), asset: "USDC".to_string(), count: 2, amount: "19400".to_string() }],
+        },
+        transactions: vec![
+            SarTransaction {
+                flow_type: "intent".to_string(),
+                id: 11,
+                at,
+                direction: "debit".to_string(),
+                wallet: "0x5fbdb2315678afecb367f032d93f642f64180aa3".to_string(),
+                counterparty: "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+                asset: "USDC".to_string(),
+                amount: "9500".to_string(),
+                tx_hash: Some("0xabc".to_string()),
+            },
+            SarTransaction {
+                flow_type: "movement".to_string(),
+                id: 12,
+                at: at + Duration::hours(3),
+                direction: "debit".to_string(),
+                wallet: "0x5fbdb2315678afecb367f032d93f642f64180aa3".to_string(),
+                counterparty: "0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc".to_string(),
+                asset: "USDC".to_string(),
+                amount: "9900".to_string(),
+                tx_hash: None,
+            },
+        ],
+        indicators: vec![SarIndicator {
+            source: "monitoring_alert".to_string(),
+            id: 3,
+            kind: "structuring".to_string(),
+            status: "escalated".to_string(),
+            description: "2 payments of 9500 to 9900 USDC within 3 hours, just below the limit of 10000 USDC (high severity)".to_string(),
+            raised_at: at + Duration::hours(4),
+        }],
+        narrative: Some("Payouts split below the <10000> approval threshold".to_string()),
+        attachments: vec![SarAttachment {
+            id: 5,
+            file_name: "bank-statement.pdf".to_string(),
+            content_type: "application/pdf".to_string(),
+            content_hash: "0x1234".to_string(),
+        }],
+        missing: Vec::new(),
+    }
+}
+
+#[test]
+fn case_workflow_allows_only_its_transitions() {
+    use CaseStatus::*;
+    assert!(Open.can_transition_to(Investigating));
+    assert!(Open.can_transition_to(Closed));
+    assert!(!Open.can_transition_to(Reported));
+    assert!(Investigating.can_transition_to(Escalated));
+    assert!(!Investigating.can_transition_to(Reported));
+    assert!(Escalated.can_transition_to(Investigating));
+    assert!(Escalated.can_transition_to(Reported));
+    assert!(Reported.can_transition_to(Closed));
+    assert!(!Closed.can_transition_to(Open));
+    assert_eq!(CaseStatus::parse("reported"), Some(Reported));
+    assert!(Escalated.is_pending() && !Reported.is_pending());
+}
+
+#[test]
+fn escalated_cases_are_decided_by_a_second_reviewer() {
+    use CaseStatus::*;
+    assert!(Investigating.can_decide(Some("analyst"), "analyst"));
+    assert!(!Investigating.can_decide(Some("analyst"), "officer"));
+    assert!(!Escalated.can_decide(Some("analyst"), "analyst"));
+    assert!(Escalated.can_decide(Some("analyst"), "officer"));
+    assert!(Open.can_decide(None, "officer"));
+    assert!(!Closed.can_decide(Some("analyst"), "analyst"));
+}
+
+#[test]
+fn sla_deadline_follows_priority_and_warns_before_it() {
+    let opened_at = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+    assert_eq!(due_at("critical", opened_at), opened_at + Duration::days(1));
+    assert_eq!(due_at("high", opened_at), opened_at + Duration::days(7));
+    assert_eq!(due_at("low", opened_at), opened_at + Duration::days(30));
+
+    let due = due_at("medium", opened_at);
+    // Four fifths of the 14 days have passed
+    assert_eq!(warn_at(opened_at, due), due - Duration::minutes(14 * 24 * 60 / 5));
+}
+
+#[test]
+fn drafts_list_what_is_missing_before_filing() {
+    let mut draft = draft();
+    assert!(missing_information(&draft).is_empty());
+
+    draft.subjects[0].legal_name = None;
+    draft.transactions.clear();
+    draft.narrative = None;
+    let missing = missing_information(&draft);
+    assert_eq!(missing.len(), 3);
+    assert!(missing[0].contains("legal name of entity 7"));
+    assert!(missing[1].starts_with("transactions"));
+    assert!(missing[2].starts_with("narrative"));
+}
+
+#[test]
+fn renders_draft_as_xml() {
+    let xml = to_xml(&draft());
+    let document = roxmltree::Document::parse(&xml).unwrap();
+    let root = document.root_element();
+    assert_eq!(root.tag_name().namespace(), Some(XML_NAMESPACE));
+
+    let report = root.first_element_child().unwrap();
+    assert_eq!(report.tag_name().name(), "SuspiciousActivityReport");
+    assert_eq!(report.attribute("Type"), Some("SAR"));
+    assert_eq!(report.attribute("Status"), Some("draft"));
+
+    let text = |name: &str| report.descendants().find(|n| n.has_tag_name((XML_NAMESPACE, name))).and_then(|n| n.text());
+    assert_eq!(text("Reference"), Some("CASE-000042-SAR"));
+    assert_eq!(text("DueAt"), Some("2026-03-09T09:00:00Z"));
+    assert_eq!(text("LegalName"), Some("Northwind Trading & Co"));
+    assert_eq!(text("Narrative"), Some("Payouts split below the <10000> approval threshold"));
+    assert_eq!(text("From"), Some("2026-03-01T14:30:00Z"));
+
+    let transactions: Vec<_> = report.descendants().filter(|n| n.has_tag_name((XML_NAMESPACE, "Transaction"))).collect();
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(transactions[1].attribute("Type"), Some("movement"));
+    // Optional elements are left out rather than written empty
+    assert!(!transactions[1].children().any(|n| n.has_tag_name((XML_NAMESPACE, "TxHash"))));
+
+    let total = report.descendants().find(|n| n.has_tag_name((XML_NAMESPACE, "Total"))).unwrap();
+    assert_eq!(total.attribute("Count"), Some("2"));
+    assert_eq!(total.text(), Some("19400"));
+}
+
+#[test]
+fn str_drafts_use_their_own_root_element() {
+    let mut draft = draft();
+    draft.report_type = "STR".to_string();
+    let xml = to_xml(&draft);
+    assert!(xml.contains("<SuspiciousTransactionReport Type=\"STR\" Status=\"draft\">"));
+}
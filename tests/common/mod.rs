@@ -0,0 +1,24 @@
+// Shared by the ISO 20022 test crates; each uses only some of these helpers.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+/// `tests/<dir>/iso20022/<name>`, where `dir` is `golden` (our output) or `fixtures` (messages from banks).
+pub fn iso20022_path(dir: &str, name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join(dir).join("iso20022").join(name)
+}
+
+pub fn read(dir: &str, name: &str) -> String {
+    let path = iso20022_path(dir, name);
+    std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("missing file {}", path.display()))
+}
+
+/// Compares against `tests/golden/iso20022/<name>`; run with `UPDATE_GOLDEN=1` to rewrite the files.
+pub fn assert_golden(name: &str, actual: &str) {
+    let path = iso20022_path("golden", name);
+    if std::env::var("UPDATE_GOLDEN").is_ok() {
+        std::fs::write(&path, actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("missing golden file {}", path.display()));
+    assert_eq!(actual, expected, "{} differs from golden output", name);
+}
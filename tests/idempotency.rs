@@ -0,0 +1,107 @@
+use aegis_fintech_v1::idempotency::{idempotent, key_state, resource_id, KeyState, StoredKey, IDEMPOTENCY_HEADER, IN_PROGRESS_LEASE_SECS};
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::Json;
+use chrono::{Duration, TimeZone, Utc};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn stored(response_status: Option<i16>, response_body: Option<&str>) -> StoredKey {
+    StoredKey {
+        request_hash: "0xaaaa".to_string(),
+        response_status,
+        response_body: response_body.map(str::to_string),
+        resource: None,
+        created_at: Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap(),
+    }
+}
+
+#[test]
+fn completed_requests_are_replayed() {
+    let key = stored(Some(200), Some("{\"tx_hash\":\"0x1\"}"));
+    let later = key.created_at + Duration::hours(2);
+    assert_eq!(key_state(&key, "0xaaaa", later), KeyState::Completed(200, "{\"tx_hash\":\"0x1\"}".to_string()));
+
+    // Failures are stored and replayed too
+    let key = stored(Some(500), None);
+    assert_eq!(key_state(&key, "0xaaaa", later), KeyState::Completed(500, String::new()));
+}
+
+#[test]
+fn a_different_body_never_reuses_the_key() {
+    let key = stored(Some(200), Some("{}"));
+    assert_eq!(key_state(&key, "0xbbbb", key.created_at), KeyState::Mismatch);
+
+    let key = stored(None, None);
+    assert_eq!(key_state(&key, "0xbbbb", key.created_at + Duration::hours(1)), KeyState::Mismatch);
+}
+
+#[test]
+fn in_progress_claims_expire_after_the_lease() {
+    let key = stored(None, None);
+    let lease = Duration::seconds(IN_PROGRESS_LEASE_SECS);
+    assert_eq!(key_state(&key, "0xaaaa", key.created_at), KeyState::InProgress);
+    assert_eq!(key_state(&key, "0xaaaa", key.created_at + lease - Duration::seconds(1)), KeyState::InProgress);
+    assert_eq!(key_state(&key, "0xaaaa", key.created_at + lease), KeyState::Interrupted(None));
+}
+
+#[test]
+fn interrupted_requests_report_what_they_created() {
+    let key = StoredKey { resource: Some("payment_intent:42".to_string()), ..stored(None, None) };
+    let lease = Duration::seconds(IN_PROGRESS_LEASE_SECS);
+    assert_eq!(key_state(&key, "0xaaaa", key.created_at), KeyState::InProgress);
+    assert_eq!(key_state(&key, "0xaaaa", key.created_at + lease), KeyState::Interrupted(Some("payment_intent:42".to_string())));
+
+    assert_eq!(resource_id::<i32>("payment_intent:42", "payment_intent"), Ok(42));
+    assert!(resource_id::<i32>("payout_batch:42", "payment_intent").is_err());
+    assert!(resource_id::<i32>("payment_intent:", "payment_intent").is_err());
+}
+
+/// Runs a retry against a claim whose lease lapsed while it had created `resource`, counting handler runs.
+async fn retry_after_lapse(pool: &sqlx::PgPool, key: &str, resource: Option<&str>) -> (StatusCode, usize) {
+    let request = serde_json::json!({ "wallet_id": 1, "amount": "1" });
+    let request_hash = format!("0x{}", ethers::utils::hex::encode(ethers::utils::keccak256(serde_json::to_vec(&request).unwrap())));
+    sqlx::query(
+        r#"
+        INSERT INTO idempotency_keys (username, scope, idempotency_key, request_hash, resource, created_at)
+        VALUES ('idempotency-test', 'agent.pay', $1, $2, $3, NOW() - make_interval(secs => $4))
+        "#
+    )
+    .bind(key)
+    .bind(&request_hash)
+    .bind(resource)
+    .bind(IN_PROGRESS_LEASE_SECS as f64 + 60.0)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    let mut headers = HeaderMap::new();
+    headers.insert(IDEMPOTENCY_HEADER, HeaderValue::from_str(key).unwrap());
+    let created = AtomicUsize::new(0);
+    let response = idempotent(
+        pool, &headers, "idempotency-test", "agent.pay", &request,
+        |_| async {
+            // Stands in for creating and broadcasting a second payment intent
+            created.fetch_add(1, Ordering::SeqCst);
+            Ok(Json(serde_json::json!({ "intent_id": 2 })))
+        },
+        |resource| async move { Ok(Json(serde_json::json!({ "intent_id": resource_id::<i32>(&resource, "payment_intent")? }))) },
+    ).await;
+
+    sqlx::query("DELETE FROM idempotency_keys WHERE username = 'idempotency-test' AND idempotency_key = $1")
+        .bind(key)
+        .execute(pool)
+        .await
+        .unwrap();
+    (response.status(), created.load(Ordering::SeqCst))
+}
+
+#[tokio::test]
+#[ignore = "needs a migrated database in DATABASE_URL"]
+async fn a_lapsed_lease_never_creates_a_second_intent() {
+    let pool = sqlx::PgPool::connect(&std::env::var("DATABASE_URL").expect("set DATABASE_URL")).await.unwrap();
+    let key = format!("lapsed-{}", Utc::now().timestamp_nanos_opt().unwrap_or_default());
+
+    // The recorded intent is reported instead
+    assert_eq!(retry_after_lapse(&pool, &format!("{}-recorded", key), Some("payment_intent:1")).await, (StatusCode::OK, 0));
+    // Nothing recorded: the original may still have paid, so it is left for manual resolution
+    assert_eq!(retry_after_lapse(&pool, &format!("{}-unrecorded", key), None).await, (StatusCode::CONFLICT, 0));
+}
@@ -0,0 +1,139 @@
+use aegis_fintech_v1::compliance::iso20022::camt053::{Balance, Camt053, Direction, Entry, Statement};
+use aegis_fintech_v1::compliance::iso20022::camt054::{Camt054, Notification};
+use aegis_fintech_v1::compliance::iso20022::pacs008::{Account, AccountId, Amount, Party, NOT_PROVIDED};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+mod common;
+use common::assert_golden;
+
+fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 1, 15, hour, minute, 0).unwrap()
+}
+
+fn owner() -> (Party, Account) {
+    let party = Party {
+        name: Some("Acme Robotics GmbH".to_string()),
+        lei: Some("5493001KJTIIGC8Y1R12".to_string()),
+        country: Some("DE".to_string()),
+        ..Party::default()
+    };
+    let account = Account {
+        id: AccountId::Other { id: "AEGIS-W1".to_string(), scheme: Some("AEGIS".to_string()) },
+        currency: None,
+        name: Some("Treasury".to_string()),
+        proxy: Some(("EVM".to_string(), "0xe7f1725e7734ce288f8367e1bb143e90bb3f0512".to_string())),
+    };
+    (party, account)
+}
+
+/// An address that is not one of our wallets.
+fn outsider(address: &str) -> (Party, Account) {
+    let party = Party { name: Some(address.to_string()), ..Party::default() };
+    let account = Account {
+        id: AccountId::Other { id: NOT_PROVIDED.to_string(), scheme: None },
+        currency: None,
+        name: None,
+        proxy: Some(("EVM".to_string(), address.to_string())),
+    };
+    (party, account)
+}
+
+fn entry(id: u32, amount: &str, currency: &str, direction: Direction, booked_at: DateTime<Utc>, event: &str, counterparty: &str) -> Entry {
+    let (counterparty, counterparty_account) = outsider(counterparty);
+    Entry {
+        reference: format!("AEGIS-MV{}", id),
+        amount: Amount::new(amount, currency).unwrap(),
+        direction,
+        booked_at,
+        event: event.to_string(),
+        end_to_end_id: None,
+        counterparty,
+        counterparty_account,
+        details: Some(format!("{} log 0 of transaction 0x{:064x} in block {}", event, id, 100 + id)),
+    }
+}
+
+fn balance(amount: &str, currency: &str, direction: Direction) -> Balance {
+    Balance { amount: Amount::new(amount, currency).unwrap(), direction, date: NaiveDate::from_ymd_opt(2026, 1, 15).unwrap() }
+}
+
+fn statement(n: u32, currency: &str, opening: Balance, closing: Balance, entries: Vec<Entry>, info: &str) -> Statement {
+    let (owner, account) = owner();
+    Statement {
+        id: format!("STMT-W1-20260115-{}", n),
+        sequence: Some(3),
+        from: at(0, 0),
+        to: Utc.with_ymd_and_hms(2026, 1, 15, 23, 59, 59).unwrap(),
+        account: Account { currency: Some(currency.to_string()), ..account },
+        owner,
+        opening,
+        closing,
+        entries,
+        info: Some(info.to_string()),
+    }
+}
+
+#[test]
+fn end_of_day_statement() {
+    let mut funding = entry(7, "2.5", "ETH", Direction::Credit, at(9, 12), "Received", "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+    funding.end_to_end_id = Some("AEGIS-FUND-4".to_string());
+    let mut payment = entry(8, "0.75", "ETH", Direction::Debit, at(14, 3), "Executed", "0x70997970c51812dc3a010c7d01b50e0d17dc79c8");
+    payment.end_to_end_id = Some("AEGIS-INTENT-42".to_string());
+
+    let message = Camt053 {
+        msg_id: "STMT-W1-20260115".to_string(),
+        created_at: Utc.with_ymd_and_hms(2026, 1, 16, 0, 5, 0).unwrap(),
+        statements: vec![
+            statement(1, "ETH", balance("1", "ETH", Direction::Credit), balance("2.75", "ETH", Direction::Credit), vec![funding, payment], "ETH (native)"),
+            statement(
+                2,
+                "USD",
+                balance("0", "USD", Direction::Credit),
+                balance("1250.5", "USD", Direction::Credit),
+                vec![entry(9, "1250.5", "USD", Direction::Credit, at(16, 45), "Transfer", "0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc")],
+                "USDC token 0x5fbdb2315678afecb367f032d93f642f64180aa3",
+            ),
+        ],
+    };
+    assert_golden("camt053_wallet.xml", &message.to_xml().unwrap());
+}
+
+#[test]
+fn statement_without_movements() {
+    let message = Camt053 {
+        msg_id: "STMT-W1-20260115".to_string(),
+        created_at: at(0, 5),
+        statements: vec![statement(1, "ETH", balance("0.1", "ETH", Direction::Debit), balance("0.1", "ETH", Direction::Debit), vec![], "ETH (native)")],
+    };
+    let xml = message.to_xml().unwrap();
+    assert!(!xml.contains("<TxsSummry>"));
+    assert!(xml.contains("<CdtDbtInd>DBIT</CdtDbtInd>"));
+}
+
+#[test]
+fn movement_notification() {
+    let (owner, account) = owner();
+    let message = Camt054 {
+        msg_id: "NTF-MV8".to_string(),
+        created_at: at(14, 4),
+        notifications: vec![Notification {
+            id: "NTF-MV8".to_string(),
+            account: Account { currency: Some("ETH".to_string()), ..account },
+            owner,
+            entries: vec![entry(8, "0.75", "ETH", Direction::Debit, at(14, 3), "Executed", "0x70997970c51812dc3a010c7d01b50e0d17dc79c8")],
+            info: Some("ETH (native)".to_string()),
+        }],
+    };
+    assert_golden("camt054_movement.xml", &message.to_xml().unwrap());
+}
+
+#[test]
+fn notification_needs_an_entry() {
+    let (owner, account) = owner();
+    let message = Camt054 {
+        msg_id: "NTF-MV8".to_string(),
+        created_at: at(14, 4),
+        notifications: vec![Notification { id: "NTF-MV8".to_string(), account, owner, entries: vec![], info: None }],
+    };
+    assert!(message.to_xml().is_err());
+}
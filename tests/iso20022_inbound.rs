@@ -0,0 +1,98 @@
+use aegis_fintech_v1::compliance::iso20022::pacs008::{AccountId, ChargeBearer, Pacs008, SettlementMethod};
+use aegis_fintech_v1::compliance::iso20022::pain001::Pain001;
+use chrono::{NaiveDate, TimeZone, Utc};
+
+mod common;
+use common::read;
+
+#[test]
+fn parses_bank_pain001() {
+    let message = Pain001::from_xml(&read("fixtures", "pain001_bank.xml")).unwrap();
+    assert_eq!(message.msg_id, "ACME-20260115-001");
+    assert_eq!(message.created_at, Utc.with_ymd_and_hms(2026, 1, 15, 8, 0, 0).unwrap());
+    assert_eq!(message.initiating_party.lei.as_deref(), Some("5493001KJTIIGC8Y1R12"));
+    assert_eq!(message.payments.len(), 2);
+
+    let payment = &message.payments[0];
+    assert_eq!(payment.payment_info_id, "ACME-PMT-1");
+    assert_eq!(payment.method, "TRF");
+    assert_eq!(payment.execution_date, NaiveDate::from_ymd_opt(2026, 1, 16).unwrap());
+    assert_eq!(payment.debtor.country.as_deref(), Some("DE"));
+    assert!(matches!(&payment.debtor_account.id, AccountId::Iban(iban) if iban == "DE89370400440532013000"));
+    assert_eq!(payment.debtor_agent.bic.as_deref(), Some("DEUTDEFF"));
+
+    let first = &payment.transactions[0];
+    assert_eq!(first.end_to_end_id, "INV-2026-0042");
+    assert_eq!((first.amount.value(), first.amount.currency()), ("1250.5", "USD"));
+    assert_eq!(
+        first.creditor_account.as_ref().and_then(|a| a.proxy.clone()),
+        Some(("EVM".to_string(), "0xE7f1725E7734CE288F8367e1Bb143E90bb3F0512".to_string()))
+    );
+    assert_eq!(first.remittance_info.as_deref(), Some("Invoice 2026-0042 January services"));
+
+    let second = &payment.transactions[1];
+    assert_eq!(second.uetr.as_deref(), Some("7f0c7a4e-2b1d-4c3a-9e8f-0a1b2c3d4e5f"));
+    assert_eq!(second.creditor.as_ref().and_then(|c| c.other_id.clone()), Some(("HRB 12345".to_string(), "TXID".to_string())));
+    assert_eq!(second.creditor_agent.as_ref().and_then(|a| a.bic.as_deref()), Some("COBADEFFXXX"));
+
+    let cheque = &message.payments[1];
+    assert_eq!(cheque.method, "CHK");
+    assert_eq!(cheque.execution_date, NaiveDate::from_ymd_opt(2026, 1, 17).unwrap());
+    assert!(matches!(&cheque.debtor_account.id, AccountId::Other { id, scheme } if id == "AEGIS-W1" && scheme.as_deref() == Some("AEGIS")));
+    assert!(cheque.transactions[0].creditor.is_none());
+}
+
+#[test]
+fn parses_bank_pacs008() {
+    let message = Pacs008::from_xml(&read("fixtures", "pacs008_bank.xml")).unwrap();
+    assert_eq!(message.msg_id, "COBA-20260115-7781");
+    assert_eq!(message.created_at, Utc.with_ymd_and_hms(2026, 1, 15, 13, 22, 5).unwrap() + chrono::Duration::milliseconds(123));
+    assert_eq!(message.settlement_date, NaiveDate::from_ymd_opt(2026, 1, 15));
+    assert_eq!(message.settlement_method, SettlementMethod::Inda);
+
+    let tx = &message.transactions[0];
+    assert_eq!((tx.amount.value(), tx.amount.currency()), ("500000", "USD"));
+    assert_eq!(tx.charge_bearer, ChargeBearer::Shar);
+    assert_eq!(tx.debtor.other_id, Some(("GB-CUST-1182".to_string(), "COBA".to_string())));
+    assert_eq!(tx.debtor_agent.bic.as_deref(), Some("NWBKGB2L"));
+    assert_eq!(tx.creditor.lei.as_deref(), Some("5493001KJTIIGC8Y1R12"));
+    assert_eq!(tx.creditor.country.as_deref(), Some("DE"));
+    assert!(matches!(&tx.creditor_account.as_ref().unwrap().id, AccountId::Other { id, .. } if id == "AEGIS-W1"));
+    assert_eq!(tx.remittance_info.as_deref(), Some("PO 99812 & PO 99813"));
+}
+
+#[test]
+fn reads_back_generated_pacs008() {
+    let xml = read("golden", "pacs008_registered.xml");
+    let message = Pacs008::from_xml(&xml).unwrap();
+    assert_eq!(message.to_xml().unwrap(), xml);
+}
+
+#[test]
+fn skips_components_outside_the_profile() {
+    // The bank fixtures carry a cheque instruction, charges, an intermediary agent and structured remittance
+    let xml = read("fixtures", "pacs008_bank.xml");
+    assert!(xml.contains("<ChrgsInf>") && xml.contains("<IntrmyAgt1>") && xml.contains("<Strd>"));
+    assert_eq!(Pacs008::from_xml(&xml).unwrap().transactions[0].remittance_info.as_deref(), Some("PO 99812 & PO 99813"));
+
+    let xml = read("fixtures", "pain001_bank.xml");
+    assert!(xml.contains("<ChqInstr>"));
+    assert_eq!(Pain001::from_xml(&xml).unwrap().payments[1].transactions[0].end_to_end_id, "CHQ-1");
+}
+
+#[test]
+fn rejects_invalid_mapped_components() {
+    let xml = read("fixtures", "pacs008_bank.xml").replace("<SttlmMtd>INDA</SttlmMtd>", "<SttlmMtd>WIRE</SttlmMtd>");
+    let error = Pacs008::from_xml(&xml).err().unwrap();
+    assert!(error.contains("SttlmMtd"), "{}", error);
+
+    let xml = read("fixtures", "pain001_bank.xml").replace("<EndToEndId>CHQ-1</EndToEndId>", "");
+    let error = Pain001::from_xml(&xml).err().unwrap();
+    assert!(error.contains("expected EndToEndId"), "{}", error);
+}
+
+#[test]
+fn rejects_the_wrong_message_type() {
+    let error = Pain001::from_xml(&read("fixtures", "pacs008_bank.xml")).err().unwrap();
+    assert!(error.contains("Expected root element"), "{}", error);
+}
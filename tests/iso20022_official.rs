@@ -0,0 +1,65 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The unmodified ISO 20022 schemas are not redistributed with the repository. Download them from the
+/// ISO 20022 message catalogue into one directory (keeping names like `pacs.008.001.08.xsd`) and run
+/// `ISO20022_XSD_DIR=<dir> cargo test --test iso20022_official -- --ignored`; xmllint must be installed.
+const XSD_DIR_VAR: &str = "ISO20022_XSD_DIR";
+
+/// Every golden and fixture document, with the official schema file its root namespace names.
+fn documents() -> Vec<(PathBuf, String)> {
+    let tests = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests");
+    let mut documents = Vec::new();
+    for dir in ["golden", "fixtures"] {
+        let dir = tests.join(dir).join("iso20022");
+        for entry in std::fs::read_dir(&dir).unwrap_or_else(|_| panic!("missing directory {}", dir.display())) {
+            let path = entry.unwrap().path();
+            let xml = std::fs::read_to_string(&path).unwrap();
+            let doc = roxmltree::Document::parse(&xml).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+            let namespace = doc.root_element().tag_name().namespace().unwrap_or_default();
+            let message = namespace.rsplit(':').next().unwrap_or_default();
+            documents.push((path, format!("{}.xsd", message)));
+        }
+    }
+    documents.sort();
+    documents
+}
+
+fn xmllint(xsd: &Path, document: &Path) -> Result<(), String> {
+    let output = Command::new("xmllint")
+        .arg("--noout")
+        .arg("--schema")
+        .arg(xsd)
+        .arg(document)
+        .output()
+        .map_err(|e| format!("cannot run xmllint: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[test]
+#[ignore = "needs the official ISO 20022 schemas in ISO20022_XSD_DIR and xmllint"]
+fn documents_are_valid_against_the_official_schemas() {
+    let dir = PathBuf::from(std::env::var(XSD_DIR_VAR).unwrap_or_else(|_| panic!("set {} to the official schemas", XSD_DIR_VAR)));
+
+    let mut failures = Vec::new();
+    for (document, schema) in documents() {
+        let xsd = dir.join(&schema);
+        assert!(xsd.exists(), "{} is missing from {}", schema, dir.display());
+        if let Err(e) = xmllint(&xsd, &document) {
+            failures.push(format!("{} against {}: {}", document.display(), schema, e));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn every_document_names_a_bundled_message_type() {
+    let bundled = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("schemas/iso20022");
+    for (document, schema) in documents() {
+        assert!(bundled.join(&schema).exists(), "{} uses {}, which has no profile schema", document.display(), schema);
+    }
+}
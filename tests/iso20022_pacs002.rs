@@ -0,0 +1,107 @@
+use aegis_fintech_v1::compliance::iso20022::pacs002::{OriginalGroup, Pacs002, StatusReason, TransactionReport, TransactionStatus};
+use chrono::{TimeZone, Utc};
+
+mod common;
+use common::assert_golden;
+
+fn transaction(end_to_end_id: &str, status: TransactionStatus, reason: Option<StatusReason>) -> TransactionReport {
+    TransactionReport {
+        status_id: None,
+        original_instruction_id: None,
+        original_end_to_end_id: end_to_end_id.to_string(),
+        original_tx_id: None,
+        original_uetr: None,
+        status,
+        reason,
+        accepted_at: None,
+    }
+}
+
+fn report(original: OriginalGroup, transactions: Vec<TransactionReport>) -> Pacs002 {
+    Pacs002 {
+        msg_id: "STS-I42-20260115100000".to_string(),
+        created_at: Utc.with_ymd_and_hms(2026, 1, 15, 10, 0, 0).unwrap(),
+        original,
+        transactions,
+    }
+}
+
+#[test]
+fn reason_codes_from_failures() {
+    let code = |reason: &str| StatusReason::from_failure(reason).code;
+    assert_eq!(code("Reverted: AegisRules: Daily limit exceeded"), "AM14");
+    assert_eq!(code("Reverted: AegisRules: Value limit exceeded"), "AM14");
+    assert_eq!(code("Reverted: AegisWallet: Caller not verified Agent"), "AG01");
+    assert_eq!(code("Reverted: ERC20: transfer amount exceeds balance"), "AM04");
+    assert_eq!(code("Payments are frozen (wallet freeze 3): incident 17"), "AC06");
+    assert_eq!(code("Counterparty 0xabc (mixer) is on the global blocklist"), "RR04");
+    assert_eq!(code("Amount exceeds agent payroll-bot's daily limit of 5 ETH"), "AM14");
+    assert_eq!(code("Denied by policy 2 v1 rule #0 (max_per_transaction): Amount 9 exceeds the per-transaction maximum of 5"), "AM02");
+    assert_eq!(code("Denied by policy 2 v3 rule #1 (counterparty_jurisdiction): KP is denied"), "RR04");
+    assert_eq!(code("Creditor: Initech AG is not a registered entity or wallet"), "AC01");
+    assert_eq!(code("No allowed token settles EUR"), "AM03");
+    assert_eq!(code("Transaction 0x12 reverted"), "NARR");
+}
+
+#[test]
+fn rejected_payment() {
+    let mut tx = transaction(
+        "AEGIS-INTENT-42",
+        TransactionStatus::Rjct,
+        Some(StatusReason::from_failure("Reverted: AegisRules: Daily limit exceeded")),
+    );
+    tx.original_tx_id = Some("AEGIS-INTENT-42".to_string());
+    tx.original_uetr = Some("7f0c7a4e-2b1d-4c3a-9e8f-0a1b2c3d4e5f".to_string());
+
+    let original = OriginalGroup {
+        msg_id: "MSG-AEGIS-INTENT-42".to_string(),
+        message_name: "pacs.008.001.08".to_string(),
+        created_at: Some(Utc.with_ymd_and_hms(2026, 1, 15, 9, 30, 0).unwrap()),
+        complete: false,
+    };
+    let report = report(original, vec![tx]);
+    assert_eq!(report.status_codes(), "RJCT");
+    assert_golden("pacs002_rejected.xml", &report.to_xml().unwrap());
+}
+
+#[test]
+fn whole_inbound_message() {
+    let long_reason = format!("Denied by policy 7 v2 rule #3 (allowed_hours): {}", "outside the configured trading window ".repeat(3));
+    let mut settled = transaction("INV-2026-0042", TransactionStatus::Acsc, None);
+    settled.original_instruction_id = Some("ACME-1-1".to_string());
+    settled.accepted_at = Some(Utc.with_ymd_and_hms(2026, 1, 16, 11, 5, 0).unwrap());
+
+    let original = OriginalGroup {
+        msg_id: "ACME-20260115-001".to_string(),
+        message_name: "pain.001.001.09".to_string(),
+        created_at: Some(Utc.with_ymd_and_hms(2026, 1, 15, 8, 0, 0).unwrap()),
+        complete: true,
+    };
+    let report = report(original, vec![
+        settled,
+        transaction("INV-2026-0043", TransactionStatus::Rjct, Some(StatusReason::from_failure(&long_reason))),
+        transaction("CHQ-1", TransactionStatus::Rjct, Some(StatusReason::from_failure("Payment method CHK is not supported; only TRF is"))),
+    ]);
+    assert_eq!(report.group_status(), "PART");
+    assert_eq!(report.status_codes(), "ACSC,RJCT,RJCT");
+    assert_golden("pacs002_inbound.xml", &report.to_xml().unwrap());
+}
+
+#[test]
+fn group_status() {
+    let original = || OriginalGroup { msg_id: "M".to_string(), message_name: "pacs.008.001.08".to_string(), created_at: None, complete: true };
+    let pending = report(original(), vec![
+        transaction("A", TransactionStatus::Acsc, None),
+        transaction("B", TransactionStatus::Pdng, None),
+    ]);
+    assert_eq!(pending.group_status(), "PDNG");
+
+    let settled = report(original(), vec![transaction("A", TransactionStatus::Acsc, None)]);
+    assert_eq!(settled.group_status(), "ACSC");
+}
+
+#[test]
+fn needs_a_transaction() {
+    let original = OriginalGroup { msg_id: "M".to_string(), message_name: "pacs.008.001.08".to_string(), created_at: None, complete: false };
+    assert!(report(original, vec![]).to_xml().is_err());
+}
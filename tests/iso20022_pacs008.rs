@@ -0,0 +1,155 @@
+use aegis_fintech_v1::compliance::iso20022::identifiers::{validate_bic, validate_iban, validate_lei};
+use aegis_fintech_v1::compliance::iso20022::pacs008::{
+    self, Account, AccountId, Agent, Amount, ChargeBearer, CreditTransfer, Pacs008, Party, SettlementMethod,
+};
+use chrono::{NaiveDate, TimeZone, Utc};
+
+mod common;
+use common::{assert_golden, read};
+
+fn wallet_account(id: i32, address: &str) -> Account {
+    Account {
+        id: AccountId::Other { id: format!("AEGIS-W{}", id), scheme: Some("AEGIS".to_string()) },
+        currency: None,
+        name: None,
+        proxy: Some(("EVM".to_string(), address.to_string())),
+    }
+}
+
+fn transfer(amount: Amount, debtor: Party, creditor: Party) -> CreditTransfer {
+    CreditTransfer {
+        instruction_id: None,
+        end_to_end_id: "AEGIS-INTENT-42".to_string(),
+        tx_id: Some("AEGIS-INTENT-42".to_string()),
+        uetr: Some("7f0c7a4e-2b1d-4c3a-9e8f-0a1b2c3d4e5f".to_string()),
+        amount,
+        charge_bearer: ChargeBearer::Slev,
+        debtor,
+        debtor_account: Some(wallet_account(1, "0x5fbdb2315678afecb367f032d93f642f64180aa3")),
+        debtor_agent: Agent { bic: Some("DEUTDEFF".to_string()), ..Agent::default() },
+        creditor_agent: Agent::not_provided(),
+        creditor,
+        creditor_account: Some(wallet_account(2, "0xe7f1725e7734ce288f8367e1bb143e90bb3f0512")),
+        remittance_info: Some("On-chain settlement 0x9c22ff5f21f0b81b113e63f7db6da94fedef11b2119b4088b89664fb9a3cb658".to_string()),
+    }
+}
+
+fn message(transactions: Vec<CreditTransfer>) -> Pacs008 {
+    Pacs008 {
+        msg_id: "MSG-AEGIS-INTENT-42".to_string(),
+        created_at: Utc.with_ymd_and_hms(2026, 1, 15, 9, 30, 0).unwrap(),
+        settlement_date: NaiveDate::from_ymd_opt(2026, 1, 15),
+        settlement_method: SettlementMethod::Clrg,
+        transactions,
+    }
+}
+
+fn acme() -> Party {
+    Party {
+        name: Some("Acme Treasury GmbH".to_string()),
+        lei: Some("5493001KJTIIGC8Y1R12".to_string()),
+        country: Some("DE".to_string()),
+        ..Party::default()
+    }
+}
+
+#[test]
+fn registered_counterparties() {
+    let creditor = Party {
+        name: Some("Globex Ltd".to_string()),
+        bic: Some("COBADEFFXXX".to_string()),
+        country: Some("GB".to_string()),
+        ..Party::default()
+    };
+    let xml = message(vec![transfer(Amount::new("1250.5", "ETH").unwrap(), acme(), creditor)]).to_xml().unwrap();
+    assert_golden("pacs008_registered.xml", &xml);
+}
+
+#[test]
+fn unregistered_creditor_with_iban() {
+    let creditor = Party { name: Some("0xe7f1725e7734ce288f8367e1bb143e90bb3f0512".to_string()), ..Party::default() };
+    let mut tx = transfer(Amount::new("99.99", "USD").unwrap(), acme(), creditor);
+    tx.creditor_account = Some(Account {
+        id: AccountId::Iban("DE89370400440532013000".to_string()),
+        currency: Some("EUR".to_string()),
+        name: None,
+        proxy: None,
+    });
+    let xml = message(vec![tx]).to_xml().unwrap();
+    assert_golden("pacs008_iban.xml", &xml);
+}
+
+#[test]
+fn escapes_markup_in_names() {
+    let debtor = Party { name: Some("Smith & Sons <Holdings> \"UK\"".to_string()), ..Party::default() };
+    let creditor = Party { name: Some("O'Brien & Co".to_string()), ..Party::default() };
+    let xml = message(vec![transfer(Amount::new("1", "ETH").unwrap(), debtor, creditor)]).to_xml().unwrap();
+    assert!(xml.contains("Smith &amp; Sons &lt;Holdings&gt;"));
+    assert!(roxmltree::Document::parse(&xml).is_ok());
+    assert_golden("pacs008_escaped.xml", &xml);
+}
+
+#[test]
+fn normalizes_amounts() {
+    assert_eq!(Amount::new("000123.45000", "EUR").unwrap().value(), "123.45");
+    assert_eq!(Amount::new("7.", "EUR").unwrap().value(), "7");
+    assert_eq!(Amount::new(".5", "EUR").unwrap().value(), "0.5");
+    assert_eq!(Amount::new("0.000010000", "EUR").unwrap().value(), "0.00001");
+}
+
+#[test]
+fn rejects_amounts_iso_20022_cannot_carry() {
+    assert!(Amount::new("0.000001", "EUR").is_err());
+    assert!(Amount::new("1234567890123456789", "EUR").is_err());
+    assert!(Amount::new("-5", "EUR").is_err());
+    assert!(Amount::new("1e5", "EUR").is_err());
+}
+
+#[test]
+fn rejects_non_iso_currencies() {
+    assert!(Amount::new("1", "USDC").is_err());
+    assert!(Amount::new("1", "usd").is_err());
+}
+
+#[test]
+fn validates_identifiers() {
+    assert!(validate_bic("DEUTDEFF").is_ok());
+    assert!(validate_bic("COBADEFFXXX").is_ok());
+    assert!(validate_bic("DEUTDEF").is_err());
+    assert!(validate_iban("DE89370400440532013000").is_ok());
+    assert!(validate_iban("DE89370400440532013001").is_err());
+    assert!(validate_lei("5493001KJTIIGC8Y1R12").is_ok());
+    assert!(validate_lei("5493001KJTIIGC8Y1R13").is_err());
+}
+
+#[test]
+fn refuses_invalid_identifiers_before_serializing() {
+    let mut debtor = acme();
+    debtor.lei = Some("5493001KJTIIGC8Y1R13".to_string());
+    let err = message(vec![transfer(Amount::new("1", "ETH").unwrap(), debtor, Party::default())]).to_xml().unwrap_err();
+    assert!(err.contains("check digit"), "{}", err);
+}
+
+#[test]
+fn schema_rejects_overlong_identifiers() {
+    let mut tx = transfer(Amount::new("1", "ETH").unwrap(), acme(), Party::default());
+    tx.end_to_end_id = "X".repeat(36);
+    let err = message(vec![tx]).to_xml().unwrap_err();
+    assert!(err.contains("EndToEndId"), "{}", err);
+}
+
+#[test]
+fn schema_accepts_golden_files_and_rejects_tampered_ones() {
+    let golden = read("golden", "pacs008_registered.xml");
+    assert!(pacs008::schema().validate(&golden).is_ok());
+
+    // ChrgBr must come after the settlement amount
+    let reordered = golden.replace("<ChrgBr>SLEV</ChrgBr>", "").replace("<PmtId>", "<ChrgBr>SLEV</ChrgBr><PmtId>");
+    assert!(pacs008::schema().validate(&reordered).is_err());
+
+    let bad_currency = golden.replace("Ccy=\"ETH\"", "Ccy=\"ETHER\"");
+    assert!(pacs008::schema().validate(&bad_currency).is_err());
+
+    let missing_agent = golden.replace("<DbtrAgt>", "<Ignored>").replace("</DbtrAgt>", "</Ignored>");
+    assert!(pacs008::schema().validate(&missing_agent).is_err());
+}
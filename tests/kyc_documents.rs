@@ -0,0 +1,59 @@
+use aegis_fintech_v1::compliance::documents::DocumentStore;
+use std::path::PathBuf;
+
+const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+fn store_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aegis-kyc-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[tokio::test]
+async fn documents_round_trip_encrypted() {
+    let dir = store_dir("round-trip");
+    let store = DocumentStore::new(&dir, KEY).unwrap();
+    let content = b"%PDF-1.7 certificate of incorporation";
+
+    let storage_key = store.put(content).await.unwrap();
+    assert_eq!(store.get(&storage_key).await.unwrap(), content);
+
+    // Nothing readable lands on disk
+    let on_disk = std::fs::read(dir.join(format!("{}.bin", storage_key))).unwrap();
+    assert!(!on_disk.windows(8).any(|w| w == b"%PDF-1.7"));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn tampered_or_moved_documents_fail_to_decrypt() {
+    let dir = store_dir("tamper");
+    let store = DocumentStore::new(&dir, KEY).unwrap();
+    let first = store.put(b"register extract").await.unwrap();
+    let second = store.put(b"proof of address").await.unwrap();
+
+    // A file copied onto another document's key is refused
+    std::fs::copy(dir.join(format!("{}.bin", first)), dir.join(format!("{}.bin", second))).unwrap();
+    assert!(store.get(&second).await.is_err());
+
+    let path = dir.join(format!("{}.bin", first));
+    let mut bytes = std::fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    std::fs::write(&path, bytes).unwrap();
+    assert!(store.get(&first).await.is_err());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn documents_need_the_key_they_were_written_with() {
+    let dir = store_dir("key");
+    let storage_key = DocumentStore::new(&dir, KEY).unwrap().put(b"ubo declaration").await.unwrap();
+
+    let other = DocumentStore::new(&dir, &KEY.replace("1f", "20")).unwrap();
+    assert!(other.get(&storage_key).await.is_err());
+    assert!(DocumentStore::new(&dir, "0011").is_err());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,83 @@
+use aegis_fintech_v1::compliance::models::{KycReviewPeriod, LegalEntity};
+use aegis_fintech_v1::compliance::reverification::{applicable, review, LAPSE_DOWNGRADE, LAPSE_SUSPEND};
+use chrono::{Duration, TimeZone, Utc};
+
+fn period(id: i32, jurisdiction: Option<&str>, kyc_level: Option<i16>, review_days: i32, on_lapse: &str) -> KycReviewPeriod {
+    KycReviewPeriod {
+        id,
+        jurisdiction: jurisdiction.map(str::to_string),
+        kyc_level,
+        review_days,
+        warning_days: 14,
+        on_lapse: on_lapse.to_string(),
+        downgrade_to: (on_lapse == LAPSE_DOWNGRADE).then_some(1),
+        created_by: "compliance".to_string(),
+        created_at: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+    }
+}
+
+fn entity(jurisdiction: &str, kyc_level: i16) -> LegalEntity {
+    LegalEntity {
+        id: 7,
+        hash_id: "DE_HRB_123456".to_string(),
+        jurisdiction: jurisdiction.to_string(),
+        kyc_level,
+        on_chain_id: Some("3".to_string()),
+        created_at: Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap(),
+        legal_name: Some("Acme Robotics GmbH".to_string()),
+        lei: None,
+        bic: None,
+        iban: None,
+        kyc_case_id: Some(11),
+        kyc_verified_at: Some(Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap()),
+        kyc_status: "verified".to_string(),
+    }
+}
+
+fn periods() -> Vec<KycReviewPeriod> {
+    vec![
+        period(1, None, None, 365, LAPSE_SUSPEND),
+        period(2, None, Some(3), 180, LAPSE_DOWNGRADE),
+        period(3, Some("DE"), None, 730, LAPSE_SUSPEND),
+        period(4, Some("DE"), Some(3), 90, LAPSE_DOWNGRADE),
+    ]
+}
+
+#[test]
+fn most_specific_period_applies() {
+    let periods = periods();
+    assert_eq!(applicable(&periods, "de", 3).map(|p| p.id), Some(4));
+    assert_eq!(applicable(&periods, "DE", 2).map(|p| p.id), Some(3));
+    assert_eq!(applicable(&periods, "FR", 3).map(|p| p.id), Some(2));
+    assert_eq!(applicable(&periods, "FR", 1).map(|p| p.id), Some(1));
+    assert!(applicable(&periods[1..2], "FR", 1).is_none());
+}
+
+#[test]
+fn deadlines_follow_the_applicable_period() {
+    let entity = entity("DE", 3);
+    let verified_at = entity.kyc_verified_at.unwrap();
+
+    let review = review(&entity, &periods()).unwrap();
+    assert_eq!(review.review_period_id, Some(4));
+    assert_eq!(review.due_at, verified_at + Duration::days(90));
+    assert_eq!(review.warn_at, verified_at + Duration::days(76));
+    assert_eq!(review.on_lapse, LAPSE_DOWNGRADE);
+}
+
+#[test]
+fn unconfigured_entities_use_the_default_validity() {
+    let entity = entity("Delaware, US", 2);
+    let review = review(&entity, &[]).unwrap();
+
+    assert_eq!(review.review_period_id, None);
+    assert_eq!(review.due_at, entity.kyc_verified_at.unwrap() + Duration::days(365));
+    assert_eq!(review.on_lapse, LAPSE_SUSPEND);
+}
+
+#[test]
+fn unverified_entities_have_no_review() {
+    let mut unverified = entity("DE", 0);
+    unverified.kyc_verified_at = None;
+    assert!(review(&unverified, &periods()).is_none());
+}
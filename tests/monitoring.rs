@@ -0,0 +1,146 @@
+use aegis_fintech_v1::compliance::monitoring::alerts::AlertStatus;
+use aegis_fintech_v1::compliance::monitoring::scenarios::{
+    dormant_reactivation, fan_out, rapid_in_out, structuring, Evaluation, Flow, CREDIT, DEBIT, FLOW_INTENT, FLOW_MOVEMENT,
+};
+use aegis_fintech_v1::finance::amounts::parse_amount;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::collections::HashSet;
+
+const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+fn at(hours: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap() + Duration::hours(hours)
+}
+
+fn flow(id: i32, hours: i64, direction: &'static str, amount: &str, counterparty: &str) -> Flow {
+    Flow {
+        flow_type: if direction == DEBIT { FLOW_INTENT } else { FLOW_MOVEMENT },
+        id,
+        wallet_id: 1,
+        at: at(hours),
+        direction,
+        token_address: Some(USDC.to_lowercase()),
+        symbol: "USDC".to_string(),
+        amount: parse_amount(amount, 6).unwrap(),
+        decimals: 6,
+        counterparty: counterparty.to_string(),
+        tx_hash: None,
+    }
+}
+
+fn ids(flows: &[Flow]) -> Vec<i32> {
+    flows.iter().map(|f| f.id).collect()
+}
+
+#[test]
+fn structuring_flags_payments_just_below_the_limit() {
+    let flows = vec![
+        flow(1, 0, DEBIT, "9500", "0xa"),
+        flow(2, 2, DEBIT, "9900", "0xb"),
+        flow(3, 3, DEBIT, "5000", "0xc"),
+        flow(4, 5, DEBIT, "9990", "0xd"),
+        flow(5, 6, CREDIT, "9950", "0xe"),
+        flow(6, 40, DEBIT, "9800", "0xf"),
+    ];
+    let alerted = HashSet::new();
+    let eval = Evaluation { from: at(0), alerted: &alerted };
+    let limit = parse_amount("10000", 6).unwrap();
+
+    let findings = structuring(&flows, &eval, Some(USDC), limit, 10, Duration::hours(24), 3);
+    assert_eq!(findings.len(), 1);
+    // Neither the small payment, the credit nor the payment outside the window count
+    assert_eq!(ids(&findings[0].flows), [1, 2, 4]);
+    assert_eq!(findings[0].metrics["total"], "29390");
+    assert!(findings[0].summary.starts_with("3 payments of 9500 to 9990 USDC within 5 hours"));
+
+    // Other assets and payments at the limit are not structuring
+    assert!(structuring(&flows, &eval, None, limit, 10, Duration::hours(24), 3).is_empty());
+    assert!(structuring(&flows, &eval, Some(USDC), parse_amount("9500", 6).unwrap(), 10, Duration::hours(24), 2).is_empty());
+}
+
+#[test]
+fn flows_that_already_raised_an_alert_are_not_reported_again() {
+    let flows = vec![flow(1, 0, DEBIT, "9500", "0xa"), flow(2, 2, DEBIT, "9900", "0xb"), flow(3, 4, DEBIT, "9990", "0xc")];
+    let limit = parse_amount("10000", 6).unwrap();
+
+    let alerted: HashSet<_> = [(FLOW_INTENT, 2)].into_iter().collect();
+    let eval = Evaluation { from: at(0), alerted: &alerted };
+    assert!(structuring(&flows, &eval, Some(USDC), limit, 10, Duration::hours(24), 3).is_empty());
+
+    // Patterns completed before the evaluation period are history
+    let alerted = HashSet::new();
+    let eval = Evaluation { from: at(5), alerted: &alerted };
+    assert!(structuring(&flows, &eval, Some(USDC), limit, 10, Duration::hours(24), 3).is_empty());
+}
+
+#[test]
+fn rapid_in_out_matches_credits_paid_out_within_the_window() {
+    let flows = vec![
+        flow(1, 0, CREDIT, "50000", "0xfund"),
+        flow(2, 1, DEBIT, "20000", "0xa"),
+        flow(3, 3, DEBIT, "21000", "0xb"),
+        flow(4, 4, DEBIT, "1000", "0xc"),
+        flow(5, 10, CREDIT, "50000", "0xfund"),
+        flow(6, 40, DEBIT, "49000", "0xd"),
+    ];
+    let alerted = HashSet::new();
+    let eval = Evaluation { from: at(0), alerted: &alerted };
+
+    let findings = rapid_in_out(&flows, &eval, Some(USDC), parse_amount("10000", 6).unwrap(), Duration::hours(24), 80);
+    assert_eq!(findings.len(), 1);
+    // Paying out stops counting once 80% is reached
+    assert_eq!(ids(&findings[0].flows), [1, 2, 3]);
+    assert_eq!(findings[0].metrics["paid_out"], "41000");
+    assert_eq!(findings[0].metrics["ratio_percent"], 82);
+
+    // Credits below the minimum amount are ignored
+    assert!(rapid_in_out(&flows, &eval, Some(USDC), parse_amount("60000", 6).unwrap(), Duration::hours(24), 80).is_empty());
+}
+
+#[test]
+fn fan_out_counts_only_new_counterparties() {
+    let mut flows = vec![flow(1, 0, DEBIT, "10", "0xknown")];
+    flows.extend((0..4).map(|i| flow(10 + i, 100 + i as i64, DEBIT, "10", &format!("0xnew{}", i))));
+    flows.push(flow(20, 105, DEBIT, "10", "0xknown"));
+    let alerted = HashSet::new();
+    let eval = Evaluation { from: at(96), alerted: &alerted };
+
+    // The counterparty paid four days earlier is not new
+    assert!(fan_out(&flows, &eval, Duration::hours(24), 5, Duration::days(90)).is_empty());
+
+    let findings = fan_out(&flows, &eval, Duration::hours(24), 5, Duration::days(3));
+    assert_eq!(findings.len(), 1);
+    assert_eq!(ids(&findings[0].flows), [10, 11, 12, 13, 20]);
+    assert!(findings[0].summary.starts_with("Paid 5 counterparties"));
+}
+
+#[test]
+fn dormant_wallets_are_flagged_on_their_first_movement() {
+    let flows = vec![flow(1, 0, CREDIT, "100", "0xa"), flow(2, 24 * 200, DEBIT, "100", "0xb"), flow(3, 24 * 201, DEBIT, "1", "0xc")];
+    let alerted = HashSet::new();
+    let eval = Evaluation { from: at(24 * 190), alerted: &alerted };
+
+    let findings = dormant_reactivation(&flows, &eval, at(-24 * 30), Duration::days(180));
+    assert_eq!(findings.len(), 1);
+    assert_eq!(ids(&findings[0].flows), [2]);
+    assert_eq!(findings[0].metrics["inactive_days"], 200);
+
+    // A wallet with no flows in the loaded history counts as inactive since `active_since`
+    let eval = Evaluation { from: at(0), alerted: &alerted };
+    let findings = dormant_reactivation(&flows[..1], &eval, at(-24 * 181), Duration::days(180));
+    assert_eq!(ids(&findings[0].flows), [1]);
+}
+
+#[test]
+fn alert_workflow_only_allows_triage_transitions() {
+    use AlertStatus::*;
+    assert!(Open.can_transition_to(Investigating));
+    assert!(Open.can_transition_to(Dismissed));
+    assert!(!Open.can_transition_to(Resolved));
+    assert!(Investigating.can_transition_to(Escalated));
+    assert!(Escalated.can_transition_to(Resolved));
+    assert!(!Escalated.can_transition_to(Dismissed));
+    assert!(!Resolved.can_transition_to(Open));
+    assert_eq!(AlertStatus::parse("escalated"), Some(Escalated));
+    assert!(Dismissed.is_closed() && !Escalated.is_closed());
+}
@@ -0,0 +1,244 @@
+use aegis_fintech_v1::agent::models::PaymentIntent;
+use aegis_fintech_v1::compliance::models::LegalEntity;
+use aegis_fintech_v1::finance::amounts::{parse_amount, NATIVE_DECIMALS};
+use aegis_fintech_v1::governance::models::Rule;
+use aegis_fintech_v1::governance::policy::{check_rule, current_policies, decide, verdict, Outcome, Payment};
+use chrono::{DateTime, TimeZone, Utc};
+use serde_json::json;
+
+const USDC: &str = "0x5fbdb2315678afecb367f032d93f642f64180aa3";
+
+fn rule(value: serde_json::Value) -> Rule {
+    serde_json::from_value(value).unwrap()
+}
+
+fn intent(amount: &str, token_address: Option<&str>) -> PaymentIntent {
+    let created = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+    PaymentIntent {
+        id: 1,
+        wallet_id: 1,
+        target_address: "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+        amount: amount.to_string(),
+        token_address: token_address.map(str::to_string),
+        status: "created".to_string(),
+        tx_hash: None,
+        failure_reason: None,
+        created_by: "agent".to_string(),
+        created_at: created,
+        updated_at: created,
+        required_approvals: 0,
+        approval_expires_at: None,
+        initiated_by: "agent".to_string(),
+    }
+}
+
+fn entity(jurisdiction: &str, kyc_level: i16) -> LegalEntity {
+    LegalEntity {
+        id: 1,
+        hash_id: "entity".to_string(),
+        jurisdiction: jurisdiction.to_string(),
+        kyc_level,
+        on_chain_id: None,
+        created_at: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+        legal_name: None,
+        lei: None,
+        bic: None,
+        iban: None,
+        kyc_case_id: None,
+        kyc_verified_at: None,
+        kyc_status: "verified".to_string(),
+    }
+}
+
+/// Monday 2 March 2026, 09:30 UTC.
+fn monday_morning() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, 2, 9, 30, 0).unwrap()
+}
+
+fn payment(intent: &PaymentIntent, counterparty: Option<LegalEntity>) -> Payment<'_> {
+    Payment {
+        intent,
+        amount: parse_amount(&intent.amount, NATIVE_DECIMALS).unwrap(),
+        decimals: NATIVE_DECIMALS,
+        now: monday_morning(),
+        counterparty,
+    }
+}
+
+fn allowed(rule: &Rule, payment: &Payment<'_>, recent: &[&str]) -> bool {
+    let recent: Vec<String> = recent.iter().map(|a| a.to_string()).collect();
+    decide(rule, payment, &recent).unwrap().0
+}
+
+fn outcome(policy_id: i32, rule_index: usize, allowed: bool) -> Outcome {
+    Outcome {
+        policy_id,
+        policy_version: 1,
+        rule_index,
+        rule_type: "max_per_transaction",
+        allowed,
+        reason: format!("policy {} rule {}", policy_id, rule_index),
+    }
+}
+
+#[test]
+fn rules_are_validated_before_they_are_stored() {
+    let valid = [
+        json!({"type": "max_per_transaction", "amount": "1.5"}),
+        json!({"type": "velocity", "max_count": 3}),
+        json!({"type": "velocity", "window_seconds": 60, "max_amount": "10"}),
+        json!({"type": "allowed_tokens", "tokens": [USDC], "native": true}),
+        json!({"type": "allowed_hours", "days": ["Mon", "Fri"], "from_hour": 8, "to_hour": 24}),
+        json!({"type": "counterparty_jurisdiction", "denied": ["KP"]}),
+        json!({"type": "min_kyc_level", "level": 2}),
+    ];
+    for value in valid {
+        assert_eq!(check_rule(&rule(value.clone()), NATIVE_DECIMALS), Ok(()), "{}", value);
+    }
+
+    let invalid = [
+        json!({"type": "max_per_transaction", "amount": "lots"}),
+        // More decimals than the asset has
+        json!({"type": "max_per_transaction", "amount": "0.0000001"}),
+        json!({"type": "velocity", "window_seconds": 3600}),
+        json!({"type": "velocity", "window_seconds": 59, "max_count": 3}),
+        json!({"type": "velocity", "max_count": -1}),
+        json!({"type": "allowed_tokens", "tokens": ["not an address"]}),
+        json!({"type": "allowed_hours", "days": [], "from_hour": 8, "to_hour": 18}),
+        json!({"type": "allowed_hours", "days": ["Mon"], "from_hour": 18, "to_hour": 8}),
+        json!({"type": "allowed_hours", "days": ["Mon"], "from_hour": 8, "to_hour": 25}),
+        json!({"type": "counterparty_jurisdiction"}),
+        json!({"type": "min_kyc_level", "level": -1}),
+    ];
+    for value in invalid {
+        assert!(check_rule(&rule(value.clone()), 6).is_err(), "{}", value);
+    }
+}
+
+#[test]
+fn per_transaction_maximum_is_inclusive() {
+    let max = rule(json!({"type": "max_per_transaction", "amount": "1.5"}));
+    assert!(allowed(&max, &payment(&intent("1.5", None), None), &[]));
+    assert!(!allowed(&max, &payment(&intent("1.500000000000000001", None), None), &[]));
+
+    // Amount rules only apply to payments in their own asset
+    let token_max = rule(json!({"type": "max_per_transaction", "amount": "1", "token_address": USDC}));
+    assert!(allowed(&token_max, &payment(&intent("5", None), None), &[]));
+}
+
+#[test]
+fn velocity_counts_the_payment_being_decided() {
+    let by_count = rule(json!({"type": "velocity", "max_count": 3}));
+    let payment_intent = intent("1", None);
+    let p = payment(&payment_intent, None);
+    assert!(allowed(&by_count, &p, &["1", "1"]));
+    assert!(!allowed(&by_count, &p, &["1", "1", "1"]));
+
+    let by_amount = rule(json!({"type": "velocity", "max_amount": "3"}));
+    assert!(allowed(&by_amount, &p, &["1", "1"]));
+    assert!(!allowed(&by_amount, &p, &["1", "1.5"]));
+}
+
+#[test]
+fn allowed_tokens_cover_native_eth_separately() {
+    let tokens = rule(json!({"type": "allowed_tokens", "tokens": [USDC]}));
+    // Addresses compare case-insensitively
+    let usdc = intent("1", Some("0x5FbDB2315678afecb367f032d93F642f64180aa3"));
+    assert!(allowed(&tokens, &payment(&usdc, None), &[]));
+    let other = intent("1", Some("0xe7f1725e7734ce288f8367e1bb143e90bb3f0512"));
+    assert!(!allowed(&tokens, &payment(&other, None), &[]));
+    let eth = intent("1", None);
+    assert!(!allowed(&tokens, &payment(&eth, None), &[]));
+    assert!(allowed(&rule(json!({"type": "allowed_tokens", "tokens": [], "native": true})), &payment(&eth, None), &[]));
+}
+
+#[test]
+fn allowed_hours_include_the_start_and_exclude_the_end() {
+    let payment_intent = intent("1", None);
+    let p = payment(&payment_intent, None);
+    assert!(allowed(&rule(json!({"type": "allowed_hours", "days": ["Mon"], "from_hour": 9, "to_hour": 17})), &p, &[]));
+    assert!(!allowed(&rule(json!({"type": "allowed_hours", "days": ["Mon"], "from_hour": 0, "to_hour": 9})), &p, &[]));
+    assert!(!allowed(&rule(json!({"type": "allowed_hours", "days": ["Tue"], "from_hour": 0, "to_hour": 24})), &p, &[]));
+}
+
+#[test]
+fn counterparty_rules_deny_unknown_entities() {
+    let payment_intent = intent("1", None);
+    let jurisdiction = rule(json!({"type": "counterparty_jurisdiction", "allowed": ["DE", "FR"], "denied": ["FR"]}));
+    assert!(allowed(&jurisdiction, &payment(&payment_intent, Some(entity("de", 1))), &[]));
+    // A denied jurisdiction wins over the allowed list
+    assert!(!allowed(&jurisdiction, &payment(&payment_intent, Some(entity("FR", 1))), &[]));
+    assert!(!allowed(&jurisdiction, &payment(&payment_intent, Some(entity("US", 1))), &[]));
+    assert!(!allowed(&jurisdiction, &payment(&payment_intent, None), &[]));
+
+    let kyc = rule(json!({"type": "min_kyc_level", "level": 2}));
+    assert!(allowed(&kyc, &payment(&payment_intent, Some(entity("DE", 2))), &[]));
+    assert!(!allowed(&kyc, &payment(&payment_intent, Some(entity("DE", 1))), &[]));
+    assert!(!allowed(&kyc, &payment(&payment_intent, None), &[]));
+}
+
+#[test]
+fn any_deny_overrides_allows_and_the_first_is_reported() {
+    assert_eq!(verdict(&[]), Ok(()));
+    assert_eq!(verdict(&[outcome(1, 0, true), outcome(2, 0, true)]), Ok(()));
+
+    let denied = verdict(&[outcome(1, 0, true), outcome(1, 1, false), outcome(2, 0, false)]).unwrap_err();
+    assert!(denied.starts_with("Denied by policy 1 v1 rule #1 (max_per_transaction)"), "{}", denied);
+    assert!(denied.ends_with("policy 1 rule 1"), "{}", denied);
+}
+
+#[tokio::test]
+#[ignore = "needs a migrated database in DATABASE_URL"]
+async fn only_the_current_version_of_enabled_policies_applies() {
+    let pool = sqlx::PgPool::connect(&std::env::var("DATABASE_URL").expect("set DATABASE_URL")).await.unwrap();
+    let tag = Utc::now().timestamp_nanos_opt().unwrap_or_default();
+
+    let wallet_id: i32 = sqlx::query_scalar(
+        r#"
+        WITH entity AS (
+            INSERT INTO legal_entities (hash_id, jurisdiction, kyc_level) VALUES ($1, 'DE', 1) RETURNING id
+        )
+        INSERT INTO wallets (address, entity_id, owner_address, rules_contract, chain_id, deploy_tx_hash)
+        SELECT $2, id, $2, $2, 31337, $1 FROM entity
+        RETURNING id
+        "#
+    )
+    .bind(format!("policy-test-{}", tag))
+    .bind(format!("0x{:040x}", tag))
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let mut policy_ids = Vec::new();
+    for (enabled, current_version) in [(true, 2), (false, 1)] {
+        let policy_id: i32 = sqlx::query_scalar(
+            "INSERT INTO policies (name, wallet_id, enabled, current_version, created_by) VALUES ('test', $1, $2, $3, 'test') RETURNING id"
+        )
+        .bind(wallet_id)
+        .bind(enabled)
+        .bind(current_version)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        for (version, amount) in [(1, "1"), (2, "2")] {
+            sqlx::query("INSERT INTO policy_versions (policy_id, version, rules, created_by) VALUES ($1, $2, $3, 'test')")
+                .bind(policy_id)
+                .bind(version)
+                .bind(json!([{"type": "max_per_transaction", "amount": amount}]))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        policy_ids.push(policy_id);
+    }
+
+    let policies: Vec<_> = current_policies(&pool, wallet_id).await.unwrap()
+        .into_iter()
+        .filter(|(policy_id, _, _)| policy_ids.contains(policy_id))
+        .collect();
+    assert_eq!(policies.len(), 1, "the disabled policy is skipped");
+    let (policy_id, version, rules) = &policies[0];
+    assert_eq!((*policy_id, *version), (policy_ids[0], 2));
+    assert!(matches!(rules.as_slice(), [Rule::MaxPerTransaction { amount, .. }] if amount == "2"));
+}
@@ -0,0 +1,162 @@
+use aegis_fintech_v1::compliance::models::SanctionsCandidate;
+use aegis_fintech_v1::compliance::sanctions::matching::{keys, normalize, similarity, NameIndex, MATCH_THRESHOLD};
+use aegis_fintech_v1::compliance::sanctions::{
+    eu, normalize_address, ofac, ENTRY_ENTITY, ENTRY_INDIVIDUAL, ENTRY_VESSEL, SOURCE_EU, SOURCE_OFAC_SDN,
+};
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join("sanctions").join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("missing file {}", path.display()))
+}
+
+fn candidate(entry_id: i32, source: &str, listed_name: &str, name: &str) -> SanctionsCandidate {
+    let normalized = normalize(name);
+    SanctionsCandidate {
+        entry_id,
+        list_id: 1,
+        source: source.to_string(),
+        source_uid: (90000 + entry_id).to_string(),
+        listed_name: listed_name.to_string(),
+        name: name.to_string(),
+        keys: keys(&normalized),
+        normalized,
+    }
+}
+
+fn candidates() -> Vec<SanctionsCandidate> {
+    vec![
+        candidate(1, SOURCE_OFAC_SDN, "NORTHWIND SHIPPING COMPANY LIMITED", "NORTHWIND SHIPPING COMPANY LIMITED"),
+        candidate(1, SOURCE_OFAC_SDN, "NORTHWIND SHIPPING COMPANY LIMITED", "NORTHWIND MARITIME"),
+        candidate(2, SOURCE_OFAC_SDN, "Viktor Ivanovich KORSAKOV", "Viktor Ivanovich KORSAKOV"),
+        candidate(3, SOURCE_EU, "Société Générale de Transit Baltique SARL", "Société Générale de Transit Baltique SARL"),
+        candidate(4, SOURCE_EU, "Bank Melli Iran", "Bank Melli Iran"),
+    ]
+}
+
+#[test]
+fn parses_ofac_sdn_xml() {
+    let list = ofac::parse_xml(&fixture("sdn.xml")).unwrap();
+    assert_eq!(list.version.as_deref(), Some("2026-01-15"));
+    assert_eq!(list.entries.len(), 3);
+
+    let company = &list.entries[0];
+    assert_eq!(company.source_uid, "90001");
+    assert_eq!(company.entry_type, ENTRY_ENTITY);
+    assert_eq!(company.name, "NORTHWIND SHIPPING COMPANY LIMITED");
+    assert_eq!(company.aliases, ["NORTHWIND MARITIME"]);
+    assert_eq!(company.programs, ["SDGT", "IRGC"]);
+    // Registration numbers are not addresses
+    assert_eq!(company.addresses.len(), 2);
+    assert_eq!(company.addresses[0].currency, "ETH");
+    assert_eq!(company.addresses[0].address, "0x7F367cC41522cE07553e823bf3be79A889DEbe1B");
+    assert_eq!(company.addresses[1].currency, "XBT");
+
+    let person = &list.entries[1];
+    assert_eq!(person.entry_type, ENTRY_INDIVIDUAL);
+    assert_eq!(person.name, "Viktor Ivanovich KORSAKOV");
+    assert_eq!(person.aliases, ["Viktor KORSAKOFF"]);
+    assert_eq!(list.entries[2].entry_type, ENTRY_VESSEL);
+}
+
+#[test]
+fn parses_ofac_sdn_csv() {
+    let list = ofac::parse_csv(&fixture("sdn.csv")).unwrap();
+    assert_eq!(list.version, None);
+    assert_eq!(list.entries.len(), 3);
+
+    let company = &list.entries[0];
+    assert_eq!(company.entry_type, ENTRY_ENTITY);
+    assert_eq!(company.programs, ["SDGT", "IRGC"]);
+    assert_eq!(company.aliases, ["NORTHWIND MARITIME"]);
+    let addresses: Vec<(&str, &str)> = company.addresses.iter().map(|a| (a.currency.as_str(), a.address.as_str())).collect();
+    assert_eq!(addresses, [("ETH", "0x7F367cC41522cE07553e823bf3be79A889DEbe1B"), ("XBT", "1AjZPMsnmpdK2Rv9KQNfMurTXinscVro9V")]);
+
+    let person = &list.entries[1];
+    assert_eq!(person.name, "KORSAKOV, Viktor Ivanovich");
+    assert_eq!(person.aliases, ["KORSAKOFF, Viktor"]);
+    assert!(person.addresses.is_empty());
+    assert_eq!(list.entries[2].entry_type, ENTRY_VESSEL);
+}
+
+#[test]
+fn parses_eu_consolidated_list() {
+    let list = eu::parse_xml(&fixture("eu_consolidated.xml")).unwrap();
+    assert_eq!(list.version.as_deref(), Some("2026-01-14"));
+    assert_eq!(list.entries.len(), 2);
+
+    let person = &list.entries[0];
+    assert_eq!(person.source_uid, "140211");
+    assert_eq!(person.entry_type, ENTRY_INDIVIDUAL);
+    assert_eq!(person.name, "Viktor Ivanovich Korsakov");
+    // Repeated spellings are listed once
+    assert_eq!(person.aliases, ["Виктор Иванович Корсаков"]);
+    assert_eq!(person.programs, ["RUS", "UKR"]);
+
+    let company = &list.entries[1];
+    assert_eq!(company.entry_type, ENTRY_ENTITY);
+    assert_eq!(company.aliases, ["Baltic Transit General Company"]);
+}
+
+#[test]
+fn rejects_files_of_another_list() {
+    assert!(ofac::parse_xml(&fixture("eu_consolidated.xml")).is_err());
+    assert!(eu::parse_xml(&fixture("sdn.xml")).is_err());
+}
+
+#[test]
+fn names_normalize_across_scripts_punctuation_and_legal_forms() {
+    assert_eq!(normalize("Northwind Shipping Co., Ltd."), "NORTHWIND SHIPPING");
+    assert_eq!(normalize("Société Générale de Transit Baltique S.A.R.L."), "SOCIETE GENERALE DE TRANSIT BALTIQUE");
+    assert_eq!(normalize("KORSAKOV, Viktor"), "KORSAKOV VIKTOR");
+    assert_eq!(normalize("Виктор Корсаков"), "VIKTOR KORSAKOV");
+    assert_eq!(normalize("The Company Ltd"), "THE COMPANY LTD");
+    assert_eq!(keys("MOHAMMED ALI"), keys("MUHAMMAD ALI"));
+}
+
+#[test]
+fn similarity_ignores_token_order() {
+    assert_eq!(similarity("KORSAKOV VIKTOR", "VIKTOR KORSAKOV"), 1.0);
+    assert!(similarity("KORSAKOV VIKTOR", "KORSAKOFF VIKTOR") >= MATCH_THRESHOLD);
+    assert!(similarity("ACME ROBOTICS", "ACME ROBOTICS INTERNATIONAL HOLDINGS") < MATCH_THRESHOLD);
+    assert_eq!(similarity("", "ACME"), 0.0);
+}
+
+#[test]
+fn fuzzy_matching_finds_listed_names_and_aliases() {
+    let candidates = candidates();
+    let index = NameIndex::new(&candidates);
+
+    let matches = index.search("North-Wind Maritime GmbH");
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].candidate.entry_id, 1);
+    assert_eq!(matches[0].candidate.name, "NORTHWIND MARITIME");
+
+    let matches = index.search("Korsakoff, Viktor Ivanovitch");
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].candidate.listed_name, "Viktor Ivanovich KORSAKOV");
+    assert!(matches[0].score >= MATCH_THRESHOLD && matches[0].score < 1.0);
+
+    let matches = index.search("Societe Generale de Transit Baltique");
+    assert_eq!(matches[0].candidate.source, SOURCE_EU);
+    assert_eq!(matches[0].score, 1.0);
+}
+
+#[test]
+fn fuzzy_matching_ignores_unrelated_names() {
+    let candidates = candidates();
+    let index = NameIndex::new(&candidates);
+
+    assert!(index.search("Acme Robotics GmbH").is_empty());
+    assert!(index.search("Northern Lights Trading AG").is_empty());
+    assert!(index.search("Bank of Ireland").is_empty());
+    assert!(index.search("").is_empty());
+}
+
+#[test]
+fn addresses_compare_case_insensitively_where_the_encoding_allows() {
+    assert_eq!(normalize_address(" 0x7F367cC41522cE07553e823bf3be79A889DEbe1B "), "0x7f367cc41522ce07553e823bf3be79a889debe1b");
+    assert_eq!(normalize_address("BC1QXY2KGDYGJRSQTZQ2N0YRF2493P83KKFJHX0WLH"), "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh");
+    // base58 is case-sensitive
+    assert_eq!(normalize_address("1AjZPMsnmpdK2Rv9KQNfMurTXinscVro9V"), "1AjZPMsnmpdK2Rv9KQNfMurTXinscVro9V");
+}
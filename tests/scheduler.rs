@@ -0,0 +1,79 @@
+use aegis_fintech_v1::agent::models::PaymentSchedule;
+use aegis_fintech_v1::agent::scheduler::{next_occurrence, Cadence};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+fn at(day: u32, hour: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, day, hour, min, 0).unwrap()
+}
+
+fn schedule(cron_expression: Option<&str>, interval_seconds: Option<i64>) -> PaymentSchedule {
+    PaymentSchedule {
+        id: 1,
+        wallet_id: 1,
+        target_address: "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+        amount: "1".to_string(),
+        token_address: None,
+        cron_expression: cron_expression.map(str::to_string),
+        interval_seconds,
+        start_at: at(2, 9, 0),
+        end_at: None,
+        max_occurrences: None,
+        occurrences: 0,
+        next_run_at: None,
+        status: "active".to_string(),
+        created_by: "agent".to_string(),
+        created_at: at(1, 0, 0),
+        updated_at: at(1, 0, 0),
+    }
+}
+
+#[test]
+fn intervals_are_anchored_to_the_start() {
+    let hourly = schedule(None, Some(3600));
+    // Before the start, the start itself is the first occurrence
+    assert_eq!(next_occurrence(&hourly, at(1, 12, 0)), Ok(Some(at(2, 9, 0))));
+    // Strictly after `after`, on the start's grid
+    assert_eq!(next_occurrence(&hourly, at(2, 9, 0)), Ok(Some(at(2, 10, 0))));
+    assert_eq!(next_occurrence(&hourly, at(2, 10, 59)), Ok(Some(at(2, 11, 0))));
+    // A late worker does not replay missed occurrences
+    assert_eq!(next_occurrence(&hourly, at(3, 9, 30)), Ok(Some(at(3, 10, 0))));
+}
+
+#[test]
+fn cron_schedules_follow_the_expression() {
+    // sec min hour day-of-month month day-of-week: 08:00 on weekdays
+    let weekdays = schedule(Some("0 0 8 * * Mon-Fri"), None);
+    // 2026-03-06 is a Friday
+    assert_eq!(next_occurrence(&weekdays, at(6, 8, 0)), Ok(Some(at(9, 8, 0))));
+    // Nothing before the start date
+    assert_eq!(next_occurrence(&weekdays, at(1, 0, 0)), Ok(Some(at(3, 8, 0))));
+}
+
+#[test]
+fn end_date_and_occurrence_cap_exhaust_the_schedule() {
+    let mut hourly = schedule(None, Some(3600));
+    hourly.end_at = Some(at(2, 11, 0));
+    assert_eq!(next_occurrence(&hourly, at(2, 10, 0)), Ok(Some(at(2, 11, 0))));
+    assert_eq!(next_occurrence(&hourly, at(2, 11, 0)), Ok(None));
+
+    let mut capped = schedule(None, Some(3600));
+    capped.max_occurrences = Some(3);
+    capped.occurrences = 2;
+    assert!(next_occurrence(&capped, at(2, 10, 0)).unwrap().is_some());
+    capped.occurrences = 3;
+    assert_eq!(next_occurrence(&capped, at(2, 10, 0)), Ok(None));
+}
+
+#[test]
+fn cadence_needs_exactly_one_valid_definition() {
+    assert!(matches!(Cadence::parse(None, Some(60)), Ok(Cadence::Interval(step)) if step == Duration::minutes(1)));
+    assert!(Cadence::parse(None, Some(59)).is_err());
+    // Out-of-range intervals are rejected instead of overflowing
+    assert!(Cadence::parse(None, Some(10 * 366 * 24 * 3600)).is_ok());
+    assert!(Cadence::parse(None, Some(10 * 366 * 24 * 3600 + 1)).is_err());
+    assert!(Cadence::parse(None, Some(i64::MAX)).is_err());
+    assert!(Cadence::parse(Some("not cron"), None).is_err());
+    assert!(Cadence::parse(Some("0 0 8 * * *"), Some(3600)).is_err());
+    assert!(Cadence::parse(None, None).is_err());
+    assert!(next_occurrence(&schedule(Some("0 0 25 * * *"), None), at(2, 9, 0)).is_err());
+}